@@ -0,0 +1,130 @@
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+use crate::{Point, Sprite, SpriteSheet};
+use crate::assets::AssetManager;
+use crate::timers::Timer;
+
+/// Number of frames of an explosion
+const EXPLOSION_FRAMES: u32 = 8;
+/// Time each frame of an explosion is shown for
+const EXPLOSION_FRAME_DURATION: f32 = 0.05;
+/// Sprite sheet explosions are drawn with once it is loaded
+const EXPLOSION_IMAGES: &[SpriteSheet] = &[SpriteSheet::new("/images/explosion.png", EXPLOSION_FRAMES)];
+/// Color of explosions drawn without their sprite sheet
+const EXPLOSION_COLOR: graphics::Color = graphics::Color::new(1.0, 0.6, 0.2, 1.0);
+
+/// Plays the frames of a sprite sheet one after another, each for the same time, either once or over and over.
+/// Animations only advance by the time they are given, so they follow the simulation's time scale like timers do
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Animation {
+    frames: u32,
+    frame_duration: f32,
+    timer: Timer,
+}
+
+impl Animation {
+    /// Create an animation of the given number of frames, each shown for the given time, that starts over once it
+    /// reaches its last frame
+    pub fn looping(frames: u32, frame_duration: f32) -> Animation {
+        let frames = frames.max(1);
+        return Animation { frames, frame_duration, timer: Timer::looping(frames as f32 * frame_duration) };
+    }
+
+    /// Create an animation of the given number of frames, each shown for the given time, that plays once and then
+    /// holds its last frame
+    pub fn once(frames: u32, frame_duration: f32) -> Animation {
+        let frames = frames.max(1);
+        return Animation { frames, frame_duration, timer: Timer::new(frames as f32 * frame_duration) };
+    }
+
+    /// Create an animation that plays once like `once`, but has already played, so it waits to be restarted when
+    /// what it shows happens
+    pub fn played(frames: u32, frame_duration: f32) -> Animation {
+        let mut animation = Animation::once(frames, frame_duration);
+        animation.timer.update(animation.timer.get_duration());
+        return animation;
+    }
+
+    /// Advance the animation by the given time
+    pub fn update(&mut self, dt: f32) {
+        self.timer.update(dt);
+    }
+
+    /// Play the animation again from its first frame
+    pub fn restart(&mut self) {
+        self.timer.restart();
+    }
+
+    /// Get the number of the frame being shown, from 0
+    pub fn get_frame(&self) -> u32 {
+        if self.frame_duration <= 0.0 {
+            return self.frames - 1;
+        }
+        return ((self.timer.get_elapsed() / self.frame_duration) as u32).min(self.frames - 1);
+    }
+
+    /// Get the number of frames of the animation
+    pub fn get_frame_count(&self) -> u32 {
+        return self.frames;
+    }
+
+    /// Get how far through the animation it is, from 0 to 1
+    pub fn get_progress(&self) -> f32 {
+        return (self.timer.get_elapsed() / self.timer.get_duration()).min(1.0);
+    }
+
+    /// Check if the animation is playing: looping animations always are, and the others until they have played once
+    pub fn is_playing(&self) -> bool {
+        return !self.timer.is_finished();
+    }
+}
+
+/// Explosion left where something blew up or was destroyed, which plays once and is gone. Explosions are only for
+/// show, so they aren't saved and don't change how a run plays out
+pub struct Explosion {
+    position: Point,
+    radius: f32,
+    animation: Animation,
+}
+
+impl Explosion {
+    /// Create an explosion of the given radius at the given position
+    pub fn new(position: Point, radius: f32) -> Explosion {
+        return Explosion { position, radius, animation: Animation::once(EXPLOSION_FRAMES, EXPLOSION_FRAME_DURATION) };
+    }
+
+    /// Advance the explosion by the given time
+    pub fn update(&mut self, dt: f32) {
+        self.animation.update(dt);
+    }
+
+    /// Check if the explosion has played out and can be removed
+    pub fn is_finished(&self) -> bool {
+        return !self.animation.is_playing();
+    }
+
+    /// Draw the frame of the explosion's sprite sheet that is due, or a ring that grows and fades if the sheet
+    /// hasn't been loaded
+    pub fn draw(&self, ctx: &mut Context, assets: &AssetManager) -> GameResult {
+        let position = [self.position.x, self.position.y];
+        let sprite = Sprite::new(EXPLOSION_IMAGES, position, self.radius * 2.0, 0.0, graphics::WHITE)
+            .with_frame(self.animation.get_frame());
+        if sprite.draw(ctx, assets)? {
+            return Ok(());
+        }
+
+        let progress = self.animation.get_progress();
+        let mut color = EXPLOSION_COLOR;
+        color.a = 1.0 - progress;
+        let ring = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::stroke(3.0),
+            [0.0, 0.0],
+            (self.radius * progress).max(1.0),
+            0.5,
+            color,
+        )?;
+        return graphics::draw(ctx, &ring, (position,));
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use ggez::{Context, GameResult, filesystem};
+use ggez::audio::SoundData;
+use ggez::graphics::{Font, Image};
+
+/// Loads the images, fonts, and sounds of the game from the resources directory through ggez's filesystem, and
+/// keeps everything it has loaded so each file is only read once. What it hands out is cheap to copy, so actors and
+/// menus can hold on to it instead of loading their assets again every frame
+#[derive(Default)]
+pub struct AssetManager {
+    images: HashMap<String, Rc<Image>>,
+    fonts: HashMap<String, Font>,
+    sounds: HashMap<String, SoundData>,
+}
+
+impl AssetManager {
+    /// Create an asset manager that hasn't loaded anything yet
+    pub fn new() -> AssetManager {
+        return AssetManager::default();
+    }
+
+    /// Check if there is a file at the given path in the resources, to load optional assets without an error
+    pub fn exists(ctx: &mut Context, path: &str) -> bool {
+        return filesystem::exists(ctx, path);
+    }
+
+    /// Get the image at the given path, loading it the first time it is asked for
+    pub fn load_image(&mut self, ctx: &mut Context, path: &str) -> GameResult<Rc<Image>> {
+        if let Some(image) = self.images.get(path) {
+            return Ok(Rc::clone(image));
+        }
+        let image = Rc::new(Image::new(ctx, path)?);
+        log::debug!(target: "turrets::assets", "loaded image {}", path);
+        self.images.insert(path.to_string(), Rc::clone(&image));
+        return Ok(image);
+    }
+
+    /// Get the font at the given path, loading it the first time it is asked for
+    pub fn load_font(&mut self, ctx: &mut Context, path: &str) -> GameResult<Font> {
+        if let Some(font) = self.fonts.get(path) {
+            return Ok(*font);
+        }
+        let font = Font::new(ctx, path)?;
+        log::debug!(target: "turrets::assets", "loaded font {}", path);
+        self.fonts.insert(path.to_string(), font);
+        return Ok(font);
+    }
+
+    /// Get the sound at the given path, loading it the first time it is asked for
+    pub fn load_sound(&mut self, ctx: &mut Context, path: &str) -> GameResult<SoundData> {
+        if let Some(sound) = self.sounds.get(path) {
+            return Ok(sound.clone());
+        }
+        let sound = SoundData::new(ctx, path)?;
+        log::debug!(target: "turrets::assets", "loaded sound {}", path);
+        self.sounds.insert(path.to_string(), sound.clone());
+        return Ok(sound);
+    }
+
+    /// Get the image at the given path if it has already been loaded
+    pub fn get_image(&self, path: &str) -> Option<Rc<Image>> {
+        return self.images.get(path).cloned();
+    }
+
+    /// Get the font at the given path if it has already been loaded
+    pub fn get_font(&self, path: &str) -> Option<Font> {
+        return self.fonts.get(path).copied();
+    }
+
+    /// Get the sound at the given path if it has already been loaded
+    pub fn get_sound(&self, path: &str) -> Option<SoundData> {
+        return self.sounds.get(path).cloned();
+    }
+
+    /// Get the number of assets that have been loaded
+    pub fn get_count(&self) -> usize {
+        return self.images.len() + self.fonts.len() + self.sounds.len();
+    }
+
+    /// Forget every asset that has been loaded, so they are read from disk again the next time they are asked for
+    pub fn clear(&mut self) {
+        self.images.clear();
+        self.fonts.clear();
+        self.sounds.clear();
+    }
+}
@@ -0,0 +1,126 @@
+use ggez::{Context, GameResult};
+use ggez::audio::{SoundData, SoundSource, Source};
+use rand::Rng;
+
+use crate::assets::AssetManager;
+
+/// Largest number of samples loaded for a single sound event
+const MAX_SAMPLES: usize = 8;
+/// Largest fraction the pitch of a sound is raised or lowered by
+const PITCH_VARIATION: f32 = 0.1;
+/// Largest number of copies of the same event played in a single tick
+const MAX_PLAYS_PER_TICK: usize = 4;
+
+/// The events in the game that make a sound
+#[derive(Clone, Copy, PartialEq)]
+pub enum SoundEvent {
+    /// A shot was fired
+    Fire,
+    /// Two actors hit each other
+    Impact,
+}
+
+impl SoundEvent {
+    /// All the sound events
+    pub const ALL: [SoundEvent; 2] = [SoundEvent::Fire, SoundEvent::Impact];
+
+    /// Get the name the samples of this event start with in the sounds directory
+    fn get_file_name(&self) -> &'static str {
+        return match self {
+            SoundEvent::Fire => "fire",
+            SoundEvent::Impact => "impact",
+        };
+    }
+}
+
+/// Plays the sounds of the game, picking a random sample and pitch every time an event is played so that
+/// repeated events don't all sound the same
+pub struct AudioManager {
+    samples: Vec<(SoundEvent, Vec<SoundData>)>,
+    queued: Vec<SoundEvent>,
+    volume: f32,
+}
+
+impl AudioManager {
+    /// Get the paths of the samples of every event in the sounds directory, named like /sounds/fire_1.ogg,
+    /// /sounds/fire_2.ogg, and so on
+    pub fn get_sample_paths(ctx: &mut Context) -> Vec<String> {
+        let mut paths = Vec::new();
+        for event in SoundEvent::ALL.iter() {
+            paths.extend(AudioManager::get_event_paths(ctx, *event));
+        }
+        return paths;
+    }
+
+    /// Get the paths of the samples of the given event, stopping at the first number that is missing
+    fn get_event_paths(ctx: &mut Context, event: SoundEvent) -> Vec<String> {
+        return (1..=MAX_SAMPLES)
+            .map(|i| format!("/sounds/{}_{}.ogg", event.get_file_name(), i))
+            .take_while(|path| AssetManager::exists(ctx, path))
+            .collect();
+    }
+
+    /// Load the samples of every event from the sounds directory through the given asset manager, replacing any
+    /// loaded before. Events without any samples are silent
+    pub fn load_samples(&mut self, ctx: &mut Context, assets: &mut AssetManager) {
+        let mut samples = Vec::new();
+        for event in SoundEvent::ALL.iter() {
+            let event_samples: Vec<SoundData> = AudioManager::get_event_paths(ctx, *event).iter()
+                .filter_map(|path| assets.load_sound(ctx, path).ok())
+                .collect();
+            samples.push((*event, event_samples));
+        }
+        self.samples = samples;
+    }
+
+    /// Create a manager with no samples, for games that run without sound
+    pub fn silent() -> AudioManager {
+        return AudioManager {
+            samples: Vec::new(),
+            queued: Vec::new(),
+            volume: 1.0,
+        };
+    }
+
+    /// Set the volume the sounds are played at, from 0 (muted) to 1
+    pub fn set_volume(&mut self, volume: f32) {
+        self.volume = volume.clamp(0.0, 1.0);
+    }
+
+    /// Queue an event to be played at the end of the tick, unless there is no sound to play for it
+    pub fn queue(&mut self, event: SoundEvent) {
+        if self.samples.iter().any(|(sample_event, samples)| *sample_event == event && !samples.is_empty()) {
+            self.queued.push(event);
+        }
+    }
+
+    /// Play the queued events, limiting how many copies of the same event are played at once. The given random
+    /// number generator should be the cosmetic stream, so that sound variation never changes how a run plays out
+    pub fn play_queued<R: Rng>(&mut self, ctx: &mut Context, rng: &mut R) -> GameResult {
+        let queued = std::mem::take(&mut self.queued);
+        for event in SoundEvent::ALL.iter() {
+            let count = queued.iter().filter(|queued_event| *queued_event == event).count();
+            for _ in 0..count.min(MAX_PLAYS_PER_TICK) {
+                self.play(ctx, *event, rng)?;
+            }
+        }
+
+        return Ok(());
+    }
+
+    /// Play a random sample of the given event with a randomized pitch
+    fn play<R: Rng>(&self, ctx: &mut Context, event: SoundEvent, rng: &mut R) -> GameResult {
+        let samples = match self.samples.iter().find(|(sample_event, _)| *sample_event == event) {
+            Some((_, samples)) if !samples.is_empty() => samples,
+            _ => return Ok(()),
+        };
+
+        let data = samples[rng.gen_range(0, samples.len())].clone();
+        let mut source = Source::from_data(ctx, data)?;
+        source.set_pitch(1.0 + rng.gen_range(-PITCH_VARIATION, PITCH_VARIATION));
+        source.set_volume(self.volume);
+        source.play_detached()?;
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,195 @@
+use std::f32::consts::PI;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+
+use crate::{Damage, GameMode, MainState, Point, Shot, ShipClass, Team, Turret, Velocity};
+use crate::observation::Action;
+
+/// Number of turrets the benchmark fills the arena with
+pub const BENCHMARK_TURRETS: usize = 200;
+/// Number of shots the benchmark keeps flying around the arena
+pub const BENCHMARK_SHOTS: usize = 3000;
+/// Number of seconds of play the benchmark runs for
+pub const BENCHMARK_SECONDS: u64 = 20;
+/// Number of ticks the benchmark runs for without a window, which always ticks at the default rate
+pub const BENCHMARK_TICKS: u64 = BENCHMARK_SECONDS * crate::DEFAULT_TICK_RATE as u64;
+/// Size of the arena of benchmarks run without a window
+pub const BENCHMARK_BOUNDS: (f32, f32) = (1280.0, 720.0);
+/// Speed of the shots the benchmark fires, in pixels per second
+const BENCHMARK_SHOT_SPEED: f32 = 150.0;
+/// Seconds the shots the benchmark fires last for
+const BENCHMARK_SHOT_LIFESPAN: f32 = 3.0;
+
+/// Measures the time taken by each step of a tick or a frame. A stopwatch that isn't running measures nothing, so
+/// games that aren't being benchmarked don't pay for it
+pub struct Stopwatch {
+    last: Option<Instant>,
+    laps: Vec<(&'static str, Duration)>,
+}
+
+impl Stopwatch {
+    /// Start a new stopwatch, running if it is enabled
+    pub fn start(enabled: bool) -> Stopwatch {
+        return Stopwatch { last: if enabled { Some(Instant::now()) } else { None }, laps: Vec::new() };
+    }
+
+    /// Record the time since the last lap as the time taken by the given step
+    pub fn lap(&mut self, name: &'static str) {
+        if let Some(last) = self.last {
+            let now = Instant::now();
+            self.laps.push((name, now - last));
+            self.last = Some(now);
+        }
+    }
+}
+
+/// Total time taken by each step over every tick or frame measured, in the order the steps were first measured
+pub struct SystemTimings {
+    systems: Vec<(&'static str, Duration)>,
+    samples: u32,
+}
+
+impl SystemTimings {
+    /// Create new timings with nothing measured
+    pub fn new() -> SystemTimings {
+        return SystemTimings { systems: Vec::new(), samples: 0 };
+    }
+
+    /// Add the laps of the given stopwatch to the timings
+    pub fn record(&mut self, stopwatch: &Stopwatch) {
+        for &(name, duration) in &stopwatch.laps {
+            match self.systems.iter_mut().find(|(system, _)| *system == name) {
+                Some((_, total)) => *total += duration,
+                None => self.systems.push((name, duration)),
+            }
+        }
+        self.samples += 1;
+    }
+
+    /// Get the number of ticks or frames measured
+    pub fn get_samples(&self) -> u32 {
+        return self.samples;
+    }
+
+    /// Describe the average time each step took, and its share of the total, one line per step under the given
+    /// heading
+    pub fn describe(&self, heading: &str) -> Vec<String> {
+        let total: Duration = self.systems.iter().map(|(_, duration)| *duration).sum();
+        let samples = self.samples.max(1);
+        let mut lines = vec![format!("{} ({} samples, {:.3} ms average):", heading, self.samples,
+                                     (total / samples).as_secs_f64() * 1000.0)];
+        for (name, duration) in &self.systems {
+            let share = duration.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON) * 100.0;
+            lines.push(format!("  {:<24} {:>9.3} ms {:>5.1}%", name, (*duration / samples).as_secs_f64() * 1000.0,
+                               share));
+        }
+        return lines;
+    }
+}
+
+impl Default for SystemTimings {
+    fn default() -> SystemTimings {
+        return SystemTimings::new();
+    }
+}
+
+/// Stress test that fills the arena with turrets and keeps thousands of shots flying while a bot plays, measuring
+/// how long each system of the update and the drawing takes
+pub struct Benchmark {
+    ticks_left: u64,
+    started: Instant,
+    update: SystemTimings,
+    draw: SystemTimings,
+}
+
+impl Benchmark {
+    /// Start a benchmark of the given game that runs for the given number of ticks, filling its arena with turrets
+    pub fn start(state: &mut MainState, ticks: u64) -> Benchmark {
+        state.start(GameMode::Waves, ShipClass::ALL[1]);
+        // Let the actors pile up past the population caps, which would otherwise despawn most of the shots
+        state.population = crate::population::PopulationLimits::unlimited();
+
+        // Lay the turrets out in a grid that covers the arena
+        let (width, height) = state.world.get_size();
+        let columns = (BENCHMARK_TURRETS as f32 * width / height).sqrt().ceil() as usize;
+        let rows = BENCHMARK_TURRETS.div_ceil(columns);
+        for i in 0..BENCHMARK_TURRETS {
+            let x = (i % columns) as f32 + 0.5;
+            let y = (i / columns) as f32 + 0.5;
+            let position = Point::new(x * width / columns as f32, y * height / rows as f32);
+            let turret = Turret::new(position, &state.world, &state.tuning);
+            state.add_turret(turret);
+        }
+
+        return Benchmark { ticks_left: ticks, started: Instant::now(), update: SystemTimings::new(),
+                           draw: SystemTimings::new() };
+    }
+
+    /// Get the game ready for the next tick: keep the player alive and playing, and top the shots back up
+    pub fn prepare_tick(&mut self, state: &mut MainState) {
+        self.ticks_left = self.ticks_left.saturating_sub(1);
+        // Take the first perk offered after a wave, so the run never stops to wait for a choice
+        if state.is_choosing_perk() {
+            state.choose_perk(0);
+        }
+        state.player.health.refill();
+        state.player.apply_action(Action { thrust: 0, turn: 1, fire: true, grapple: false });
+
+        // Fire harmless shots from random points in random directions, alternating between the teams so they
+        // collide with everything
+        let shots = state.actors.iter().filter(|actor| actor.get_kind().get_name() == "shot").count();
+        let (width, height) = state.world.get_size();
+        for i in shots..BENCHMARK_SHOTS {
+            let position = Point::new(state.rng.cosmetic().gen_range(0.0, width),
+                                      state.rng.cosmetic().gen_range(0.0, height));
+            let velocity = Velocity::new(BENCHMARK_SHOT_SPEED, state.rng.cosmetic().gen_range(-PI, PI));
+            let team = if i % 2 == 0 { Team::Hostile } else { Team::Player };
+            let mut shot = Shot::new(position, &state.world, velocity, Damage::kinetic(0.0), BENCHMARK_SHOT_LIFESPAN,
+                                     None, team);
+            shot.radius = state.tuning.shot_radius;
+            state.add_actor(Box::new(shot));
+        }
+    }
+
+    /// Add the laps of a tick to the update timings
+    pub fn record_update(&mut self, stopwatch: &Stopwatch) {
+        self.update.record(stopwatch);
+    }
+
+    /// Add the laps of a frame to the draw timings
+    pub fn record_draw(&mut self, stopwatch: &Stopwatch) {
+        self.draw.record(stopwatch);
+    }
+
+    /// Check if the benchmark has run all of its ticks
+    pub fn is_finished(&self) -> bool {
+        return self.ticks_left == 0;
+    }
+
+    /// Describe how long the benchmark took, and how long each system of the update and the drawing took
+    pub fn get_summary(&self) -> String {
+        let elapsed = self.started.elapsed().as_secs_f32();
+        let ticks = self.update.get_samples();
+        let mut lines = vec![
+            format!("Benchmark: {} turrets, {} shots, {} ticks in {:.2}s ({:.1} ticks per second)",
+                    BENCHMARK_TURRETS, BENCHMARK_SHOTS, ticks, elapsed, ticks as f32 / elapsed.max(f32::EPSILON)),
+        ];
+        lines.extend(self.update.describe("Update"));
+        if self.draw.get_samples() > 0 {
+            lines.extend(self.draw.describe("Draw"));
+        }
+        return lines.join("\n");
+    }
+}
+
+/// Run the benchmark for the given number of ticks without a window, so only the update is measured, and return
+/// its summary
+pub fn run_headless(ticks: u64) -> String {
+    let mut state = MainState::headless(BENCHMARK_BOUNDS, 0);
+    state.start_benchmark(ticks);
+    while !state.is_benchmark_finished() {
+        state.simulate_tick();
+    }
+    return state.get_benchmark_summary().unwrap_or_default();
+}
@@ -0,0 +1,163 @@
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+use crate::timers::Timer;
+
+/// Width of the boss bar
+const BAR_WIDTH: f32 = 400.0;
+/// Height of the boss bar
+const BAR_HEIGHT: f32 = 14.0;
+/// Distance from the top of the screen to the boss bar, leaving room for the status line and the boss's name
+const BAR_TOP: f32 = 52.0;
+/// Radius of the pips under the bar that mark the phases the boss has gone through
+const PIP_RADIUS: f32 = 4.0;
+/// Number of seconds the bar flashes for after the boss is hurt
+const FLASH_DURATION: f32 = 0.12;
+/// Number of seconds a pip pulses for after the boss enters a new phase
+const TRANSITION_DURATION: f32 = 0.8;
+
+/// Color of the health left in the bar
+const BAR_COLOR: graphics::Color = graphics::Color::new(0.85, 0.15, 0.2, 1.0);
+
+/// Boss the bar is showing
+#[derive(Clone, Serialize, Deserialize)]
+struct TrackedBoss {
+    id: u32,
+    name: String,
+    max_health: f32,
+    health: f32,
+    phases: u32,
+    phase: u32,
+    flash: Timer,
+    transition: Timer,
+}
+
+/// Health bar at the top of the screen for the current boss, split into one segment per phase, which flashes when
+/// the boss is hurt and lights a pip every time the boss enters a new phase. Hidden while there is no boss
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BossBar {
+    boss: Option<TrackedBoss>,
+}
+
+impl BossBar {
+    /// Create a new hidden boss bar
+    pub fn new() -> BossBar {
+        return BossBar { boss: None };
+    }
+
+    /// Show the bar for the boss with the given actor ID, name, full health, and number of phases
+    pub fn track(&mut self, id: u32, name: &str, max_health: f32, phases: u32) {
+        self.boss = Some(TrackedBoss {
+            id,
+            name: name.to_string(),
+            max_health,
+            health: max_health,
+            phases: phases.max(1),
+            phase: 0,
+            flash: Timer::finished(),
+            transition: Timer::finished(),
+        });
+    }
+
+    /// Get the actor ID of the boss being shown, if there is one
+    pub fn get_boss(&self) -> Option<u32> {
+        return self.boss.as_ref().map(|boss| boss.id);
+    }
+
+    /// Update the bar with the boss's current health, or hide the bar if the boss is gone
+    pub fn update(&mut self, dt: f32, health: Option<f32>) {
+        let health = match health {
+            Some(health) if health > 0.0 => health,
+            _ => {
+                self.boss = None;
+                return;
+            }
+        };
+        let boss = match &mut self.boss {
+            Some(boss) => boss,
+            None => return,
+        };
+
+        boss.flash.update(dt);
+        boss.transition.update(dt);
+
+        // Flash the bar if the boss was hurt, and pulse the pip of a new phase
+        if health < boss.health {
+            boss.flash.restart_with(FLASH_DURATION);
+        }
+        boss.health = health;
+        let lost = 1.0 - (health / boss.max_health).clamp(0.0, 1.0);
+        let phase = ((lost * boss.phases as f32) as u32).min(boss.phases - 1);
+        if phase > boss.phase {
+            boss.phase = phase;
+            boss.transition.restart_with(TRANSITION_DURATION);
+        }
+    }
+
+    /// Draw the bar centered at the top of the screen, if there is a boss
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let boss = match &self.boss {
+            Some(boss) => boss,
+            None => return Ok(()),
+        };
+        let (width, _) = graphics::drawable_size(ctx);
+        let left = (width - BAR_WIDTH) / 2.0;
+
+        // Draw the boss's name above the bar
+        let name = graphics::Text::new(boss.name.as_str());
+        let name_width = name.width(ctx) as f32;
+        graphics::draw(ctx, &name, ([(width - name_width) / 2.0, BAR_TOP - 20.0], graphics::WHITE))?;
+
+        // Fill the bar with the health left, turning it white for a moment when the boss is hurt
+        let fill = (boss.health / boss.max_health).clamp(0.0, 1.0);
+        let color = if boss.flash.is_finished() { BAR_COLOR } else { graphics::WHITE };
+        if fill > 0.0 {
+            let health = graphics::Mesh::new_rectangle(
+                ctx,
+                graphics::DrawMode::fill(),
+                graphics::Rect::new(left, BAR_TOP, BAR_WIDTH * fill, BAR_HEIGHT),
+                color,
+            )?;
+            graphics::draw(ctx, &health, ([0.0, 0.0],))?;
+        }
+        let outline = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::stroke(1.0),
+            graphics::Rect::new(left, BAR_TOP, BAR_WIDTH, BAR_HEIGHT),
+            graphics::WHITE,
+        )?;
+        graphics::draw(ctx, &outline, ([0.0, 0.0],))?;
+
+        // Split the bar into one segment per phase
+        for i in 1..boss.phases {
+            let x = left + BAR_WIDTH * (1.0 - i as f32 / boss.phases as f32);
+            let divider = graphics::Mesh::new_line(ctx, &[[x, BAR_TOP], [x, BAR_TOP + BAR_HEIGHT]], 2.0,
+                                                   graphics::WHITE)?;
+            graphics::draw(ctx, &divider, ([0.0, 0.0],))?;
+        }
+
+        // Draw a pip for every phase under the bar, filling the ones the boss has entered and pulsing the newest
+        let pips_left = (width - (boss.phases as f32 * 4.0 - 2.0) * PIP_RADIUS) / 2.0 + PIP_RADIUS;
+        for i in 0..boss.phases {
+            let entered = i <= boss.phase;
+            let pulsing = i == boss.phase && !boss.transition.is_finished();
+            let radius = if pulsing {
+                PIP_RADIUS * (1.0 + boss.transition.get_remaining() / TRANSITION_DURATION)
+            } else {
+                PIP_RADIUS
+            };
+            let mode = if entered { graphics::DrawMode::fill() } else { graphics::DrawMode::stroke(1.0) };
+            let pip = graphics::Mesh::new_circle(ctx, mode, [0.0, 0.0], radius, 0.5, BAR_COLOR)?;
+            let x = pips_left + i as f32 * 4.0 * PIP_RADIUS;
+            graphics::draw(ctx, &pip, ([x, BAR_TOP + BAR_HEIGHT + 10.0],))?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl Default for BossBar {
+    fn default() -> BossBar {
+        return BossBar::new();
+    }
+}
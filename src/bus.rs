@@ -0,0 +1,66 @@
+use crate::{ActorKind, PickupKind, Point, Team};
+
+/// Something that happened during a tick of the simulation, for the parts of the game that react to it
+#[derive(Clone)]
+pub enum GameEvent {
+    /// An actor was hurt by the given amount, by the actor with the given source ID if it came from one
+    ActorDamaged { id: u32, source: Option<u32>, amount: f32 },
+    /// An actor of the given kind died at the given position and was removed from the game
+    ActorDied { id: u32, kind: ActorKind, position: Point },
+    /// An actor of the given kind was removed from the game without dying, to keep under the population caps
+    ActorDespawned { id: u32, kind: ActorKind },
+    /// A shot was fired for the given team
+    ShotFired { id: u32, team: Team },
+    /// Two actors started touching
+    ContactBegan { id: u32, other: u32 },
+    /// Two actors that were touching came apart, or one of them was removed
+    ContactEnded { id: u32, other: u32 },
+    /// Two actors hit each other, or something exploded, at the given position
+    Impact { position: Point },
+    /// Every enemy of the given wave was destroyed
+    WaveCompleted { wave: u32 },
+    /// The player picked up a pickup
+    PickupCollected { kind: PickupKind },
+}
+
+/// Queue that collects the events emitted while a tick is simulated and publishes them once it is over
+pub struct EventBus {
+    pending: Vec<GameEvent>,
+    published: Vec<GameEvent>,
+}
+
+impl EventBus {
+    /// Create a new bus with no events
+    pub fn new() -> EventBus {
+        return EventBus { pending: Vec::new(), published: Vec::new() };
+    }
+
+    /// Add an event to the ones emitted during the current tick
+    pub fn emit(&mut self, event: GameEvent) {
+        self.pending.push(event);
+    }
+
+    /// Publish the events emitted during the current tick, replacing the ones published for the last tick
+    pub fn publish(&mut self) {
+        self.published = std::mem::take(&mut self.pending);
+    }
+
+    /// Publish the events emitted since the events of the current tick were published, adding them to those, and
+    /// return them
+    pub fn publish_late(&mut self) -> Vec<GameEvent> {
+        let late = std::mem::take(&mut self.pending);
+        self.published.extend(late.iter().cloned());
+        return late;
+    }
+
+    /// Get the events published at the end of the last tick, in the order they were emitted
+    pub fn get_published(&self) -> &[GameEvent] {
+        return &self.published;
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> EventBus {
+        return EventBus::new();
+    }
+}
@@ -0,0 +1,82 @@
+use ggez::graphics;
+
+use crate::{Point, Vec2, World};
+
+/// View onto the arena that is drawn to the window. The camera keeps the player in the middle of the view, but
+/// stops at the edges of the arena so nothing past them is shown, and centers arenas smaller than the view. Edges
+/// that wrap are no different: the arena isn't drawn again past them, so a player crossing one is followed from the
+/// far side of the arena, and what is just across the seam only comes into view once the player is over it
+#[derive(Clone, Debug)]
+pub struct Camera {
+    center: Point,
+    view_size: (f32, f32),
+}
+
+impl Camera {
+    /// Create a camera showing a view of the given size, centered on it
+    pub fn new(view_size: (f32, f32)) -> Camera {
+        return Camera { center: Point::new(view_size.0 / 2.0, view_size.1 / 2.0), view_size };
+    }
+
+    /// Get the width and height of the view, which is the size of the window
+    pub fn get_view_size(&self) -> (f32, f32) {
+        return self.view_size;
+    }
+
+    /// Change the size of the view, when the window is resized
+    pub fn set_view_size(&mut self, view_size: (f32, f32)) {
+        self.view_size = view_size;
+    }
+
+    /// Get the point in the arena at the middle of the view
+    pub fn get_center(&self) -> &Point {
+        return &self.center;
+    }
+
+    /// Center the view on the given point in the given arena, as far as the edges of the arena allow, whatever they
+    /// do to the actors that reach them
+    pub fn follow(&mut self, target: &Point, world: &World) {
+        let (width, height) = world.get_size();
+        let (view_width, view_height) = self.view_size;
+        self.center = Point::new(follow_axis(target.x, width, view_width), follow_axis(target.y, height, view_height));
+    }
+
+    /// Get the point in the arena at the top left corner of the view
+    pub fn get_top_left(&self) -> Point {
+        return Point::new(self.center.x - self.view_size.0 / 2.0, self.center.y - self.view_size.1 / 2.0);
+    }
+
+    /// Get where in the window the given point in the arena is drawn
+    pub fn to_screen(&self, point: &Point) -> Vec2 {
+        let top_left = self.get_top_left();
+        return Vec2::new(point.x - top_left.x, point.y - top_left.y);
+    }
+
+    /// Get the point in the arena drawn at the given point in the window
+    pub fn to_world(&self, point: &Vec2) -> Point {
+        let top_left = self.get_top_left();
+        return Point::new(point.x + top_left.x, point.y + top_left.y);
+    }
+
+    /// Check if any of a circle of the given radius around the given point in the arena is in view
+    pub fn is_visible(&self, position: &Point, radius: f32) -> bool {
+        let (view_width, view_height) = self.view_size;
+        return (position.x - self.center.x).abs() - radius < view_width / 2.0
+            && (position.y - self.center.y).abs() - radius < view_height / 2.0;
+    }
+
+    /// Get the transform that draws the arena through this camera, thrown by the given offset in the window
+    pub fn get_transform(&self, offset: &Vec2) -> graphics::DrawParam {
+        let top_left = self.get_top_left();
+        return graphics::DrawParam::default().dest([offset.x - top_left.x, offset.y - top_left.y]);
+    }
+}
+
+/// Get where along one axis of an arena of the given length the middle of a view of the given length goes to follow
+/// the given target, keeping the view inside the arena or centering it on an arena shorter than the view
+fn follow_axis(target: f32, length: f32, view_length: f32) -> f32 {
+    if length <= view_length {
+        return length / 2.0;
+    }
+    return target.clamp(view_length / 2.0, length - view_length / 2.0);
+}
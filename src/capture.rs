@@ -0,0 +1,92 @@
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+use crate::Point;
+
+/// Radius of a capture zone
+pub const ZONE_RADIUS: f32 = 60.0;
+/// Number of seconds the player must stand in a zone to capture it
+const CAPTURE_TIME: f32 = 5.0;
+/// Fraction of capture progress lost per second while the player is outside a zone
+const PROGRESS_DECAY: f32 = 0.05;
+
+/// Color of a zone that has not been captured yet
+const ZONE_COLOR: graphics::Color = graphics::Color::new(1.0, 0.85, 0.3, 1.0);
+/// Color of a captured zone
+const CAPTURED_COLOR: graphics::Color = graphics::Color::new(0.3, 1.0, 0.4, 1.0);
+
+/// Zone the player captures by standing inside it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CaptureZone {
+    position: Point,
+    progress: f32, // 0 to 1
+    captured: bool,
+}
+
+impl CaptureZone {
+    /// Create a new uncaptured zone at the given position
+    pub fn new(position: Point) -> CaptureZone {
+        return CaptureZone { position, progress: 0.0, captured: false };
+    }
+
+    /// Check if this zone has been captured
+    pub fn is_captured(&self) -> bool {
+        return self.captured;
+    }
+
+    /// Update the capture progress given the player's position, returning true if the zone was captured
+    /// during this update
+    pub fn update(&mut self, dt: f32, player_position: &Point) -> bool {
+        if self.captured {
+            return false;
+        }
+
+        // Progress is made while the player's center is inside the zone, and slowly lost otherwise
+        if self.position.distance_to(player_position) < ZONE_RADIUS {
+            self.progress += dt / CAPTURE_TIME;
+        } else {
+            self.progress = (self.progress - dt * PROGRESS_DECAY).max(0.0);
+        }
+
+        if self.progress >= 1.0 {
+            self.progress = 1.0;
+            self.captured = true;
+            return true;
+        }
+        return false;
+    }
+
+    /// Draw this zone with its capture progress
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let color = if self.captured { CAPTURED_COLOR } else { ZONE_COLOR };
+
+        // Draw the edge of the zone
+        let outline = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::stroke(2.0),
+            [0.0, 0.0],
+            ZONE_RADIUS,
+            0.5,
+            color,
+        )?;
+        graphics::draw(ctx, &outline, ([self.position.x, self.position.y],))?;
+
+        // Fill the zone from the center outwards as it is captured
+        if self.progress > 0.0 {
+            let fill = graphics::Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::fill(),
+                [0.0, 0.0],
+                ZONE_RADIUS * self.progress,
+                0.5,
+                graphics::Color::new(color.r, color.g, color.b, 0.25),
+            )?;
+            graphics::draw(ctx, &fill, ([self.position.x, self.position.y],))?;
+        }
+
+        let label = graphics::Text::new(format!("{:.0}%", self.progress * 100.0));
+        graphics::draw(ctx, &label, ([self.position.x - 12.0, self.position.y + ZONE_RADIUS + 4.0], color))?;
+
+        return Ok(());
+    }
+}
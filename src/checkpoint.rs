@@ -0,0 +1,54 @@
+use crate::{Actor, Director, MainState, Player, Telemetry};
+use crate::capture::CaptureZone;
+use crate::formations::DriftingAnchor;
+use crate::spawns::SpawnSchedule;
+use crate::streaming::WorldStreamer;
+
+/// Snapshot of a run that the player restarts from when they die
+pub struct Checkpoint {
+    player: Player,
+    actors: Vec<Box<dyn Actor>>,
+    streamer: WorldStreamer,
+    formations: Vec<DriftingAnchor>,
+    wave: u32,
+    wave_enemies: Vec<u32>,
+    spawns: SpawnSchedule,
+    zones: Vec<CaptureZone>,
+    score: u32,
+    telemetry: Telemetry,
+    director: Director,
+}
+
+impl Checkpoint {
+    /// Take a snapshot of the current state of the game
+    pub fn capture(state: &MainState) -> Checkpoint {
+        return Checkpoint {
+            player: state.player.clone(),
+            actors: state.actors.iter().map(|actor| actor.clone_actor()).collect(),
+            streamer: state.streamer.clone(),
+            formations: state.formations.clone(),
+            wave: state.wave,
+            wave_enemies: state.wave_enemies.clone(),
+            spawns: state.spawns.clone(),
+            zones: state.zones.clone(),
+            score: state.score,
+            telemetry: state.telemetry.clone(),
+            director: state.director.clone(),
+        };
+    }
+
+    /// Put the game back into the state of this snapshot
+    pub fn restore(&self, state: &mut MainState) {
+        state.player = self.player.clone();
+        state.actors = self.actors.iter().map(|actor| actor.clone_actor()).collect();
+        state.streamer = self.streamer.clone();
+        state.formations = self.formations.clone();
+        state.wave = self.wave;
+        state.wave_enemies = self.wave_enemies.clone();
+        state.spawns = self.spawns.clone();
+        state.zones = self.zones.clone();
+        state.score = self.score;
+        state.telemetry = self.telemetry.clone();
+        state.director = self.director.clone();
+    }
+}
@@ -0,0 +1,91 @@
+use crate::{Actor, ActorKind, Damage, Point, StatusEffect, Team, Vec2, Velocity};
+use crate::layers::CollisionLayer;
+
+/// The components of every actor in the game, stored column by column so each system only reads the components
+/// it works on: position, velocity, health, weapon, and team. Row `i` of every column belongs to the actor at index
+/// `i` of the list of actors, as of the last time they were gathered
+pub struct Components {
+    pub ids: Vec<u32>,
+    pub kinds: Vec<ActorKind>,
+    /// Position, the radius around it, and how far the actor moved over the last tick
+    pub positions: Vec<Point>,
+    pub radii: Vec<f32>,
+    pub displacements: Vec<f32>,
+    /// Velocity of actors that move on their own, and mass of actors that collision physics push around
+    pub velocities: Vec<Option<Velocity>>,
+    pub masses: Vec<Option<f32>>,
+    /// Health of actors that can be hurt
+    pub healths: Vec<Option<f32>>,
+    /// Damage and status effect the actor deals to whatever it touches
+    pub weapons: Vec<Damage>,
+    pub effects: Vec<Option<StatusEffect>>,
+    /// Team, and the layer the actor collides on
+    pub teams: Vec<Team>,
+    pub layers: Vec<CollisionLayer>,
+}
+
+impl Components {
+    /// Create an empty table
+    pub fn new() -> Components {
+        return Components {
+            ids: Vec::new(),
+            kinds: Vec::new(),
+            positions: Vec::new(),
+            radii: Vec::new(),
+            displacements: Vec::new(),
+            velocities: Vec::new(),
+            masses: Vec::new(),
+            healths: Vec::new(),
+            weapons: Vec::new(),
+            effects: Vec::new(),
+            teams: Vec::new(),
+            layers: Vec::new(),
+        };
+    }
+
+    /// Gather the components of the given actors, replacing the ones gathered before while reusing their memory
+    pub fn gather(&mut self, actors: &[Box<dyn Actor>]) {
+        self.clear();
+        for actor in actors {
+            self.ids.push(actor.get_id());
+            self.kinds.push(actor.get_kind());
+            self.positions.push(*actor.get_position());
+            self.radii.push(actor.get_radius());
+            self.displacements.push(actor.get_displacement().map(Vec2::length).unwrap_or(0.0));
+            self.velocities.push(actor.get_velocity().cloned());
+            self.masses.push(actor.get_mass());
+            self.healths.push(actor.get_health());
+            self.weapons.push(actor.get_damage());
+            self.effects.push(actor.get_status_effect());
+            self.teams.push(actor.get_team());
+            self.layers.push(actor.get_collision_layer());
+        }
+    }
+
+    /// Remove every row
+    fn clear(&mut self) {
+        self.ids.clear();
+        self.kinds.clear();
+        self.positions.clear();
+        self.radii.clear();
+        self.displacements.clear();
+        self.velocities.clear();
+        self.masses.clear();
+        self.healths.clear();
+        self.weapons.clear();
+        self.effects.clear();
+        self.teams.clear();
+        self.layers.clear();
+    }
+
+    /// Get the number of actors the components were gathered from
+    pub fn len(&self) -> usize {
+        return self.ids.len();
+    }
+}
+
+impl Default for Components {
+    fn default() -> Components {
+        return Components::new();
+    }
+}
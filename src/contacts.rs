@@ -0,0 +1,140 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::ActorKind;
+
+/// Seconds between hits while two actors stay in contact, for pairs that keep hitting each other
+pub const CONTACT_REPEAT_INTERVAL: f32 = 0.5;
+
+/// How two kinds of actor respond to touching each other
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ContactResponse {
+    /// Hit each other once when they start touching, and not again until they have come apart
+    Once,
+    /// Hit each other when they start touching, then again every given number of seconds while they stay in contact
+    Repeat(f32),
+    /// Touch without hitting each other
+    Ignore,
+}
+
+/// An actor in a contact, by its ID and kind
+pub type Contactant = (u32, ActorKind);
+
+/// The contacts that began, the contacts that ended, and the pairs that hit each other during a tick, by the IDs
+/// of the actors in them
+pub struct ContactChanges {
+    pub began: Vec<(u32, u32)>,
+    pub ended: Vec<(u32, u32)>,
+    pub hits: Vec<(u32, u32)>,
+}
+
+/// Pair of actors in contact as it is saved, by their IDs, with the seconds until their next hit if they will hit
+/// again
+#[derive(Serialize, Deserialize)]
+pub struct SavedContact {
+    first: u32,
+    second: u32,
+    next_hit: Option<f32>,
+}
+
+/// Keeps track of which actors are touching from tick to tick, so touching actors hit each other as their response
+/// allows instead of every tick they overlap
+pub struct ContactTracker {
+    /// Seconds until the next hit of every pair in contact, or none if the pair won't hit again
+    contacts: HashMap<(u32, u32), Option<f32>>,
+    /// The contacts of the tick being worked out, kept between ticks so its memory is reused
+    current: HashMap<(u32, u32), Option<f32>>,
+    responses: HashMap<(ActorKind, ActorKind), ContactResponse>,
+}
+
+impl ContactTracker {
+    /// Create a new tracker with no contacts, where every pair keeps hitting at the repeat interval
+    pub fn new() -> ContactTracker {
+        return ContactTracker { contacts: HashMap::new(), current: HashMap::new(), responses: HashMap::new() };
+    }
+
+    /// Set how the given kinds of actor respond to touching each other, in either order
+    pub fn set_response(&mut self, first: ActorKind, second: ActorKind, response: ContactResponse) {
+        self.responses.insert((first, second), response);
+        self.responses.insert((second, first), response);
+    }
+
+    /// Get how the given kinds of actor respond to touching each other
+    pub fn get_response(&self, first: ActorKind, second: ActorKind) -> ContactResponse {
+        let default = ContactResponse::Repeat(CONTACT_REPEAT_INTERVAL);
+        return self.responses.get(&(first, second)).copied().unwrap_or(default);
+    }
+
+    /// Forget every contact, keeping the responses
+    pub fn clear(&mut self) {
+        self.contacts.clear();
+    }
+
+    /// Get the contacts to save, in order of their IDs
+    pub fn get_saved(&self) -> Vec<SavedContact> {
+        let mut saved: Vec<SavedContact> = self.contacts.iter()
+            .map(|(&(first, second), &next_hit)| SavedContact { first, second, next_hit })
+            .collect();
+        saved.sort_by_key(|contact| (contact.first, contact.second));
+        return saved;
+    }
+
+    /// Replace the contacts with the given saved ones, keeping the responses
+    pub fn restore(&mut self, saved: Vec<SavedContact>) {
+        self.contacts = saved.into_iter().map(|contact| ((contact.first, contact.second), contact.next_hit)).collect();
+    }
+
+    /// Advance the contacts by the given time, given every pair of actors touching now. Hits are listed in the
+    /// order the pairs were given, each pair is listed with the actor it was given first, and the contacts that
+    /// ended are listed in order of their IDs
+    pub fn update(&mut self, dt: f32, touching: &[(Contactant, Contactant)]) -> ContactChanges {
+        let mut changes = ContactChanges { began: Vec::new(), ended: Vec::new(), hits: Vec::new() };
+        self.current.clear();
+        for &((first, first_kind), (second, second_kind)) in touching {
+            let key = (first.min(second), first.max(second));
+            let response = self.get_response(first_kind, second_kind);
+            let (hit, next_hit) = match self.contacts.get(&key) {
+                // A pair that has just started touching hits at once, unless it ignores the contact
+                None => {
+                    changes.began.push((first, second));
+                    match response {
+                        ContactResponse::Once => (true, None),
+                        ContactResponse::Repeat(interval) => (true, Some(interval)),
+                        ContactResponse::Ignore => (false, None),
+                    }
+                }
+                // A pair still touching hits again once its interval is up
+                Some(Some(remaining)) => {
+                    let remaining = remaining - dt;
+                    match response {
+                        ContactResponse::Repeat(interval) if remaining <= 0.0 => (true, Some(remaining + interval)),
+                        _ => (false, Some(remaining)),
+                    }
+                }
+                Some(None) => (false, None),
+            };
+            if hit {
+                changes.hits.push((first, second));
+            }
+            self.current.insert(key, next_hit);
+        }
+
+        // Every pair that was touching but isn't any more has come apart, listed in a fixed order so a run plays out
+        // the same every time it is replayed
+        for &key in self.contacts.keys() {
+            if !self.current.contains_key(&key) {
+                changes.ended.push(key);
+            }
+        }
+        changes.ended.sort_unstable();
+        std::mem::swap(&mut self.contacts, &mut self.current);
+        return changes;
+    }
+}
+
+impl Default for ContactTracker {
+    fn default() -> ContactTracker {
+        return ContactTracker::new();
+    }
+}
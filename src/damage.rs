@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+/// The different types of damage
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DamageType {
+    /// Physical impacts such as bullets and ramming
+    Kinetic,
+    /// Energy weapons and burning
+    Energy,
+    /// Explosions
+    Explosive,
+}
+
+impl DamageType {
+    /// Get the speed, in pixels per second, a hit of this type knocks the player back by for every point of damage
+    pub fn get_knockback_per_damage(&self) -> f32 {
+        return match self {
+            DamageType::Kinetic => 3.0,
+            DamageType::Energy => 4.0,
+            DamageType::Explosive => 5.0,
+        };
+    }
+}
+
+/// An amount of damage of a specific type
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Damage {
+    amount: f32,
+    kind: DamageType,
+}
+
+impl Damage {
+    /// Create a new amount of damage of the given type
+    pub fn new(amount: f32, kind: DamageType) -> Damage {
+        return Damage { amount, kind };
+    }
+
+    /// Create a new amount of kinetic damage
+    pub fn kinetic(amount: f32) -> Damage {
+        return Damage::new(amount, DamageType::Kinetic);
+    }
+
+    /// Create a new amount of energy damage
+    pub fn energy(amount: f32) -> Damage {
+        return Damage::new(amount, DamageType::Energy);
+    }
+
+    /// Create a new amount of explosive damage
+    pub fn explosive(amount: f32) -> Damage {
+        return Damage::new(amount, DamageType::Explosive);
+    }
+
+    /// Get the raw amount of this damage before any resistances
+    pub fn get_amount(&self) -> f32 {
+        return self.amount;
+    }
+
+    /// Get the type of this damage
+    pub fn get_kind(&self) -> DamageType {
+        return self.kind;
+    }
+
+    /// Get the speed, in pixels per second, this damage knocks the player back by
+    pub fn get_knockback(&self) -> f32 {
+        return self.amount * self.kind.get_knockback_per_damage();
+    }
+}
+
+/// Resistances of an Actor against each damage type. Each value is the fraction of incoming damage that
+/// is blocked, so 0.5 halves the damage and negative values make the Actor take extra damage
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct Resistances {
+    kinetic: f32,
+    energy: f32,
+    explosive: f32,
+}
+
+impl Resistances {
+    /// Create a new set of resistances
+    pub fn new(kinetic: f32, energy: f32, explosive: f32) -> Resistances {
+        return Resistances { kinetic, energy, explosive };
+    }
+
+    /// Create a set of resistances that does not block any damage
+    pub fn none() -> Resistances {
+        return Resistances::new(0.0, 0.0, 0.0);
+    }
+
+    /// Get the amount of the given damage that gets through these resistances
+    pub fn apply(&self, damage: Damage) -> f32 {
+        let resistance = match damage.kind {
+            DamageType::Kinetic => self.kinetic,
+            DamageType::Energy => self.energy,
+            DamageType::Explosive => self.explosive,
+        };
+
+        // Resistances can never heal the Actor
+        return (damage.amount * (1.0 - resistance)).max(0.0);
+    }
+}
+
+impl Default for Resistances {
+    fn default() -> Resistances {
+        return Resistances::none();
+    }
+}
@@ -0,0 +1,54 @@
+use ggez::{Context, GameResult, graphics};
+
+use crate::Actor;
+
+/// Color of the hitboxes
+const HITBOX_COLOR: graphics::Color = graphics::Color::new(0.0, 1.0, 0.0, 0.8);
+/// Color of the velocity vectors
+const VELOCITY_COLOR: graphics::Color = graphics::Color::new(0.2, 0.6, 1.0, 0.9);
+/// Color of the aim directions of turrets
+const AIM_COLOR: graphics::Color = graphics::Color::new(1.0, 0.3, 0.3, 0.9);
+/// Seconds of travel a velocity vector is drawn for, so faster actors get longer vectors
+const VELOCITY_SECONDS: f32 = 0.5;
+/// Length of the aim direction lines, beyond the edge of the actor
+const AIM_LENGTH: f32 = 30.0;
+
+/// Draw the hitbox, velocity vector, aim direction, and ID of the given actor on top of it
+pub fn draw_actor(ctx: &mut Context, actor: &dyn Actor) -> GameResult {
+    let position = actor.get_position();
+    let center = [position.x, position.y];
+
+    // Outline exactly the shape collisions are checked against
+    let hitbox = match actor.get_hitbox().get_corners(position) {
+        Some(corners) => {
+            let points: Vec<[f32; 2]> = corners.iter().map(|corner| [corner.x, corner.y]).collect();
+            graphics::Mesh::new_polygon(ctx, graphics::DrawMode::stroke(1.0), &points, HITBOX_COLOR)?
+        }
+        None => graphics::Mesh::new_circle(ctx, graphics::DrawMode::stroke(1.0), center, actor.get_radius(), 0.5,
+                                           HITBOX_COLOR)?,
+    };
+    graphics::draw(ctx, &hitbox, graphics::DrawParam::default())?;
+
+    // Point out where the actor will be in a moment, if it is moving
+    if let Some(velocity) = actor.get_velocity().filter(|velocity| velocity.speed.abs() > 0.0) {
+        let (velocity_x, velocity_y) = velocity.get_components();
+        let end = [position.x + velocity_x * VELOCITY_SECONDS, position.y + velocity_y * VELOCITY_SECONDS];
+        let line = graphics::Mesh::new_line(ctx, &[center, end], 1.0, VELOCITY_COLOR)?;
+        graphics::draw(ctx, &line, graphics::DrawParam::default())?;
+    }
+
+    // Point out where the actor is aiming, if it aims
+    if let Some(heading) = actor.get_aim() {
+        let length = actor.get_radius() + AIM_LENGTH;
+        let end = [position.x + length * heading.cos(), position.y + length * heading.sin()];
+        let line = graphics::Mesh::new_line(ctx, &[center, end], 1.0, AIM_COLOR)?;
+        graphics::draw(ctx, &line, graphics::DrawParam::default())?;
+    }
+
+    // Label the actor with its ID just past its top right edge
+    let label = graphics::Text::new(actor.get_id().to_string());
+    let label_position = [position.x + actor.get_radius(), position.y - actor.get_radius() - 12.0];
+    graphics::draw(ctx, &label, (label_position, HITBOX_COLOR))?;
+
+    return Ok(());
+}
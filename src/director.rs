@@ -0,0 +1,85 @@
+use serde::{Deserialize, Serialize};
+
+use crate::telemetry::Telemetry;
+
+/// Lowest and highest intensity the director can set
+const MIN_INTENSITY: f32 = 0.5;
+const MAX_INTENSITY: f32 = 2.0;
+/// How fast the intensity moves towards its target, per second
+const INTENSITY_RATE: f32 = 0.1;
+/// Player stress the director tries to keep the player at
+const TARGET_STRESS: f32 = 0.4;
+/// Kills per minute at which the player is considered to be tearing through the wave
+const FAST_KILL_RATE: f32 = 10.0;
+/// Reinforcement turrets the director builds up per second at an intensity of 1
+const REINFORCEMENT_RATE: f32 = 1.0 / 15.0;
+/// Player stress above which no reinforcements are sent
+const REINFORCEMENT_STRESS_LIMIT: f32 = 0.5;
+
+/// Pacing director that watches how the player is doing and throttles or intensifies the spawn pressure
+/// to keep the run challenging without being overwhelming
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Director {
+    intensity: f32,
+    reinforcement_budget: f32,
+    reinforcements_left: u32,
+}
+
+impl Director {
+    /// Create a new director at normal intensity
+    pub fn new() -> Director {
+        return Director {
+            intensity: 1.0,
+            reinforcement_budget: 0.0,
+            reinforcements_left: 0,
+        };
+    }
+
+    /// Get the current intensity, where 1 is normal pressure
+    pub fn get_intensity(&self) -> f32 {
+        return self.intensity;
+    }
+
+    /// Get the number of reinforcement turrets the director may still send this wave
+    pub fn get_reinforcements_left(&self) -> u32 {
+        return self.reinforcements_left;
+    }
+
+    /// Start a new wave, allowing up to the given number of reinforcements, and get the number of turrets the
+    /// wave should have instead of the given base number
+    pub fn start_wave(&mut self, base_turrets: usize, max_reinforcements: u32) -> usize {
+        self.reinforcement_budget = 0.0;
+        self.reinforcements_left = max_reinforcements;
+        return ((base_turrets as f32 * self.intensity).round() as usize).max(1);
+    }
+
+    /// Adjust the intensity based on the latest measurements
+    pub fn update(&mut self, dt: f32, telemetry: &Telemetry) {
+        // Push harder when the player is relaxed or killing quickly, and ease off when they are struggling
+        let stress = telemetry.get_player_stress();
+        let mut change = TARGET_STRESS - stress;
+        if telemetry.get_kill_rate() > FAST_KILL_RATE {
+            change += 0.2;
+        }
+        self.intensity = (self.intensity + change * INTENSITY_RATE * dt).clamp(MIN_INTENSITY, MAX_INTENSITY);
+
+        // Build up reinforcements, but hold them back while the player is under pressure
+        if stress < REINFORCEMENT_STRESS_LIMIT && self.reinforcements_left > 0 {
+            self.reinforcement_budget += self.intensity * REINFORCEMENT_RATE * dt;
+        }
+    }
+
+    /// Take the number of reinforcement turrets that should be spawned now
+    pub fn take_reinforcements(&mut self) -> u32 {
+        let count = (self.reinforcement_budget.floor() as u32).min(self.reinforcements_left);
+        self.reinforcement_budget -= count as f32;
+        self.reinforcements_left -= count;
+        return count;
+    }
+}
+
+impl Default for Director {
+    fn default() -> Director {
+        return Director::new();
+    }
+}
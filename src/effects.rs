@@ -0,0 +1,118 @@
+use ggez::graphics::Color;
+use serde::{Deserialize, Serialize};
+
+/// Tint used to draw an Actor that is burning
+const BURN_TINT: Color = Color::new(1.0, 0.55, 0.2, 1.0);
+/// Tint used to draw an Actor that is slowed
+const SLOW_TINT: Color = Color::new(0.45, 0.7, 1.0, 1.0);
+/// Tint used to draw an Actor that is overdriven
+const OVERDRIVE_TINT: Color = Color::new(1.0, 0.3, 0.3, 1.0);
+
+/// The different kinds of status effect
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum StatusEffectKind {
+    /// Deals damage over time, strength is damage per second
+    Burn,
+    /// Reduces movement and turn speed, strength is the fraction of speed that is removed
+    Slow,
+    /// Increases fire rate, strength is the multiplier applied to the fire rate
+    Overdrive,
+}
+
+/// A status effect that can be applied to an Actor for a limited time. The same value describes the effect a shot
+/// or a turret hands out and counts down once it is active, and re-applying an effect compares the time left, so
+/// the duration stays a plain count of seconds rather than a `Timer`
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StatusEffect {
+    kind: StatusEffectKind,
+    strength: f32,
+    duration: f32, // Seconds left
+}
+
+impl StatusEffect {
+    /// Create a burn effect that deals the given damage per second for the given duration
+    pub fn burn(damage_per_second: f32, duration: f32) -> StatusEffect {
+        return StatusEffect { kind: StatusEffectKind::Burn, strength: damage_per_second, duration };
+    }
+
+    /// Create a slow effect that removes the given fraction (0 to 1) of speed for the given duration
+    pub fn slow(amount: f32, duration: f32) -> StatusEffect {
+        return StatusEffect { kind: StatusEffectKind::Slow, strength: amount.clamp(0.0, 1.0), duration };
+    }
+
+    /// Create an overdrive effect that multiplies the fire rate by the given amount for the given duration
+    pub fn overdrive(fire_rate_multiplier: f32, duration: f32) -> StatusEffect {
+        return StatusEffect { kind: StatusEffectKind::Overdrive, strength: fire_rate_multiplier, duration };
+    }
+}
+
+/// The status effects currently active on an Actor
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct StatusEffects {
+    active: Vec<StatusEffect>,
+}
+
+impl StatusEffects {
+    /// Create an empty set of status effects
+    pub fn new() -> StatusEffects {
+        return StatusEffects { active: Vec::new() };
+    }
+
+    /// Apply a new effect. Re-applying an effect of the same kind refreshes it, keeping the
+    /// stronger strength and the longer duration
+    pub fn apply(&mut self, effect: StatusEffect) {
+        match self.active.iter_mut().find(|active| active.kind == effect.kind) {
+            Some(active) => {
+                active.strength = active.strength.max(effect.strength);
+                active.duration = active.duration.max(effect.duration);
+            }
+            None => self.active.push(effect),
+        }
+    }
+
+    /// Advance the effects by the given time and return the damage done by them during it
+    pub fn update(&mut self, dt: f32) -> f32 {
+        let mut damage = 0.0;
+
+        for effect in &mut self.active {
+            // Only count the part of the time step during which the effect was still active
+            let active_time = dt.min(effect.duration);
+            if effect.kind == StatusEffectKind::Burn {
+                damage += effect.strength * active_time;
+            }
+            effect.duration -= dt;
+        }
+
+        // Drop the effects that have run out
+        self.active.retain(|effect| effect.duration > 0.0);
+
+        return damage;
+    }
+
+    /// Get the multiplier to apply to movement and turn speed
+    pub fn get_speed_multiplier(&self) -> f32 {
+        return self.active.iter()
+            .filter(|effect| effect.kind == StatusEffectKind::Slow)
+            .fold(1.0, |multiplier, effect| multiplier * (1.0 - effect.strength));
+    }
+
+    /// Get the multiplier to apply to fire rate
+    pub fn get_fire_rate_multiplier(&self) -> f32 {
+        return self.active.iter()
+            .filter(|effect| effect.kind == StatusEffectKind::Overdrive)
+            .fold(1.0, |multiplier, effect| multiplier * effect.strength);
+    }
+
+    /// Get the color to draw the affected Actor with, the tint of the kind of effect that was added last, or the
+    /// given base color if there are no effects. Refreshing an effect that is already active doesn't move it ahead
+    pub fn get_tint(&self, base: Color) -> Color {
+        return match self.active.last() {
+            Some(effect) => match effect.kind {
+                StatusEffectKind::Burn => BURN_TINT,
+                StatusEffectKind::Slow => SLOW_TINT,
+                StatusEffectKind::Overdrive => OVERDRIVE_TINT,
+            },
+            None => base,
+        };
+    }
+}
@@ -0,0 +1,110 @@
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::mods::ModList;
+use crate::regions::SpawnRegion;
+
+/// A kind of turret defined by a mod, which joins the waves alongside the game's own enemies
+#[derive(Clone, Debug, Deserialize)]
+#[serde(default)]
+pub struct EnemyDefinition {
+    /// Name of the definition, taken from its file
+    #[serde(skip)]
+    name: String,
+    /// First wave the turrets join
+    from_wave: u32,
+    /// Number of turrets added to every wave they join
+    count: u32,
+    /// Multiplier of the radius of a normal turret
+    size: f32,
+    /// Multiplier of the health of a normal turret
+    toughness: f32,
+    /// Whether the turrets are armored against kinetic damage
+    armored: bool,
+    /// Name of the script that aims and fires the turrets instead of their own timer
+    script: Option<String>,
+    /// Region the turrets spawn in, instead of anywhere in the top half of the arena
+    region: Option<SpawnRegion>,
+}
+
+impl EnemyDefinition {
+    /// Create a definition of a normal turret joining every wave, with the given name
+    pub fn new(name: &str) -> EnemyDefinition {
+        return EnemyDefinition {
+            name: name.to_string(),
+            from_wave: 1,
+            count: 1,
+            size: 1.0,
+            toughness: 1.0,
+            armored: false,
+            script: None,
+            region: None,
+        };
+    }
+
+    /// Load the definition in the given file, named after the file
+    pub fn load(path: &Path) -> Result<EnemyDefinition, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let mut definition: EnemyDefinition = toml::from_str(&text)
+            .map_err(|error| format!("{}: invalid enemy: {}", path.display(), error))?;
+        definition.name = path.file_stem().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        return Ok(definition);
+    }
+
+    /// Load the enemy definitions of the active mods in load order. A definition replaces any definition of the
+    /// same name loaded before it. Also returns a description of every definition that couldn't be read
+    pub fn load_all(mods: &ModList) -> (Vec<EnemyDefinition>, Vec<String>) {
+        let mut definitions: Vec<EnemyDefinition> = Vec::new();
+        let mut issues = Vec::new();
+        for entry in mods.get_active_mods() {
+            for file in entry.get_manifest().get_actors() {
+                match EnemyDefinition::load(&entry.get_directory().join(file)) {
+                    Ok(definition) => {
+                        definitions.retain(|other| other.name != definition.name);
+                        definitions.push(definition);
+                    }
+                    Err(issue) => issues.push(issue),
+                }
+            }
+        }
+        return (definitions, issues);
+    }
+
+    /// Get the name of this definition
+    pub fn get_name(&self) -> &str {
+        return &self.name;
+    }
+
+    /// Get the number of turrets of this kind that join the given wave
+    pub fn get_count(&self, wave: u32) -> u32 {
+        return if wave >= self.from_wave { self.count } else { 0 };
+    }
+
+    /// Get the multipliers of the radius and health of a normal turret
+    pub fn get_scale(&self) -> (f32, f32) {
+        return (self.size, self.toughness);
+    }
+
+    /// Check if the turrets are armored against kinetic damage
+    pub fn is_armored(&self) -> bool {
+        return self.armored;
+    }
+
+    /// Get the name of the script that aims and fires the turrets, if they are scripted
+    pub fn get_script(&self) -> Option<&str> {
+        return self.script.as_deref();
+    }
+
+    /// Get the region the turrets spawn in, if they have one of their own
+    pub fn get_region(&self) -> Option<&SpawnRegion> {
+        return self.region.as_ref();
+    }
+}
+
+impl Default for EnemyDefinition {
+    fn default() -> EnemyDefinition {
+        return EnemyDefinition::new("");
+    }
+}
@@ -0,0 +1,139 @@
+use ggez::{Context, GameResult, graphics};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+use crate::timers::Timer;
+
+/// Shortest and longest number of seconds between the end of one event and the start of the next
+const MIN_EVENT_INTERVAL: f32 = 30.0;
+const MAX_EVENT_INTERVAL: f32 = 60.0;
+/// Number of seconds an event is announced on screen after it starts
+const ANNOUNCEMENT_TIME: f32 = 3.0;
+
+/// Color the announcements are drawn in
+const ANNOUNCEMENT_COLOR: graphics::Color = graphics::Color::new(1.0, 0.85, 0.3, 1.0);
+
+/// Random events that affect the whole arena for a while
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GlobalEvent {
+    /// Meteors rain down from the top of the arena, hurting anything they hit
+    MeteorShower,
+    /// Every turret fires twice as fast
+    Overdrive,
+    /// Extra pickups fall into the arena
+    PickupRain,
+}
+
+impl GlobalEvent {
+    /// All the global events
+    pub const ALL: [GlobalEvent; 3] = [GlobalEvent::MeteorShower, GlobalEvent::Overdrive, GlobalEvent::PickupRain];
+
+    /// Get the name of this event
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            GlobalEvent::MeteorShower => "Meteor shower",
+            GlobalEvent::Overdrive => "Turret overdrive",
+            GlobalEvent::PickupRain => "Pickup rain",
+        };
+    }
+
+    /// Get the message shown when this event starts
+    pub fn get_announcement(&self) -> &'static str {
+        return match self {
+            GlobalEvent::MeteorShower => "Meteor shower incoming!",
+            GlobalEvent::Overdrive => "Turret overdrive! Turrets fire twice as fast",
+            GlobalEvent::PickupRain => "Pickup rain! Grab them while you can",
+        };
+    }
+
+    /// Get the number of seconds this event lasts
+    pub fn get_duration(&self) -> f32 {
+        return match self {
+            GlobalEvent::MeteorShower => 10.0,
+            GlobalEvent::Overdrive => 8.0,
+            GlobalEvent::PickupRain => 1.0,
+        };
+    }
+}
+
+/// Global event that is going on, and the time it has left
+#[derive(Clone, Serialize, Deserialize)]
+struct ActiveEvent {
+    event: GlobalEvent,
+    timer: Timer,
+}
+
+/// Schedules the global events of a run, starting a random one at random intervals
+#[derive(Clone, Serialize, Deserialize)]
+pub struct GlobalEvents {
+    next_event: Timer,
+    active: Option<ActiveEvent>,
+    announcement: Timer,
+}
+
+impl GlobalEvents {
+    /// Create a new schedule with no event active
+    pub fn new() -> GlobalEvents {
+        return GlobalEvents {
+            next_event: Timer::new(MAX_EVENT_INTERVAL),
+            active: None,
+            announcement: Timer::finished(),
+        };
+    }
+
+    /// Get the event that is currently active, if any
+    pub fn get_active(&self) -> Option<GlobalEvent> {
+        return self.active.as_ref().map(|active| active.event);
+    }
+
+    /// Advance the schedule by the given time, returning the event that started during it, if any
+    pub fn update<R: Rng>(&mut self, dt: f32, rng: &mut R) -> Option<GlobalEvent> {
+        self.announcement.update(dt);
+
+        // Count down the active event, and wait a random time for the next one once it ends
+        if let Some(active) = &mut self.active {
+            if active.timer.update(dt) {
+                self.active = None;
+                self.next_event.restart_with(rng.gen_range(MIN_EVENT_INTERVAL, MAX_EVENT_INTERVAL));
+            }
+            return None;
+        }
+
+        if !self.next_event.update(dt) {
+            return None;
+        }
+
+        let event = *GlobalEvent::ALL.choose(rng).unwrap();
+        self.active = Some(ActiveEvent { event, timer: Timer::new(event.get_duration()) });
+        self.announcement.restart_with(ANNOUNCEMENT_TIME);
+        return Some(event);
+    }
+
+    /// Draw the announcement of the event that just started, and how long the active event has left
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let ActiveEvent { event, timer } = match &self.active {
+            Some(active) => active,
+            None => return Ok(()),
+        };
+        let (width, height) = graphics::drawable_size(ctx);
+
+        if !self.announcement.is_finished() {
+            let announcement = graphics::Text::new(event.get_announcement());
+            let text_width = announcement.width(ctx) as f32;
+            graphics::draw(ctx, &announcement, ([(width - text_width) / 2.0, height / 3.0], ANNOUNCEMENT_COLOR))?;
+        }
+
+        let status = graphics::Text::new(format!("{}: {:.0}s", event.get_name(), timer.get_remaining()));
+        graphics::draw(ctx, &status, ([10.0, height - 30.0], ANNOUNCEMENT_COLOR))?;
+
+        return Ok(());
+    }
+}
+
+impl Default for GlobalEvents {
+    fn default() -> GlobalEvents {
+        return GlobalEvents::new();
+    }
+}
@@ -0,0 +1,65 @@
+use crate::{Point, Velocity};
+
+/// Distance within which the members of a flock steer with each other
+pub const FLOCK_RADIUS: f32 = 80.0;
+/// Distance members of a flock try to keep between each other
+const SEPARATION_DISTANCE: f32 = 25.0;
+/// Distance from the target at which the members of a flock stop flocking and dive straight at it
+const DIVE_DISTANCE: f32 = 150.0;
+
+/// Weights of the steering rules
+const COHESION_WEIGHT: f32 = 0.6;
+const ALIGNMENT_WEIGHT: f32 = 0.8;
+const SEPARATION_WEIGHT: f32 = 1.5;
+const TARGET_WEIGHT: f32 = 1.0;
+
+/// Get the heading a member of a flock at the given position and with the given velocity should steer
+/// towards, given the positions and velocities of its neighbours and the target the flock is chasing
+pub fn get_heading(position: &Point, velocity: &Velocity, neighbours: &[(Point, Velocity)],
+                   target: Option<&Point>) -> f32 {
+    // Dive straight at the target once it is close enough
+    if let Some(target) = target {
+        if position.distance_to(target) < DIVE_DISTANCE || neighbours.is_empty() {
+            return position.heading_to(target);
+        }
+    }
+    if neighbours.is_empty() {
+        return velocity.heading;
+    }
+
+    let count = neighbours.len() as f32;
+    let (mut x, mut y) = (0.0, 0.0);
+
+    // Steer towards the center of the neighbours
+    let center_x = neighbours.iter().map(|(neighbour, _)| neighbour.x).sum::<f32>() / count;
+    let center_y = neighbours.iter().map(|(neighbour, _)| neighbour.y).sum::<f32>() / count;
+    let cohesion = position.heading_to(&Point::new(center_x, center_y));
+    x += cohesion.cos() * COHESION_WEIGHT;
+    y += cohesion.sin() * COHESION_WEIGHT;
+
+    // Steer to fly the same way as the neighbours
+    for (_, neighbour_velocity) in neighbours {
+        x += neighbour_velocity.heading.cos() * ALIGNMENT_WEIGHT / count;
+        y += neighbour_velocity.heading.sin() * ALIGNMENT_WEIGHT / count;
+    }
+
+    // Steer away from neighbours that are too close, more strongly the closer they are
+    for (neighbour, _) in neighbours {
+        let distance = position.distance_to(neighbour);
+        if distance < SEPARATION_DISTANCE && distance > 0.0 {
+            let away = neighbour.heading_to(position);
+            let strength = SEPARATION_WEIGHT * (1.0 - distance / SEPARATION_DISTANCE);
+            x += away.cos() * strength;
+            y += away.sin() * strength;
+        }
+    }
+
+    // Steer towards the target
+    if let Some(target) = target {
+        let towards = position.heading_to(target);
+        x += towards.cos() * TARGET_WEIGHT;
+        y += towards.sin() * TARGET_WEIGHT;
+    }
+
+    return y.atan2(x);
+}
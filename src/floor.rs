@@ -0,0 +1,67 @@
+use ggez::{Context, GameResult, graphics};
+
+use crate::Point;
+
+/// Speed multiplier of a boost strip
+const BOOST_MULTIPLIER: f32 = 1.75;
+/// Speed multiplier of a slow field
+const SLOW_MULTIPLIER: f32 = 0.4;
+
+/// Color of a boost strip
+const BOOST_COLOR: graphics::Color = graphics::Color::new(0.3, 0.9, 1.0, 0.2);
+/// Color of a slow field
+const SLOW_COLOR: graphics::Color = graphics::Color::new(0.6, 0.5, 0.2, 0.3);
+
+/// The kinds of floor zone
+#[derive(Clone, Copy, PartialEq)]
+pub enum FloorKind {
+    /// Speeds up whatever moves across it
+    Boost,
+    /// Sticky floor that slows down whatever moves across it
+    Slow,
+}
+
+impl FloorKind {
+    /// Get the multiplier this kind of floor applies to movement speed
+    pub fn get_speed_multiplier(&self) -> f32 {
+        return match self {
+            FloorKind::Boost => BOOST_MULTIPLIER,
+            FloorKind::Slow => SLOW_MULTIPLIER,
+        };
+    }
+}
+
+/// Rectangular area of the floor that changes the movement speed of the actors inside it
+#[derive(Clone)]
+pub struct FloorZone {
+    area: graphics::Rect,
+    kind: FloorKind,
+}
+
+impl FloorZone {
+    /// Create a new zone of the given kind covering the given area
+    pub fn new(kind: FloorKind, x: f32, y: f32, width: f32, height: f32) -> FloorZone {
+        return FloorZone { area: graphics::Rect::new(x, y, width, height), kind };
+    }
+
+    /// Get the combined speed multiplier of the given zones at the given position, where overlapping zones
+    /// stack
+    pub fn get_speed_multiplier(zones: &[FloorZone], position: &Point) -> f32 {
+        return zones.iter()
+            .filter(|zone| zone.area.contains([position.x, position.y]))
+            .map(|zone| zone.kind.get_speed_multiplier())
+            .product();
+    }
+
+    /// Draw this zone
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let color = match self.kind {
+            FloorKind::Boost => BOOST_COLOR,
+            FloorKind::Slow => SLOW_COLOR,
+        };
+        let rectangle = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), self.area, color)?;
+        graphics::draw(ctx, &rectangle, ([0.0, 0.0],))?;
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,119 @@
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Serialize};
+
+/// The shapes a group of turrets can be arranged in
+#[derive(Clone, Copy, PartialEq)]
+pub enum FormationShape {
+    /// A horizontal line centered on the anchor
+    Line,
+    /// A circle around the anchor
+    Ring,
+    /// A V shape with the leader on the anchor
+    Wedge,
+}
+
+impl FormationShape {
+    /// Get the offset from the anchor of every member of a formation with the given size and spacing
+    pub fn get_offsets(&self, count: usize, spacing: f32) -> Vec<(f32, f32)> {
+        return (0..count).map(|i| {
+            match self {
+                FormationShape::Line => {
+                    // Spread the members out evenly either side of the anchor
+                    let x = (i as f32 - (count as f32 - 1.0) / 2.0) * spacing;
+                    (x, 0.0)
+                }
+                FormationShape::Ring => {
+                    // Use the spacing as the radius of the ring
+                    let angle = i as f32 * 2.0 * PI / count as f32;
+                    (angle.cos() * spacing, angle.sin() * spacing)
+                }
+                FormationShape::Wedge => {
+                    // Put the leader on the anchor and the others in rows behind it, alternating sides
+                    let row = (i / 2 + i % 2) as f32;
+                    let side = if i % 2 == 1 { -1.0 } else { 1.0 };
+                    (side * row * spacing, -row * spacing)
+                }
+            }
+        }).collect();
+    }
+}
+
+/// Place of a turret in a formation: the ID of the formation and the turret's offset from the formation's anchor.
+/// Members look their anchor up by the ID every tick, so moving the anchor moves the whole formation
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct FormationSlot {
+    formation: u32,
+    offset: (f32, f32),
+}
+
+impl FormationSlot {
+    /// Create a new place in the formation with the given ID, at the given offset from its anchor
+    pub fn new(formation: u32, offset: (f32, f32)) -> FormationSlot {
+        return FormationSlot { formation, offset };
+    }
+
+    /// Get the ID of the formation
+    pub fn get_formation(&self) -> u32 {
+        return self.formation;
+    }
+
+    /// Get the position of this place in the formation when its anchor is at the given position
+    pub fn get_position(&self, anchor: (f32, f32)) -> (f32, f32) {
+        return (anchor.0 + self.offset.0, anchor.1 + self.offset.1);
+    }
+}
+
+/// Anchor of a formation that drifts across the arena, bouncing off the edges. The anchor owns the position of the
+/// formation, which its members follow
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DriftingAnchor {
+    id: u32,
+    position: (f32, f32),
+    velocity: (f32, f32), // Pixels per second
+    margin: f32,
+}
+
+impl DriftingAnchor {
+    /// Create a new drifting anchor for the formation with the given ID at the given position, that keeps the given
+    /// distance from the edges of the arena
+    pub fn new(id: u32, position: (f32, f32), velocity: (f32, f32), margin: f32) -> DriftingAnchor {
+        return DriftingAnchor { id, position, velocity, margin };
+    }
+
+    /// Get the ID of the formation this anchor moves
+    pub fn get_id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Get the position of the anchor
+    pub fn get_position(&self) -> (f32, f32) {
+        return self.position;
+    }
+
+    /// Move the anchor for the given time, reversing direction when it reaches an edge
+    pub fn update(&mut self, dt: f32, bounds: (f32, f32)) {
+        let (max_x, max_y) = bounds;
+        let (mut x, mut y) = self.position;
+        x += self.velocity.0 * dt;
+        y += self.velocity.1 * dt;
+
+        if x < self.margin {
+            x = self.margin;
+            self.velocity.0 = self.velocity.0.abs();
+        } else if x > max_x - self.margin {
+            x = max_x - self.margin;
+            self.velocity.0 = -self.velocity.0.abs();
+        }
+
+        if y < self.margin {
+            y = self.margin;
+            self.velocity.1 = self.velocity.1.abs();
+        } else if y > max_y - self.margin {
+            y = max_y - self.margin;
+            self.velocity.1 = -self.velocity.1.abs();
+        }
+
+        self.position = (x, y);
+    }
+}
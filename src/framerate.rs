@@ -0,0 +1,44 @@
+use std::time::{Duration, Instant};
+
+/// Holds the frames drawn to a cap by sleeping off what is left of each frame's share of a second. Frames that run
+/// late don't make the next ones hurry to catch up
+pub struct FrameLimiter {
+    cap: Option<u32>,
+    next_frame: Option<Instant>,
+}
+
+impl FrameLimiter {
+    /// Create a limiter that holds the frames to the given number per second, or doesn't hold them back if there is
+    /// no cap
+    pub fn new(cap: Option<u32>) -> FrameLimiter {
+        return FrameLimiter { cap, next_frame: None };
+    }
+
+    /// Get the most frames drawn per second, or none if they aren't capped
+    pub fn get_cap(&self) -> Option<u32> {
+        return self.cap;
+    }
+
+    /// Change the most frames drawn per second, from the next frame
+    pub fn set_cap(&mut self, cap: Option<u32>) {
+        self.cap = cap.filter(|&cap| cap > 0);
+        self.next_frame = None;
+    }
+
+    /// Wait until the next frame is due, once a frame has been drawn
+    pub fn wait(&mut self) {
+        let cap = match self.cap {
+            Some(cap) => cap,
+            None => return,
+        };
+        let now = Instant::now();
+        let due = match self.next_frame {
+            Some(due) if due > now => {
+                std::thread::sleep(due - now);
+                due
+            }
+            _ => now,
+        };
+        self.next_frame = Some(due + Duration::from_secs_f64(1.0 / cap as f64));
+    }
+}
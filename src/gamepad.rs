@@ -0,0 +1,57 @@
+use ggez::event::{Axis, Button};
+use ggez::input::keyboard::KeyCode;
+
+/// How far the left stick has to be tilted to count as holding down the arrow key in its direction
+const STICK_THRESHOLD: f32 = 0.5;
+
+/// Get the key the given gamepad button stands for, if any. The d-pad stands for the arrow keys, and the face
+/// buttons for Enter, Escape, and the keys of the actions during a run
+pub fn get_button_key(button: Button) -> Option<KeyCode> {
+    return match button {
+        Button::DPadUp => Some(KeyCode::Up),
+        Button::DPadDown => Some(KeyCode::Down),
+        Button::DPadLeft => Some(KeyCode::Left),
+        Button::DPadRight => Some(KeyCode::Right),
+        Button::South => Some(KeyCode::Return),
+        Button::East => Some(KeyCode::Escape),
+        Button::West => Some(KeyCode::Space),
+        Button::North => Some(KeyCode::G),
+        Button::Start => Some(KeyCode::P),
+        _ => None,
+    };
+}
+
+/// The arrow keys the left stick of a gamepad is holding down
+#[derive(Default)]
+pub struct Stick {
+    horizontal: Option<KeyCode>,
+    vertical: Option<KeyCode>,
+}
+
+impl Stick {
+    /// Create a stick that is at rest
+    pub fn new() -> Stick {
+        return Stick::default();
+    }
+
+    /// Move the stick along the given axis to the given value. Returns the arrow key that is let go of and the one
+    /// that is pressed, if either changed
+    pub fn update(&mut self, axis: Axis, value: f32) -> (Option<KeyCode>, Option<KeyCode>) {
+        // Find the key the stick holds down along the axis now, with up being positive
+        let (held, key) = match axis {
+            Axis::LeftStickX if value <= -STICK_THRESHOLD => (&mut self.horizontal, Some(KeyCode::Left)),
+            Axis::LeftStickX if value >= STICK_THRESHOLD => (&mut self.horizontal, Some(KeyCode::Right)),
+            Axis::LeftStickX => (&mut self.horizontal, None),
+            Axis::LeftStickY if value <= -STICK_THRESHOLD => (&mut self.vertical, Some(KeyCode::Down)),
+            Axis::LeftStickY if value >= STICK_THRESHOLD => (&mut self.vertical, Some(KeyCode::Up)),
+            Axis::LeftStickY => (&mut self.vertical, None),
+            _ => return (None, None),
+        };
+
+        if *held == key {
+            return (None, None);
+        }
+        let released = std::mem::replace(held, key);
+        return (released, key);
+    }
+}
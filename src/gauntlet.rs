@@ -0,0 +1,159 @@
+use ggez::{Context, GameResult, graphics};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Point, Tuning};
+
+/// Radius of the exit zone at the end of a gauntlet
+pub const EXIT_RADIUS: f32 = 40.0;
+/// Distance kept between the ends of the corridor and the edges of the arena
+const MARGIN: f32 = 60.0;
+/// Largest fraction of the arena's height the corridor can turn up or down by between two segments
+const MAX_TURN: f32 = 0.25;
+
+/// Color of the corridor's edges
+const CORRIDOR_COLOR: graphics::Color = graphics::Color::new(0.4, 0.4, 0.5, 0.5);
+/// Color of the exit zone
+const EXIT_COLOR: graphics::Color = graphics::Color::new(0.3, 1.0, 0.4, 1.0);
+
+/// Settings of the gauntlet generator
+#[derive(Clone, Copy, Debug)]
+pub struct GauntletParams {
+    /// Number of turrets on each side of the corridor for every 100 pixels of its length
+    pub density: f32,
+    /// Width of the path between the two rows of turrets
+    pub corridor_width: f32,
+    /// Number of straight segments the corridor is made of
+    pub segments: usize,
+}
+
+impl GauntletParams {
+    /// Create the default settings: a corridor of 4 segments, 140 pixels wide, with a turret every 80 pixels
+    pub fn new() -> GauntletParams {
+        return GauntletParams { density: 1.25, corridor_width: 140.0, segments: 4 };
+    }
+}
+
+impl Default for GauntletParams {
+    fn default() -> GauntletParams {
+        return GauntletParams::new();
+    }
+}
+
+/// Corridor that winds across the arena from left to right between two rows of turrets, ending in an exit zone
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Gauntlet {
+    path: Vec<Point>,
+    corridor_width: f32,
+    turrets: Vec<Point>,
+}
+
+impl Gauntlet {
+    /// Generate a new gauntlet that fits in an arena of the given size, spacing its turrets by their size in the
+    /// given balance
+    pub fn generate<R: Rng>(bounds: (f32, f32), params: &GauntletParams, tuning: &Tuning, rng: &mut R) -> Gauntlet {
+        let (width, height) = bounds;
+        let segments = params.segments.max(1);
+        let half_width = params.corridor_width / 2.0;
+
+        // Lay out the corridor's path, turning up or down a random amount at every joint while staying far enough
+        // from the top and bottom for the turrets to fit
+        let lowest = MARGIN + half_width;
+        let highest = (height - MARGIN - half_width).max(lowest);
+        let mut path = Vec::with_capacity(segments + 1);
+        let mut y = height / 2.0;
+        for i in 0..=segments {
+            let x = MARGIN + (width - 2.0 * MARGIN) * i as f32 / segments as f32;
+            path.push(Point::new(x, y));
+            y = (y + rng.gen_range(-MAX_TURN, MAX_TURN) * height).max(lowest).min(highest);
+        }
+
+        // Line both sides of every segment with turrets, spaced by the density and set out square to the segment.
+        // Turrets on the inside of a turn that would stand in the next segment are left out
+        let spacing = 100.0 / params.density.max(0.01);
+        let turret_radius = tuning.turret_radius;
+        let clearance = half_width + turret_radius;
+        let mut turrets = Vec::new();
+        for segment in path.windows(2) {
+            let (start, end) = (&segment[0], &segment[1]);
+            let length = start.distance_to(end);
+            let (dx, dy) = ((end.x - start.x) / length, (end.y - start.y) / length);
+            let count = (length / spacing).floor() as usize;
+            for j in 0..count {
+                let along = (j as f32 + 0.5) * spacing;
+                for side in [-1.0, 1.0].iter() {
+                    let offset = side * clearance;
+                    let position = Point::new(start.x + dx * along - dy * offset, start.y + dy * along + dx * offset);
+                    let inside = position.x > 0.0 && position.x < width && position.y > 0.0 && position.y < height;
+                    if inside && distance_to_path(&path, &position) > clearance - 1.0 {
+                        turrets.push(position);
+                    }
+                }
+            }
+        }
+
+        return Gauntlet { path, corridor_width: params.corridor_width, turrets };
+    }
+
+    /// Get the position the player starts at, at the beginning of the corridor
+    pub fn get_start(&self) -> &Point {
+        return &self.path[0];
+    }
+
+    /// Get the position of the exit zone, at the end of the corridor
+    pub fn get_exit(&self) -> &Point {
+        return &self.path[self.path.len() - 1];
+    }
+
+    /// Get the positions of the turrets flanking the corridor
+    pub fn get_turret_positions(&self) -> &[Point] {
+        return &self.turrets;
+    }
+
+    /// Check if the given position is inside the exit zone
+    pub fn has_reached_exit(&self, position: &Point) -> bool {
+        return self.get_exit().distance_to(position) < EXIT_RADIUS;
+    }
+
+    /// Draw the edges of the corridor and the exit zone
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let half_width = self.corridor_width / 2.0;
+        for segment in self.path.windows(2) {
+            let (start, end) = (&segment[0], &segment[1]);
+            let length = start.distance_to(end).max(f32::EPSILON);
+            let (nx, ny) = (-(end.y - start.y) / length, (end.x - start.x) / length);
+            for side in [-1.0, 1.0].iter() {
+                let (ox, oy) = (nx * side * half_width, ny * side * half_width);
+                let points = [[start.x + ox, start.y + oy], [end.x + ox, end.y + oy]];
+                let edge = graphics::Mesh::new_line(ctx, &points, 1.0, CORRIDOR_COLOR)?;
+                graphics::draw(ctx, &edge, ([0.0, 0.0],))?;
+            }
+        }
+
+        let exit = self.get_exit();
+        let zone = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::stroke(3.0),
+            [0.0, 0.0],
+            EXIT_RADIUS,
+            0.5,
+            EXIT_COLOR,
+        )?;
+        graphics::draw(ctx, &zone, ([exit.x, exit.y],))?;
+
+        return Ok(());
+    }
+}
+
+/// Get the distance from the given position to the nearest point on the given path
+fn distance_to_path(path: &[Point], position: &Point) -> f32 {
+    return path.windows(2)
+        .map(|segment| {
+            let (start, end) = (&segment[0], &segment[1]);
+            let (dx, dy) = (end.x - start.x, end.y - start.y);
+            let length_squared = (dx * dx + dy * dy).max(f32::EPSILON);
+            let t = (((position.x - start.x) * dx + (position.y - start.y) * dy) / length_squared).clamp(0.0, 1.0);
+            return position.distance_to(&Point::new(start.x + dx * t, start.y + dy * t));
+        })
+        .fold(f32::INFINITY, f32::min);
+}
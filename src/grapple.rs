@@ -0,0 +1,127 @@
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+use crate::{Point, World};
+
+/// Speed of the grapple's hook as it flies out, in pixels per second
+const HOOK_SPEED: f32 = 900.0;
+/// Farthest the hook can fly before the line runs out
+const MAX_LENGTH: f32 = 450.0;
+/// Speed the player is pulled towards the anchor at, in pixels per second
+const PULL_SPEED: f32 = 600.0;
+/// Gap left between the player and whatever the hook caught on when the pull stops
+const RELEASE_GAP: f32 = 10.0;
+
+/// Color of the grapple line
+const LINE_COLOR: graphics::Color = graphics::Color::new(0.8, 0.8, 0.6, 1.0);
+
+/// The states of a grapple
+#[derive(Clone, Serialize, Deserialize)]
+enum GrappleState {
+    /// Not in use
+    Idle,
+    /// The hook is flying out with the given heading, having travelled the given distance
+    Flying { hook: Point, heading: f32, travelled: f32 },
+    /// The hook has caught on something of the given radius and is pulling the player towards it
+    Pulling { anchor: Point, anchor_radius: f32 },
+}
+
+/// Grappling hook that fires a line and pulls the player towards whatever it catches on
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Grapple {
+    state: GrappleState,
+}
+
+impl Grapple {
+    /// Create a new grapple that is not in use
+    pub fn new() -> Grapple {
+        return Grapple { state: GrappleState::Idle };
+    }
+
+    /// Fire the hook from the given position with the given heading, unless the grapple is already in use
+    pub fn fire(&mut self, from: &Point, heading: f32) {
+        if let GrappleState::Idle = self.state {
+            self.state = GrappleState::Flying { hook: *from, heading, travelled: 0.0 };
+        }
+    }
+
+    /// Let go of the line
+    pub fn release(&mut self) {
+        self.state = GrappleState::Idle;
+    }
+
+    /// Check if the grapple is in use
+    pub fn is_active(&self) -> bool {
+        return !matches!(self.state, GrappleState::Idle);
+    }
+
+    /// Get the position of the hook while it is flying
+    pub fn get_flying_hook(&self) -> Option<&Point> {
+        return match &self.state {
+            GrappleState::Flying { hook, .. } => Some(hook),
+            _ => None,
+        };
+    }
+
+    /// Catch the flying hook on something with the given position and radius, starting the pull
+    pub fn attach(&mut self, anchor: Point, anchor_radius: f32) {
+        if let GrappleState::Flying { .. } = self.state {
+            self.state = GrappleState::Pulling { anchor, anchor_radius };
+        }
+    }
+
+    /// Advance the grapple by the given time in the given world, moving the given position of the player (of the
+    /// given radius) if it is being pulled. Returns whether the player is being pulled, in which case it shouldn't
+    /// move on its own
+    pub fn update(&mut self, dt: f32, position: &mut Point, radius: f32, world: &World) -> bool {
+        match &mut self.state {
+            GrappleState::Idle => return false,
+            GrappleState::Flying { hook, heading, travelled } => {
+                // Fly the hook out, catching on the wall if it reaches one, or giving up if the line runs out
+                let distance = HOOK_SPEED * dt;
+                hook.move_distance(distance, *heading);
+                *travelled += distance;
+                if hook.is_out_of_bounds(world) {
+                    let mut anchor = *hook;
+                    anchor.keep_in_bounds(world);
+                    self.state = GrappleState::Pulling { anchor, anchor_radius: 0.0 };
+                } else if *travelled > MAX_LENGTH {
+                    self.state = GrappleState::Idle;
+                }
+                return false;
+            }
+            GrappleState::Pulling { anchor, anchor_radius } => {
+                // Reel the player in, letting go just before it touches the anchor
+                let distance = position.distance_to(anchor);
+                let release_distance = radius + *anchor_radius + RELEASE_GAP;
+                if distance <= release_distance {
+                    self.state = GrappleState::Idle;
+                    return false;
+                }
+                let heading = position.heading_to(anchor);
+                position.move_distance((PULL_SPEED * dt).min(distance - release_distance), heading);
+                return true;
+            }
+        }
+    }
+
+    /// Draw the grapple line from the given position of the player
+    pub fn draw(&self, ctx: &mut Context, from: &Point) -> GameResult {
+        let end = match &self.state {
+            GrappleState::Idle => return Ok(()),
+            GrappleState::Flying { hook, .. } => hook,
+            GrappleState::Pulling { anchor, .. } => anchor,
+        };
+
+        let line = graphics::Mesh::new_line(ctx, &[[from.x, from.y], [end.x, end.y]], 2.0, LINE_COLOR)?;
+        graphics::draw(ctx, &line, ([0.0, 0.0],))?;
+
+        return Ok(());
+    }
+}
+
+impl Default for Grapple {
+    fn default() -> Grapple {
+        return Grapple::new();
+    }
+}
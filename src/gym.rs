@@ -0,0 +1,163 @@
+use crate::{GameMode, MainState, ShipClass};
+use crate::observation::{Action, ACTOR_FEATURES, Observation, PLAYER_FEATURES};
+
+/// How much each thing that happens during a step is worth to the agent
+#[derive(Clone, Copy, Debug)]
+pub struct RewardWeights {
+    /// Reward for every second survived
+    pub survival: f32,
+    /// Reward for every enemy destroyed
+    pub kill: f32,
+    /// Reward for every point of damage dealt to enemies
+    pub damage_dealt: f32,
+    /// Reward for every point of damage taken (usually negative)
+    pub damage_taken: f32,
+    /// Reward for the run ending with the player dead (usually negative)
+    pub death: f32,
+}
+
+impl RewardWeights {
+    /// Create the default weights, which reward surviving and destroying turrets and punish getting hurt
+    pub fn new() -> RewardWeights {
+        return RewardWeights { survival: 1.0, kill: 10.0, damage_dealt: 0.05, damage_taken: -0.1, death: -100.0 };
+    }
+}
+
+impl Default for RewardWeights {
+    fn default() -> RewardWeights {
+        return RewardWeights::new();
+    }
+}
+
+/// Settings of an environment
+#[derive(Clone, Copy, Debug)]
+pub struct EnvironmentConfig {
+    pub mode: GameMode,
+    pub class: ShipClass,
+    /// Size of the arena
+    pub bounds: (f32, f32),
+    /// Number of nearest actors included in the flat observations
+    pub max_actors: usize,
+    /// Number of ticks simulated for every action
+    pub ticks_per_step: u32,
+    /// Number of steps after which an episode is cut short, if any
+    pub max_steps: Option<u32>,
+    pub weights: RewardWeights,
+}
+
+impl EnvironmentConfig {
+    /// Create the default settings: the waves mode in the balanced ship, in an 800 by 600 arena
+    pub fn new() -> EnvironmentConfig {
+        return EnvironmentConfig {
+            mode: GameMode::Waves,
+            class: ShipClass::Fighter,
+            bounds: (800.0, 600.0),
+            max_actors: 32,
+            ticks_per_step: 4,
+            max_steps: Some(10_000),
+            weights: RewardWeights::new(),
+        };
+    }
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> EnvironmentConfig {
+        return EnvironmentConfig::new();
+    }
+}
+
+/// Result of one step of an environment
+#[derive(Clone, Debug)]
+pub struct Step {
+    /// Flat observation of the game after the step
+    pub observation: Vec<f32>,
+    pub reward: f32,
+    /// Whether the episode has ended, after which the environment has to be reset
+    pub done: bool,
+}
+
+/// Gym-style environment that runs the headless game for agents to train against
+pub struct Environment {
+    config: EnvironmentConfig,
+    state: MainState,
+    steps: u32,
+    done: bool,
+}
+
+impl Environment {
+    /// Create a new environment with the given settings, starting its first episode with seed 0
+    pub fn new(config: EnvironmentConfig) -> Environment {
+        let mut environment = Environment {
+            config,
+            state: MainState::headless(config.bounds, 0),
+            steps: 0,
+            done: false,
+        };
+        environment.reset(0);
+        return environment;
+    }
+
+    /// Get the length of the flat observations
+    pub fn get_observation_size(&self) -> usize {
+        return PLAYER_FEATURES + self.config.max_actors * ACTOR_FEATURES;
+    }
+
+    /// Start a new episode with the given seed, returning the first flat observation
+    pub fn reset(&mut self, seed: u64) -> Vec<f32> {
+        self.state = MainState::headless(self.config.bounds, seed);
+        self.state.start(self.config.mode, self.config.class);
+        self.steps = 0;
+        self.done = false;
+        return self.observe().to_flat(self.config.max_actors);
+    }
+
+    /// Get a full observation of the game
+    pub fn observe(&self) -> Observation {
+        return self.state.observe();
+    }
+
+    /// Take the given action and simulate the game until the next step, returning what the agent observes and
+    /// how it was rewarded
+    pub fn step(&mut self, action: Action) -> Step {
+        if self.done {
+            return Step { observation: self.observe().to_flat(self.config.max_actors), reward: 0.0, done: true };
+        }
+
+        let weights = self.config.weights;
+        let telemetry = self.state.get_telemetry();
+        let (kills, dealt, taken) =
+            (telemetry.get_total_kills(), telemetry.get_total_damage_dealt(), telemetry.get_total_damage_taken());
+
+        self.state.apply_action(action);
+        let mut reward = 0.0;
+        for _ in 0..self.config.ticks_per_step {
+            // Agents don't pick perks, so the first perk offered is always taken
+            if self.state.is_choosing_perk() {
+                self.state.choose_perk(0);
+            }
+            if !self.state.simulate_tick() {
+                reward += weights.death;
+                self.done = true;
+                break;
+            }
+            reward += weights.survival / self.state.get_tick_rate() as f32;
+            if self.state.get_outcome().is_some() {
+                self.done = true;
+                break;
+            }
+        }
+
+        // Reward what happened during the step
+        let telemetry = self.state.get_telemetry();
+        reward += weights.kill * (telemetry.get_total_kills() - kills) as f32;
+        reward += weights.damage_dealt * (telemetry.get_total_damage_dealt() - dealt);
+        reward += weights.damage_taken * (telemetry.get_total_damage_taken() - taken);
+
+        self.steps += 1;
+        if let Some(max_steps) = self.config.max_steps {
+            self.done |= self.steps >= max_steps;
+        }
+
+        return Step { observation: self.observe().to_flat(self.config.max_actors), reward, done: self.done };
+    }
+}
@@ -0,0 +1,92 @@
+use std::time::{Duration, Instant};
+
+use crate::{GameMode, MainState, RunOutcome, ShipClass};
+use crate::observation::{Action, Observation};
+
+/// How a run played without a window ended
+#[derive(Clone, Debug)]
+pub struct HeadlessResult {
+    pub ticks: u64,
+    pub score: u32,
+    pub wave: u32,
+    /// The outcome of the run, or none if the player died first or it was stopped at the tick limit
+    pub outcome: Option<RunOutcome>,
+    /// Real time it took to simulate the run
+    pub elapsed: Duration,
+}
+
+/// Run stepped without a window, sound, or ggez context, for tests, benchmarks, and bots. Nothing is drawn; the
+/// window only ever draws the state the simulation leaves behind
+pub struct HeadlessRun {
+    state: MainState,
+    over: bool,
+}
+
+impl HeadlessRun {
+    /// Start a run in the given mode with the given ship class, in an arena of the given size with the given seed
+    pub fn new(bounds: (f32, f32), seed: u64, mode: GameMode, class: ShipClass) -> HeadlessRun {
+        return HeadlessRun::with_state(MainState::headless(bounds, seed), mode, class);
+    }
+
+    /// Start a run in the given mode with the given ship class in the given headless game, which can be set up
+    /// first, like choosing its level
+    pub fn with_state(mut state: MainState, mode: GameMode, class: ShipClass) -> HeadlessRun {
+        state.start(mode, class);
+        return HeadlessRun { state, over: false };
+    }
+
+    /// Get the state of the game being run
+    pub fn get_state(&self) -> &MainState {
+        return &self.state;
+    }
+
+    /// Get the state of the game being run, to change it between ticks
+    pub fn get_state_mut(&mut self) -> &mut MainState {
+        return &mut self.state;
+    }
+
+    /// Check if the run has ended, either with an outcome or with the player out of restarts
+    pub fn is_over(&self) -> bool {
+        return self.over;
+    }
+
+    /// Simulate one tick with the player pressing the given controls. The first perk offered after a wave is
+    /// always taken. Returns false once the run is over
+    pub fn step(&mut self, action: Action) -> bool {
+        if self.over {
+            return false;
+        }
+        if self.state.is_choosing_perk() {
+            self.state.choose_perk(0);
+        }
+        self.state.apply_action(action);
+        self.over = !self.state.simulate_tick() || self.state.get_outcome().is_some();
+        return !self.over;
+    }
+
+    /// Play the run until it is over or has lasted the given number of ticks, with the given bot choosing the
+    /// controls for each tick from what it sees
+    pub fn play<F: FnMut(&Observation) -> Action>(&mut self, max_ticks: u64, mut bot: F) -> HeadlessResult {
+        let start = Instant::now();
+        for _ in 0..max_ticks {
+            let action = bot(&self.state.observe());
+            if !self.step(action) {
+                break;
+            }
+        }
+
+        let observation = self.state.observe();
+        return HeadlessResult {
+            ticks: observation.tick,
+            score: observation.score,
+            wave: observation.wave,
+            outcome: self.state.get_outcome(),
+            elapsed: start.elapsed(),
+        };
+    }
+}
+
+/// Bot that turns on the spot and fires on every tick, as a baseline to measure other bots and changes against
+pub fn spin_and_fire(_observation: &Observation) -> Action {
+    return Action { thrust: 0, turn: 1, fire: true, grapple: false };
+}
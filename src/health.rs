@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+
+use crate::damage::{Damage, Resistances};
+use crate::tuning::rescale_health;
+
+/// Health of an Actor that can be hurt, kept between zero and its maximum, with the armor that reduces the damage
+/// it takes
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Health {
+    current: f32,
+    max: f32,
+    #[serde(default)]
+    armor: Resistances,
+}
+
+impl Health {
+    /// Create a new full health of the given maximum, without armor
+    pub fn new(max: f32) -> Health {
+        return Health { current: max, max, armor: Resistances::none() };
+    }
+
+    /// Create a new full health of the given maximum, protected by the given armor
+    pub fn armored(max: f32, armor: Resistances) -> Health {
+        return Health { current: max, max, armor };
+    }
+
+    /// Get the health left
+    pub fn get_current(&self) -> f32 {
+        return self.current;
+    }
+
+    /// Get the maximum health
+    pub fn get_max(&self) -> f32 {
+        return self.max;
+    }
+
+    /// Get the fraction of the maximum health that is left, from 0 to 1
+    pub fn get_fraction(&self) -> f32 {
+        if self.max <= 0.0 {
+            return 0.0;
+        }
+        return (self.current / self.max).clamp(0.0, 1.0);
+    }
+
+    /// Check if there is no health left
+    pub fn is_depleted(&self) -> bool {
+        return self.current <= 0.0;
+    }
+
+    /// Change the armor protecting this health
+    pub fn set_armor(&mut self, armor: Resistances) {
+        self.armor = armor;
+    }
+
+    /// Get the amount of the given damage that gets through the armor
+    pub fn get_damage_taken(&self, damage: Damage) -> f32 {
+        return self.armor.apply(damage);
+    }
+
+    /// Take the given damage, reduced by the armor. Returns the amount of health actually lost
+    pub fn damage(&mut self, damage: Damage) -> f32 {
+        let amount = self.get_damage_taken(damage);
+        return self.lose(amount);
+    }
+
+    /// Lose the given amount of health, ignoring the armor, down to none. A negative amount loses nothing, as
+    /// health is only ever gained by healing. Returns the amount actually lost
+    pub fn lose(&mut self, amount: f32) -> f32 {
+        let before = self.current;
+        self.current = (self.current - amount.max(0.0)).clamp(0.0, self.max.max(0.0));
+        return (before - self.current).max(0.0);
+    }
+
+    /// Heal by the given amount, up to the maximum. Returns the amount actually healed
+    pub fn heal(&mut self, amount: f32) -> f32 {
+        let healed = amount.max(0.0).min(self.max - self.current).max(0.0);
+        self.current += healed;
+        return healed;
+    }
+
+    /// Raise the maximum by the given amount, healing by the same amount
+    pub fn raise_max(&mut self, amount: f32) {
+        self.max += amount;
+        self.current += amount;
+    }
+
+    /// Multiply both the health left and the maximum by the given factor
+    pub fn scale(&mut self, factor: f32) {
+        self.current *= factor;
+        self.max *= factor;
+    }
+
+    /// Scale this health from the given old full health to the given new one, so it keeps the fraction it has left
+    pub fn rescale(&mut self, old_max: f32, new_max: f32) {
+        self.current = rescale_health(self.current, old_max, new_max);
+        self.max = rescale_health(self.max, old_max, new_max);
+    }
+
+    /// Fill this health back up to the maximum
+    pub fn refill(&mut self) {
+        self.current = self.max;
+    }
+}
@@ -0,0 +1,115 @@
+use std::f32::consts::PI;
+
+use crate::{Point, Vec2};
+
+/// How far two hitboxes have to overlap before they count as touching, so actors resting edge to edge don't
+const COLLISION_TOLERANCE: f32 = 0.1;
+
+/// Shape of the area an Actor collides with, centered on its position
+#[derive(Clone, Debug, PartialEq)]
+pub enum Hitbox {
+    /// A circle of the given radius
+    Circle(f32),
+    /// A rectangle lined up with the arena, of the given half width and half height
+    Rect { half_width: f32, half_height: f32 },
+    /// A rectangle of the given half width and half height, turned by the given angle in radians
+    OrientedRect { half_width: f32, half_height: f32, rotation: f32 },
+}
+
+impl Hitbox {
+    /// Get the radius of the smallest circle around the center that holds this whole hitbox
+    pub fn get_bounding_radius(&self) -> f32 {
+        return match self {
+            Hitbox::Circle(radius) => *radius,
+            Hitbox::Rect { half_width, half_height } | Hitbox::OrientedRect { half_width, half_height, .. } => {
+                half_width.hypot(*half_height)
+            }
+        };
+    }
+
+    /// Get the corners of this hitbox if it is a rectangle, centered on the given position, in clockwise order
+    pub fn get_corners(&self, position: &Point) -> Option<[Point; 4]> {
+        let (half_width, half_height, rotation) = self.as_rect()?;
+        let corner = |x: f32, y: f32| position + &Vec2::new(x, y).rotate(rotation);
+        return Some([
+            corner(-half_width, -half_height),
+            corner(half_width, -half_height),
+            corner(half_width, half_height),
+            corner(-half_width, half_height),
+        ]);
+    }
+
+    /// Check if this hitbox, centered on the given position, overlaps another hitbox centered on another position
+    pub fn overlaps(&self, position: &Point, other: &Hitbox, other_position: &Point) -> bool {
+        return match (self.as_rect(), other.as_rect()) {
+            (None, None) => {
+                // Circles overlap if the distance between them is less than the sum of their radii
+                let reach = self.get_bounding_radius() + other.get_bounding_radius() - COLLISION_TOLERANCE;
+                position.distance_to(other_position) < reach
+            }
+            (Some(rect), None) => circle_overlaps_rect(other_position, other.get_bounding_radius(), position, rect),
+            (None, Some(rect)) => circle_overlaps_rect(position, self.get_bounding_radius(), other_position, rect),
+            (Some(rect), Some(other_rect)) => rects_overlap(position, rect, other_position, other_rect),
+        };
+    }
+
+    /// Check if this hitbox, moving in a straight line from the start position to the end position, overlaps
+    /// another hitbox centered on another position anywhere along the way. Two circles are tested as the capsule the
+    /// moving circle sweeps out; rectangles are tested in steps no longer than the smaller of the two hitboxes
+    pub fn sweep_overlaps(&self, start: &Point, end: &Point, other: &Hitbox, other_position: &Point) -> bool {
+        if self.as_rect().is_none() && other.as_rect().is_none() {
+            // The circles meet if the point on the path closest to the other circle is close enough to it
+            let path = end - start;
+            let length_squared = path.dot(&path);
+            let fraction = if length_squared > 0.0 {
+                ((other_position - start).dot(&path) / length_squared).max(0.0).min(1.0)
+            } else {
+                0.0
+            };
+            let reach = self.get_bounding_radius() + other.get_bounding_radius() - COLLISION_TOLERANCE;
+            return start.lerp(end, fraction).distance_to(other_position) < reach;
+        }
+
+        let step = self.get_bounding_radius().min(other.get_bounding_radius()).max(1.0);
+        let steps = (start.distance_to(end) / step).ceil() as u32;
+        return self.overlaps(end, other, other_position) || (0..steps).any(|i| {
+            self.overlaps(&start.lerp(end, i as f32 / steps as f32), other, other_position)
+        });
+    }
+
+    /// Get the half width, half height, and rotation of this hitbox if it is a rectangle
+    fn as_rect(&self) -> Option<(f32, f32, f32)> {
+        return match self {
+            Hitbox::Circle(_) => None,
+            Hitbox::Rect { half_width, half_height } => Some((*half_width, *half_height, 0.0)),
+            Hitbox::OrientedRect { half_width, half_height, rotation } => Some((*half_width, *half_height, *rotation)),
+        };
+    }
+}
+
+/// Check if a circle overlaps a rectangle, given as its half width, half height, and rotation, by finding the
+/// point of the rectangle closest to the circle's center
+fn circle_overlaps_rect(center: &Point, radius: f32, rect_center: &Point, rect: (f32, f32, f32)) -> bool {
+    let (half_width, half_height, rotation) = rect;
+    // Work in the rectangle's own frame, where it is lined up with the axes
+    let local = (center - rect_center).rotate(-rotation);
+    let closest = Vec2::new(local.x.max(-half_width).min(half_width), local.y.max(-half_height).min(half_height));
+    return local.distance_to(&closest) < radius - COLLISION_TOLERANCE;
+}
+
+/// Check if two rectangles, each given as its half width, half height, and rotation, overlap, by looking for a
+/// gap between them along each of their edges
+fn rects_overlap(center: &Point, rect: (f32, f32, f32), other_center: &Point, other_rect: (f32, f32, f32)) -> bool {
+    let between = other_center - center;
+    let axes = |rotation: f32| [Vec2::from_heading(rotation), Vec2::from_heading(rotation + PI / 2.0)];
+    // Get how far a rectangle reaches from its center along an axis
+    let reach = |(half_width, half_height, rotation): (f32, f32, f32), axis: &Vec2| {
+        let [along, across] = axes(rotation);
+        return half_width * along.dot(axis).abs() + half_height * across.dot(axis).abs();
+    };
+    let [first, second] = axes(rect.2);
+    let [third, fourth] = axes(other_rect.2);
+    return [first, second, third, fourth].iter().all(|axis| {
+        between.dot(axis).abs() < reach(rect, axis) + reach(other_rect, axis) - COLLISION_TOLERANCE
+    });
+}
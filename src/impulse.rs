@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Point, Vec2};
+
+/// Rate the speed of an impulse fades at, as a fraction of what is left per second
+const DECAY_RATE: f32 = 6.0;
+/// Speed below which an impulse has faded out completely
+const MIN_SPEED: f32 = 1.0;
+
+/// Push that moves an actor on top of its own movement and fades out over time, such as knockback from a hit
+#[derive(Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Impulse {
+    x: f32,
+    y: f32,
+}
+
+impl Impulse {
+    /// Create an impulse that doesn't push at all
+    pub fn new() -> Impulse {
+        return Impulse { x: 0.0, y: 0.0 };
+    }
+
+    /// Add a push at the given speed in the given direction to this impulse
+    pub fn add(&mut self, heading: f32, speed: f32) {
+        self.x += heading.cos() * speed;
+        self.y += heading.sin() * speed;
+    }
+
+    /// Add a push at the given velocity to this impulse
+    pub fn push(&mut self, velocity: &Vec2) {
+        self.x += velocity.x;
+        self.y += velocity.y;
+    }
+
+    /// Check if this impulse is still pushing
+    pub fn is_active(&self) -> bool {
+        return self.x.hypot(self.y) >= MIN_SPEED;
+    }
+
+    /// Move the given position by this impulse for the given time, and let the impulse fade
+    pub fn update(&mut self, dt: f32, position: &mut Point) {
+        if !self.is_active() {
+            *self = Impulse::new();
+            return;
+        }
+        position.x += self.x * dt;
+        position.y += self.y * dt;
+
+        let decay = (-DECAY_RATE * dt).exp();
+        self.x *= decay;
+        self.y *= decay;
+    }
+}
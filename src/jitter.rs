@@ -0,0 +1,58 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::rng::Stream;
+
+/// Small random variation applied to a turret's fire interval and shot speed, so that identical turrets drift
+/// out of step with each other. Each turret has its own stream, seeded from the gameplay stream when it is
+/// spawned, so the variation is the same every time a run is replayed from its seed
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Jitter {
+    fire_interval_spread: f32, // Largest fraction the fire interval is changed by
+    shot_speed_spread: f32, // Largest fraction the shot speed is changed by
+    // Jitters saved before their streams could be saved start a new one
+    #[serde(default = "new_stream")]
+    rng: Stream,
+}
+
+impl Jitter {
+    /// Create a new jitter that changes the fire interval and shot speed by up to the given fractions
+    pub fn new(fire_interval_spread: f32, shot_speed_spread: f32) -> Jitter {
+        return Jitter { fire_interval_spread, shot_speed_spread, rng: new_stream() };
+    }
+
+    /// Create a jitter that changes nothing
+    pub fn none() -> Jitter {
+        return Jitter::new(0.0, 0.0);
+    }
+
+    /// Seed the stream of this jitter
+    pub fn seed(&mut self, seed: u64) {
+        self.rng = Stream::new(seed);
+    }
+
+    /// Vary the given value by up to the given fraction either way
+    fn vary(&mut self, value: f32, spread: f32) -> f32 {
+        if spread <= 0.0 {
+            return value;
+        }
+        return value * (1.0 + self.rng.gen_range(-spread, spread));
+    }
+
+    /// Get the time until the next volley, varied from the given interval
+    pub fn next_fire_interval(&mut self, interval: f32) -> f32 {
+        let spread = self.fire_interval_spread;
+        return self.vary(interval, spread);
+    }
+
+    /// Get the speed of the shots of the next volley, varied from the given speed
+    pub fn next_shot_speed(&mut self, speed: f32) -> f32 {
+        let spread = self.shot_speed_spread;
+        return self.vary(speed, spread);
+    }
+}
+
+/// Start the stream of a jitter that hasn't been seeded yet
+fn new_stream() -> Stream {
+    return Stream::new(0);
+}
@@ -0,0 +1,147 @@
+use ggez::conf::FullscreenType;
+
+/// Description of the command-line options, printed by `--help` and after a mistake in the options
+pub const USAGE: &str = "\
+Usage: turrets [options]
+
+Options:
+  --size <width>x<height>  Open the window at this size, or play headless runs in an arena of this size
+  --world <width>x<height> Play in an arena of this size, scrolling with the player, instead of one that fills the
+                           window
+  --fullscreen             Fill the screen, changing its resolution to the size of the window
+  --borderless             Fill the screen with a borderless window at the desktop's resolution
+  --windowed               Open in a window, whatever the settings say
+  --vsync, --no-vsync      Wait for the display between frames (the default), or draw as fast as possible
+  --level <name>           Lay the waves out by the level with this name
+  --seed <seed>            Play the runs with this seed instead of a random one
+  --headless [seed]        Play a run without a window with the baseline bot, and print how it went
+  --benchmark              Stress test the game and print how long each system took; add --headless to time
+                           only the update, in a fixed arena that can't be changed
+  -h, --help               Print this message";
+
+/// What the game does when it is launched
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum LaunchMode {
+    /// Open the window and play
+    Window,
+    /// Play a run without a window with the baseline bot
+    Headless,
+    /// Stress test the game in a window
+    Benchmark,
+    /// Stress test the update of the game without a window
+    HeadlessBenchmark,
+    /// Print the command-line options
+    Help,
+}
+
+/// Options the game is launched with, read from the command line
+#[derive(Clone, Debug, PartialEq)]
+pub struct LaunchOptions {
+    pub mode: LaunchMode,
+    /// Size of the window, or of the arena of headless runs, if it was chosen
+    pub size: Option<(f32, f32)>,
+    /// Size of the arena, or none for an arena that fills the window
+    pub world: Option<(f32, f32)>,
+    /// Fullscreen type of the window, or none to use the one in the settings
+    pub fullscreen: Option<FullscreenType>,
+    pub vsync: bool,
+    /// Name of the level the waves are laid out by, or none for the level in the custom game rules
+    pub level: Option<String>,
+    /// Seed of the runs, or none for random seeds
+    pub seed: Option<u64>,
+}
+
+impl LaunchOptions {
+    /// Create the options the game is launched with when none are given: a window with the settings' fullscreen
+    /// type, vsync, and random seeds
+    pub fn new() -> LaunchOptions {
+        return LaunchOptions {
+            mode: LaunchMode::Window,
+            size: None,
+            world: None,
+            fullscreen: None,
+            vsync: true,
+            level: None,
+            seed: None,
+        };
+    }
+
+    /// Read the options from the given command-line arguments, without the name of the program. Returns a
+    /// description of the first argument that can't be understood
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<LaunchOptions, String> {
+        let mut options = LaunchOptions::new();
+        let (mut headless, mut benchmark, mut help) = (false, false, false);
+        let mut args = args.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--size" => options.size = Some(parse_size(&take_value(&arg, args.next())?)?),
+                "--world" => options.world = Some(parse_size(&take_value(&arg, args.next())?)?),
+                "--fullscreen" => options.fullscreen = Some(FullscreenType::True),
+                "--borderless" => options.fullscreen = Some(FullscreenType::Desktop),
+                "--windowed" => options.fullscreen = Some(FullscreenType::Windowed),
+                "--vsync" => options.vsync = true,
+                "--no-vsync" => options.vsync = false,
+                "--level" => options.level = Some(take_value(&arg, args.next())?),
+                "--seed" => options.seed = Some(parse_seed(&take_value(&arg, args.next())?)?),
+                "--headless" => {
+                    headless = true;
+                    // The seed can follow the flag, as it could before the other options were added
+                    if let Some(seed) = args.peek().and_then(|next| next.parse().ok()) {
+                        options.seed = Some(seed);
+                        args.next();
+                    }
+                }
+                "--benchmark" => benchmark = true,
+                "-h" | "--help" => help = true,
+                _ => return Err(format!("unknown option {}", arg)),
+            }
+        }
+
+        options.mode = match (help, headless, benchmark) {
+            (true, _, _) => LaunchMode::Help,
+            (false, true, true) => LaunchMode::HeadlessBenchmark,
+            (false, true, false) => LaunchMode::Headless,
+            (false, false, true) => LaunchMode::Benchmark,
+            (false, false, false) => LaunchMode::Window,
+        };
+        // Benchmarks without a window always play the same arena and seed with no level, so their timings can be
+        // compared
+        let changes_run = options.size.is_some() || options.world.is_some() || options.seed.is_some();
+        if options.mode == LaunchMode::HeadlessBenchmark && (changes_run || options.level.is_some()) {
+            let options = "--size, --world, --seed, or --level";
+            return Err(format!("--benchmark --headless can't be combined with {}", options));
+        }
+        return Ok(options);
+    }
+}
+
+impl Default for LaunchOptions {
+    fn default() -> LaunchOptions {
+        return LaunchOptions::new();
+    }
+}
+
+/// Get the value given after the given option, or a description of it missing
+fn take_value(option: &str, value: Option<String>) -> Result<String, String> {
+    return value.ok_or_else(|| format!("{} needs a value", option));
+}
+
+/// Read a size written as a width and height joined by an x, like 1280x720
+fn parse_size(text: &str) -> Result<(f32, f32), String> {
+    let invalid = || format!("invalid size {}, expected a width and height like 1280x720", text);
+    let (width, height) = match text.split_once('x') {
+        Some(size) => size,
+        None => return Err(invalid()),
+    };
+    let width: f32 = width.parse().map_err(|_| invalid())?;
+    let height: f32 = height.parse().map_err(|_| invalid())?;
+    if !width.is_finite() || !height.is_finite() || width < 1.0 || height < 1.0 {
+        return Err(invalid());
+    }
+    return Ok((width, height));
+}
+
+/// Read a seed, which is a whole number
+fn parse_seed(text: &str) -> Result<u64, String> {
+    return text.parse().map_err(|_| format!("invalid seed {}, expected a whole number", text));
+}
@@ -0,0 +1,119 @@
+/// The layers actors and obstacles collide on. Two things are only tested for contact if each one's layer is in
+/// the mask of the other's
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CollisionLayer {
+    Player,
+    PlayerShot,
+    EnemyShot,
+    /// Every other actor that fights, on either team, like turrets, drones, and kamikazes
+    Enemy,
+    Pickup,
+    Obstacle,
+}
+
+impl CollisionLayer {
+    /// Every layer, in the order of their bits
+    pub const ALL: [CollisionLayer; 6] = [
+        CollisionLayer::Player,
+        CollisionLayer::PlayerShot,
+        CollisionLayer::EnemyShot,
+        CollisionLayer::Enemy,
+        CollisionLayer::Pickup,
+        CollisionLayer::Obstacle,
+    ];
+
+    /// Get the bit of this layer in a mask
+    fn get_bit(&self) -> u32 {
+        return 1 << *self as u32;
+    }
+}
+
+/// A set of collision layers, stored as bits
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LayerMask(u32);
+
+impl LayerMask {
+    /// Mask of every layer
+    pub const ALL: LayerMask = LayerMask((1 << CollisionLayer::ALL.len()) - 1);
+    /// Mask of no layers
+    pub const NONE: LayerMask = LayerMask(0);
+
+    /// Create a mask of the given layers
+    pub fn of(layers: &[CollisionLayer]) -> LayerMask {
+        return LayerMask(layers.iter().fold(0, |bits, layer| bits | layer.get_bit()));
+    }
+
+    /// Check if the given layer is in this mask
+    pub fn contains(&self, layer: CollisionLayer) -> bool {
+        return self.0 & layer.get_bit() != 0;
+    }
+
+    /// Get this mask with the given layer added or removed
+    pub fn with(&self, layer: CollisionLayer, included: bool) -> LayerMask {
+        return if included { LayerMask(self.0 | layer.get_bit()) } else { LayerMask(self.0 & !layer.get_bit()) };
+    }
+}
+
+/// The mask of every collision layer, which decides the layers it collides with before any contact is tested, and
+/// the layers that are destroyed by running into an obstacle instead of being blocked by it
+#[derive(Clone, Debug)]
+pub struct CollisionRules {
+    masks: [LayerMask; 6],
+    stopped_by_obstacles: LayerMask,
+}
+
+impl CollisionRules {
+    /// Create the game's own rules, where everything collides except shots with other shots, and obstacles, which
+    /// only block the player and stop shots. Teams still decide which of the colliding actors can harm each other
+    pub fn new() -> CollisionRules {
+        let shots = [CollisionLayer::PlayerShot, CollisionLayer::EnemyShot];
+        let mut rules = CollisionRules { masks: [LayerMask::ALL; 6], stopped_by_obstacles: LayerMask::of(&shots) };
+        for &shot in shots.iter() {
+            rules.set_collides(shot, CollisionLayer::PlayerShot, false);
+            rules.set_collides(shot, CollisionLayer::EnemyShot, false);
+        }
+        rules.set_mask(CollisionLayer::Obstacle, LayerMask::of(&[
+            CollisionLayer::Player,
+            CollisionLayer::PlayerShot,
+            CollisionLayer::EnemyShot,
+        ]));
+        return rules;
+    }
+
+    /// Get the mask of the layers the given layer collides with
+    pub fn get_mask(&self, layer: CollisionLayer) -> LayerMask {
+        return self.masks[layer as usize];
+    }
+
+    /// Set the mask of the layers the given layer collides with
+    pub fn set_mask(&mut self, layer: CollisionLayer, mask: LayerMask) {
+        self.masks[layer as usize] = mask;
+    }
+
+    /// Set whether the given layers collide with each other, in either order
+    pub fn set_collides(&mut self, first: CollisionLayer, second: CollisionLayer, collides: bool) {
+        self.masks[first as usize] = self.masks[first as usize].with(second, collides);
+        self.masks[second as usize] = self.masks[second as usize].with(first, collides);
+    }
+
+    /// Check if things on the given layers collide, which each of their masks has to allow
+    pub fn collides(&self, first: CollisionLayer, second: CollisionLayer) -> bool {
+        return self.get_mask(first).contains(second) && self.get_mask(second).contains(first);
+    }
+
+    /// Set whether actors on the given layer are destroyed by running into an obstacle, or blocked by it
+    pub fn set_stopped_by_obstacles(&mut self, layer: CollisionLayer, stopped: bool) {
+        self.stopped_by_obstacles = self.stopped_by_obstacles.with(layer, stopped);
+    }
+
+    /// Check if actors on the given layer are destroyed by running into an obstacle instead of being blocked by it
+    pub fn is_stopped_by_obstacles(&self, layer: CollisionLayer) -> bool {
+        return self.stopped_by_obstacles.contains(layer);
+    }
+}
+
+impl Default for CollisionRules {
+    fn default() -> CollisionRules {
+        return CollisionRules::new();
+    }
+}
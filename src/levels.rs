@@ -0,0 +1,271 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ArenaShape, EdgeBehavior, GameMode, Point, World};
+use crate::medals::ParScores;
+use crate::mods::ModList;
+use crate::obstacles::Obstacle;
+use crate::regions::{SpawnRegion, SpawnZone};
+
+/// Directory that levels are loaded from, next to the game
+pub const LEVELS_DIRECTORY: &str = "levels";
+/// Name of the built-in level
+pub const STANDARD_LEVEL: &str = "Standard";
+
+/// The kinds of turret a level can place
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TurretKind {
+    /// A turret with no special abilities
+    Normal,
+    /// A turret whose shots set the player on fire
+    Burning,
+    /// A turret whose shots slow the player down
+    Slowing,
+    /// A turret armored against kinetic damage
+    Armored,
+}
+
+impl TurretKind {
+    /// Get the name this kind of turret is registered under in the actor registry
+    pub fn get_actor_name(&self) -> &'static str {
+        return match self {
+            TurretKind::Normal => "turret.basic",
+            TurretKind::Burning => "turret.burning",
+            TurretKind::Slowing => "turret.slowing",
+            TurretKind::Armored => "turret.armored",
+        };
+    }
+}
+
+impl Default for TurretKind {
+    fn default() -> TurretKind {
+        return TurretKind::Normal;
+    }
+}
+
+/// How a run on a level is won
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum WinCondition {
+    /// The waves never end, and the run goes on until the player runs out of restarts
+    Endless,
+    /// The run is won once the given number of waves have been cleared
+    Waves(u32),
+    /// The run is won once the player has survived for the given number of seconds
+    Survive(f32),
+}
+
+impl Default for WinCondition {
+    fn default() -> WinCondition {
+        return WinCondition::Endless;
+    }
+}
+
+/// A turret placed by a level, at a position in the level's arena
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TurretPlacement {
+    x: f32,
+    y: f32,
+    #[serde(default)]
+    kind: TurretKind,
+    /// Name of a registered actor to place instead of a turret of the kind
+    #[serde(default)]
+    actor: Option<String>,
+}
+
+/// An obstacle placed by a level, at a position in the level's arena
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ObstaclePlacement {
+    x: f32,
+    y: f32,
+    radius: f32,
+}
+
+/// Layout of the turrets every wave starts with, the obstacles in the arena, and how the run is won. Positions are
+/// given in an arena of the level's size, which is stretched to fit the window
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Level {
+    /// Name of the level, taken from its file
+    #[serde(skip)]
+    name: String,
+    arena: (f32, f32),
+    turrets: Vec<TurretPlacement>,
+    #[serde(default)]
+    obstacles: Vec<ObstaclePlacement>,
+    #[serde(default)]
+    win: WinCondition,
+    /// Named rectangles of the arena that spawn regions can refer to
+    #[serde(default)]
+    zones: BTreeMap<String, SpawnZone>,
+    /// Region the mods' turrets and the director's reinforcements spawn in, instead of their own places
+    #[serde(default)]
+    spawn_region: Option<SpawnRegion>,
+    /// Shape of the play area, which fills the window
+    #[serde(default)]
+    shape: ArenaShape,
+    /// What each edge of the shape does to actors that reach it, in the order of the edges
+    #[serde(default)]
+    edges: Vec<EdgeBehavior>,
+    /// Thresholds for the medals of a run on this level, instead of the built in par of the mode
+    #[serde(default)]
+    par: Option<ParScores>,
+}
+
+impl Level {
+    /// Create the built-in level, with a turret near each corner of the arena and no obstacles
+    pub fn standard() -> Level {
+        let placement = |x, y, kind| TurretPlacement { x, y, kind, actor: None };
+        return Level {
+            name: String::from(STANDARD_LEVEL),
+            arena: (4.0, 4.0),
+            turrets: vec![
+                placement(1.0, 1.0, TurretKind::Burning),
+                placement(1.0, 3.0, TurretKind::Slowing),
+                placement(3.0, 1.0, TurretKind::Normal),
+                placement(3.0, 3.0, TurretKind::Armored),
+            ],
+            obstacles: Vec::new(),
+            win: WinCondition::Endless,
+            zones: BTreeMap::new(),
+            spawn_region: None,
+            shape: ArenaShape::Rect,
+            edges: Vec::new(),
+            par: None,
+        };
+    }
+
+    /// Load the level in the given file, named after the file
+    pub fn load(path: &Path) -> Result<Level, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let mut level: Level = serde_json::from_str(&text)
+            .map_err(|error| format!("{}: invalid level: {}", path.display(), error))?;
+        if level.arena.0 <= 0.0 || level.arena.1 <= 0.0 {
+            return Err(format!("{}: invalid level: the arena has no area", path.display()));
+        }
+        if let ArenaShape::Polygon { corners } = &level.shape {
+            if corners.len() < 3 {
+                return Err(format!("{}: invalid level: the arena's polygon has fewer than 3 corners", path.display()));
+            }
+        }
+        if let Some(SpawnRegion::Zone(zone)) = &level.spawn_region {
+            if !level.zones.contains_key(zone) {
+                return Err(format!("{}: invalid level: enemies spawn in zone {}, which isn't defined", path.display(),
+                                   zone));
+            }
+        }
+        level.name = path.file_stem().map(|name| name.to_string_lossy().to_string()).unwrap_or_default();
+        return Ok(level);
+    }
+
+    /// Load every level in the levels directory, followed by the levels of the active mods in load order, so a
+    /// mod's level replaces any level of the same name loaded before it. Also returns a description of every level
+    /// that couldn't be read
+    pub fn load_all(mods: &ModList) -> (Vec<Level>, Vec<String>) {
+        return Level::load_paths(&Level::get_paths(mods));
+    }
+
+    /// Get the paths of every level in the levels directory, followed by the levels of the active mods in load
+    /// order
+    pub fn get_paths(mods: &ModList) -> Vec<PathBuf> {
+        let mut paths = Vec::new();
+        if let Ok(entries) = fs::read_dir(LEVELS_DIRECTORY) {
+            let mut found: Vec<_> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path()))
+                .filter(|path| matches!(path.extension(), Some(extension) if extension == "json"))
+                .collect();
+            found.sort();
+            paths.append(&mut found);
+        }
+        for entry in mods.get_active_mods() {
+            paths.extend(entry.get_manifest().get_levels().iter().map(|level| entry.get_directory().join(level)));
+        }
+        return paths;
+    }
+
+    /// Load the levels at the given paths in order, so a level replaces any level of the same name loaded before
+    /// it. Also returns a description of every level that couldn't be read
+    pub fn load_paths(paths: &[PathBuf]) -> (Vec<Level>, Vec<String>) {
+        let mut levels: Vec<Level> = Vec::new();
+        let mut issues = Vec::new();
+        for path in paths {
+            match Level::load(path) {
+                Ok(level) => {
+                    levels.retain(|other| other.name != level.name);
+                    levels.push(level);
+                }
+                Err(issue) => issues.push(issue),
+            }
+        }
+        return (levels, issues);
+    }
+
+    /// Get the name of this level
+    pub fn get_name(&self) -> &str {
+        return &self.name;
+    }
+
+    /// Get how a run on this level is won
+    pub fn get_win_condition(&self) -> WinCondition {
+        return self.win;
+    }
+
+    /// Move the given position in the level's arena to the same place in an arena of the given size
+    fn fit(&self, x: f32, y: f32, bounds: (f32, f32)) -> Point {
+        return Point::new(x * bounds.0 / self.arena.0, y * bounds.1 / self.arena.1);
+    }
+
+    /// Get the position and kind of every turret of this level, in an arena of the given size
+    pub fn get_turrets(&self, bounds: (f32, f32)) -> Vec<(Point, TurretKind)> {
+        return self.turrets.iter().map(|turret| (self.fit(turret.x, turret.y, bounds), turret.kind)).collect();
+    }
+
+    /// Get the position and registered name of every actor this level places, in an arena of the given size
+    pub fn get_actors(&self, bounds: (f32, f32)) -> Vec<(Point, String)> {
+        return self.turrets.iter()
+            .map(|turret| {
+                let name = turret.actor.clone().unwrap_or_else(|| turret.kind.get_actor_name().to_string());
+                (self.fit(turret.x, turret.y, bounds), name)
+            })
+            .collect();
+    }
+
+    /// Get the named zones of this level, in an arena of the given size
+    pub fn get_zones(&self, bounds: (f32, f32)) -> BTreeMap<String, SpawnZone> {
+        let (scale_x, scale_y) = (bounds.0 / self.arena.0, bounds.1 / self.arena.1);
+        return self.zones.iter().map(|(name, zone)| (name.clone(), zone.scaled(scale_x, scale_y))).collect();
+    }
+
+    /// Get the thresholds for the medals of a run in the given mode on this level, the level's own or otherwise
+    /// the built in par of the mode
+    pub fn get_par(&self, mode: GameMode) -> ParScores {
+        return self.par.unwrap_or_else(|| ParScores::for_mode(mode));
+    }
+
+    /// Get the region the mods' turrets and the director's reinforcements spawn in, if the level chooses one
+    pub fn get_spawn_region(&self) -> Option<&SpawnRegion> {
+        return self.spawn_region.as_ref();
+    }
+
+    /// Get the world of this level's shape and edges, filling an arena of the given size
+    pub fn get_world(&self, bounds: (f32, f32)) -> World {
+        return World::new(bounds, self.shape.clone(), self.edges.clone());
+    }
+
+    /// Create the obstacles of this level in an arena of the given size. Obstacles stay round, scaled by the
+    /// smaller of the two stretches
+    pub fn create_obstacles(&self, bounds: (f32, f32)) -> Vec<Obstacle> {
+        let scale = (bounds.0 / self.arena.0).min(bounds.1 / self.arena.1);
+        return self.obstacles.iter()
+            .map(|obstacle| Obstacle::new(self.fit(obstacle.x, obstacle.y, bounds), obstacle.radius * scale))
+            .collect();
+    }
+}
+
+impl Default for Level {
+    fn default() -> Level {
+        return Level::standard();
+    }
+}
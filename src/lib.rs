@@ -1,85 +1,301 @@
+use std::collections::{HashMap, HashSet};
 use std::f32::consts::PI;
 use std::sync::{Arc, Mutex};
 
 use ggez::{Context, event, GameError, GameResult, graphics};
-use ggez::event::{EventHandler, KeyMods};
+use ggez::conf::FullscreenType;
+use ggez::event::{Axis, Button, EventHandler, KeyMods};
+use ggez::input::gamepad::GamepadId;
 use ggez::input::keyboard::KeyCode;
 use ggez::timer;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 
-const FPS: u32 = 60;
+pub use animation::Animation;
+use animation::Explosion;
+pub use assets::AssetManager;
+use audio::{AudioManager, SoundEvent};
+use benchmark::{Benchmark, Stopwatch};
+use boss::BossBar;
+pub use camera::Camera;
+pub use bus::GameEvent;
+use bus::EventBus;
+pub use damage::{Damage, DamageType, Resistances};
+pub use director::Director;
+pub use effects::{StatusEffect, StatusEffectKind};
+pub use formations::FormationShape;
+use formations::{DriftingAnchor, FormationSlot};
+use gamepad::Stick;
+pub use gauntlet::GauntletParams;
+use gauntlet::Gauntlet;
+use grapple::Grapple;
+pub use health::Health;
+pub use hitboxes::Hitbox;
+use impulse::Impulse;
+use physics::Body;
+use jitter::Jitter;
+pub use layers::{CollisionLayer, CollisionRules, LayerMask};
+pub use levels::{Level, TurretKind, WinCondition};
+pub use loading::LoadJob;
+use loading::Loader;
+use capture::CaptureZone;
+use checkpoint::Checkpoint;
+use components::Components;
+pub use contacts::ContactResponse;
+use contacts::ContactTracker;
+use effects::StatusEffects;
+pub use enemies::EnemyDefinition;
+use events::{GlobalEvent, GlobalEvents};
+use flocking::FLOCK_RADIUS;
+use floor::{FloorKind, FloorZone};
+use framerate::FrameLimiter;
+use lod::UpdateScheduler;
+use reports::{BugReport, InputHistory, RecentLog};
+use results::RunSummary;
+use rewind::RewindBuffer;
+use observation::{Action, ActorObservation, Observation, PlayerObservation};
+use obstacles::Obstacle;
+pub use particles::{Emitter, ParticleSystem};
+pub use regions::{SpawnRegion, SpawnZone};
+pub use registry::{ActorFactory, ActorRegistry};
+pub use render::{ActorRenderer, Render, Sprite, SpriteSheet};
+use rng::RandomStreams;
+#[cfg(feature = "scripts")]
+use script_engine::ScriptEngine;
+use scripting::{FirePattern, ScriptBinding, ScriptCommand};
+#[cfg(feature = "scripts")]
+use scripting::{ActorView, InstructionBudget, ScriptApi};
+pub use scenes::Scene;
+use scenes::MenuItem;
+use spatial::SpatialGrid;
+pub use spawns::SpawnKind;
+use spawns::{SpawnSchedule, Spawner};
+use streaming::WorldStreamer;
+use targeting::TargetPicker;
+use teleporters::TeleporterPair;
+use text_entry::VirtualKeyboard;
+use timescale::TimeScale;
+pub use modes::{GameMode, RunOutcome};
+pub use modifiers::{Modifier, ModifierSet};
+pub use mods::{ModEntry, ModList, ModManifest};
+pub use perks::Perk;
+pub use medals::Medal;
+use medals::Profile;
+use population::PopulationLimits;
+use saves::{ActorData, SaveGame, SavedActor};
+use rulesets::{CustomGameAction, MapLayout, Preset, RuleItem, Ruleset};
+use settings::{Settings, SettingsItem};
+use shake::ScreenShake;
+use stats::RunStats;
+pub use ships::{ShipClass, ShipStats, ShipWeapon};
+pub use telemetry::Telemetry;
+pub use timers::{Cooldown, Timer};
+pub use tuning::Tuning;
+pub use vector::Vec2;
+pub use weapons::{Gun, Muzzle, Weapon};
+use weapons::Volley;
+pub use world::{ArenaShape, EdgeBehavior, World};
+use tuning::TuningWatcher;
 
-const SHOT_RADIUS: f32 = 5.0;
-const TURRET_RADIUS: f32 = 15.0;
-const PLAYER_RADIUS: f32 = 20.0;
+mod animation;
+mod assets;
+mod audio;
+pub mod benchmark;
+mod boss;
+mod bus;
+mod camera;
+mod capture;
+mod checkpoint;
+mod components;
+mod contacts;
+mod damage;
+mod debug_overlay;
+mod director;
+mod effects;
+mod enemies;
+mod events;
+mod flocking;
+mod floor;
+mod formations;
+mod framerate;
+mod gamepad;
+mod gauntlet;
+mod grapple;
+mod health;
+mod hitboxes;
+mod impulse;
+mod jitter;
+mod layers;
+pub mod launch;
+mod levels;
+mod loading;
+pub mod logging;
+mod lod;
+mod medals;
+mod modes;
+mod modifiers;
+mod mods;
+mod obstacles;
+mod particles;
+mod perks;
+mod physics;
+mod population;
+mod regions;
+mod registry;
+mod render;
+mod reports;
+mod results;
+mod rewind;
+mod rng;
+mod rulesets;
+mod saves;
+mod scenes;
+#[cfg(feature = "scripts")]
+mod script_engine;
+mod settings;
+mod shake;
+mod snapshot;
+#[cfg(feature = "gym")]
+pub mod gym;
+pub mod headless;
+pub mod observation;
+pub mod replay;
+pub mod scripting;
+mod ships;
+mod spatial;
+mod spawns;
+mod stats;
+mod streaming;
+mod systems;
+mod targeting;
+mod telemetry;
+mod teleporters;
+mod text_entry;
+mod timers;
+mod timescale;
+mod tuning;
+mod vector;
+mod versions;
+mod view;
+mod vision;
+mod weapons;
+mod world;
 
-/// Point data structure containing X and Y coordinates
-#[derive(Clone)]
-pub struct Point {
-    x: f32,
-    y: f32,
-}
-
-impl Point {
-    /// Create a new point with the given coordinates
-    fn new(x: f32, y: f32) -> Point {
-        return Point { x, y };
-    }
-
-    /// Find the linear distance to another point
-    fn distance_to(&self, other: &Point) -> f32 {
-        // Use the Pythagorean theorem to calculate the distance between the points
-        return ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt();
-    }
+/// Number of ticks simulated per second of play unless the settings choose another rate
+const DEFAULT_TICK_RATE: u32 = 60;
+const MAX_FRAME_TIME: f32 = 0.25;
 
-    /// Update the position of this point after moving for a given time at a given velocity
-    fn move_time(&mut self, dt: f32, velocity: &Velocity) {
-        // Get the X and Y components of the velocity
-        let (dx, dy) = velocity.get_components();
+const BOSS_PHASES: u32 = 3;
+const BOSS_WAVE_INTERVAL: u32 = 5;
+const ENRAGE_HEALTH_FRACTION: f32 = 0.25;
+const ENRAGE_TURN_MULTIPLIER: f32 = 2.0;
+const ENRAGE_FIRE_RATE_MULTIPLIER: f32 = 2.0;
+const ALERT_RADIUS: f32 = 300.0;
+const ALERT_DELAY: f32 = 0.75;
 
-        // Multiply the components by the change in time and add to the current position
-        self.x += dx * dt;
-        self.y += dy * dt;
-    }
+const KAMIKAZE_SWARM_SIZE: u32 = 4;
+const KAMIKAZE_SWARM_DELAY: f32 = 3.0;
+const REINFORCEMENT_DELAY: f32 = 1.5;
+const SPAWNER_FIRST_WAVE: u32 = 3;
+const SPAWNER_KAMIKAZES: u32 = 3;
+const SPAWNER_INTERVAL: f32 = 4.0;
+const SPAWNER_CALL_DISTANCE: f32 = 60.0; // Distance from a spawner that the drones it calls in arrive at
+const PHYSICS_DENSITY: f32 = 0.01; // Mass of an actor pushed around by collision physics, per square pixel of radius
+const SPAWN_CLEARANCE: f32 = 200.0; // Distance from the player that enemies spawning in a region try to keep
+const MIN_ARENA_SIZE: f32 = 200.0; // Smallest width and height of the arena, so there is always room to spawn in
+const SHOT_HEALTH_PER_SECOND: f32 = 10.0; // Health a shot has for every second of its lifespan, and loses every second
+#[cfg(feature = "scripts")]
+const MAX_SCRIPT_TURRETS: usize = 32; // Turrets in the arena past which scripts can't spawn any more
 
-    /// Move this point a linear distance in a given direction
-    fn move_distance(&mut self, distance: f32, heading: f32) {
-        // Multiply the XY components of the heading by the distance and add to the current position
-        self.x += heading.cos() * distance;
-        self.y += heading.sin() * distance;
-    }
+const PICKUP_LIFESPAN: f32 = 10.0;
+const PICKUP_SPAWN_INTERVAL: f32 = 20.0;
+const GEMS_PER_KILL: u32 = 3;
+const GEM_SCORE: u32 = 20;
+const GEM_SCATTER: f32 = 30.0;
+const GEM_MAGNET_RADIUS: f32 = 150.0;
+const GEM_MAGNET_SPEED: f32 = 300.0;
+const SHIELD_PICKUP_CHARGES: u32 = 3;
 
-    /// Check if this point is outside of the given bounds
-    fn is_out_of_bounds(&self, bounds: (f32, f32)) -> bool {
-        let (max_x, max_y) = bounds;
+const TURRET_KILL_SCORE: u32 = 100;
+const CHECKPOINT_RESTARTS: u32 = 3;
+const CHECKPOINT_SCORE_PENALTY: u32 = 500;
+const SEED_DIGITS: usize = 19;
+const PRESET_NAME_LENGTH: usize = 12;
+const INITIALS_LENGTH: usize = 3;
+const ROUND_TRIP_INTERVAL: u32 = 60;
+const ROUND_TRIP_TICKS: u32 = 10;
+const METEORS_PER_SECOND: f32 = 3.0;
+const PICKUP_RAIN_COUNT: u32 = 5;
 
-        return self.x > max_x || self.x < 0.0 || self.y > max_y || self.y < 0.0;
-    }
+const AUTO_AIM_CONE: f32 = 0.5;
 
-    /// If this point is out of bounds, wrap it to other side of those bounds
-    fn wrap_bounds(&mut self, bounds: (f32, f32)) {
-        let (max_x, max_y) = bounds;
+const HACK_RANGE: f32 = 200.0;
+const HACKABLE_HEALTH: f32 = 50.0;
 
-        if self.x > max_x {self.x = 0.0}
-        else if self.x < 0.0 {self.x = max_x}
+const ZONE_CAPTURE_SCORE: u32 = 500;
 
-        if self.y > max_y {self.y = 0.0}
-        else if self.y < 0.0 {self.y = max_y}
-    }
+const DEFENSE_WAVES: u32 = 10;
+const DEFENSE_STARTING_CREDITS: u32 = 200;
+const DEFENSE_KILL_CREDITS: u32 = 25;
+const DEFENSE_WAVE_CREDITS: u32 = 100;
+const DEFENSE_TURRET_COST: u32 = 100;
 
-    /// Prevent this point from going out of bounds
-    fn keep_in_bounds(&mut self, bounds: (f32, f32)) {
-        let (max_x, max_y) = bounds;
+const FRIENDLY_COLOR: graphics::Color = graphics::Color::new(0.3, 1.0, 0.4, 1.0);
+const HOSTILE_DRONE_COLOR: graphics::Color = graphics::Color::new(1.0, 0.35, 0.3, 1.0);
+const DORMANT_COLOR: graphics::Color = graphics::Color::new(0.5, 0.5, 0.5, 1.0);
+const ENRAGED_COLOR: graphics::Color = graphics::Color::new(1.0, 0.45, 0.2, 1.0);
+const GEM_COLOR: graphics::Color = graphics::Color::new(1.0, 0.85, 0.3, 1.0);
+const BOMB_COLOR: graphics::Color = graphics::Color::new(1.0, 0.6, 0.2, 1.0);
+const SHIELD_COLOR: graphics::Color = graphics::Color::new(0.4, 0.8, 1.0, 1.0);
 
-        if self.x > max_x {self.x = max_x}
-        else if self.x < 0.0 {self.x = 0.0}
+// Sprite sheets the actors are drawn with once they are loaded, from the most to the least fitting. Animated sheets
+// fall back to the still images
+const PLAYER: SpriteSheet = SpriteSheet::still("/images/player.png");
+const TURRET: SpriteSheet = SpriteSheet::still("/images/turret.png");
+const BOSS_TURRET: SpriteSheet = SpriteSheet::still("/images/turret_boss.png");
+const FRIENDLY_TURRET: SpriteSheet = SpriteSheet::still("/images/turret_friendly.png");
+const PLAYER_IMAGES: &[SpriteSheet] = &[PLAYER];
+const THRUSTING_PLAYER_IMAGES: &[SpriteSheet] =
+    &[SpriteSheet::new("/images/player_thrust.png", THRUST_FRAMES), PLAYER];
+const TURRET_IMAGES: &[SpriteSheet] = &[TURRET];
+const FIRING_TURRET_IMAGES: &[SpriteSheet] = &[SpriteSheet::new("/images/turret_firing.png", FIRE_FRAMES), TURRET];
+const BOSS_TURRET_IMAGES: &[SpriteSheet] = &[BOSS_TURRET, TURRET];
+const FIRING_BOSS_TURRET_IMAGES: &[SpriteSheet] =
+    &[SpriteSheet::new("/images/turret_boss_firing.png", FIRE_FRAMES), BOSS_TURRET, TURRET];
+const FRIENDLY_TURRET_IMAGES: &[SpriteSheet] = &[FRIENDLY_TURRET, TURRET];
+const FIRING_FRIENDLY_TURRET_IMAGES: &[SpriteSheet] =
+    &[SpriteSheet::new("/images/turret_friendly_firing.png", FIRE_FRAMES), FRIENDLY_TURRET, TURRET];
+const PLAYER_SHOT_IMAGES: &[SpriteSheet] =
+    &[SpriteSheet::still("/images/shot_player.png"), SpriteSheet::still("/images/shot.png")];
+const HOSTILE_SHOT_IMAGES: &[SpriteSheet] =
+    &[SpriteSheet::still("/images/shot_hostile.png"), SpriteSheet::still("/images/shot.png")];
+// Frames of the animations of the actors, and the time each frame is shown for
+const THRUST_FRAMES: u32 = 4;
+const THRUST_FRAME_DURATION: f32 = 0.06;
+const FIRE_FRAMES: u32 = 4;
+const FIRE_FRAME_DURATION: f32 = 0.04;
+const EXPLOSION_RADIUS: f32 = 40.0; // Radius of the explosions left by destroyed actors, other than bombs
+// Particles thrown off by destroyed actors, by shots hitting something, and by the player's engine, as their
+// lifetimes, speeds, spreads to either side, sizes, and colors
+const DEATH_BURST: Emitter =
+    Emitter::new((0.4, 0.9), (60.0, 220.0), PI, 2.5, graphics::Color::new(1.0, 0.7, 0.3, 1.0));
+const DEATH_BURST_PARTICLES: u32 = 40;
+const IMPACT_SPARKS: Emitter =
+    Emitter::new((0.1, 0.3), (80.0, 200.0), PI, 1.5, graphics::Color::new(1.0, 0.95, 0.6, 1.0));
+const IMPACT_SPARK_PARTICLES: u32 = 8;
+const EXHAUST: Emitter =
+    Emitter::new((0.2, 0.5), (30.0, 80.0), 0.3, 2.0, graphics::Color::new(0.6, 0.8, 1.0, 0.8)).with_rate(60.0);
+// Strength the view shakes by, from 0 to 1, when the player is hurt, when something blows up, and when a boss fires
+const PLAYER_HIT_SHAKE: f32 = 0.4;
+const EXPLOSION_SHAKE: f32 = 0.25;
+const BOMB_SHAKE: f32 = 0.6;
+const BOSS_ATTACK_SHAKE: f32 = 0.2;
 
-        if self.y > max_y {self.y = max_y}
-        else if self.y < 0.0 {self.y = 0.0}
-    }
-}
+/// Position in the arena, in pixels from its top left corner
+pub type Point = Vec2;
 
 /// Velocity data type containing a speed and heading
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Velocity {
     speed: f32, // Pixels per second
     heading: f32, // Radians
@@ -87,82 +303,297 @@ pub struct Velocity {
 
 impl Velocity {
     /// Create a new velocity object with the given speed and heading
-    fn new(speed: f32, heading: f32) -> Velocity {
+    pub fn new(speed: f32, heading: f32) -> Velocity {
         return Velocity { speed, heading };
     }
 
+    /// Create a velocity moving by the given vector every second
+    pub fn from_vector(vector: &Vec2) -> Velocity {
+        return Velocity::new(vector.length(), vector.get_heading());
+    }
+
+    /// Get the vector this velocity moves by every second
+    pub fn to_vector(&self) -> Vec2 {
+        return Vec2::from_heading(self.heading) * self.speed;
+    }
+
     /// Get the X and Y components of this velocity
-    fn get_components(&self) -> (f32, f32) {
-        let x = self.heading.cos() * self.speed;
-        let y = self.heading.sin() * self.speed;
-        return (x, y);
+    pub fn get_components(&self) -> (f32, f32) {
+        let vector = self.to_vector();
+        return (vector.x, vector.y);
     }
 }
 
-/// Trait specifying the methods an Actor in the game must have
+/// Trait specifying the methods an Actor in the game must have to be simulated. Drawing is left to the Render trait
 pub trait Actor {
-    /// Get the unique ID number of this Actor
+    /// Get the unique ID number of this Actor, which is 0 until it is added to a game
     fn get_id(&self) -> u32;
-    /// Get the radius of this Actor
+
+    /// Give this Actor the unique ID number the game chose for it, as it is added to the game
+    fn set_id(&mut self, id: u32);
+    /// Get the radius of this Actor, or of a circle around its whole hitbox if that isn't a circle
     fn get_radius(&self) -> f32;
     /// Get the positions of this Actor
     fn get_position(&self) -> &Point;
+    /// Get the kind of this Actor, so game rules, scoring, and drawing can branch on it
+    fn get_kind(&self) -> ActorKind;
 
-    /// Draw this Actor
-    fn draw(&self, ctx: &mut Context) -> GameResult;
+    /// Get the drawing of this Actor, or none if it isn't drawn, like an actor only simulated in tests
+    fn as_render(&self) -> Option<&dyn Render> {
+        return None;
+    }
     /// Update the state of this Actor
     fn update(&mut self, dt: f32);
 
+    /// Get the shape this Actor collides with, which is a circle of its radius unless it says otherwise
+    fn get_hitbox(&self) -> Hitbox {
+        return Hitbox::Circle(self.get_radius());
+    }
+
+    /// Get how far this Actor moved over the last tick, if it moves fast enough to need its whole path checked for
+    /// collisions
+    fn get_displacement(&self) -> Option<&Vec2> {
+        return None;
+    }
+
     /// Check if this Actor has collided with another Actor
-    fn check_for_collision(&mut self, other: &Box<dyn Actor>) -> bool {
-        // The actors have collided if the distance between them is less than the sum of their radii (minus a tolerance)
-        // and their ID's are not equal (they are not the same actor)
-        return self.get_position().distance_to(other.get_position()) < (self.get_radius() + other.get_radius() - 0.1)
+    fn check_for_collision(&self, other: &Box<dyn Actor>) -> bool {
+        // The actors have collided if their hitboxes overlap (by more than a tolerance) and their ID's are not equal
+        // (they are not the same actor)
+        return hitboxes_touch(&self.get_hitbox(), self.get_position(), self.get_displacement(), other.as_ref())
             && self.get_id() != other.get_id();
     }
 
-    /// Get the amount of damage that this Actor does during a collision
-    fn get_damage(&self) -> f32;
-    /// Do damage to this Actor
-    fn do_damage(&mut self, damage: f32);
+    /// Get the damage that this Actor does during a collision
+    fn get_damage(&self) -> Damage;
+    /// Do damage to this Actor, returning the health it actually lost
+    fn do_damage(&mut self, damage: Damage) -> f32;
+    /// Get the status effect that this Actor applies during a collision, if any
+    fn get_status_effect(&self) -> Option<StatusEffect>;
+    /// Apply a status effect to this Actor
+    fn apply_status_effect(&mut self, effect: StatusEffect);
+    /// Take the pickup this Actor gives to the Player when touched, if any
+    fn take_pickup(&mut self) -> Option<PickupKind>;
     /// Get the new Shots that this Actor has created
     fn collect_shots(&mut self) -> Vec<Shot>;
+    /// Create a boxed copy of this Actor, used to take snapshots of the game
+    fn clone_actor(&self) -> Box<dyn Actor>;
+    /// Get the saved state of this Actor, if it can be saved to disk
+    fn to_saved(&self) -> Option<SavedActor> {
+        return None;
+    }
+    /// Check if this Actor can be updated less often while it is far from the player
+    fn allows_reduced_update_rate(&self) -> bool {
+        return false;
+    }
+    /// Check if this Actor can be parked while its part of the arena is far from the player
+    fn allows_streaming(&self) -> bool {
+        return false;
+    }
+    /// Set the multiplier the floor under this Actor applies to its movement speed
+    fn set_floor_speed_multiplier(&mut self, _multiplier: f32) {}
+    /// Change the arena this Actor is in, after the window is resized or a level gives the arena a shape of its own
+    fn set_world(&mut self, _world: &World) {}
+    /// Apply a change of balance from the old tuning to the new one to this live Actor, where that can be done
+    /// safely. Actors read the rest of their balance as they are created
+    fn retune(&mut self, _old: &Tuning, _new: &Tuning) {}
+    /// Get the name of the script that aims and fires this Actor, and what it tells the script about itself, if it
+    /// is scripted
+    fn get_script(&self) -> Option<ScriptBinding<'_>> {
+        return None;
+    }
+    /// Carry out a command the script of this Actor issued
+    fn apply_script_command(&mut self, _command: &ScriptCommand) {}
+    /// Set this Actor off, if it is something that explodes
+    fn detonate(&mut self) {}
+    /// Get the radius and damage of the blast of this Actor if it has just exploded
+    fn take_explosion(&mut self) -> Option<(f32, Damage)> {
+        return None;
+    }
+    /// Get the health of this Actor, if it can be hurt
+    fn get_health(&self) -> Option<f32> {
+        return None;
+    }
+
+    /// Get the full health of this Actor, if it can be hurt
+    fn get_max_health(&self) -> Option<f32> {
+        return None;
+    }
+    /// Get the velocity of this Actor, if it moves on its own
+    fn get_velocity(&self) -> Option<&Velocity> {
+        return None;
+    }
+    /// Get the heading this Actor is aiming at, if it aims
+    fn get_aim(&self) -> Option<f32> {
+        return None;
+    }
+    /// Get the velocity of this Actor if it flies in a flock, which its neighbours steer by
+    fn get_flock_velocity(&self) -> Option<&Velocity> {
+        return None;
+    }
+    /// Set the heading the flock this Actor is in wants it to steer towards
+    fn set_flock_heading(&mut self, _heading: f32) {}
+    /// Get the ID of the formation this Actor follows, if it is in one
+    fn get_formation(&self) -> Option<u32> {
+        return None;
+    }
+    /// Move this Actor to its place in its formation, whose anchor is at the given position
+    fn follow_formation(&mut self, _anchor: (f32, f32)) {}
+    /// Check if this Actor is carried by teleporters that carry shots
+    fn allows_teleporting(&self) -> bool {
+        return false;
+    }
+    /// Move this Actor to the given position, if it allows teleporting
+    fn teleport(&mut self, _destination: &Point) {}
+    /// Get the mass of this Actor if collision physics push it around, or infinity if nothing can move it
+    fn get_mass(&self) -> Option<f32> {
+        return None;
+    }
+    /// Move this Actor by the given offset out of something it has run into, and push it at the given velocity,
+    /// as collision physics resolve a collision
+    fn bounce(&mut self, _offset: &Vec2, _push: &Vec2) {}
+    /// Get the team this Actor is on
+    fn get_team(&self) -> Team {
+        return Team::Hostile;
+    }
+    /// Get the layer this Actor collides on, which by default follows from its kind and team
+    fn get_collision_layer(&self) -> CollisionLayer {
+        return match self.get_kind() {
+            ActorKind::Player => CollisionLayer::Player,
+            ActorKind::Shot if self.get_team() == Team::Player => CollisionLayer::PlayerShot,
+            ActorKind::Shot => CollisionLayer::EnemyShot,
+            ActorKind::Pickup => CollisionLayer::Pickup,
+            _ => CollisionLayer::Enemy,
+        };
+    }
+    /// Try to convert this Actor to the player's team for the given time, returning whether it was converted
+    fn hack(&mut self, _duration: f32) -> bool {
+        return false;
+    }
+    /// Tell this Actor where the nearest enemy it could aim at is
+    fn set_target(&mut self, _target: Option<Point>) {}
+    /// Check if this Actor has raised an alarm since the last check, which alerts the actors around it
+    fn take_alarm(&mut self) -> bool {
+        return false;
+    }
+    /// Alert this Actor to an alarm raised nearby, to react to after the given delay
+    fn alert(&mut self, _delay: f32) {}
+    /// Take the spawns this Actor has called in since the last check, which arrive through the spawn schedule
+    fn take_spawns(&mut self) -> Vec<(SpawnKind, Point)> {
+        return Vec::new();
+    }
+
+    /// Seed any random variation of this Actor from the gameplay stream as it is spawned, so it is the same every
+    /// time a run is replayed
+    fn seed(&mut self, _seed: u64) {}
     /// Check if this Actor is dead
     fn is_dead(&self) -> bool;
 }
 
-/// Generate a new unique ID for new Actor
-fn get_next_actor_id() -> u32 {
-    let id;
-    unsafe {
-        static mut NEXT: u32 = 0;
-        NEXT += 1;
-        id = NEXT;
+/// Check if the given hitbox, at the given position after moving by the given displacement over the last tick,
+/// touches another actor. If either moved farther than the two of them are wide, the whole path is checked, so fast
+/// shots can't skip through what they should hit between one tick and the next
+fn hitboxes_touch(hitbox: &Hitbox, position: &Point, displacement: Option<&Vec2>, other: &dyn Actor) -> bool {
+    let other_hitbox = other.get_hitbox();
+    let reach = hitbox.get_bounding_radius() + other_hitbox.get_bounding_radius();
+    let still = Vec2::zero();
+    let displacement = displacement.unwrap_or(&still);
+    let other_displacement = other.get_displacement().unwrap_or(&still);
+    if displacement.length() <= reach && other_displacement.length() <= reach {
+        return hitbox.overlaps(position, &other_hitbox, other.get_position());
+    }
+
+    // Follow the path this hitbox took relative to the other actor, as if the other actor had stood still where it
+    // ended up
+    let start = position - &(displacement - other_displacement);
+    return hitbox.sweep_overlaps(&start, position, &other_hitbox, other.get_position());
+}
+
+/// Get the body the given actor collides as under collision physics, if it has a mass
+fn get_body(actor: &dyn Actor) -> Option<Body> {
+    let mass = actor.get_mass()?;
+    let velocity = actor.get_velocity().map(Velocity::to_vector).unwrap_or_default();
+    return Some(Body::new(actor.get_position(), actor.get_radius(), mass, velocity));
+}
+
+/// Separate the given actors and bounce them off each other as their masses dictate, if both have a mass and they
+/// overlap
+fn push_apart(actor: &mut dyn Actor, other_actor: &mut dyn Actor) {
+    let (body, other_body) = match (get_body(actor), get_body(other_actor)) {
+        (Some(body), Some(other_body)) => (body, other_body),
+        _ => return,
+    };
+    if let Some((response, other_response)) = physics::resolve(&body, &other_body) {
+        actor.bounce(&response.offset, &response.push);
+        other_actor.bounce(&other_response.offset, &other_response.push);
+    }
+}
+
+/// Bounce the given actor off the given body that nothing can move, if the actor has a mass and overlaps it
+fn bounce_off(actor: &mut dyn Actor, wall: &Body) {
+    if let Some((response, _)) = get_body(actor).and_then(|body| physics::resolve(&body, wall)) {
+        actor.bounce(&response.offset, &response.push);
     }
-    return id;
+}
+
+/// Get the animation of a turret firing, which waits for the turret to fire
+fn get_fire_animation() -> Animation {
+    return Animation::played(FIRE_FRAMES, FIRE_FRAME_DURATION);
+}
+
+/// Get the animation of the player's engine, which loops while the player is moving
+fn get_thrust_animation() -> Animation {
+    return Animation::looping(THRUST_FRAMES, THRUST_FRAME_DURATION);
+}
+
+/// Get the number of ticks simulated per second in files written before the tick rate could be changed
+fn get_default_tick_rate() -> u32 {
+    return DEFAULT_TICK_RATE;
+}
+
+/// Get the floor speed multiplier of actors saved before the floor could change their speed
+fn get_full_speed() -> f32 {
+    return 1.0;
 }
 
 /// Shot data structure
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct Shot {
     id: u32,
     position: Point,
-    bounds: (f32, f32),
+    world: World,
     velocity: Velocity,
-    damage: f32,
-    health: f32,
+    damage: Damage,
+    health: Health, // Runs out over the lifespan of the shot, or sooner if it is hit
+    radius: f32,
+    effect: Option<StatusEffect>,
+    ricochets: u32,
+    team: Team,
+    #[serde(default = "get_full_speed")]
+    floor_speed_multiplier: f32,
+    #[serde(skip)]
+    displacement: Vec2, // How far the shot moved over the last tick
 }
 
 impl Shot {
-    /// Create a new shot with the given starting position, velocity, damage, and lifespan
-    fn new(position: Point, bounds: (f32, f32), velocity: Velocity, damage: f32, lifespan: f32) -> Shot {
+    /// Create a new shot on the given team with the given starting position, velocity, damage, lifespan, and
+    /// status effect, the size of a shot in the default balance. Shots fired from a muzzle are sized by the balance
+    /// of whatever fired them
+    pub fn new(position: Point, world: &World, velocity: Velocity, damage: Damage, lifespan: f32,
+           effect: Option<StatusEffect>, team: Team) -> Shot {
         return Shot {
-            id: get_next_actor_id(),
+            id: 0,
             position,
-            bounds,
+            world: world.clone(),
             velocity,
             damage,
-            health: lifespan * 10.0,
+            health: Health::new(lifespan * SHOT_HEALTH_PER_SECOND),
+            radius: Tuning::DEFAULT.shot_radius,
+            effect,
+            ricochets: 0,
+            team,
+            floor_speed_multiplier: 1.0,
+            displacement: Vec2::zero(),
         }
     }
 }
@@ -173,9 +604,14 @@ impl Actor for Shot {
         return self.id;
     }
 
+    /// Give this Shot the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
     /// Get the radius of this Shot
     fn get_radius(&self) -> f32 {
-        return SHOT_RADIUS;
+        return self.radius;
     }
 
     /// Get the position of this Shot
@@ -183,37 +619,90 @@ impl Actor for Shot {
         return &self.position;
     }
 
-    /// Draw this Shot
-    fn draw(&self, ctx: &mut Context) -> GameResult {
-        let circle = graphics::Mesh::new_circle(
-            ctx,
-            graphics::DrawMode::fill(),
-            [0.0, 0.0],
-            self.get_radius(),
-            0.1,
-            graphics::WHITE,
-        )?;
-        graphics::draw(ctx, &circle, ([self.position.x, self.position.y], self.velocity.heading, graphics::WHITE,))?;
+    /// Get the kind of this Shot
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Shot;
+    }
 
-        return Ok(());
+    /// Resize this Shot to the new balance
+    fn retune(&mut self, _old: &Tuning, new: &Tuning) {
+        self.radius = new.shot_radius;
+    }
+
+    /// Get the drawing of this Shot
+    fn as_render(&self) -> Option<&dyn Render> {
+        return Some(self);
+    }
+
+    /// Change the arena this Shot flies in, which it dies once it leaves
+    fn set_world(&mut self, world: &World) {
+        self.world = world.clone();
+    }
+
+    /// Set the multiplier the floor under this Shot applies to its speed
+    fn set_floor_speed_multiplier(&mut self, multiplier: f32) {
+        self.floor_speed_multiplier = multiplier;
     }
 
     /// Update the state of this Shot
     fn update(&mut self, dt: f32) {
-        // Move the shot
-        self.position.move_time(dt, &self.velocity);
-        // Reduce the health of the shot by 10 for every second that passes
-        self.health -= dt * 10.0;
+        // Move the shot, at the speed the floor under it allows
+        let start = self.position;
+        let mut velocity = self.velocity.clone();
+        velocity.speed *= self.floor_speed_multiplier;
+        self.position.move_time(dt, &velocity);
+
+        // If the shot has left the arena and can still ricochet, bounce it back in off the wall (or walls) it hit.
+        // Otherwise it comes back in across edges that wrap or bounce, and dies past the rest
+        let mut wrapped = false;
+        if let Some(edge) = self.world.find_edge(&self.position) {
+            if self.ricochets > 0 {
+                self.world.bounce(&mut self.position, &mut self.velocity);
+                self.ricochets -= 1;
+            } else {
+                match self.world.get_edge_behavior(edge) {
+                    EdgeBehavior::Wrap => {
+                        self.position.wrap_bounds(&self.world);
+                        wrapped = true;
+                    }
+                    EdgeBehavior::Bounce => self.world.bounce(&mut self.position, &mut self.velocity),
+                    EdgeBehavior::Clamp | EdgeBehavior::Kill => (),
+                }
+            }
+        }
+        // A shot that wrapped jumped across the arena rather than sweeping across it, like a teleported one
+        self.displacement = if wrapped { Vec2::zero() } else { &self.position - &start };
+
+        // Reduce the health of the shot for every second that passes
+        self.health.lose(dt * SHOT_HEALTH_PER_SECOND);
     }
 
-    /// Get the amount of damage this Shot does
-    fn get_damage(&self) -> f32 {
+    /// Get the damage this Shot does
+    fn get_damage(&self) -> Damage {
         return self.damage;
     }
 
-    /// Do damage to this Shot
-    fn do_damage(&mut self, damage: f32) {
-        self.health -= damage;
+    /// Get the health of this Shot, which runs out over its lifespan
+    fn get_health(&self) -> Option<f32> {
+        return Some(self.health.get_current());
+    }
+
+    /// Do damage to this Shot (shots have no resistances)
+    fn do_damage(&mut self, damage: Damage) -> f32 {
+        return self.health.damage(damage);
+    }
+
+    /// Get the status effect this Shot applies to whatever it hits
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return self.effect.clone();
+    }
+
+    /// Apply a status effect to this Shot (shots are not affected by status effects)
+    fn apply_status_effect(&mut self, _effect: StatusEffect) {}
+
+    /// Take the pickup this Shot gives (shots never give pickups)
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
     }
 
     /// Get any new Shots this Shot has created (this will always be an empty vector)
@@ -221,64 +710,281 @@ impl Actor for Shot {
         return Vec::new();
     }
 
+    /// Create a boxed copy of this Shot
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// Get the saved state of this Shot
+    fn to_saved(&self) -> Option<SavedActor> {
+        return Some(SavedActor(ActorData::Shot(self.clone())));
+    }
+
+    /// Get the velocity of this Shot
+    fn get_velocity(&self) -> Option<&Velocity> {
+        return Some(&self.velocity);
+    }
+
+    /// Check if this Shot is carried by teleporters that carry shots (always true)
+    fn allows_teleporting(&self) -> bool {
+        return true;
+    }
+
+    /// Move this Shot to the given position, keeping its velocity. It jumps there rather than sweeping across the
+    /// arena
+    fn teleport(&mut self, destination: &Point) {
+        self.position = *destination;
+        self.displacement = Vec2::zero();
+    }
+
+    /// Get how far this Shot moved over the last tick, so fast shots hit whatever they passed through
+    fn get_displacement(&self) -> Option<&Vec2> {
+        return Some(&self.displacement);
+    }
+
+    /// Get the team of whoever fired this Shot
+    fn get_team(&self) -> Team {
+        return self.team;
+    }
+
     /// Check if this Shot is dead and should be removed
     fn is_dead(&self) -> bool {
         // A shot is dead if the health is below 0 or it has left the game window
-        return self.health <= 0.0 || self.position.is_out_of_bounds(self.bounds);
+        return self.health.is_depleted() || self.position.is_out_of_bounds(&self.world);
+    }
+}
+
+impl Render for Shot {
+    /// Draw this Shot
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let circle = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            [0.0, 0.0],
+            self.get_radius(),
+            0.1,
+            graphics::WHITE,
+        )?;
+        graphics::draw(ctx, &circle, ([self.position.x, self.position.y], self.velocity.heading, graphics::WHITE,))?;
+
+        return Ok(());
+    }
+
+    /// Get the sprite of this Shot, which points the way it is flying and depends on who fired it
+    fn get_sprite(&self) -> Option<Sprite> {
+        let images = if self.team == Team::Player { PLAYER_SHOT_IMAGES } else { HOSTILE_SHOT_IMAGES };
+        return Some(Sprite::new(images, [self.position.x, self.position.y], self.get_radius() * 2.0,
+                                self.velocity.heading, graphics::WHITE));
     }
 }
 
 /// Turret data structure
-#[derive(Clone)]
-struct Turret {
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Turret {
     id: u32,
     position: Point,
-    bounds: (f32, f32),
-    health: f32,
+    world: World,
+    radius: f32,
+    health: Health,
     rotation: f32,
     turn_speed: f32,
+    sight_radius: f32,
+    shot_radius: f32,
     shots: Vec<Shot>,
-    time_since_last_shot: f32,
+    weapon: Box<dyn Weapon>,
+    shot_effect: Option<StatusEffect>,
+    effects: StatusEffects,
+    // Place of the turret in the formation it follows, if it is in one
+    #[serde(default)]
+    formation: Option<FormationSlot>,
+    hack_timer: Timer,
+    target: Option<Point>,
+    team: Team,
+    dormant: bool,
+    wake_timer: Option<Timer>,
+    raising_alarm: bool,
+    enraged: bool,
+    #[serde(default)]
+    boss: bool,
+    // Name of the script that aims and fires this turret instead of its own timer
+    #[serde(default)]
+    script: Option<String>,
+    #[serde(default = "Timer::stopwatch")]
+    since_last_shot: Timer,
+    // What this turret calls in instead of firing, if it is a spawner
+    #[serde(default)]
+    spawner: Option<Spawner>,
+    // Spawns called in this tick, which are taken by the game before the tick is over, so they aren't saved
+    #[serde(skip)]
+    called_spawns: Vec<(SpawnKind, Point)>,
+    // Animations are only for show, so they aren't saved
+    #[serde(skip, default = "get_fire_animation")]
+    fire_animation: Animation,
 }
 
 impl Turret {
-    /// Create a new Turret at the given position with the given bounds
-    fn new(position: Point, bounds: (f32, f32)) -> Turret {
+    /// Create a new Turret at the given position in the given world with the given balance, moved inside the world
+    /// if it is outside
+    pub fn new(mut position: Point, world: &World, tuning: &Tuning) -> Turret {
+        position.keep_in_bounds(world);
         return Turret {
-            id: get_next_actor_id(),
+            id: 0,
             position,
-            bounds,
-            health: 100.0,
+            world: world.clone(),
+            radius: tuning.turret_radius,
+            health: Health::new(tuning.turret_health),
             rotation: 0.0,
-            turn_speed: 1.0,
+            turn_speed: tuning.turret_turn_speed,
+            sight_radius: tuning.turret_sight_radius,
+            shot_radius: tuning.shot_radius,
             shots: Vec::new(),
-            time_since_last_shot: 0.0,
+            weapon: Turret::get_standard_weapon(tuning),
+            shot_effect: None,
+            effects: StatusEffects::new(),
+            formation: None,
+            hack_timer: Timer::finished(),
+            target: None,
+            team: Team::Hostile,
+            dormant: false,
+            wake_timer: None,
+            raising_alarm: false,
+            enraged: false,
+            boss: false,
+            script: None,
+            since_last_shot: Timer::stopwatch(),
+            spawner: None,
+            called_spawns: Vec::new(),
+            fire_animation: get_fire_animation(),
         };
     }
 
-    /// Fire 4 shots
-    fn fire_shots(&mut self) {
-        for i in 0..4 {
-            // Create the velocity of the new shot and rotate it 90 degrees * i
-            let mut shot_velocity = Velocity::new(200.0, self.rotation);
-            shot_velocity.heading += i as f32 * (PI/2.0);
+    /// Get the weapon turrets are armed with unless they are given another, a volley of 4 shots that varies the
+    /// fire interval by up to 10% and the shot speed by up to 5%, with the given balance
+    fn get_standard_weapon(tuning: &Tuning) -> Box<dyn Weapon> {
+        return Box::new(Volley::new(4, Jitter::new(0.1, 0.05), tuning));
+    }
+
+    /// Create a new dormant Turret that is part of a formation whose anchor is at the given position, keeping the
+    /// given place in it
+    fn in_formation(anchor: (f32, f32), slot: FormationSlot, world: &World, tuning: &Tuning) -> Turret {
+        let (x, y) = slot.get_position(anchor);
+        let mut turret = Turret::new(Point::new(x, y), world, tuning);
+        turret.formation = Some(slot);
+        turret.dormant = true;
+        // Formations are made of many identical turrets, so they are varied more to break up their volleys
+        turret.weapon = Box::new(Volley::new(4, Jitter::new(0.2, 0.1), tuning));
+        return turret;
+    }
+
+    /// Wake this Turret up, raising the alarm so that nearby turrets wake up too
+    fn wake(&mut self) {
+        if self.dormant {
+            self.dormant = false;
+            self.wake_timer = None;
+            self.raising_alarm = true;
+        }
+    }
+
+    /// Create a new Turret whose shots apply the given status effect
+    pub fn with_shot_effect(position: Point, world: &World, tuning: &Tuning, effect: StatusEffect) -> Turret {
+        let mut turret = Turret::new(position, world, tuning);
+        turret.shot_effect = Some(effect);
+        return turret;
+    }
+
+    /// Create a new Turret on the player's team, which aims at the nearest enemy
+    pub fn friendly(position: Point, world: &World, tuning: &Tuning) -> Turret {
+        let mut turret = Turret::new(position, world, tuning);
+        turret.team = Team::Player;
+        // The player's turrets fire like clockwork
+        turret.weapon = Box::new(Volley::new(4, Jitter::none(), tuning));
+        return turret;
+    }
+
+    /// Create a new armored Turret that shrugs off kinetic damage but is weak to explosives
+    pub fn armored(position: Point, world: &World, tuning: &Tuning) -> Turret {
+        let mut turret = Turret::new(position, world, tuning);
+        turret.health.set_armor(Resistances::new(0.5, 0.0, -0.25));
+        // Armored turrets keep a steady rhythm but vary the speed of their shots
+        turret.weapon = Box::new(Volley::new(4, Jitter::new(0.05, 0.15), tuning));
+        return turret;
+    }
+
+    /// Create a new boss Turret, which is twice the size of a normal turret and much tougher
+    pub fn boss(position: Point, world: &World, tuning: &Tuning) -> Turret {
+        let mut turret = Turret::new(position, world, tuning);
+        turret.radius = tuning.boss_radius;
+        turret.health = Health::armored(tuning.boss_health, Resistances::new(0.25, 0.25, 0.0));
+        turret.boss = true;
+        return turret;
+    }
+
+    /// Create a new spawner Turret, which doesn't fire but calls in kamikaze drones beside itself, a few seconds
+    /// apart, until it has sent its share
+    pub fn spawner(position: Point, world: &World, tuning: &Tuning) -> Turret {
+        let mut turret = Turret::new(position, world, tuning);
+        turret.spawner = Some(Spawner::new(SpawnKind::Kamikaze, SPAWNER_KAMIKAZES, SPAWNER_INTERVAL));
+        return turret;
+    }
+
+    /// Create a new Turret that is aimed and fired by the script with the given name. In games built without
+    /// scripts it never fires
+    pub fn scripted(position: Point, world: &World, tuning: &Tuning, script: &str) -> Turret {
+        let mut turret = Turret::new(position, world, tuning);
+        turret.script = Some(script.to_string());
+        return turret;
+    }
 
-            // Initialize the position of the shot and move it away fro the turret
-            let mut shot_position = self.position.clone();
-            shot_position.move_distance(self.get_radius() + SHOT_RADIUS, shot_velocity.heading);
+    /// Create a new Turret of a kind defined by a mod
+    pub fn defined(position: Point, world: &World, tuning: &Tuning, definition: &EnemyDefinition) -> Turret {
+        let mut turret = if definition.is_armored() {
+            Turret::armored(position, world, tuning)
+        } else {
+            Turret::new(position, world, tuning)
+        };
+        turret.script = definition.get_script().map(String::from);
+        let (size, toughness) = definition.get_scale();
+        turret.radius *= size;
+        turret.health.scale(toughness);
+        return turret;
+    }
+
+    /// Arm this Turret with the given weapon in place of its own
+    pub fn set_weapon(&mut self, weapon: Box<dyn Weapon>) {
+        self.weapon = weapon;
+    }
 
-            // Create the shot
-            let shot = Shot::new(
-                shot_position,
-                self.bounds,
-                shot_velocity,
-                25.0,
-                3.0,
-            );
+    /// Get the muzzle the shots of this Turret leave from, aimed where it is pointing
+    fn get_muzzle(&self) -> Muzzle {
+        let mut muzzle = Muzzle::new(&self.position, &self.world, self.get_radius(), self.rotation, self.get_team());
+        muzzle.shot_radius = self.shot_radius;
+        muzzle.effect = self.shot_effect.clone();
+        return muzzle;
+    }
 
-            // Add the shot to the list of shots
-            self.shots.push(shot);
+    /// Fire the given pattern of shots, issued by the script of this Turret
+    fn fire_pattern(&mut self, pattern: &FirePattern) {
+        let muzzle = self.get_muzzle();
+        for heading in pattern.get_headings() {
+            self.shots.push(muzzle.shot(Velocity::new(pattern.speed, heading), pattern.damage, 3.0));
         }
+        self.since_last_shot.restart();
+        self.fire_animation.restart();
+    }
+
+    /// Get the color this Turret is drawn in: tinted if it is under a status effect, or green while it has been
+    /// hacked
+    fn get_color(&self) -> graphics::Color {
+        let base_color = if self.get_team() == Team::Player {
+            FRIENDLY_COLOR
+        } else if self.dormant {
+            DORMANT_COLOR
+        } else if self.enraged {
+            ENRAGED_COLOR
+        } else {
+            graphics::WHITE
+        };
+        return self.effects.get_tint(base_color);
     }
 }
 
@@ -288,9 +994,14 @@ impl Actor for Turret {
         return self.id;
     }
 
+    /// Give this Turret the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
     /// Ge the radius of this Turret
     fn get_radius(&self) -> f32 {
-        return TURRET_RADIUS;
+        return self.radius;
     }
 
     /// Get the position of this Turret
@@ -298,43 +1009,125 @@ impl Actor for Turret {
         return &self.position;
     }
 
-    /// Draw this Turret
-    fn draw(&self, ctx: &mut Context) -> GameResult {
-        let circle = graphics::Mesh::new_circle(
-            ctx,
-            graphics::DrawMode::fill(),
-            [0.0, 0.0],
-            self.get_radius(),
-            5.0,
-            graphics::WHITE,
-        )?;
-        graphics::draw(ctx, &circle, ([self.position.x, self.position.y], self.rotation, graphics::WHITE,))?;
+    /// Get the kind of this Turret
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Turret;
+    }
 
-        return Ok(());
+    /// Get the drawing of this Turret
+    fn as_render(&self) -> Option<&dyn Render> {
+        return Some(self);
+    }
+
+    /// Get the heading this Turret is aiming at
+    fn get_aim(&self) -> Option<f32> {
+        return Some(self.rotation);
+    }
+
+    /// Change the arena this Turret is in, moving it back inside if the arena has shrunk past it
+    fn set_world(&mut self, world: &World) {
+        self.world = world.clone();
+        self.position.keep_in_bounds(world);
     }
 
     /// Update the state of this Turret
     fn update(&mut self, dt: f32) {
-        // Rotate the turret
-        self.rotation += dt * self.turn_speed;
+        // Take damage from any burn effects and slow down if a slow effect is active
+        let burn_damage = self.effects.update(dt);
+        self.do_damage(Damage::energy(burn_damage));
+        let speed_multiplier = self.effects.get_speed_multiplier();
+        self.fire_animation.update(dt);
 
-        // If enough time has elapsed since the last shot, fire again
-        if self.time_since_last_shot > 2.0 {
-            self.fire_shots();
-            self.time_since_last_shot = 0.0;
+        // A dormant turret does nothing until it spots an enemy or the alarm raised by another turret reaches it
+        if self.dormant {
+            let spotted = match &self.target {
+                Some(target) => self.position.distance_to(target) < self.sight_radius,
+                None => false,
+            };
+            let woken = match &mut self.wake_timer {
+                Some(timer) => timer.update(dt) || timer.is_finished(),
+                None => spotted,
+            };
+            if woken {
+                self.wake();
+            }
+            return;
+        }
+
+        // Enrage the turret once its health drops low enough, making it turn and fire faster while it is hostile
+        if self.health.get_current() < self.health.get_max() * ENRAGE_HEALTH_FRACTION {
+            self.enraged = true;
+        }
+        let (turn_multiplier, fire_rate_multiplier) = if self.enraged && self.get_team() == Team::Hostile {
+            (ENRAGE_TURN_MULTIPLIER, ENRAGE_FIRE_RATE_MULTIPLIER)
         } else {
-            self.time_since_last_shot += dt;
+            (1.0, 1.0)
+        };
+
+        // A scripted turret is aimed and fired by its script, which is run before the actors are updated
+        self.hack_timer.update(dt);
+        if self.script.is_some() {
+            self.since_last_shot.update(dt);
+            return;
+        }
+
+        // Rotate the turret, or point it at the nearest enemy while it is on the player's team
+        match &self.target {
+            Some(target) if self.get_team() == Team::Player => self.rotation = self.position.heading_to(target),
+            _ => self.rotation += dt * self.turn_speed * speed_multiplier * turn_multiplier,
+        }
+
+        // A spawner calls in drones where it is pointing instead of firing, and stops while it has been hacked
+        if let Some(spawner) = &mut self.spawner {
+            if self.hack_timer.is_finished() {
+                if let Some(kind) = spawner.update(dt * fire_rate_multiplier) {
+                    let mut position = self.position;
+                    position.move_distance(SPAWNER_CALL_DISTANCE, self.rotation);
+                    position.keep_in_bounds(&self.world);
+                    self.called_spawns.push((kind, position));
+                }
+            }
+            return;
         }
+
+        // If enough time has elapsed since the last shot, fire again (sooner if an overdrive effect is active or
+        // the turret is enraged)
+        self.weapon.update(dt * self.effects.get_fire_rate_multiplier() * fire_rate_multiplier);
+        if self.weapon.is_ready() {
+            let muzzle = self.get_muzzle();
+            self.shots.extend(self.weapon.fire(&muzzle));
+            self.fire_animation.restart();
+        }
+    }
+
+    /// Get the damage that hitting this Turret causes
+    fn get_damage(&self) -> Damage {
+        return Damage::kinetic(100.0);
+    }
+
+    /// Do damage to this Turret, reduced by its armor. Being hurt wakes a dormant turret up
+    fn do_damage(&mut self, damage: Damage) -> f32 {
+        let amount = self.health.get_damage_taken(damage);
+        let lost = self.health.lose(amount);
+        if amount > 0.0 {
+            self.wake();
+        }
+        return lost;
+    }
+
+    /// Get the status effect hitting this Turret causes (turrets do not apply any)
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
     }
 
-    /// Get the amount of damage that hitting this Turret causes
-    fn get_damage(&self) -> f32 {
-        return 100.0;
+    /// Apply a status effect to this Turret
+    fn apply_status_effect(&mut self, effect: StatusEffect) {
+        self.effects.apply(effect);
     }
 
-    /// Do damage to this Turret
-    fn do_damage(&mut self, damage: f32) {
-        self.health -= damage;
+    /// Take the pickup this Turret gives (turrets never give pickups)
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
     }
 
     /// Get the new shots this Turret has created since last shot collection
@@ -347,122 +1140,152 @@ impl Actor for Turret {
         return shots_copy;
     }
 
-    /// Check if this Turret is dead
-    fn is_dead(&self) -> bool {
-        // Turret is dead if its health goes below 0
-        return self.health <= 0.0;
+    /// Create a boxed copy of this Turret
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
     }
-}
 
-/// Player data structure
-#[derive(Clone)]
-struct Player {
-    id: u32,
-    position: Point,
-    bounds: (f32, f32),
-    health: f32,
-    velocity: Velocity,
-    shots: Vec<Shot>,
-    current_pressed_key: KeyCode,
-}
+    /// Get the saved state of this Turret. A weapon that can't be saved, like one an embedder made, is saved as the
+    /// standard weapon of turrets in the default balance instead
+    fn to_saved(&self) -> Option<SavedActor> {
+        let mut saved = self.clone();
+        if self.weapon.to_saved().is_none() {
+            log::warn!(target: "turrets", "Turret {} is saved with the standard weapon, as its {} can't be saved",
+                       self.id, self.weapon.get_name());
+            saved.weapon = Turret::get_standard_weapon(&Tuning::DEFAULT);
+        }
+        return Some(SavedActor(ActorData::Turret(Box::new(saved))));
+    }
 
-impl Player {
-    /// Create a new Player at the given position with the given bounds
-    fn new(position: Point, bounds: (f32, f32)) -> Player {
-        return Player {
-            id: get_next_actor_id(),
-            position,
-            bounds,
-            health: 100.0,
-            velocity: Velocity::new(0.0, 0.0),
-            shots: Vec::new(),
-            current_pressed_key: KeyCode::Delete,
-        };
+    /// Turrets far from the player can be updated less often
+    fn allows_reduced_update_rate(&self) -> bool {
+        return true;
     }
 
-    /// Fire a shot out the front of the Player
-    fn fire_shot(&mut self) {
-        // Clone the velocity of the player and 200 to the speed to use as the speed of the shot
-        let mut shot_velocity = self.velocity.clone();
-        shot_velocity.speed += 200.0;
-
-        // Clone the position of the player and move it away from the player to use as the position of the shot
-        let mut shot_position = self.position.clone();
-        shot_position.move_distance(self.get_radius() + SHOT_RADIUS, shot_velocity.heading);
-
-        // Initialize the shot
-        let shot = Shot::new(
-            shot_position,
-            self.bounds,
-            shot_velocity,
-            20.0,
-            5.0,
-        );
+    /// Turrets far from the player can be parked, including hacked ones, whose hack is paused while they are parked.
+    /// Turrets built by the player keep defending while it is away
+    fn allows_streaming(&self) -> bool {
+        return self.team == Team::Hostile;
+    }
 
-        // Add the shot to the list of shots
-        self.shots.push(shot);
+    /// Resize this Turret, scale its health to the new balance, keeping the fraction of health it has left, and
+    /// rearm it with the new balance
+    fn retune(&mut self, old: &Tuning, new: &Tuning) {
+        let (old_health, new_health) = if self.boss {
+            self.radius = new.boss_radius;
+            (old.boss_health, new.boss_health)
+        } else {
+            // Turrets defined by mods keep their size relative to a normal turret
+            self.radius *= new.turret_radius / old.turret_radius.max(1.0);
+            (old.turret_health, new.turret_health)
+        };
+        self.health.rescale(old_health, new_health);
+        self.turn_speed = new.turret_turn_speed;
+        self.sight_radius = new.turret_sight_radius;
+        self.shot_radius = new.shot_radius;
+        self.weapon.retune(new);
     }
 
-    /// Handle a key down event
-    fn handle_key_down_event(&mut self, keycode: KeyCode, repeat: bool) {
-        match keycode {
-            // If the up arrow is pressed, move forwards
-            KeyCode::Up => {
-                self.velocity.speed = 150.0;
-            }
-            // If the down arrow is pressed, move backwards
-            KeyCode::Down => {
-                self.velocity.speed = -150.0;
-            }
-            // If the spacebar is pressed, fire a shot
-            KeyCode::Space => {
-                if !repeat {
-                    self.fire_shot();
-                }
-            }
-            // If any other key is pressed, track what key is currently pressed
-            _ => {
-                self.current_pressed_key = keycode;
-            }
+    /// Get the name of the script of this Turret, with where it is aimed and how long ago it fired. Dormant
+    /// turrets aren't run until they wake up
+    fn get_script(&self) -> Option<ScriptBinding<'_>> {
+        if self.dormant {
+            return None;
         }
+        return self.script.as_deref().map(|name| ScriptBinding {
+            name,
+            heading: self.rotation,
+            time_since_last_shot: self.since_last_shot.get_elapsed(),
+        });
     }
 
-    /// Handle a key up event
-    fn handle_key_up_event(&mut self, keycode: KeyCode) {
-        match keycode {
-            // If either the up arrow or the down arrow is released, stop moving
-            KeyCode::Up | KeyCode::Down => {
-                self.velocity.speed = 0.0;
-            }
-            // If any other key is pressed, track what key is currently pressed
-            _ => {
-                // If the released key was the last key to be pressed down (other than up down or space),
-                // reset the current key to delete (placeholder for no key)
-                if keycode == self.current_pressed_key {
-                    self.current_pressed_key = KeyCode::Delete;
-                }
-            }
+    /// Aim or fire this Turret as its script commands. Turrets can't move
+    fn apply_script_command(&mut self, command: &ScriptCommand) {
+        match command {
+            ScriptCommand::Aim { heading } => self.rotation = *heading,
+            ScriptCommand::FirePattern(pattern) => self.fire_pattern(pattern),
+            _ => (),
         }
     }
-}
 
-impl Actor for Player {
-    /// Get the ID of this Player
-    fn get_id(&self) -> u32 {
-        return self.id;
+    /// Get the health of this Turret
+    fn get_health(&self) -> Option<f32> {
+        return Some(self.health.get_current());
     }
 
-    /// Get the radius of this Player
-    fn get_radius(&self) -> f32 {
-        return PLAYER_RADIUS;
+    /// Get the full health of this Turret
+    fn get_max_health(&self) -> Option<f32> {
+        return Some(self.health.get_max());
     }
 
-    /// Get the position of this Player
-    fn get_position(&self) -> &Point {
-        return &self.position;
+    /// Get the team of this Turret, which is the player's while it has been hacked
+    fn get_team(&self) -> Team {
+        return if self.hack_timer.is_finished() { self.team } else { Team::Player };
     }
 
-    /// Draw this Player
+    /// Hack this Turret for the given time if it is damaged enough
+    fn hack(&mut self, duration: f32) -> bool {
+        if self.health.get_current() > HACKABLE_HEALTH {
+            return false;
+        }
+        self.hack_timer.restart_with(duration);
+        return true;
+    }
+
+    /// Aim at the given target while this Turret is on the player's team, or watch for it while it is dormant
+    fn set_target(&mut self, target: Option<Point>) {
+        self.target = target;
+    }
+
+    /// Get the ID of the formation this Turret follows, if it is in one
+    fn get_formation(&self) -> Option<u32> {
+        return self.formation.map(|slot| slot.get_formation());
+    }
+
+    /// Move this Turret to its place in its formation, whose anchor is at the given position
+    fn follow_formation(&mut self, anchor: (f32, f32)) {
+        if let Some(slot) = self.formation {
+            let (x, y) = slot.get_position(anchor);
+            self.position = Point::new(x, y);
+            self.position.keep_in_bounds(&self.world);
+        }
+    }
+
+    /// Check if this Turret has just woken up and is raising the alarm
+    fn take_alarm(&mut self) -> bool {
+        return std::mem::replace(&mut self.raising_alarm, false);
+    }
+
+    /// Wake this Turret up after the given delay if it is dormant
+    fn alert(&mut self, delay: f32) {
+        if self.dormant && self.wake_timer.is_none() {
+            self.wake_timer = Some(Timer::new(delay));
+        }
+    }
+
+    /// Take the drones this Turret has called in, if it is a spawner
+    fn take_spawns(&mut self) -> Vec<(SpawnKind, Point)> {
+        return std::mem::take(&mut self.called_spawns);
+    }
+
+    /// Get the mass of this Turret, which is fixed in place
+    fn get_mass(&self) -> Option<f32> {
+        return Some(f32::INFINITY);
+    }
+
+    /// Seed the jitter of the weapon of this Turret, which varies the time until its first volley
+    fn seed(&mut self, seed: u64) {
+        self.weapon.seed(seed);
+    }
+
+    /// Check if this Turret is dead
+    fn is_dead(&self) -> bool {
+        return self.health.is_depleted();
+    }
+}
+
+impl Render for Turret {
+    /// Draw this Turret
     fn draw(&self, ctx: &mut Context) -> GameResult {
         let circle = graphics::Mesh::new_circle(
             ctx,
@@ -472,39 +1295,352 @@ impl Actor for Player {
             5.0,
             graphics::WHITE,
         )?;
-        graphics::draw(ctx, &circle, ([self.position.x, self.position.y], self.velocity.heading, graphics::WHITE,))?;
+        graphics::draw(ctx, &circle, ([self.position.x, self.position.y], self.rotation, self.get_color(),))?;
 
         return Ok(());
     }
 
+    /// Get the sprite of this Turret, which is turned to where it is aiming and plays its firing animation after
+    /// every shot. Boss turrets and turrets on the player's team have images of their own
+    fn get_sprite(&self) -> Option<Sprite> {
+        let firing = self.fire_animation.is_playing();
+        let images = match (self.get_team() == Team::Player, self.boss, firing) {
+            (true, _, true) => FIRING_FRIENDLY_TURRET_IMAGES,
+            (true, _, false) => FRIENDLY_TURRET_IMAGES,
+            (false, true, true) => FIRING_BOSS_TURRET_IMAGES,
+            (false, true, false) => BOSS_TURRET_IMAGES,
+            (false, false, true) => FIRING_TURRET_IMAGES,
+            (false, false, false) => TURRET_IMAGES,
+        };
+        let sprite = Sprite::new(images, [self.position.x, self.position.y], self.get_radius() * 2.0, self.rotation,
+                                 self.get_color());
+        return Some(sprite.with_frame(self.fire_animation.get_frame()));
+    }
+}
+
+/// Player data structure
+#[derive(Clone, Serialize, Deserialize)]
+struct Player {
+    id: u32,
+    position: Point,
+    world: World,
+    stats: ShipStats,
+    health: Health,
+    velocity: Velocity,
+    turn_speed: f32,
+    shot_radius: f32,
+    weapon: Box<dyn Weapon>,
+    shots: Vec<Shot>,
+    #[serde(skip)]
+    current_pressed_key: Option<KeyCode>,
+    effects: StatusEffects,
+    shield_charges: u32,
+    extra_shots: u32,
+    shot_ricochets: u32,
+    damage_multiplier: f32,
+    damage_taken_multiplier: f32,
+    floor_speed_multiplier: f32,
+    grapple: Grapple,
+    knockback: Impulse,
+    #[serde(skip, default = "get_thrust_animation")]
+    thrust_animation: Animation,
+}
+
+impl Player {
+    /// Create a new Player of the given ship class at the given position in the given world with the given balance
+    fn new(position: Point, world: &World, class: ShipClass, tuning: &Tuning) -> Player {
+        let stats = class.get_stats();
+        return Player {
+            id: 0,
+            position,
+            world: world.clone(),
+            stats,
+            health: Health::new(stats.health),
+            velocity: Velocity::new(0.0, 0.0),
+            turn_speed: tuning.player_turn_speed,
+            shot_radius: tuning.shot_radius,
+            weapon: Box::new(Gun::new(stats.weapon, tuning)),
+            shots: Vec::new(),
+            current_pressed_key: None,
+            effects: StatusEffects::new(),
+            shield_charges: 0,
+            extra_shots: 0,
+            shot_ricochets: 0,
+            damage_multiplier: 1.0,
+            damage_taken_multiplier: 1.0,
+            floor_speed_multiplier: 1.0,
+            grapple: Grapple::new(),
+            knockback: Impulse::new(),
+            thrust_animation: get_thrust_animation(),
+        };
+    }
+
+    /// Get how much health the given damage takes from this Player, after its armor and any damage assist
+    fn get_damage_taken(&self, damage: Damage) -> f32 {
+        return self.health.get_damage_taken(damage) * self.damage_taken_multiplier;
+    }
+
+    /// Take a hit of the given damage, coming in along the given heading, and get knocked back along it by the
+    /// damage that got through. A hit the player is invulnerable to doesn't move it. Returns the health the player
+    /// actually lost
+    fn take_hit(&mut self, damage: Damage, heading: f32) -> f32 {
+        let taken = self.get_damage_taken(damage);
+        let lost = self.do_damage(damage);
+        if taken > 0.0 {
+            self.knockback.add(heading, Damage::new(taken, damage.get_kind()).get_knockback());
+        }
+        return lost;
+    }
+
+    /// Apply a perk chosen between waves
+    fn apply_perk(&mut self, perk: Perk) {
+        match perk {
+            Perk::ExtraShots => self.extra_shots += 2,
+            Perk::Ricochet => self.shot_ricochets += 1,
+            Perk::Speed => self.stats.speed *= 1.2,
+            Perk::Damage => self.damage_multiplier *= 1.25,
+            Perk::Health => self.health.raise_max(25.0),
+        }
+    }
+
+    /// Apply the effect of a pickup the Player has collected
+    fn apply_pickup(&mut self, pickup: PickupKind) {
+        match pickup {
+            PickupKind::Shield(charges) => {
+                self.shield_charges += charges;
+            }
+            // Points are added to the score of the run rather than to the player
+            PickupKind::Score(_) => (),
+        }
+    }
+
+    /// Use up a shield charge to absorb an incoming hit, returning whether the hit was absorbed
+    fn absorb_hit(&mut self) -> bool {
+        if self.shield_charges > 0 {
+            self.shield_charges -= 1;
+            return true;
+        }
+        return false;
+    }
+
+    /// Fire the Player's weapon out the front of the Player, if it is ready
+    fn fire_shot(&mut self) {
+        if !self.weapon.is_ready() {
+            return;
+        }
+        let mut muzzle = Muzzle::new(&self.position, &self.world, self.get_radius(), 0.0, Team::Player);
+        // Shots are fired the way the Player is facing and carry its speed
+        muzzle.velocity = self.velocity.clone();
+        muzzle.shot_radius = self.shot_radius;
+        muzzle.damage_multiplier = self.damage_multiplier;
+        muzzle.ricochets = self.shot_ricochets;
+        muzzle.extra_shots = self.extra_shots;
+        self.shots.extend(self.weapon.fire(&muzzle));
+    }
+
+    /// Handle a key down event
+    fn handle_key_down_event(&mut self, keycode: KeyCode, repeat: bool) {
+        match keycode {
+            // If the up arrow is pressed, move forwards
+            KeyCode::Up => {
+                self.velocity.speed = self.stats.speed;
+            }
+            // If the down arrow is pressed, move backwards
+            KeyCode::Down => {
+                self.velocity.speed = -self.stats.speed;
+            }
+            // If the spacebar is pressed, fire a shot
+            KeyCode::Space => {
+                if !repeat {
+                    self.fire_shot();
+                }
+            }
+            // If G is pressed, fire the grapple forwards, or let go of it if it is already out
+            KeyCode::G => {
+                if !repeat {
+                    self.toggle_grapple();
+                }
+            }
+            // If any other key is pressed, track what key is currently pressed
+            _ => {
+                self.current_pressed_key = Some(keycode);
+            }
+        }
+    }
+
+    /// Fire the grapple forwards, or let go of it if it is already out
+    fn toggle_grapple(&mut self) {
+        if self.grapple.is_active() {
+            self.grapple.release();
+        } else {
+            self.grapple.fire(&self.position, self.velocity.heading);
+        }
+    }
+
+    /// Press the controls of the given action instead of the keyboard
+    fn apply_action(&mut self, action: Action) {
+        self.velocity.speed = self.stats.speed * action.thrust.signum() as f32;
+        self.current_pressed_key = match action.turn.signum() {
+            1 => Some(KeyCode::Right),
+            -1 => Some(KeyCode::Left),
+            _ => None,
+        };
+        if action.fire {
+            self.fire_shot();
+        }
+        if action.grapple {
+            self.toggle_grapple();
+        }
+    }
+
+    /// Handle a key up event
+    fn handle_key_up_event(&mut self, keycode: KeyCode) {
+        match keycode {
+            // If either the up arrow or the down arrow is released, stop moving
+            KeyCode::Up | KeyCode::Down => {
+                self.velocity.speed = 0.0;
+            }
+            // If any other key is pressed, track what key is currently pressed
+            _ => {
+                // If the released key was the last key to be pressed down (other than up down or space),
+                // no key is pressed any more
+                if self.current_pressed_key == Some(keycode) {
+                    self.current_pressed_key = None;
+                }
+            }
+        }
+    }
+}
+
+impl Actor for Player {
+    /// Get the ID of this Player
+    fn get_id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Give this Player the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
+    /// Get the radius of this Player
+    fn get_radius(&self) -> f32 {
+        return self.stats.radius;
+    }
+
+    /// Get the position of this Player
+    fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the kind of this Player
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Player;
+    }
+
+    /// Turn this Player at the speed of the new balance, and rearm it with the new balance
+    fn retune(&mut self, _old: &Tuning, new: &Tuning) {
+        self.turn_speed = new.player_turn_speed;
+        self.shot_radius = new.shot_radius;
+        self.weapon.retune(new);
+    }
+
+    /// Get the drawing of this Player
+    fn as_render(&self) -> Option<&dyn Render> {
+        return Some(self);
+    }
+
+    /// Get the velocity of this Player
+    fn get_velocity(&self) -> Option<&Velocity> {
+        return Some(&self.velocity);
+    }
+
+    /// Get the heading this Player is aiming at, which is the way it is facing
+    fn get_aim(&self) -> Option<f32> {
+        return Some(self.velocity.heading);
+    }
+
+    /// Get the mass of this Player, which grows with its ship
+    fn get_mass(&self) -> Option<f32> {
+        return Some(PHYSICS_DENSITY * self.stats.radius.powi(2));
+    }
+
+    /// Move this Player out of what it ran into, and let the push knock it back
+    fn bounce(&mut self, offset: &Vec2, push: &Vec2) {
+        self.position += *offset;
+        self.knockback.push(push);
+    }
+
+    /// Change the arena this Player is in, moving it back inside if the arena has shrunk past it
+    fn set_world(&mut self, world: &World) {
+        self.world = world.clone();
+        self.position.keep_in_bounds(world);
+    }
+
+    /// Set the multiplier the floor under this Player applies to its speed
+    fn set_floor_speed_multiplier(&mut self, multiplier: f32) {
+        self.floor_speed_multiplier = multiplier;
+    }
+
     /// Update the state of this Player
     fn update(&mut self, dt: f32) {
+        // Take damage from any burn effects and slow down if a slow effect is active
+        let burn_damage = self.effects.update(dt);
+        self.do_damage(Damage::energy(burn_damage));
+        let speed_multiplier = self.effects.get_speed_multiplier();
+        self.weapon.update(dt);
+        self.thrust_animation.update(dt);
+
+        let turn = self.turn_speed * speed_multiplier * dt;
         match self.current_pressed_key {
             // If the right arrow key is being held down, turn right
-            KeyCode::Right => {
-                self.velocity.heading += 0.05;
+            Some(KeyCode::Right) => {
+                self.velocity.heading += turn;
             }
             // If the left arrow key is being held down, turn left
-            KeyCode::Left => {
-                self.velocity.heading -= 0.05;
+            Some(KeyCode::Left) => {
+                self.velocity.heading -= turn;
             }
             _ => ()
         }
 
-        // Move the player
-        self.position.move_time(dt, &self.velocity);
-        // Prevent the player from leaving the bounds of the window
-        self.position.keep_in_bounds(self.bounds);
+        // Let the grapple pull the player, or move the player at its speed, as changed by effects and the floor
+        if !self.grapple.update(dt, &mut self.position, self.stats.radius, &self.world) {
+            let mut velocity = self.velocity.clone();
+            velocity.speed *= speed_multiplier * self.floor_speed_multiplier;
+            self.position.move_time(dt, &velocity);
+        }
+        // Let any knockback from hits push the player
+        self.knockback.update(dt, &mut self.position);
+        // Keep the player in the arena, unless its edge destroys the ship
+        if self.world.confine(&mut self.position, &mut self.velocity) {
+            self.health.lose(self.health.get_current());
+        }
     }
 
     /// Get the damage the Player does when collided with
-    fn get_damage(&self) -> f32 {
-        return 100.0;
+    fn get_damage(&self) -> Damage {
+        return Damage::kinetic(100.0);
+    }
+
+    /// Do damage to this Player, reduced by its armor and any damage assist
+    fn do_damage(&mut self, damage: Damage) -> f32 {
+        return self.health.lose(self.get_damage_taken(damage));
+    }
+
+    /// Get the status effect colliding with the Player causes (the player does not apply any)
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
     }
 
-    /// Do damage to this Player
-    fn do_damage(&mut self, damage: f32) {
-        self.health -= damage;
+    /// Apply a status effect to this Player
+    fn apply_status_effect(&mut self, effect: StatusEffect) {
+        self.effects.apply(effect);
+    }
+
+    /// Take the pickup this Player gives (the player never gives pickups)
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
     }
 
     /// Get the new shots this Player has created since last shot collection
@@ -517,164 +1653,4169 @@ impl Actor for Player {
         return shots_copy;
     }
 
+    /// Create a boxed copy of this Player
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// The Player is on its own team
+    fn get_team(&self) -> Team {
+        return Team::Player;
+    }
+
     /// Check if this player is dead
     fn is_dead(&self) -> bool {
-        // The player is dead if health goes below 0
-        return self.health <= 0.0;
+        return self.health.is_depleted();
     }
 }
 
-/// Data structure to store the main state of the game
-pub struct MainState {
-    player: Player,
-    actors: Vec<Box<dyn Actor>>,
-}
+impl Render for Player {
+    /// Draw this Player
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let circle = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            [0.0, 0.0],
+            self.get_radius(),
+            5.0,
+            graphics::WHITE,
+        )?;
+        // Tint the player if it is under a status effect
+        let color = self.effects.get_tint(graphics::WHITE);
+        graphics::draw(ctx, &circle, ([self.position.x, self.position.y], self.velocity.heading, color,))?;
 
-impl MainState {
-    /// Initialize the state of the game
-    pub fn new(ctx: &Context) -> MainState {
-        // Get the size of the window
-        let bounds = graphics::drawable_size(ctx);
-        let (width, height) = bounds;
+        return self.draw_overlay(ctx);
+    }
 
-        // Initialize a new MainState object
-        let mut state = MainState {
-            // Initialize the Player
-            player: Player::new(Point::new(width/2.0, height/2.0), bounds),
-            // Initialize a vector to hold the actors in the game
-            actors: Vec::new(),
-        };
+    /// Get the sprite of this Player, which points the way it is heading and plays its engine animation while it
+    /// is moving
+    fn get_sprite(&self) -> Option<Sprite> {
+        let images = if self.velocity.speed != 0.0 { THRUSTING_PLAYER_IMAGES } else { PLAYER_IMAGES };
+        let sprite = Sprite::new(images, [self.position.x, self.position.y], self.get_radius() * 2.0,
+                                 self.velocity.heading, self.effects.get_tint(graphics::WHITE));
+        return Some(sprite.with_frame(self.thrust_animation.get_frame()));
+    }
 
-        // Create 4 turrets and add them to the game
-        state.add_actor(Box::new(Turret::new(Point::new(width/4.0, height/4.0), bounds)));
-        state.add_actor(Box::new(Turret::new(Point::new(width/4.0, height*0.75), bounds)));
-        state.add_actor(Box::new(Turret::new(Point::new(width*0.75, height/4.0), bounds)));
-        state.add_actor(Box::new(Turret::new(Point::new(width*0.75, height*0.75), bounds)));
+    /// Draw the grapple of this Player and its shield bubble
+    fn draw_overlay(&self, ctx: &mut Context) -> GameResult {
+        self.grapple.draw(ctx, &self.position)?;
 
-        return state;
-    }
+        // Draw the shield bubble with the number of hits it has left
+        if self.shield_charges > 0 {
+            let bubble = graphics::Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::stroke(2.0),
+                [0.0, 0.0],
+                self.get_radius() + 8.0,
+                1.0,
+                SHIELD_COLOR,
+            )?;
+            graphics::draw(ctx, &bubble, ([self.position.x, self.position.y],))?;
 
-    /// Add an actor to the game
-    fn add_actor(&mut self, actor: Box<dyn Actor>) {
-        self.actors.push(actor);
+            let counter = graphics::Text::new(self.shield_charges.to_string());
+            let counter_position = [self.position.x + self.get_radius(), self.position.y - self.get_radius() - 16.0];
+            graphics::draw(ctx, &counter, (counter_position, SHIELD_COLOR))?;
+        }
+
+        return Ok(());
     }
+}
 
-    /// Collect any new shots created by any actor
-    fn collect_shots(&mut self) {
-        // Create a vector to hold all of the new shots
-        let mut new_shots: Vec<Shot> = Vec::new();
+/// The sides an Actor can be on
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Team {
+    /// The player, its shots, and anything fighting for it
+    Player,
+    /// The turrets and their shots
+    Hostile,
+    /// Actors that don't fight, like pickups
+    Neutral,
+}
 
-        // Collect the shots from the player and add them to the list of shots
-        new_shots.append(&mut self.player.collect_shots());
+impl Team {
+    /// Check if actors of this team and another team harm each other when they collide. Hostile actors still
+    /// hit each other, but nothing on the player's team hurts anything else on it
+    pub fn can_harm(&self, other: Team) -> bool {
+        return !(*self == Team::Player && other == Team::Player);
+    }
+}
 
-        // Collect the shots from all the other actors and add them to the list of shots
-        for actor in &mut self.actors {
-            new_shots.append(&mut actor.collect_shots());
-        }
+/// The kinds of Actor in the game
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ActorKind {
+    Player,
+    Shot,
+    Turret,
+    Pickup,
+    Drone,
+    Kamikaze,
+    Bomb,
+    Base,
+    /// An actor added from outside the game, with the given name
+    Custom(&'static str),
+}
 
-        // Add all the shots to the game
-        for shot in new_shots {
-            self.add_actor(Box::new(shot));
-        }
+impl ActorKind {
+    /// Get the name of this kind, which population caps, statistics, and snapshots refer to
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            ActorKind::Player => "player",
+            ActorKind::Shot => "shot",
+            ActorKind::Turret => "turret",
+            ActorKind::Pickup => "pickup",
+            ActorKind::Drone => "drone",
+            ActorKind::Kamikaze => "kamikaze",
+            ActorKind::Bomb => "bomb",
+            ActorKind::Base => "base",
+            ActorKind::Custom(name) => name,
+        };
     }
+}
 
-    /// Handle collision between all of the actors
-    fn handle_collisions(&mut self) {
-        // Loop through all of the actors in the game
-        for i in 0..self.actors.len() {
-            // Get the list of actors after the current actor in the list
-            let (head, tail) = self.actors.split_at_mut(i+1);
-            // Get a reference to the current actors
-            let actor = &mut head[i];
+/// The different kinds of pickup
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub enum PickupKind {
+    /// Shield bubble that absorbs the given number of hits
+    Shield(u32),
+    /// Gem worth the given number of points, dropped by destroyed enemies
+    Score(u32),
+}
 
-            // Check if the current actor has collided with the player
-            if self.player.check_for_collision(actor) {
-                // If it has, do damage to the player and the actor
-                self.player.do_damage(actor.get_damage());
-                actor.do_damage(self.player.get_damage());
-            }
+/// Pickup data structure
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Pickup {
+    id: u32,
+    position: Point,
+    kind: PickupKind,
+    radius: f32,
+    lifespan: Timer,
+    collected: bool,
+    target: Option<Point>,
+    #[serde(default = "get_full_speed")]
+    floor_speed_multiplier: f32,
+}
 
-            // Loop over the remaining actors in the list
-            for j in 0..tail.len() {
-                // Get a reference to the next actor in the list
-                let other_actor = &mut tail[j];
-                // Check if the two actors have collided
-                if actor.check_for_collision(other_actor) {
-                    // If they have, do damage to both actors
-                    actor.do_damage(other_actor.get_damage());
-                    other_actor.do_damage(actor.get_damage());
-                }
-            }
-        }
+impl Pickup {
+    /// Create a new Pickup of the given kind at the given position, sized by the given balance
+    pub fn new(position: Point, kind: PickupKind, tuning: &Tuning) -> Pickup {
+        return Pickup {
+            id: 0,
+            position,
+            kind,
+            radius: Pickup::get_tuned_radius(kind, tuning),
+            lifespan: Timer::new(PICKUP_LIFESPAN),
+            collected: false,
+            target: None,
+            floor_speed_multiplier: 1.0,
+        };
     }
 
-    /// Remove the dead actors from the game
-    fn remove_dead(&mut self) {
-        // Only keep the actors that are not dead in the list of actors
-        self.actors.retain(|actor| !actor.is_dead());
+    /// Get the radius of a Pickup of the given kind in the given balance
+    fn get_tuned_radius(kind: PickupKind, tuning: &Tuning) -> f32 {
+        return match kind {
+            PickupKind::Score(_) => tuning.gem_radius,
+            _ => tuning.pickup_radius,
+        };
+    }
+
+    /// Check if this Pickup is drawn towards the player when it is close
+    fn is_magnetic(&self) -> bool {
+        return matches!(self.kind, PickupKind::Score(_));
     }
 }
 
-impl EventHandler for MainState {
-    /// Update the MainState
-    fn update(&mut self, ctx: &mut Context) -> GameResult {
-        while timer::check_update_time(ctx, FPS) {
-            // Update the state of the player
-            self.player.update(1.0 / FPS as f32);
-            // Update the state of every actor
-            for actor in &mut self.actors {
-                actor.update(1.0 / FPS as f32);
-            }
+impl Actor for Pickup {
+    /// Get the ID of this Pickup
+    fn get_id(&self) -> u32 {
+        return self.id;
+    }
 
-            // Collect shots
-            self.collect_shots();
-            // Handle collisions
-            self.handle_collisions();
-            // Remove dead actors
-            self.remove_dead();
+    /// Give this Pickup the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
 
-            // If the player has died, end the game
-            if self.player.is_dead() {
-                event::quit(ctx);
-            }
-        }
+    /// Get the radius of this Pickup
+    fn get_radius(&self) -> f32 {
+        return self.radius;
+    }
 
-        return Ok(());
+    /// Get the position of this Pickup
+    fn get_position(&self) -> &Point {
+        return &self.position;
     }
 
-    /// Draw the game
-    fn draw(&mut self, ctx: &mut Context) -> GameResult {
-        // Clear the canvas
-        graphics::clear(ctx, graphics::BLACK);
+    /// Get the kind of this Pickup
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Pickup;
+    }
 
-        // Draw the player
-        self.player.draw(ctx)?;
-        // Draw all the actors
-        for actor in &self.actors {
-            actor.draw(ctx)?;
-        }
+    /// Resize this Pickup to the new balance
+    fn retune(&mut self, _old: &Tuning, new: &Tuning) {
+        self.radius = Pickup::get_tuned_radius(self.kind, new);
+    }
 
-        // Show the game to the user
-        graphics::present(ctx)?;
+    /// Get the drawing of this Pickup
+    fn as_render(&self) -> Option<&dyn Render> {
+        return Some(self);
+    }
 
-        timer::yield_now();
+    /// Move this Pickup back inside the given arena if the arena has shrunk past it
+    fn set_world(&mut self, world: &World) {
+        self.position.keep_in_bounds(world);
+    }
 
-        return Ok(());
+    /// Set the multiplier the floor under this Pickup applies to the speed it drifts towards the player at
+    fn set_floor_speed_multiplier(&mut self, multiplier: f32) {
+        self.floor_speed_multiplier = multiplier;
     }
 
-    /// Handle key down event
-    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods, repeat: bool) {
-        // If escape is pressed, end the game
-        if keycode == KeyCode::Escape {
-            event::quit(ctx);
+    /// Update the state of this Pickup
+    fn update(&mut self, dt: f32) {
+        // Pickups disappear if they are not collected in time
+        self.lifespan.update(dt);
+
+        // Magnetic pickups drift towards the player once it is close enough, faster the closer it gets, as far as
+        // the floor under them allows
+        if let Some(target) = &self.target {
+            let distance = self.position.distance_to(target);
+            if self.is_magnetic() && distance < GEM_MAGNET_RADIUS {
+                let speed = (GEM_MAGNET_SPEED * (1.0 - distance / GEM_MAGNET_RADIUS) + GEM_MAGNET_SPEED / 4.0)
+                    * self.floor_speed_multiplier;
+                let heading = self.position.heading_to(target);
+                self.position.move_distance((speed * dt).min(distance), heading);
+            }
         }
-        // Forward the key event to the player object
-        self.player.handle_key_down_event(keycode, repeat);
+    }
+
+    /// Get the damage touching this Pickup causes (none)
+    fn get_damage(&self) -> Damage {
+        return Damage::kinetic(0.0);
+    }
+
+    /// Do damage to this Pickup (pickups cannot be destroyed)
+    fn do_damage(&mut self, _damage: Damage) -> f32 {
+        return 0.0;
+    }
+
+    /// Get the status effect touching this Pickup causes (none)
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
+    }
+
+    /// Apply a status effect to this Pickup (pickups are not affected by status effects)
+    fn apply_status_effect(&mut self, _effect: StatusEffect) {}
+
+    /// Take the pickup, which can only be done once
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        if self.collected {
+            return None;
+        }
+        self.collected = true;
+        return Some(self.kind);
+    }
+
+    /// Get any new Shots this Pickup has created (this will always be an empty vector)
+    fn collect_shots(&mut self) -> Vec<Shot> {
+        return Vec::new();
+    }
+
+    /// Create a boxed copy of this Pickup
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// Get the saved state of this Pickup
+    fn to_saved(&self) -> Option<SavedActor> {
+        return Some(SavedActor(ActorData::Pickup(self.clone())));
+    }
+
+    /// Pickups don't take sides
+    fn get_team(&self) -> Team {
+        return Team::Neutral;
+    }
+
+    /// Remember where the player is, which magnetic pickups drift towards
+    fn set_target(&mut self, target: Option<Point>) {
+        self.target = target;
+    }
+
+    /// Check if this Pickup has been collected or has expired
+    fn is_dead(&self) -> bool {
+        return self.collected || self.lifespan.is_finished();
+    }
+}
+
+impl Render for Pickup {
+    /// Draw this Pickup
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let color = match self.kind {
+            PickupKind::Shield(_) => SHIELD_COLOR,
+            PickupKind::Score(_) => GEM_COLOR,
+        };
+        let ring = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::stroke(2.0),
+            [0.0, 0.0],
+            self.get_radius(),
+            0.5,
+            color,
+        )?;
+        graphics::draw(ctx, &ring, ([self.position.x, self.position.y],))?;
+
+        return Ok(());
+    }
+}
+
+/// Drone that flies in a straight line, either a friendly drone the player escorts across the arena or a
+/// hostile drone attacking the player's base
+#[derive(Clone, Serialize, Deserialize)]
+struct Drone {
+    id: u32,
+    position: Point,
+    world: World,
+    radius: f32,
+    health: Health,
+    velocity: Velocity,
+    damage: f32,
+    floor_speed_multiplier: f32,
+    team: Team,
+    #[serde(default)]
+    knockback: Impulse,
+}
+
+impl Drone {
+    /// Create a new friendly Drone at the given position that flies right towards the other side of the world, with
+    /// the given balance
+    fn new(position: Point, world: &World, tuning: &Tuning) -> Drone {
+        return Drone {
+            id: 0,
+            position,
+            world: world.clone(),
+            radius: tuning.drone_radius,
+            health: Health::new(tuning.drone_health),
+            velocity: Velocity::new(tuning.drone_speed, 0.0),
+            // The player's drones don't hurt what they touch
+            damage: 0.0,
+            floor_speed_multiplier: 1.0,
+            team: Team::Player,
+            knockback: Impulse::new(),
+        };
+    }
+
+    /// Create a new hostile Drone at the given position that flies towards the given target, with the given balance
+    fn attacker(position: Point, world: &World, tuning: &Tuning, target: &Point) -> Drone {
+        let heading = position.heading_to(target);
+        return Drone {
+            id: 0,
+            position,
+            world: world.clone(),
+            radius: tuning.drone_radius,
+            health: Health::new(tuning.attacker_health),
+            velocity: Velocity::new(tuning.attacker_speed, heading),
+            damage: tuning.attacker_damage,
+            floor_speed_multiplier: 1.0,
+            team: Team::Hostile,
+            knockback: Impulse::new(),
+        };
+    }
+}
+
+impl Actor for Drone {
+    /// Get the ID of this Drone
+    fn get_id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Give this Drone the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
+    /// Get the radius of this Drone
+    fn get_radius(&self) -> f32 {
+        return self.radius;
+    }
+
+    /// Get the position of this Drone
+    fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the kind of this Drone
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Drone;
+    }
+
+    /// Get the drawing of this Drone
+    fn as_render(&self) -> Option<&dyn Render> {
+        return Some(self);
+    }
+
+    /// Change the arena this Drone is in, moving it back inside if the arena has shrunk past it
+    fn set_world(&mut self, world: &World) {
+        self.world = world.clone();
+        self.position.keep_in_bounds(world);
+    }
+
+    /// Update the state of this Drone
+    fn update(&mut self, dt: f32) {
+        // Fly towards the drone's destination at the speed the floor allows, as pushed by any collision, and stop
+        // at the edge unless the arena's edge does something else
+        let mut velocity = self.velocity.clone();
+        velocity.speed *= self.floor_speed_multiplier;
+        self.position.move_time(dt, &velocity);
+        self.knockback.update(dt, &mut self.position);
+        if self.world.confine(&mut self.position, &mut self.velocity) {
+            self.health.lose(self.health.get_current());
+        }
+    }
+
+    /// Get the health of this Drone
+    fn get_health(&self) -> Option<f32> {
+        return Some(self.health.get_current());
+    }
+
+    /// Get the full health of this Drone
+    fn get_max_health(&self) -> Option<f32> {
+        return Some(self.health.get_max());
+    }
+
+    /// Get the velocity of this Drone
+    fn get_velocity(&self) -> Option<&Velocity> {
+        return Some(&self.velocity);
+    }
+
+    /// Get the mass of this Drone
+    fn get_mass(&self) -> Option<f32> {
+        return Some(PHYSICS_DENSITY * self.get_radius().powi(2));
+    }
+
+    /// Move this Drone out of what it ran into, and let the push knock it off course for a moment
+    fn bounce(&mut self, offset: &Vec2, push: &Vec2) {
+        self.position += *offset;
+        self.knockback.push(push);
+    }
+
+    /// Set the multiplier the floor under this Drone applies to its speed
+    fn set_floor_speed_multiplier(&mut self, multiplier: f32) {
+        self.floor_speed_multiplier = multiplier;
+    }
+
+    /// Change the speed and scale the health of this Drone to the new balance
+    fn retune(&mut self, old: &Tuning, new: &Tuning) {
+        let (old_health, new_health, speed) = if self.team == Team::Player {
+            (old.drone_health, new.drone_health, new.drone_speed)
+        } else {
+            self.damage = new.attacker_damage;
+            (old.attacker_health, new.attacker_health, new.attacker_speed)
+        };
+        self.health.rescale(old_health, new_health);
+        self.velocity.speed = speed;
+        self.radius = new.drone_radius;
+    }
+
+    /// Get the damage touching this Drone causes (none if it is friendly)
+    fn get_damage(&self) -> Damage {
+        return Damage::kinetic(self.damage);
+    }
+
+    /// Do damage to this Drone
+    fn do_damage(&mut self, damage: Damage) -> f32 {
+        return self.health.damage(damage);
+    }
+
+    /// Get the status effect touching this Drone causes (none)
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
+    }
+
+    /// Apply a status effect to this Drone (the drone is shielded from status effects)
+    fn apply_status_effect(&mut self, _effect: StatusEffect) {}
+
+    /// Take the pickup this Drone gives (drones never give pickups)
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
+    }
+
+    /// Get any new Shots this Drone has created (this will always be an empty vector)
+    fn collect_shots(&mut self) -> Vec<Shot> {
+        return Vec::new();
+    }
+
+    /// Create a boxed copy of this Drone
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// Get the saved state of this Drone
+    fn to_saved(&self) -> Option<SavedActor> {
+        return Some(SavedActor(ActorData::Drone(self.clone())));
+    }
+
+    /// Drones far from the player can be updated less often
+    fn allows_reduced_update_rate(&self) -> bool {
+        return true;
+    }
+
+    /// Get the team of this Drone
+    fn get_team(&self) -> Team {
+        return self.team;
+    }
+
+    /// Check if this Drone has been destroyed
+    fn is_dead(&self) -> bool {
+        return self.health.is_depleted();
+    }
+}
+
+impl Render for Drone {
+    /// Draw this Drone with a health bar above it
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let color = if self.team == Team::Player { FRIENDLY_COLOR } else { HOSTILE_DRONE_COLOR };
+        let circle = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            [0.0, 0.0],
+            self.get_radius(),
+            1.0,
+            color,
+        )?;
+        graphics::draw(ctx, &circle, ([self.position.x, self.position.y],))?;
+
+        let radius = self.radius;
+        let health_fraction = self.health.get_fraction();
+        let bar = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(-radius, -radius - 10.0, radius * 2.0 * health_fraction, 4.0),
+            color,
+        )?;
+        graphics::draw(ctx, &bar, ([self.position.x, self.position.y],))?;
+
+        return Ok(());
+    }
+}
+
+/// Small, fast drone that flies in a swarm and dives into the player to explode
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Kamikaze {
+    id: u32,
+    position: Point,
+    world: World,
+    radius: f32,
+    health: Health,
+    velocity: Velocity,
+    turn_speed: f32,
+    damage: f32,
+    target: Option<Point>,
+    flock_heading: Option<f32>,
+    floor_speed_multiplier: f32,
+    #[serde(default)]
+    knockback: Impulse,
+}
+
+impl Kamikaze {
+    /// Create a new Kamikaze at the given position with the given balance, flying with the given heading
+    pub fn new(position: Point, world: &World, tuning: &Tuning, heading: f32) -> Kamikaze {
+        return Kamikaze {
+            id: 0,
+            position,
+            world: world.clone(),
+            radius: tuning.kamikaze_radius,
+            health: Health::new(tuning.kamikaze_health),
+            velocity: Velocity::new(tuning.kamikaze_speed, heading),
+            turn_speed: tuning.kamikaze_turn_speed,
+            damage: tuning.kamikaze_damage,
+            target: None,
+            flock_heading: None,
+            floor_speed_multiplier: 1.0,
+            knockback: Impulse::new(),
+        };
+    }
+}
+
+impl Actor for Kamikaze {
+    /// Get the ID of this Kamikaze
+    fn get_id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Give this Kamikaze the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
+    /// Get the radius of this Kamikaze
+    fn get_radius(&self) -> f32 {
+        return self.radius;
+    }
+
+    /// Get the position of this Kamikaze
+    fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the kind of this Kamikaze
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Kamikaze;
+    }
+
+    /// Get the drawing of this Kamikaze
+    fn as_render(&self) -> Option<&dyn Render> {
+        return Some(self);
+    }
+
+    /// Change the arena this Kamikaze is in, moving it back inside if the arena has shrunk past it
+    fn set_world(&mut self, world: &World) {
+        self.world = world.clone();
+        self.position.keep_in_bounds(world);
+    }
+
+    /// Update the state of this Kamikaze
+    fn update(&mut self, dt: f32) {
+        // Turn towards the heading the flock wants, or straight at the target when flying alone
+        let desired = match (&self.flock_heading, &self.target) {
+            (Some(heading), _) => *heading,
+            (None, Some(target)) => self.position.heading_to(target),
+            (None, None) => self.velocity.heading,
+        };
+        // Wrap the difference to the range -PI to PI, and turn no faster than the drone can
+        let difference = (desired - self.velocity.heading + PI).rem_euclid(2.0 * PI) - PI;
+        let max_turn = self.turn_speed * dt;
+        self.velocity.heading += difference.max(-max_turn).min(max_turn);
+
+        let mut velocity = self.velocity.clone();
+        velocity.speed *= self.floor_speed_multiplier;
+        self.position.move_time(dt, &velocity);
+        self.knockback.update(dt, &mut self.position);
+        if self.world.confine(&mut self.position, &mut self.velocity) {
+            self.health.lose(self.health.get_current());
+        }
+    }
+
+    /// Get the damage this Kamikaze does when it explodes against something
+    fn get_damage(&self) -> Damage {
+        return Damage::explosive(self.damage);
+    }
+
+    /// Do damage to this Kamikaze (kamikazes have no armor)
+    fn do_damage(&mut self, damage: Damage) -> f32 {
+        return self.health.damage(damage);
+    }
+
+    /// Get the status effect this Kamikaze applies (kamikazes apply none)
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
+    }
+
+    /// Apply a status effect to this Kamikaze (kamikazes are not affected by status effects)
+    fn apply_status_effect(&mut self, _effect: StatusEffect) {}
+
+    /// Take the pickup this Kamikaze gives (kamikazes never give pickups)
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
+    }
+
+    /// Get any new Shots this Kamikaze has created (kamikazes never shoot)
+    fn collect_shots(&mut self) -> Vec<Shot> {
+        return Vec::new();
+    }
+
+    /// Create a boxed copy of this Kamikaze
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// Get the saved state of this Kamikaze
+    fn to_saved(&self) -> Option<SavedActor> {
+        return Some(SavedActor(ActorData::Kamikaze(self.clone())));
+    }
+
+    /// Check if this Kamikaze has collided with another Actor, passing through the rest of its swarm
+    fn check_for_collision(&self, other: &Box<dyn Actor>) -> bool {
+        if other.get_flock_velocity().is_some() {
+            return false;
+        }
+        return hitboxes_touch(&self.get_hitbox(), &self.position, self.get_displacement(), other.as_ref())
+            && self.id != other.get_id();
+    }
+
+    /// Get the health of this Kamikaze
+    fn get_health(&self) -> Option<f32> {
+        return Some(self.health.get_current());
+    }
+
+    /// Get the full health of this Kamikaze
+    fn get_max_health(&self) -> Option<f32> {
+        return Some(self.health.get_max());
+    }
+
+    /// Get the velocity of this Kamikaze
+    fn get_velocity(&self) -> Option<&Velocity> {
+        return Some(&self.velocity);
+    }
+
+    /// Get the mass of this Kamikaze
+    fn get_mass(&self) -> Option<f32> {
+        return Some(PHYSICS_DENSITY * self.get_radius().powi(2));
+    }
+
+    /// Move this Kamikaze out of what it ran into, and let the push knock it off course for a moment
+    fn bounce(&mut self, offset: &Vec2, push: &Vec2) {
+        self.position += *offset;
+        self.knockback.push(push);
+    }
+
+    /// Set the multiplier the floor under this Kamikaze applies to its speed
+    fn set_floor_speed_multiplier(&mut self, multiplier: f32) {
+        self.floor_speed_multiplier = multiplier;
+    }
+
+    /// Change the speed and scale the health of this Kamikaze to the new balance
+    fn retune(&mut self, old: &Tuning, new: &Tuning) {
+        self.health.rescale(old.kamikaze_health, new.kamikaze_health);
+        self.velocity.speed = new.kamikaze_speed;
+        self.radius = new.kamikaze_radius;
+        self.turn_speed = new.kamikaze_turn_speed;
+        self.damage = new.kamikaze_damage;
+    }
+
+    /// Tell this Kamikaze where the player is
+    fn set_target(&mut self, target: Option<Point>) {
+        self.target = target;
+    }
+
+    /// Get the velocity of this Kamikaze, which its swarm steers by
+    fn get_flock_velocity(&self) -> Option<&Velocity> {
+        return Some(&self.velocity);
+    }
+
+    /// Set the heading the swarm wants this Kamikaze to steer towards
+    fn set_flock_heading(&mut self, heading: f32) {
+        self.flock_heading = Some(heading);
+    }
+
+    /// Check if this Kamikaze is dead, which it is as soon as it explodes
+    fn is_dead(&self) -> bool {
+        return self.health.is_depleted();
+    }
+}
+
+impl Render for Kamikaze {
+    /// Draw this Kamikaze as a dot with a line pointing the way it is flying
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let circle = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            [0.0, 0.0],
+            self.get_radius(),
+            0.5,
+            HOSTILE_DRONE_COLOR,
+        )?;
+        graphics::draw(ctx, &circle, ([self.position.x, self.position.y],))?;
+
+        let mut nose = self.position;
+        nose.move_distance(self.get_radius() * 2.0, self.velocity.heading);
+        let line = graphics::Mesh::new_line(ctx, &[[self.position.x, self.position.y], [nose.x, nose.y]], 2.0,
+                                            HOSTILE_DRONE_COLOR)?;
+        graphics::draw(ctx, &line, ([0.0, 0.0],))?;
+
+        return Ok(());
+    }
+}
+
+/// Bomb lobbed by the player that detonates after its fuse burns out, or when the player sets it off, hurting
+/// everything within its blast radius
+#[derive(Clone, Serialize, Deserialize)]
+struct Bomb {
+    id: u32,
+    position: Point,
+    world: World,
+    radius: f32,
+    velocity: Velocity,
+    drag: f32, // Pixels per second lost every second
+    fuse: Timer,
+    damage: Damage,
+    blast_radius: f32,
+    detonated: bool,
+    #[serde(default = "get_full_speed")]
+    floor_speed_multiplier: f32,
+}
+
+impl Bomb {
+    /// Create a new Bomb with the given balance lobbed from the given position with the given heading, that does the
+    /// given damage
+    fn new(position: Point, world: &World, tuning: &Tuning, heading: f32, damage: Damage) -> Bomb {
+        return Bomb {
+            id: 0,
+            position,
+            world: world.clone(),
+            radius: tuning.bomb_radius,
+            velocity: Velocity::new(tuning.bomb_throw_speed, heading),
+            drag: tuning.bomb_drag,
+            fuse: Timer::new(tuning.bomb_fuse),
+            damage,
+            blast_radius: tuning.bomb_blast_radius,
+            detonated: false,
+            floor_speed_multiplier: 1.0,
+        };
+    }
+}
+
+impl Actor for Bomb {
+    /// Get the ID of this Bomb
+    fn get_id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Give this Bomb the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
+    /// Get the radius of this Bomb
+    fn get_radius(&self) -> f32 {
+        return self.radius;
+    }
+
+    /// Get the position of this Bomb
+    fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the kind of this Bomb
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Bomb;
+    }
+
+    /// Resize this Bomb and its blast to the new balance, and slow it down by the new drag
+    fn retune(&mut self, _old: &Tuning, new: &Tuning) {
+        self.radius = new.bomb_radius;
+        self.drag = new.bomb_drag;
+        self.blast_radius = new.bomb_blast_radius;
+    }
+
+    /// Get the drawing of this Bomb
+    fn as_render(&self) -> Option<&dyn Render> {
+        return Some(self);
+    }
+
+    /// Change the arena this Bomb is in, moving it back inside if the arena has shrunk past it
+    fn set_world(&mut self, world: &World) {
+        self.world = world.clone();
+        self.position.keep_in_bounds(world);
+    }
+
+    /// Set the multiplier the floor under this Bomb applies to its sliding speed
+    fn set_floor_speed_multiplier(&mut self, multiplier: f32) {
+        self.floor_speed_multiplier = multiplier;
+    }
+
+    /// Update the state of this Bomb
+    fn update(&mut self, dt: f32) {
+        // Slide to a stop, as fast as the floor under it allows, staying inside the arena, or going off where it
+        // stops if the edge it reached destroys what reaches it
+        let mut velocity = self.velocity.clone();
+        velocity.speed *= self.floor_speed_multiplier;
+        self.position.move_time(dt, &velocity);
+        self.velocity.speed = (self.velocity.speed - self.drag * dt).max(0.0);
+        if self.world.confine(&mut self.position, &mut self.velocity) {
+            self.detonate();
+        }
+
+        if self.fuse.update(dt) {
+            self.detonate();
+        }
+    }
+
+    /// Get the damage touching this Bomb causes (none, it only hurts when it explodes)
+    fn get_damage(&self) -> Damage {
+        return Damage::kinetic(0.0);
+    }
+
+    /// Do damage to this Bomb (bombs can't be destroyed, only set off)
+    fn do_damage(&mut self, _damage: Damage) -> f32 {
+        return 0.0;
+    }
+
+    /// Get the status effect this Bomb applies (bombs apply none)
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
+    }
+
+    /// Apply a status effect to this Bomb (bombs are not affected by status effects)
+    fn apply_status_effect(&mut self, _effect: StatusEffect) {}
+
+    /// Take the pickup this Bomb gives (bombs never give pickups)
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
+    }
+
+    /// Get any new Shots this Bomb has created (bombs never shoot)
+    fn collect_shots(&mut self) -> Vec<Shot> {
+        return Vec::new();
+    }
+
+    /// Create a boxed copy of this Bomb
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// Get the saved state of this Bomb
+    fn to_saved(&self) -> Option<SavedActor> {
+        return Some(SavedActor(ActorData::Bomb(self.clone())));
+    }
+
+    /// Get the team of this Bomb, which belongs to the player
+    fn get_team(&self) -> Team {
+        return Team::Player;
+    }
+
+    /// Set this Bomb off
+    fn detonate(&mut self) {
+        self.detonated = true;
+    }
+
+    /// Get the blast of this Bomb if it has just gone off
+    fn take_explosion(&mut self) -> Option<(f32, Damage)> {
+        return if self.detonated { Some((self.blast_radius, self.damage)) } else { None };
+    }
+
+    /// Check if this Bomb is dead, which it is once it has gone off
+    fn is_dead(&self) -> bool {
+        return self.detonated;
+    }
+}
+
+impl Render for Bomb {
+    /// Draw this Bomb, with its blast radius and how much of its fuse is left
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let blast = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::stroke(1.0),
+            [0.0, 0.0],
+            self.blast_radius,
+            0.5,
+            graphics::Color::new(BOMB_COLOR.r, BOMB_COLOR.g, BOMB_COLOR.b, 0.3),
+        )?;
+        graphics::draw(ctx, &blast, ([self.position.x, self.position.y],))?;
+
+        // The bomb grows brighter as its fuse burns down
+        let fuse_fraction = self.fuse.get_elapsed() / self.fuse.get_duration();
+        let circle = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            [0.0, 0.0],
+            self.get_radius(),
+            0.5,
+            graphics::Color::new(BOMB_COLOR.r, BOMB_COLOR.g * (1.0 - fuse_fraction), BOMB_COLOR.b, 1.0),
+        )?;
+        graphics::draw(ctx, &circle, ([self.position.x, self.position.y],))?;
+
+        return Ok(());
+    }
+}
+
+/// Stationary base the player defends in the tower defense mode
+#[derive(Clone, Serialize, Deserialize)]
+struct Base {
+    id: u32,
+    position: Point,
+    radius: f32,
+    health: Health,
+}
+
+impl Base {
+    /// Create a new Base at the given position with the given balance
+    fn new(position: Point, tuning: &Tuning) -> Base {
+        return Base {
+            id: 0,
+            position,
+            radius: tuning.base_radius,
+            health: Health::new(tuning.base_health),
+        };
+    }
+}
+
+impl Actor for Base {
+    /// Get the ID of this Base
+    fn get_id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Give this Base the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
+    /// Get the radius of this Base
+    fn get_radius(&self) -> f32 {
+        return self.radius;
+    }
+
+    /// Get the position of this Base
+    fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the kind of this Base
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Base;
+    }
+
+    /// Resize this Base and scale its health to the new balance, keeping the fraction of health it has left
+    fn retune(&mut self, old: &Tuning, new: &Tuning) {
+        self.radius = new.base_radius;
+        self.health.rescale(old.base_health, new.base_health);
+    }
+
+    /// Get the drawing of this Base
+    fn as_render(&self) -> Option<&dyn Render> {
+        return Some(self);
+    }
+
+    /// Move this Base back inside the given arena if the arena has shrunk past it
+    fn set_world(&mut self, world: &World) {
+        self.position.keep_in_bounds(world);
+    }
+
+    /// Update the state of this Base (bases don't do anything on their own)
+    fn update(&mut self, _dt: f32) {}
+
+    /// Get the mass of this Base, which is fixed in place
+    fn get_mass(&self) -> Option<f32> {
+        return Some(f32::INFINITY);
+    }
+
+    /// Get the damage touching this Base causes, which destroys any drone that rams it
+    fn get_damage(&self) -> Damage {
+        return Damage::kinetic(self.health.get_max());
+    }
+
+    /// Do damage to this Base
+    fn do_damage(&mut self, damage: Damage) -> f32 {
+        return self.health.damage(damage);
+    }
+
+    /// Get the status effect touching this Base causes (none)
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
+    }
+
+    /// Apply a status effect to this Base (bases are not affected by status effects)
+    fn apply_status_effect(&mut self, _effect: StatusEffect) {}
+
+    /// Take the pickup this Base gives (bases never give pickups)
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
+    }
+
+    /// Get any new Shots this Base has created (this will always be an empty vector)
+    fn collect_shots(&mut self) -> Vec<Shot> {
+        return Vec::new();
+    }
+
+    /// Create a boxed copy of this Base
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// Get the saved state of this Base
+    fn to_saved(&self) -> Option<SavedActor> {
+        return Some(SavedActor(ActorData::Base(self.clone())));
+    }
+
+    /// Get the health of this Base
+    fn get_health(&self) -> Option<f32> {
+        return Some(self.health.get_current());
+    }
+
+    /// Get the full health of this Base
+    fn get_max_health(&self) -> Option<f32> {
+        return Some(self.health.get_max());
+    }
+
+    /// Bases are on the player's team
+    fn get_team(&self) -> Team {
+        return Team::Player;
+    }
+
+    /// Check if this Base has been destroyed
+    fn is_dead(&self) -> bool {
+        return self.health.is_depleted();
+    }
+}
+
+impl Render for Base {
+    /// Draw this Base with its remaining health
+    fn draw(&self, ctx: &mut Context) -> GameResult {
+        let radius = self.radius;
+        let square = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::stroke(3.0),
+            graphics::Rect::new(-radius, -radius, radius * 2.0, radius * 2.0),
+            FRIENDLY_COLOR,
+        )?;
+        graphics::draw(ctx, &square, ([self.position.x, self.position.y],))?;
+
+        let label = graphics::Text::new(format!("{:.0}", self.health.get_current()));
+        graphics::draw(ctx, &label, ([self.position.x - 12.0, self.position.y - 6.0], FRIENDLY_COLOR))?;
+
+        return Ok(());
+    }
+}
+
+/// Hook that spawns extra actors for a wave, given the number of the wave and the arena
+pub type WaveHook = Box<dyn FnMut(u32, &World) -> Vec<Box<dyn Actor>>>;
+/// Hook called whenever an actor or the player is hurt, given its ID, the ID of the actor that hurt it if any, and
+/// the amount
+pub type DamageHook = Box<dyn FnMut(u32, Option<u32>, f32)>;
+/// Hook called whenever an actor dies, given its ID, kind, and position. Actors it returns are added to the game
+pub type DeathHook = Box<dyn FnMut(u32, ActorKind, &Point) -> Vec<Box<dyn Actor>>>;
+
+/// Data structure to store the main state of the game
+pub struct MainState {
+    player: Player,
+    actors: Vec<Box<dyn Actor>>,
+    mods: ModList,
+    mod_list_selection: Option<usize>,
+    spawn_console_selection: Option<usize>,
+    scene: Scene,
+    focus_paused: bool,
+    menu_selection: usize,
+    class_selection: usize,
+    mode: GameMode,
+    zones: Vec<CaptureZone>,
+    gauntlet: Option<Gauntlet>,
+    gauntlet_params: GauntletParams,
+    teleporters: Vec<TeleporterPair>,
+    floor: Vec<FloorZone>,
+    obstacles: Vec<Obstacle>,
+    outcome: Option<RunOutcome>,
+    escort_drone: Option<u32>,
+    base: Option<u32>,
+    credits: u32,
+    formations: Vec<DriftingAnchor>,
+    wave: u32,
+    wave_enemies: Vec<u32>,
+    perk_choices: Option<Vec<Perk>>,
+    telemetry: Telemetry,
+    show_telemetry: bool,
+    show_debug_overlay: bool,
+    director: Director,
+    score: u32,
+    checkpoint: Option<Checkpoint>,
+    checkpoint_restarts: u32,
+    world: World,
+    pickup_timer: Timer,
+    next_pickup_at_top: bool,
+    spawns: SpawnSchedule,
+    audio: AudioManager,
+    assets: AssetManager,
+    loader: Option<Loader>,
+    time_scale: TimeScale,
+    events: GlobalEvents,
+    rng: RandomStreams,
+    scheduler: UpdateScheduler,
+    hack_cooldown: Cooldown,
+    streamer: WorldStreamer,
+    player_shots: HashSet<u32>,
+    result_card: Option<GameResult<String>>,
+    tick: u64,
+    tick_rate: u32,
+    unsimulated_time: f32,
+    frame_limiter: FrameLimiter,
+    log: RecentLog,
+    inputs: InputHistory,
+    bug_report: Option<Result<std::path::PathBuf, String>>,
+    bomb: Option<u32>,
+    modifiers: ModifierSet,
+    population: PopulationLimits,
+    bus: EventBus,
+    contacts: ContactTracker,
+    /// ID of the last actor added to the game. IDs are never given out twice, so an ID keeps referring to the same
+    /// actor, and finds nothing once that actor has been removed
+    last_actor_id: u32,
+    /// Index in the list of actors of every actor by its ID, as of the last time they were counted
+    actor_indices: HashMap<u32, usize>,
+    /// Position, velocity, health, weapon, and team of every actor, as of the last time the systems gathered them
+    components: Components,
+    collision_rules: CollisionRules,
+    actor_renderer: Option<ActorRenderer>,
+    boss_bar: BossBar,
+    explosions: Vec<Explosion>,
+    particles: ParticleSystem,
+    exhaust: Emitter,
+    shake: ScreenShake,
+    camera: Camera,
+    world_size: Option<(f32, f32)>,
+    round_trip_interval: Option<u32>,
+    benchmark: Option<Benchmark>,
+    /// Summary of the timings of the last benchmark to finish in the window, for the caller to report
+    benchmark_summary: Option<String>,
+    rewind: Option<RewindBuffer>,
+    profile: Profile,
+    medal: Option<Medal>,
+    medal_awarded: bool,
+    settings: Settings,
+    save_status: Option<String>,
+    run_stats: RunStats,
+    seed: Option<u64>,
+    keyboard: Option<VirtualKeyboard>,
+    stick: Stick,
+    ruleset: Ruleset,
+    presets: Vec<Preset>,
+    preset_selection: usize,
+    wave_hooks: Vec<WaveHook>,
+    damage_hooks: Vec<DamageHook>,
+    death_hooks: Vec<DeathHook>,
+    enemies: Vec<EnemyDefinition>,
+    registry: ActorRegistry,
+    levels: Vec<Level>,
+    standard_level: Level,
+    /// Names of the spawn zones that were missing from the level, which have already been warned about
+    missing_zones: HashSet<String>,
+    tuning: Tuning,
+    tuning_watcher: Option<TuningWatcher>,
+    #[cfg(feature = "scripts")]
+    scripts: ScriptEngine,
+}
+
+impl MainState {
+    /// Initialize the state of the game
+    pub fn new(ctx: &mut Context) -> MainState {
+        // Fill the window and start the random number streams with a random seed. The sound samples, the rest of the
+        // assets, and the levels are loaded behind the loading screen
+        let mut state = MainState::create(graphics::drawable_size(ctx), AudioManager::silent(),
+                                          RandomStreams::from_entropy());
+        state.load_files();
+        state.profile = Profile::load(std::path::Path::new(medals::PROFILES_DIRECTORY), medals::DEFAULT_PROFILE);
+        state.loader = Some(Loader::new(LoadJob::find_all(ctx), Level::get_paths(&state.mods)));
+        state.scene = Scene::Loading;
+
+        // Load the balance of the game with the overrides of the active mods before any actors are spawned, which
+        // headless games leave at the defaults unless they are given another
+        let tuning_path = std::path::Path::new(tuning::TUNING_FILE);
+        let (tuning, tuning_issue) = Tuning::load(tuning_path);
+        let (tuning, mod_issues) = tuning.with_mods(&state.mods);
+        state.set_tuning(tuning);
+        for issue in mod_issues {
+            state.warn(issue);
+        }
+
+        // Apply the player's saved settings, which headless games leave at their defaults
+        let (settings, settings_issue) = Settings::load(std::path::Path::new(settings::SETTINGS_FILE));
+        state.apply_settings(ctx, settings);
+        if let Some(issue) = settings_issue {
+            state.warn(issue);
+        }
+        if let Some(issue) = tuning_issue {
+            state.warn(issue);
+        }
+        // Keep watching the tuning file, so the balance can be changed while the game is running
+        state.tuning_watcher = Some(TuningWatcher::new(tuning_path));
+        // Keep the last few seconds of history to step back through in debug builds
+        if cfg!(debug_assertions) {
+            state.rewind = Some(RewindBuffer::new(state.tick_rate));
+        }
+
+        return state;
+    }
+
+    /// Initialize the state of a game that runs without a window or sound, in an arena of the given size with
+    /// the given seed. Nothing is read from or written to the working directory, so the game plays by the
+    /// defaults with no mods or levels and keeps its profile in memory, unless `load_content` is called
+    pub fn headless(bounds: (f32, f32), seed: u64) -> MainState {
+        return MainState::create(bounds, AudioManager::silent(), RandomStreams::new(seed));
+    }
+
+    /// Read the population limits, the mods and the enemies, scripts, and levels they add from the working
+    /// directory, as games in a window do. Headless games only read them when this is called
+    pub fn load_content(&mut self) {
+        self.load_files();
+        self.load_levels();
+    }
+
+    /// Initialize the state of a game with the given arena size, sounds, and random number streams
+    fn create(bounds: (f32, f32), audio: AudioManager, rng: RandomStreams) -> MainState {
+        let (width, height) = (bounds.0.max(MIN_ARENA_SIZE), bounds.1.max(MIN_ARENA_SIZE));
+        let world = World::rect(width, height);
+        // Every game starts with the default balance, until it is given another with set_tuning
+        let tuning = Tuning::new();
+
+        // Initialize a new MainState object
+        let mut state = MainState {
+            // Initialize the Player, which is replaced once the player has picked a ship class
+            player: Player::new(Point::new(width/2.0, height/2.0), &world, ShipClass::Fighter, &tuning),
+            // Initialize a vector to hold the actors in the game
+            actors: Vec::new(),
+            // The installed mods are only found for games that read the working directory
+            mods: ModList::empty(),
+            // The mod list starts closed
+            mod_list_selection: None,
+            spawn_console_selection: None,
+            // Start on the main menu with the balanced class selected
+            scene: Scene::MainMenu,
+            focus_paused: false,
+            menu_selection: 0,
+            class_selection: 1,
+            // Default to the waves mode, with no capture zones
+            mode: GameMode::Waves,
+            zones: Vec::new(),
+            // The gauntlet is generated once a run of that mode starts
+            gauntlet: None,
+            gauntlet_params: GauntletParams::new(),
+            // The teleporters are placed once the run starts
+            teleporters: Vec::new(),
+            obstacles: Vec::new(),
+            floor: Vec::new(),
+            outcome: None,
+            escort_drone: None,
+            base: None,
+            credits: 0,
+            // Initialize a vector to hold the anchors of the turret formations
+            formations: Vec::new(),
+            // Start before the first wave with no perks being offered
+            wave: 0,
+            wave_enemies: Vec::new(),
+            perk_choices: None,
+            // Start measuring the run, with the designer overlay hidden
+            telemetry: Telemetry::new(),
+            show_telemetry: false,
+            // Draw the game without the hitboxes and vectors until the debug overlay is turned on
+            show_debug_overlay: false,
+            // Start the pacing director at normal intensity
+            director: Director::new(),
+            // Start with no score and no checkpoint
+            score: 0,
+            checkpoint: None,
+            checkpoint_restarts: CHECKPOINT_RESTARTS,
+            world,
+            // Spawn the first pickup after the normal interval
+            pickup_timer: Timer::looping(PICKUP_SPAWN_INTERVAL),
+            next_pickup_at_top: true,
+            spawns: SpawnSchedule::new(),
+            audio,
+            assets: AssetManager::new(),
+            loader: None,
+            time_scale: TimeScale::new(),
+            // Wait for the first random event
+            events: GlobalEvents::new(),
+            rng,
+            scheduler: UpdateScheduler::new(),
+            // Let the player hack a turret straight away
+            hack_cooldown: Cooldown::new(tuning.hack_cooldown),
+            // Start with every actor active
+            streamer: WorldStreamer::new(),
+            // Track which shots the player fired, to measure accuracy
+            player_shots: HashSet::new(),
+            result_card: None,
+            // Start keeping the history attached to bug reports
+            tick: 0,
+            tick_rate: DEFAULT_TICK_RATE,
+            // No time has passed that still has to be simulated
+            unsimulated_time: 0.0,
+            // Draw as often as the display allows until the settings cap the frame rate
+            frame_limiter: FrameLimiter::new(None),
+            log: RecentLog::new(),
+            inputs: InputHistory::new(),
+            bug_report: None,
+            // The player has not thrown a bomb yet
+            bomb: None,
+            // Play by the normal rules unless the player turns on assists
+            modifiers: ModifierSet::new(),
+            // Cap the number of actors of each type by the default limits until any others are read
+            population: PopulationLimits::new(),
+            // Start with no events emitted
+            bus: EventBus::new(),
+            // No actors are touching yet, and every pair keeps hitting while it stays in contact
+            contacts: ContactTracker::new(),
+            // Number the actors of every game from the start, so that games with the same seed give their actors
+            // the same IDs
+            last_actor_id: 0,
+            actor_indices: HashMap::new(),
+            components: Components::new(),
+            collision_rules: CollisionRules::new(),
+            actor_renderer: None,
+            // There is no boss until a boss wave
+            boss_bar: BossBar::new(),
+            // Nothing has blown up yet
+            explosions: Vec::new(),
+            particles: ParticleSystem::new(),
+            exhaust: EXHAUST,
+            shake: ScreenShake::new(true),
+            // Show the whole arena, which fills the window until it is given a size of its own
+            camera: Camera::new(bounds),
+            world_size: None,
+            // Check that the state survives serialization once a second in debug builds
+            round_trip_interval: if cfg!(debug_assertions) { Some(ROUND_TRIP_INTERVAL) } else { None },
+            // Games are only benchmarked when asked to
+            benchmark: None,
+            benchmark_summary: None,
+            // Only games in a window keep a history to step back through
+            rewind: None,
+            // Keep the medals in memory until the player's profile is loaded
+            profile: Profile::default(),
+            medal: None,
+            medal_awarded: false,
+            settings: Settings::new(),
+            save_status: None,
+            run_stats: RunStats::new(),
+            // Runs use a random seed until the player enters one
+            seed: None,
+            keyboard: None,
+            stick: Stick::new(),
+            // Play by the standard rules until the player changes them on the custom game screen
+            ruleset: Ruleset::new(),
+            presets: Vec::new(),
+            preset_selection: 0,
+            wave_hooks: Vec::new(),
+            damage_hooks: Vec::new(),
+            death_hooks: Vec::new(),
+            enemies: Vec::new(),
+            registry: ActorRegistry::with_defaults(),
+            levels: Vec::new(),
+            standard_level: Level::standard(),
+            missing_zones: HashSet::new(),
+            // Play with the balance actors are created with, which is the default unless another is installed
+            tuning,
+            // Only games in a window watch the tuning file
+            tuning_watcher: None,
+            #[cfg(feature = "scripts")]
+            scripts: ScriptEngine::new(),
+        };
+        state.player.id = state.get_next_actor_id();
+
+        return state;
+    }
+
+    /// Read the population limits, the mods, and the enemies and scripts the mods add from the working directory
+    fn load_files(&mut self) {
+        let (population, population_issue) = PopulationLimits::load(std::path::Path::new(population::POPULATION_FILE));
+        self.population = population;
+        if let Some(issue) = population_issue {
+            self.warn(issue);
+        }
+        // Find the installed mods and work out their load order
+        self.mods = ModList::load(std::path::Path::new(mods::MODS_DIRECTORY));
+        // Read the kinds of turret the active mods add to the waves
+        let (enemies, enemy_issues) = EnemyDefinition::load_all(&self.mods);
+        // Without scripts, scripted turrets would never fire, so the enemies that need a script are left out
+        #[cfg(not(feature = "scripts"))]
+        let enemies: Vec<EnemyDefinition> = enemies.into_iter()
+            .filter(|definition| match definition.get_script() {
+                Some(script) => {
+                    self.warn(format!("Enemy {} was left out: it needs script {}, but the game was built without \
+                                        scripts", definition.get_name(), script));
+                    false
+                }
+                None => true,
+            })
+            .collect();
+        for definition in &enemies {
+            self.registry.register_definition(definition);
+        }
+        self.enemies = enemies;
+        for issue in enemy_issues {
+            self.warn(issue);
+        }
+        // Compile the scripts of the scripted turrets, from the scripts directory and the active mods
+        #[cfg(feature = "scripts")]
+        {
+            self.scripts = ScriptEngine::load(&self.mods);
+            for issue in self.scripts.take_issues() {
+                self.warn(issue);
+            }
+        }
+    }
+
+    /// Read the levels from the levels directory and the active mods
+    fn load_levels(&mut self) {
+        let (levels, issues) = Level::load_all(&self.mods);
+        self.set_levels(levels, issues);
+    }
+
+    /// Replace the levels of the game with the given levels, reporting the ones that couldn't be read
+    fn set_levels(&mut self, levels: Vec<Level>, issues: Vec<String>) {
+        self.levels = levels;
+        for issue in issues {
+            self.warn(issue);
+        }
+        // A level chosen before the levels were loaded, like one chosen on the command line, may not exist
+        if let Some(name) = self.ruleset.get_level().map(String::from) {
+            if !self.levels.iter().any(|level| level.get_name() == name) {
+                self.warn(format!("There is no level named {}, so the standard level is played", name));
+                self.ruleset.set_level(None);
+            }
+        }
+    }
+
+    /// Add the given level to the game, replacing any level of the same name, as headless games have no levels
+    /// until they are added or read from the working directory
+    pub fn add_level(&mut self, level: Level) {
+        self.levels.retain(|other| other.get_name() != level.get_name());
+        self.levels.push(level);
+    }
+
+    /// Lay the waves out by the level with the given name from now on, or by the standard level if there is none.
+    /// Returns a description of the problem if the levels have loaded and none of them has the name; a level chosen
+    /// while they are still loading is forgotten once they have loaded if it isn't one of them
+    pub fn set_level(&mut self, name: Option<&str>) -> Result<(), String> {
+        if let Some(name) = name {
+            if self.loader.is_none() && !self.levels.iter().any(|level| level.get_name() == name) {
+                return Err(format!("There is no level named {}", name));
+            }
+        }
+        self.ruleset.set_level(name.map(String::from));
+        return Ok(());
+    }
+
+    /// Play the runs from now on with the given seed, or with random seeds if there is none
+    pub fn set_seed(&mut self, seed: Option<u64>) {
+        self.seed = seed;
+        self.rng = match seed {
+            Some(seed) => RandomStreams::new(seed),
+            None => RandomStreams::from_entropy(),
+        };
+    }
+
+    /// Load some more of the content of the game while the loading screen is shown, and go on to the main menu
+    /// once all of it has loaded
+    fn update_loading(&mut self, ctx: &mut Context) {
+        let mut loader = match self.loader.take() {
+            Some(loader) => loader,
+            None => return,
+        };
+        if let Some((levels, issues)) = loader.update(ctx, &mut self.assets) {
+            self.set_levels(levels, issues);
+        }
+        for issue in loader.take_issues() {
+            self.warn(issue);
+        }
+        if !loader.is_finished() {
+            self.loader = Some(loader);
+            return;
+        }
+
+        self.audio.load_samples(ctx, &mut self.assets);
+        self.note(format!("Loaded {} assets", self.assets.get_count()));
+        // Loading that was skipped finishes in the background, wherever the game has got to by then
+        if self.scene == Scene::Loading {
+            self.set_scene(Scene::MainMenu);
+        }
+    }
+
+    /// Leave the loading screen without waiting for the assets, which keep loading in the background. The levels
+    /// are waited for, since runs are played on them
+    fn skip_loading(&mut self) {
+        if let Some(loader) = &mut self.loader {
+            if let Some((levels, issues)) = loader.wait_for_levels() {
+                self.set_levels(levels, issues);
+            }
+        }
+        self.set_scene(Scene::MainMenu);
+    }
+
+    /// Load the given asset into the assets of the game while the loading screen is shown, before the main menu
+    /// opens. Once loading has finished, assets are loaded through `get_assets` instead
+    pub fn queue_load(&mut self, job: LoadJob) {
+        if let Some(loader) = &mut self.loader {
+            loader.queue(job);
+        }
+    }
+
+    /// Add an actor to the game, giving it a new ID, which is returned. No two actors are given the same ID, so an
+    /// ID keeps referring to the same actor, and finds nothing once that actor has been removed
+    pub fn add_actor(&mut self, mut actor: Box<dyn Actor>) -> u32 {
+        let id = self.get_next_actor_id();
+        actor.set_id(id);
+        log::trace!(target: "turrets::spawns", "Spawned {} {}", actor.get_kind().get_name(), id);
+        self.actor_indices.insert(id, self.actors.len());
+        self.actors.push(actor);
+        return id;
+    }
+
+    /// Generate a new ID, for an actor or anything else numbered alongside them
+    fn get_next_actor_id(&mut self) -> u32 {
+        self.last_actor_id += 1;
+        return self.last_actor_id;
+    }
+
+    /// Get the ID of the last actor added to the game, which a saved run carries on numbering its actors from
+    pub(crate) fn get_last_actor_id(&self) -> u32 {
+        return self.last_actor_id;
+    }
+
+    /// Count the actors again by their IDs, once actors have been removed or moved around the list
+    fn index_actors(&mut self) {
+        self.actor_indices.clear();
+        self.actor_indices.extend(self.actors.iter().enumerate().map(|(i, actor)| (actor.get_id(), i)));
+    }
+
+    /// Get the index in the list of actors of the live actor with the given ID. The index the actor was last
+    /// counted at is checked first, and the whole list is searched if the actor has moved since
+    fn get_actor_index(&self, id: u32) -> Option<usize> {
+        if let Some(&index) = self.actor_indices.get(&id) {
+            if self.actors.get(index).is_some_and(|actor| actor.get_id() == id) {
+                return Some(index);
+            }
+        }
+        return self.actors.iter().position(|actor| actor.get_id() == id);
+    }
+
+    /// Change the size of the arena, keeping its shape and edges, but no smaller than the smallest arena. Anything
+    /// the arena has shrunk past is moved back inside
+    pub fn resize(&mut self, bounds: (f32, f32)) {
+        let bounds = (bounds.0.max(MIN_ARENA_SIZE), bounds.1.max(MIN_ARENA_SIZE));
+        let mut world = self.world.clone();
+        world.resize(bounds);
+        self.set_world(world);
+        self.note(format!("Arena resized to {}x{}", bounds.0, bounds.1));
+    }
+
+    /// Play in an arena of the given size, which the camera scrolls across if it is bigger than the window, or in
+    /// an arena that fills the window if there is no size
+    pub fn set_world_size(&mut self, size: Option<(f32, f32)>) {
+        self.world_size = size;
+        let bounds = size.unwrap_or_else(|| self.camera.get_view_size());
+        self.resize(bounds);
+    }
+
+    /// Get the camera the arena is drawn through
+    pub fn get_camera(&self) -> &Camera {
+        return &self.camera;
+    }
+
+    /// Get the arena the game is played in
+    pub fn get_world(&self) -> &World {
+        return &self.world;
+    }
+
+    /// Change the arena the game is played in, telling the player and every actor, including the parked ones.
+    /// Anything outside the new arena is moved back inside
+    pub fn set_world(&mut self, world: World) {
+        self.player.set_world(&world);
+        for actor in &mut self.actors {
+            actor.set_world(&world);
+        }
+        self.streamer.for_each_parked_mut(|actor| actor.set_world(&world));
+        self.world = world;
+    }
+
+    /// Find the live actor with the given ID. The ID of an actor that has been removed, or is parked far from the
+    /// player, finds nothing
+    pub fn find_actor(&self, id: u32) -> Option<&dyn Actor> {
+        return self.get_actor_index(id).map(|index| self.actors[index].as_ref());
+    }
+
+    /// Find the live actor with the given ID to change it
+    fn find_actor_mut(&mut self, id: u32) -> Option<&mut Box<dyn Actor>> {
+        let index = self.get_actor_index(id)?;
+        return Some(&mut self.actors[index]);
+    }
+
+    /// Arm the player with the given weapon in place of the one they are holding
+    pub fn set_player_weapon(&mut self, weapon: Box<dyn Weapon>) {
+        self.player.weapon = weapon;
+    }
+
+    /// Compile the given source as the script with the given name, for turrets created with `Turret::scripted`.
+    /// Replaces any script loaded from a file under that name
+    #[cfg(feature = "scripts")]
+    pub fn add_script(&mut self, name: &str, source: &str) -> Result<(), String> {
+        return self.scripts.compile(name, source);
+    }
+
+    /// Register the given constructor under the given name in the actor registry, so levels and
+    /// spawn_named can create the actor. It is given the position, the arena, and the balance of the game. Replaces
+    /// anything registered under the name before, including the game's own actors
+    pub fn register_actor<F>(&mut self, name: &str, factory: F)
+        where F: Fn(Point, &World, &Tuning) -> Box<dyn Actor> + 'static {
+        self.registry.register(name, factory);
+    }
+
+    /// Get the table of actors that can be spawned by name
+    pub fn get_registry(&self) -> &ActorRegistry {
+        return &self.registry;
+    }
+
+    /// Get the multiplier on the time every tick simulates, where 1 is normal speed
+    pub fn get_time_scale(&self) -> f32 {
+        return self.time_scale.get_scale();
+    }
+
+    /// Set the multiplier on the time every tick simulates straight away, like 0.25 for slow motion or 4 to
+    /// fast-forward, stopping any easing of it
+    pub fn set_time_scale(&mut self, scale: f32) {
+        self.time_scale.set_scale(scale);
+    }
+
+    /// Ease the multiplier on the time every tick simulates to the given scale over the given number of seconds,
+    /// counted at normal speed
+    pub fn tween_time_scale(&mut self, scale: f32, duration: f32) {
+        self.time_scale.tween_to(scale, duration);
+    }
+
+    /// Freeze the game for the given number of seconds, counted at normal speed, then carry on at the speed it was
+    /// going at, for a hit-stop
+    pub fn hit_stop(&mut self, duration: f32) {
+        self.time_scale.hit_stop(duration);
+    }
+
+    /// Get the assets of the game, to load images, fonts, and sounds through its cache. Headless games start with
+    /// none loaded
+    pub fn get_assets(&mut self) -> &mut AssetManager {
+        return &mut self.assets;
+    }
+
+    /// Spawn the actor registered under the given name at the given position, returning its ID, or none if nothing
+    /// is registered under the name. Hostile actors join the current wave
+    pub fn spawn_named(&mut self, name: &str, position: Point) -> Option<u32> {
+        let mut actor = self.registry.create(name, position, &self.world, &self.tuning)?;
+        actor.seed(self.rng.gameplay().gen());
+        if actor.get_team() == Team::Hostile {
+            return Some(self.add_wave_enemy(actor));
+        }
+        return Some(self.add_actor(actor));
+    }
+
+    /// Spawn the actor at the given place in the spawn console's list of registered names, a little ahead of the
+    /// player so it doesn't land on top of them
+    fn spawn_from_console(&mut self, selected: usize) {
+        let name = match self.registry.get_names().get(selected) {
+            Some(name) => name.to_string(),
+            None => return,
+        };
+        let mut position = Point::new(self.player.position.x, self.player.position.y - SPAWN_CLEARANCE);
+        self.world.clamp(&mut position);
+        if let Some(id) = self.spawn_named(&name, position) {
+            self.note(format!("Spawned {} as actor {}", name, id));
+        }
+    }
+
+    /// Draw every actor with the given function instead of the actors' own drawing, for an alternate renderer
+    pub fn set_actor_renderer<F: Fn(&mut Context, &dyn Actor) -> GameResult + 'static>(&mut self, renderer: F) {
+        self.actor_renderer = Some(Box::new(renderer));
+    }
+
+    /// Set whether actors that run into each other, or into obstacles, bounce apart by their masses instead of
+    /// overlapping. Off by default, and a custom rule, so runs with it on don't earn medals
+    pub fn set_collision_physics(&mut self, on: bool) {
+        self.ruleset.set_collision_physics(on);
+    }
+
+    /// Set whether actors and obstacles on the given collision layers collide with each other, in either order.
+    /// Layers that don't collide are never tested for contact
+    pub fn set_layers_collide(&mut self, first: CollisionLayer, second: CollisionLayer, collides: bool) {
+        self.collision_rules.set_collides(first, second, collides);
+    }
+
+    /// Set the mask of the collision layers the given layer collides with
+    pub fn set_collision_mask(&mut self, layer: CollisionLayer, mask: LayerMask) {
+        self.collision_rules.set_mask(layer, mask);
+    }
+
+    /// Set whether actors on the given collision layer are destroyed by running into an obstacle, like shots, or
+    /// blocked by it
+    pub fn set_stopped_by_obstacles(&mut self, layer: CollisionLayer, stopped: bool) {
+        self.collision_rules.set_stopped_by_obstacles(layer, stopped);
+    }
+
+    /// Set how the given kinds of actor respond to touching each other, in either order
+    pub fn set_contact_response(&mut self, first: ActorKind, second: ActorKind, response: ContactResponse) {
+        self.contacts.set_response(first, second, response);
+    }
+
+    /// Add an actor to the game as part of the current wave, which has to be destroyed before the wave is over,
+    /// giving it a new ID, which is returned
+    pub fn add_wave_enemy(&mut self, actor: Box<dyn Actor>) -> u32 {
+        let id = self.add_actor(actor);
+        self.wave_enemies.push(id);
+        return id;
+    }
+
+    /// Add a hook that is called with the number of every wave and the arena as the wave starts, in the waves and
+    /// tower defense modes. Hostile actors it returns join the wave, and the rest are added to the game as they are
+    pub fn add_wave_hook<F: FnMut(u32, &World) -> Vec<Box<dyn Actor>> + 'static>(&mut self, hook: F) {
+        self.wave_hooks.push(Box::new(hook));
+    }
+
+    /// Add a hook that is called with the ID of every actor hurt, including the player, the ID of the actor that
+    /// hurt it if any, and the amount, once the tick it was hurt in is over
+    pub fn add_damage_hook<F: FnMut(u32, Option<u32>, f32) + 'static>(&mut self, hook: F) {
+        self.damage_hooks.push(Box::new(hook));
+    }
+
+    /// Add a hook that is called with the ID, kind, and position of every actor that dies, once the tick it died in
+    /// is over. Actors it returns, like drops, are added to the game
+    pub fn add_death_hook<F: FnMut(u32, ActorKind, &Point) -> Vec<Box<dyn Actor>> + 'static>(&mut self, hook: F) {
+        self.death_hooks.push(Box::new(hook));
+    }
+
+    /// Get the balance of this game
+    pub fn get_tuning(&self) -> &Tuning {
+        return &self.tuning;
+    }
+
+    /// Play this game with the given balance, creating every actor it spawns from now on with it and retuning the
+    /// player and the live actors, including the parked ones. Other games keep their own balance
+    pub fn set_tuning(&mut self, tuning: Tuning) {
+        let old = std::mem::replace(&mut self.tuning, tuning);
+        self.player.retune(&old, &tuning);
+        for actor in &mut self.actors {
+            actor.retune(&old, &tuning);
+        }
+        self.streamer.for_each_parked_mut(|actor| actor.retune(&old, &tuning));
+        self.hack_cooldown.set_duration(tuning.hack_cooldown);
+    }
+
+    /// Apply any change to the tuning file to the actors spawned from now on, and retune the live actors
+    fn reload_tuning(&mut self) {
+        let reloaded = match &mut self.tuning_watcher {
+            Some(watcher) => watcher.poll(),
+            None => return,
+        };
+        match reloaded {
+            Some(Ok(tuning)) => {
+                let (tuning, mod_issues) = tuning.with_mods(&self.mods);
+                for issue in mod_issues {
+                    self.warn(issue);
+                }
+                self.set_tuning(tuning);
+                self.note(format!("Reloaded {}", tuning::TUNING_FILE));
+            }
+            Some(Err(issue)) => self.warn(issue),
+            None => (),
+        }
+    }
+
+    /// Add the actors the wave hooks spawn for the wave that has just started
+    fn run_wave_hooks(&mut self) {
+        let mut hooks = std::mem::take(&mut self.wave_hooks);
+        for hook in hooks.iter_mut() {
+            for actor in hook(self.wave, &self.world) {
+                if actor.get_team() == Team::Hostile {
+                    self.add_wave_enemy(actor);
+                } else {
+                    self.add_actor(actor);
+                }
+            }
+        }
+        self.wave_hooks = hooks;
+    }
+
+    /// Call the death hooks for the actor with the given ID, kind, and position, adding the actors they return
+    fn run_death_hooks(&mut self, id: u32, kind: ActorKind, position: &Point) {
+        let mut hooks = std::mem::take(&mut self.death_hooks);
+        for hook in hooks.iter_mut() {
+            for actor in hook(id, kind, position) {
+                self.add_actor(actor);
+            }
+        }
+        self.death_hooks = hooks;
+    }
+
+    /// Add a turret to the game as part of the current wave, returning the ID it was given
+    fn add_turret(&mut self, mut turret: Turret) -> u32 {
+        turret.seed(self.rng.gameplay().gen());
+        return self.add_wave_enemy(Box::new(turret));
+    }
+
+    /// Replace the placeholder player with one of the given class and start the run
+    fn choose_class(&mut self, class: ShipClass) {
+        let (width, height) = self.world.get_size();
+        self.player = Player::new(Point::new(width/2.0, height/2.0), &self.world, class, &self.tuning);
+        self.player.id = self.get_next_actor_id();
+        if let Some(weapon) = self.ruleset.get_starting_weapon() {
+            self.player.weapon = Box::new(Gun::new(weapon, &self.tuning));
+        }
+        self.checkpoint_restarts = self.ruleset.get_lives();
+        self.update_damage_taken();
+        self.class_selection = ShipClass::ALL.iter().position(|&other| other == class).unwrap_or(1);
+        self.set_scene(Scene::Playing);
+        self.start_run();
+    }
+
+    /// Set how much of the damage dealt to the player it takes, by the modifiers and the difficulty
+    fn update_damage_taken(&mut self) {
+        self.player.damage_taken_multiplier = self.modifiers.get_damage_taken_multiplier()
+            * self.settings.get_difficulty().get_damage_taken_multiplier();
+    }
+
+    /// Move to the given scene, if the current scene allows it
+    fn set_scene(&mut self, scene: Scene) {
+        if !self.scene.can_transition_to(scene) {
+            return;
+        }
+        self.note(format!("Moved from {:?} to {:?}", self.scene, scene));
+        self.scene = scene;
+        // Only the pause the window's focus caused is explained on the pause screen
+        if scene != Scene::Paused {
+            self.focus_paused = false;
+        }
+    }
+
+    /// Set how many ticks apart the state is serialized and deserialized to check that nothing is lost in the
+    /// round trip, or turn the check off
+    pub fn set_round_trip_interval(&mut self, interval: Option<u32>) {
+        self.round_trip_interval = interval;
+    }
+
+    /// Save the state and load it back, warning of the difference if anything was lost in the round trip
+    fn check_round_trip(&mut self) {
+        if let Err(difference) = snapshot::check_round_trip(self, ROUND_TRIP_TICKS) {
+            self.warn(format!("Serialization round trip failed at tick {}: {}", self.tick, difference));
+        }
+    }
+
+    /// Create a copy of this game without a window or sound, in the same arena with the same seed, settings, and
+    /// content, that plays the given saved run
+    pub(crate) fn copy_from_save(&self, save: SaveGame) -> MainState {
+        let mut copy = MainState::headless(self.world.get_size(), self.rng.get_seed());
+        // The content this game read or was given carries over instead of being read again, which would stall the
+        // game every time the round trip is checked
+        copy.population = self.population.clone();
+        copy.mods = self.mods.clone();
+        copy.enemies = self.enemies.clone();
+        copy.registry = self.registry.clone();
+        copy.levels = self.levels.clone();
+        #[cfg(feature = "scripts")]
+        {
+            copy.scripts = self.scripts.clone();
+        }
+        copy.round_trip_interval = None;
+        copy.set_tick_rate(self.tick_rate);
+        copy.world_size = self.world_size;
+        copy.set_tuning(self.tuning);
+        copy.modifiers = self.modifiers.clone();
+        copy.settings = self.settings;
+        copy.load_save(save);
+        return copy;
+    }
+
+    /// Move between the menus before a run, selecting the item at the given index of the new menu
+    fn open_menu(&mut self, scene: Scene, selection: usize) {
+        self.set_scene(scene);
+        self.menu_selection = selection;
+    }
+
+    /// Move the selection of the current menu up or down a list of the given length, wrapping around at the ends
+    fn move_menu_selection(&mut self, keycode: KeyCode, length: usize) {
+        match keycode {
+            KeyCode::Up => self.menu_selection = (self.menu_selection + length - 1) % length,
+            KeyCode::Down => self.menu_selection = (self.menu_selection + 1) % length,
+            _ => (),
+        }
+    }
+
+    /// Rate the run that has just ended against the par of its level, and save the medal to the profile if it is
+    /// the best one yet
+    fn award_medal(&mut self) {
+        self.medal_awarded = true;
+        // Medals compare runs against the par of their level, so runs on any level earn them, but runs with other
+        // custom rules, mutators, or assists don't
+        if !self.ruleset.is_standard_on_level() || self.modifiers.has_mutators() || self.modifiers.has_assists() {
+            return;
+        }
+        let level = self.get_level();
+        let medal = level.get_par(self.mode).evaluate(self.outcome, self.score, self.get_time());
+        // Each level keeps its own best medals, apart from the standard level's, which are kept by mode alone
+        let key = match self.ruleset.get_level() {
+            Some(_) => format!("{} on {}", self.mode.get_name(), level.get_name()),
+            None => self.mode.get_name().to_string(),
+        };
+        self.medal = medal;
+        if let Some(medal) = medal {
+            match self.profile.record(&key, medal) {
+                Ok(true) => self.note(format!("New best medal in {}: {}", key, medal.get_name())),
+                Ok(false) => (),
+                Err(error) => self.warn(format!("Could not save the profile: {}", error)),
+            }
+        }
+    }
+
+    /// Use the given settings, changing the volume, the difficulty of the run, and the window to match
+    fn apply_settings(&mut self, ctx: &mut Context, settings: Settings) {
+        self.settings = settings;
+        self.update_damage_taken();
+        self.audio.set_volume(settings.get_volume());
+        self.set_tick_rate(settings.get_tick_rate());
+        self.frame_limiter.set_cap(settings.get_frame_cap());
+        self.shake.set_enabled(settings.has_screen_shake());
+        let fullscreen = if settings.is_fullscreen() { FullscreenType::True } else { FullscreenType::Windowed };
+        if let Err(error) = graphics::set_fullscreen(ctx, fullscreen) {
+            self.warn(format!("Could not change the window: {}", error));
+        }
+    }
+
+    /// Step the value of a setting up or down, and save the settings so they are kept for the next launch
+    fn change_setting(&mut self, ctx: &mut Context, item: SettingsItem, up: bool) {
+        let mut settings = self.settings;
+        settings.adjust(item, up);
+        self.apply_settings(ctx, settings);
+        if let Err(error) = settings.save(std::path::Path::new(settings::SETTINGS_FILE)) {
+            self.warn(format!("Could not save the settings: {}", error));
+        }
+    }
+
+    /// Use the seed entered on the virtual keyboard for the next runs, or random seeds if none was entered, and go
+    /// back to the main menu
+    fn enter_seed(&mut self) {
+        let text = self.keyboard.take().map(|keyboard| keyboard.get_text().to_string()).unwrap_or_default();
+        self.seed = text.parse().ok();
+        self.rebuild();
+        self.menu_selection = MenuItem::Seed.get_index();
+    }
+
+    /// Open the custom game screen, loading the saved presets
+    fn open_custom_game(&mut self) {
+        let (presets, issues) = Preset::load_all(std::path::Path::new(rulesets::PRESETS_DIRECTORY));
+        for issue in issues {
+            self.warn(issue);
+        }
+        self.presets = presets;
+        self.preset_selection = 0;
+        self.open_menu(Scene::CustomGame, 0);
+    }
+
+    /// Save the current rules and mutators as a preset named with the text entered on the virtual keyboard, and
+    /// select it in the list of presets
+    fn save_preset(&mut self) {
+        let name = match self.keyboard.take() {
+            Some(keyboard) if !keyboard.get_text().is_empty() => keyboard.get_text().to_string(),
+            _ => return,
+        };
+        let preset = Preset::new(&name, self.ruleset.clone(), &self.modifiers);
+        match preset.save(std::path::Path::new(rulesets::PRESETS_DIRECTORY)) {
+            Ok(path) => self.note(format!("Saved the preset {} to {}", name, path.display())),
+            Err(error) => self.warn(format!("Could not save the preset: {}", error)),
+        }
+        // The new preset replaces the file of any preset with the same name in another case
+        self.presets.retain(|other| !other.has_name(&name));
+        self.presets.push(preset);
+        self.presets.sort_by(|a, b| a.get_name().cmp(b.get_name()));
+        self.preset_selection = self.presets.iter().position(|other| other.has_name(&name)).unwrap_or(0);
+    }
+
+    /// Use the rules and mutators of the selected preset, keeping the assists and challenges that are on
+    fn load_preset(&mut self) {
+        if let Some(preset) = self.presets.get(self.preset_selection) {
+            self.ruleset = preset.get_ruleset();
+            self.modifiers.set_mutators(preset.get_mutators());
+        }
+    }
+
+    /// Handle the events left over from the tick the run ended on, so its last kills count, then rate the run and
+    /// add it to the lifetime statistics, unless that has been done already
+    fn finish_run(&mut self) {
+        if self.medal_awarded {
+            return;
+        }
+        self.handle_late_events();
+        self.award_medal();
+        self.record_run_stats();
+        // Ask for the player's initials if the score makes it into the high-score table of the mode
+        if self.profile.is_high_score(self.mode.get_name(), self.score) {
+            self.keyboard = Some(VirtualKeyboard::new("New high score! Enter your initials", text_entry::LETTERS,
+                                                      INITIALS_LENGTH));
+        }
+    }
+
+    /// Add the score of the run that has ended to the high-score table of its mode, under the initials entered on
+    /// the virtual keyboard, or as an unknown player if none were entered
+    fn enter_initials(&mut self) {
+        let mut initials = self.keyboard.take().map(|keyboard| keyboard.get_text().to_string()).unwrap_or_default();
+        if initials.is_empty() {
+            initials = "?".repeat(INITIALS_LENGTH);
+        }
+        if let Err(error) = self.profile.record_high_score(self.mode.get_name(), &initials, self.score) {
+            self.warn(format!("Could not save the profile: {}", error));
+        }
+    }
+
+    /// Add the statistics of the current run to the lifetime statistics of the profile
+    fn record_run_stats(&mut self) {
+        if let Err(error) = self.profile.record_run(&self.run_stats, self.get_time()) {
+            self.warn(format!("Could not save the profile: {}", error));
+        }
+    }
+
+    /// Get the scene the game is on
+    pub fn get_scene(&self) -> Scene {
+        return self.scene;
+    }
+
+    /// Throw away the current run and go back to the main menu, keeping the chosen ship, mode, and modifiers
+    fn return_to_menu(&mut self) {
+        if !self.scene.can_transition_to(Scene::MainMenu) {
+            return;
+        }
+        self.rebuild();
+    }
+
+    /// Replace the game with a new one on the main menu, keeping the chosen ship, mode, modifiers, seed, and
+    /// settings
+    fn rebuild(&mut self) {
+        let audio = std::mem::replace(&mut self.audio, AudioManager::silent());
+        let assets = std::mem::take(&mut self.assets);
+        let rng = match self.seed {
+            Some(seed) => RandomStreams::new(seed),
+            None => RandomStreams::from_entropy(),
+        };
+        let mut state = MainState::create(self.world.get_size(), audio, rng);
+        state.assets = assets;
+        state.loader = self.loader.take();
+        // The content read from the working directory carries over instead of being read again, which would stall
+        // the game. Mods turned on or off only take effect the next time the game starts anyway
+        state.population = std::mem::take(&mut self.population);
+        state.mods = std::mem::replace(&mut self.mods, ModList::empty());
+        state.enemies = std::mem::take(&mut self.enemies);
+        state.registry = std::mem::take(&mut self.registry);
+        state.levels = std::mem::take(&mut self.levels);
+        #[cfg(feature = "scripts")]
+        state.scripts = std::mem::take(&mut self.scripts);
+        state.profile = std::mem::take(&mut self.profile);
+        state.mode = self.mode;
+        state.modifiers = self.modifiers.clone();
+        state.class_selection = self.class_selection;
+        state.gauntlet_params = self.gauntlet_params;
+        state.settings = self.settings;
+        state.set_tick_rate(self.tick_rate);
+        state.set_tuning(self.tuning);
+        state.tuning_watcher = self.tuning_watcher.take();
+        state.frame_limiter.set_cap(self.frame_limiter.get_cap());
+        state.shake.set_enabled(self.settings.has_screen_shake());
+        state.camera.set_view_size(self.camera.get_view_size());
+        state.world_size = self.world_size;
+        state.seed = self.seed;
+        state.ruleset = self.ruleset.clone();
+        state.wave_hooks = std::mem::take(&mut self.wave_hooks);
+        state.damage_hooks = std::mem::take(&mut self.damage_hooks);
+        state.death_hooks = std::mem::take(&mut self.death_hooks);
+        state.contacts = std::mem::take(&mut self.contacts);
+        state.contacts.clear();
+        state.rewind = self.rewind.take().map(|_| RewindBuffer::new(self.tick_rate));
+        *self = state;
+    }
+
+    /// Save the current run to the given file
+    pub fn save_game(&self, path: &std::path::Path) -> Result<(), String> {
+        return SaveGame::capture(self).save(path);
+    }
+
+    /// Replace the game with the run saved in the given file
+    pub fn load_game(&mut self, path: &std::path::Path) -> Result<(), String> {
+        let save = SaveGame::load(path)?;
+        self.rebuild();
+        self.load_save(save);
+        return Ok(());
+    }
+
+    /// Start a fresh run in the mode with the ship of the given saved run to lay out the arena, then put the saved
+    /// run in
+    fn load_save(&mut self, save: SaveGame) {
+        self.mode = save.get_mode();
+        self.ruleset = save.get_ruleset();
+        self.choose_class(save.get_class());
+        // Carry on numbering the actors from where the saved run left off, so the loaded run gives its new actors
+        // the same IDs as the saved one would have
+        self.last_actor_id = save.restore(self);
+        self.index_actors();
+        self.update_damage_taken();
+    }
+
+    /// Show the paused game one tick backward or forward through the history being kept
+    fn step_rewind(&mut self, forward: bool) {
+        let mut rewind = match self.rewind.take() {
+            Some(rewind) => rewind,
+            None => return,
+        };
+        if let Some((tick, snapshot)) = rewind.step(forward, self) {
+            snapshot.restore(self);
+            self.tick = tick;
+            self.index_actors();
+        }
+        self.rewind = Some(rewind);
+    }
+
+    /// Go back to the live game if the history is being shown. Only what the history keeps changes while it is
+    /// stepped through, as the game is paused, so putting that back restores the whole game
+    fn leave_rewind(&mut self) {
+        let live = self.rewind.as_mut().and_then(|rewind| rewind.take_live());
+        if let Some((tick, live)) = live {
+            live.restore(self);
+            self.tick = tick;
+            self.index_actors();
+        }
+    }
+
+    /// Start or stop keeping the history of the last few seconds to step back through
+    fn toggle_rewind(&mut self) {
+        self.leave_rewind();
+        self.rewind = match self.rewind {
+            Some(_) => None,
+            None => Some(RewindBuffer::new(self.tick_rate)),
+        };
+        let state = if self.rewind.is_some() { "on" } else { "off" };
+        self.note(format!("Rewind history turned {}", state));
+    }
+
+    /// Save the current run to the quicksave file, and tell the player how it went
+    fn quicksave(&mut self) {
+        // Save the live game, not the tick the history was stepped back to
+        self.leave_rewind();
+        let path = std::path::Path::new(saves::QUICKSAVE_FILE);
+        let message = match self.save_game(path) {
+            Ok(()) => format!("Game saved to {}", path.display()),
+            Err(error) => format!("Could not save the game: {}", error),
+        };
+        self.note(message.clone());
+        self.save_status = Some(message);
+    }
+
+    /// Load the run in the quicksave file, and tell the player how it went
+    fn quickload(&mut self) {
+        let path = std::path::Path::new(saves::QUICKSAVE_FILE);
+        let message = match self.load_game(path) {
+            Ok(()) => format!("Game loaded from {}", path.display()),
+            Err(error) => format!("Could not load the game: {}", error),
+        };
+        self.note(message.clone());
+        self.save_status = Some(message);
+    }
+
+    /// Start a new run with the same ship, mode, and modifiers as the one that has ended
+    fn restart(&mut self) {
+        self.finish_run();
+        self.return_to_menu();
+        self.choose_class(ShipClass::ALL[self.class_selection]);
+    }
+
+    /// Skip the ship class selection screen and start a run in the given mode with the given ship class, for
+    /// games that have no one to press the keys
+    pub fn start(&mut self, mode: GameMode, class: ShipClass) {
+        self.skip_loading();
+        self.mode = mode;
+        self.choose_class(class);
+    }
+
+    /// Take the offered perk at the given index and start the next wave, if perks are being offered and there is
+    /// a perk at that index
+    pub fn choose_perk(&mut self, choice: usize) {
+        let perk = match self.perk_choices.as_ref().and_then(|choices| choices.get(choice)) {
+            Some(&perk) => perk,
+            None => return,
+        };
+        self.player.apply_perk(perk);
+        self.perk_choices = None;
+        self.spawn_wave();
+        // Save a checkpoint at the start of every wave after the first
+        self.checkpoint = Some(Checkpoint::capture(self));
+    }
+
+    /// Get how the run ended, if it has
+    pub fn get_outcome(&self) -> Option<RunOutcome> {
+        return self.outcome;
+    }
+
+    /// Start a run that stress tests the game for the given number of ticks, filling the arena with turrets and
+    /// shots while a bot plays, and timing each system of the update and the drawing
+    pub fn start_benchmark(&mut self, ticks: u64) {
+        // Checking the state survives serialization and keeping the history would swamp the timings
+        self.round_trip_interval = None;
+        self.rewind = None;
+        self.benchmark = Some(Benchmark::start(self, ticks));
+    }
+
+    /// Check if the benchmark being run has run all of its ticks
+    pub fn is_benchmark_finished(&self) -> bool {
+        return self.benchmark.as_ref().is_some_and(|benchmark| benchmark.is_finished());
+    }
+
+    /// Get the summary of the timings of the benchmark being run, or of the last one to finish in the window once
+    /// it has closed the game, if there is one
+    pub fn get_benchmark_summary(&self) -> Option<String> {
+        return self.benchmark.as_ref().map(|benchmark| benchmark.get_summary())
+            .or_else(|| self.benchmark_summary.clone());
+    }
+
+    /// Get the measurements of the run
+    pub fn get_telemetry(&self) -> &Telemetry {
+        return &self.telemetry;
+    }
+
+    /// Check if the game is waiting for the player to choose a perk
+    pub fn is_choosing_perk(&self) -> bool {
+        return self.perk_choices.is_some();
+    }
+
+    /// Start the run in the chosen mode, once the player has picked a ship class
+    fn start_run(&mut self) {
+        self.note(format!("Run started in {} mode with seed {}", self.mode.get_name(), self.rng.get_seed()));
+        // Shape the arena like the chosen level in the waves mode, and as a plain rectangle in the other modes
+        let (width, height) = self.world.get_size();
+        let world = match self.mode {
+            GameMode::Waves => self.get_level().get_world((width, height)),
+            _ => World::rect(width, height),
+        };
+        self.set_world(world);
+        // Link opposite corners of the arena with a pair of teleporters, which shots can pass through too, except
+        // in the gauntlet where they would skip the corridor, and on the open map
+        let standard_map = self.ruleset.get_map() == MapLayout::Standard;
+        if self.mode != GameMode::Gauntlet && standard_map {
+            self.teleporters.push(TeleporterPair::new(Point::new(width * 0.15, height * 0.2),
+                                                      Point::new(width * 0.85, height * 0.8), true));
+        }
+        // Lay a boost strip across the middle of the arena, with slow fields in the other two corners
+        if standard_map {
+            self.floor.push(FloorZone::new(FloorKind::Boost, width * 0.3, height * 0.5 - 20.0, width * 0.4, 40.0));
+            self.floor.push(FloorZone::new(FloorKind::Slow, width * 0.2, height * 0.65, width * 0.15, height * 0.2));
+            self.floor.push(FloorZone::new(FloorKind::Slow, width * 0.65, height * 0.15, width * 0.15, height * 0.2));
+        }
+        match self.mode {
+            GameMode::Waves => {
+                // Put the obstacles of the chosen level in the arena
+                self.obstacles = self.get_level().create_obstacles(self.world.get_size());
+                self.spawn_wave();
+            }
+            GameMode::Capture => self.spawn_capture_level(),
+            GameMode::Escort => self.spawn_escort_level(),
+            GameMode::Gauntlet => self.spawn_gauntlet_level(),
+            GameMode::Defense => {
+                let (_, height) = self.world.get_size();
+                let base = Base::new(Point::new(self.tuning.base_radius * 2.0, height/2.0), &self.tuning);
+                self.base = Some(self.add_actor(Box::new(base)));
+                self.credits = DEFENSE_STARTING_CREDITS;
+                self.spawn_attack_wave();
+            }
+        }
+    }
+
+    /// Start the next wave of the tower defense mode, sending drones at the base from the right of the window, or
+    /// from the level's spawn region
+    fn spawn_attack_wave(&mut self) {
+        self.wave += 1;
+        self.note(format!("Attack wave {} started", self.wave));
+        self.wave_enemies.clear();
+
+        let (width, height) = self.world.get_size();
+        let base_position = match self.base.and_then(|id| self.find_actor(id)) {
+            Some(base) => *base.get_position(),
+            None => return,
+        };
+
+        // The wave grows by two drones every wave and is scaled by the director's intensity
+        let count = self.director.start_wave(3 + 2 * self.wave as usize, 0);
+        let radius = self.tuning.drone_radius;
+        for i in 0..count {
+            let y = self.rng.gameplay().gen_range(radius, height - radius);
+            // Stagger the drones so they don't all arrive at once
+            let x = width - radius - (i % 3) as f32 * radius * 3.0;
+            let position = self.pick_level_spawn_point(Point::new(x, y));
+            let drone = Drone::attacker(position, &self.world, &self.tuning, &base_position);
+            self.add_wave_enemy(Box::new(drone));
+        }
+        self.run_wave_hooks();
+    }
+
+    /// Spend credits to place a friendly turret at the player's position in the tower defense mode
+    fn build_turret(&mut self) {
+        if self.mode != GameMode::Defense || self.credits < DEFENSE_TURRET_COST {
+            return;
+        }
+        self.credits -= DEFENSE_TURRET_COST;
+        let mut turret = Turret::friendly(self.player.position, &self.world, &self.tuning);
+        turret.seed(self.rng.gameplay().gen());
+        self.add_actor(Box::new(turret));
+    }
+
+    /// Spawn the drone of the escort mode on the left of the window, with lines of turrets above and below its
+    /// path
+    fn spawn_escort_level(&mut self) {
+        self.wave = 1;
+        let (width, height) = self.world.get_size();
+
+        let drone = Drone::new(Point::new(self.tuning.drone_radius * 2.0, height/2.0), &self.world, &self.tuning);
+        self.escort_drone = Some(self.add_actor(Box::new(drone)));
+
+        self.spawn_formation(FormationShape::Line, 4, 90.0, Point::new(width/2.0, height/6.0), (30.0, 0.0));
+        self.spawn_formation(FormationShape::Line, 4, 90.0, Point::new(width/2.0, height*5.0/6.0), (-30.0, 0.0));
+    }
+
+    /// Generate a gauntlet, line its corridor with turrets, and put the player at its start
+    fn spawn_gauntlet_level(&mut self) {
+        self.wave = 1;
+        let bounds = self.world.get_size();
+        let gauntlet = Gauntlet::generate(bounds, &self.gauntlet_params, &self.tuning, self.rng.gameplay());
+        for position in gauntlet.get_turret_positions() {
+            self.add_turret(Turret::new(*position, &self.world, &self.tuning));
+        }
+        self.player.position = *gauntlet.get_start();
+        self.gauntlet = Some(gauntlet);
+    }
+
+    /// Set the density and corridor width of the gauntlets generated for the gauntlet mode
+    pub fn set_gauntlet_params(&mut self, params: GauntletParams) {
+        self.gauntlet_params = params;
+    }
+
+    /// Spawn the capture zones of the capture points mode, each defended by a ring of turrets
+    fn spawn_capture_level(&mut self) {
+        self.wave = 1;
+        let (width, height) = self.world.get_size();
+
+        let zone_positions = [
+            Point::new(width/5.0, height/2.0),
+            Point::new(width/2.0, height/5.0),
+            Point::new(width*0.8, height/2.0),
+        ];
+        for position in zone_positions.iter() {
+            self.zones.push(CaptureZone::new(*position));
+            // Surround the zone with a ring of turrets that stays in place
+            self.spawn_formation(FormationShape::Ring, 3, capture::ZONE_RADIUS + 30.0, *position, (0.0, 0.0));
+        }
+    }
+
+    /// Start the next wave, spawning its turrets
+    fn spawn_wave(&mut self) {
+        self.wave += 1;
+        self.note(format!("Wave {} started", self.wave));
+        self.wave_enemies.clear();
+        // The previous wave's formations have been destroyed
+        self.formations.clear();
+
+        let bounds = self.world.get_size();
+        let (width, height) = bounds;
+
+        // Add the actors of the chosen level, which by default are 4 turrets near the corners: one firing burning
+        // shots, one firing slowing shots, and one armored against kinetic damage
+        let level = self.get_level();
+        let (level_name, placements) = (level.get_name().to_string(), level.get_actors(bounds));
+        for (position, name) in placements {
+            if self.spawn_named(&name, position).is_none() {
+                self.warn(format!("Level {} places {}, which isn't a registered actor", level_name, name));
+            }
+        }
+
+        // Add a line of turrets drifting along the top of the window, which grows by one turret every wave and is
+        // scaled by the director's intensity and the rules. The director may also send a few reinforcements during
+        // the wave
+        let base_size = self.ruleset.scale_turrets(2 + self.wave as usize);
+        let formation_size = self.director.start_wave(base_size, 1 + self.wave);
+        self.spawn_formation(FormationShape::Line, formation_size, 60.0, Point::new(width/2.0, height/8.0), (40.0, 0.0));
+
+        // From the second wave, send a swarm of kamikaze drones in from the bottom of the window, or from the
+        // level's spawn region, one bigger every wave
+        if self.wave > 1 {
+            let swarm_size = self.ruleset.scale_kamikazes(KAMIKAZE_SWARM_SIZE + self.wave - 2);
+            let position = self.pick_level_spawn_point(Point::new(width/2.0, height - 40.0));
+            self.spawn_swarm(swarm_size, position);
+        }
+
+        // From the third wave, add a spawner below the middle of the line, or in the level's spawn region, which
+        // calls in drones as the wave goes on
+        if self.wave >= SPAWNER_FIRST_WAVE {
+            let position = self.pick_level_spawn_point(Point::new(width/2.0, height/4.0));
+            self.spawn_named("turret.spawner", position);
+        }
+
+        // Every few waves, add a boss in the middle of the top half of the window, unless the rules leave them out
+        if self.wave.is_multiple_of(BOSS_WAVE_INTERVAL) && self.ruleset.has_bosses() {
+            let boss = Turret::boss(Point::new(width/2.0, height/3.0), &self.world, &self.tuning);
+            let max_health = boss.health.get_max();
+            let id = self.add_turret(boss);
+            self.boss_bar.track(id, &format!("Fortress (wave {})", self.wave), max_health, BOSS_PHASES);
+        }
+
+        self.spawn_defined_enemies();
+        self.run_wave_hooks();
+    }
+
+    /// Get the level the waves are laid out by, falling back to the standard level if the chosen one isn't loaded
+    fn get_level(&self) -> &Level {
+        return self.levels.iter()
+            .find(|level| Some(level.get_name()) == self.ruleset.get_level())
+            .unwrap_or(&self.standard_level);
+    }
+
+    /// Add the turrets the active mods define for this wave at random positions in their spawn region, or the
+    /// level's, or otherwise the top half of the window
+    fn spawn_defined_enemies(&mut self) {
+        let (width, height) = self.world.get_size();
+        let margin = self.tuning.turret_radius * 2.0;
+        let level_region = self.get_level().get_spawn_region().cloned();
+        for definition in self.enemies.clone() {
+            let region = definition.get_region().cloned().or_else(|| level_region.clone());
+            for _ in 0..definition.get_count(self.wave) {
+                let position = match region.as_ref().and_then(|region| self.pick_spawn_point(region)) {
+                    Some(position) => position,
+                    None => {
+                        let rng = self.rng.gameplay();
+                        Point::new(rng.gen_range(margin, width - margin), rng.gen_range(margin, height / 2.0))
+                    }
+                };
+                self.spawn_named(&format!("turret.{}", definition.get_name()), position);
+            }
+        }
+    }
+
+    /// Pick a random point in the given spawn region of the current level, away from the player if it can be, or
+    /// none if the region is a zone the level doesn't have
+    pub fn pick_spawn_point(&mut self, region: &SpawnRegion) -> Option<Point> {
+        let zones = self.get_level().get_zones(self.world.get_size());
+        let point = region.pick_away_from(&self.world, &zones, &self.player.position, SPAWN_CLEARANCE,
+                                          self.rng.gameplay());
+        // Only warn about a missing zone the first time, instead of for every enemy of every wave
+        if let (None, SpawnRegion::Zone(name)) = (&point, region) {
+            if self.missing_zones.insert(name.clone()) {
+                self.warn(format!("Enemies spawn in zone {}, which the level doesn't have", name));
+            }
+        }
+        return point;
+    }
+
+    /// Pick a random point in the current level's spawn region, away from the player if it can be, or use the given
+    /// point if the level doesn't choose a region or the region can't be used
+    fn pick_level_spawn_point(&mut self, default: Point) -> Point {
+        let region = self.get_level().get_spawn_region().cloned();
+        return region.and_then(|region| self.pick_spawn_point(&region)).unwrap_or(default);
+    }
+
+    /// Schedule a swarm of the given number of kamikaze drones clustered around the given position, which arrives
+    /// a few seconds into the wave
+    fn spawn_swarm(&mut self, count: u32, position: Point) {
+        let mut positions = Vec::new();
+        for _ in 0..count {
+            let rng = self.rng.gameplay();
+            let mut drone_position = position;
+            drone_position.move_distance(rng.gen_range(0.0, 40.0), rng.gen_range(0.0, 2.0 * PI));
+            drone_position.keep_in_bounds(&self.world);
+            positions.push(drone_position);
+        }
+        self.spawns.schedule(KAMIKAZE_SWARM_DELAY, SpawnKind::Kamikaze, &positions);
+    }
+
+    /// Schedule any reinforcement turrets the director has decided to send, in the level's spawn region or at the
+    /// edge of the window furthest from the player, giving the player a moment of warning before they arrive
+    fn spawn_reinforcements(&mut self) {
+        let (width, height) = self.world.get_size();
+        let radius = self.tuning.turret_radius;
+        let region = self.get_level().get_spawn_region().cloned();
+        for _ in 0..self.director.take_reinforcements() {
+            if let Some(position) = region.as_ref().and_then(|region| self.pick_spawn_point(region)) {
+                self.spawns.schedule(REINFORCEMENT_DELAY, SpawnKind::Turret, &[position]);
+                continue;
+            }
+            let edges = [
+                Point::new(width/2.0, radius * 2.0),
+                Point::new(width/2.0, height - radius * 2.0),
+                Point::new(radius * 2.0, height/2.0),
+                Point::new(width - radius * 2.0, height/2.0),
+            ];
+            let player_position = self.player.get_position();
+            let furthest = edges.iter()
+                .max_by(|a, b| {
+                    a.distance_to(player_position).partial_cmp(&b.distance_to(player_position))
+                        .unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .cloned()
+                .unwrap();
+            self.spawns.schedule(REINFORCEMENT_DELAY, SpawnKind::Turret, &[furthest]);
+        }
+    }
+
+    /// Schedule one of the given kind to spawn at each of the given positions after the given number of seconds.
+    /// Enemies join the wave that is running when they arrive, and the wave isn't cleared while any are waiting
+    pub fn schedule_spawns(&mut self, delay: f32, kind: SpawnKind, positions: &[Point]) {
+        self.spawns.schedule(delay, kind, positions);
+    }
+
+    /// Create everything in the spawn schedule that is due
+    fn spawn_scheduled(&mut self, dt: f32) {
+        for spawn in self.spawns.update(dt) {
+            let position = *spawn.get_position();
+            match spawn.get_kind() {
+                SpawnKind::Turret => {
+                    self.spawn_named("turret.basic", position);
+                }
+                SpawnKind::ArmoredTurret => {
+                    self.spawn_named("turret.armored", position);
+                }
+                SpawnKind::Spawner => {
+                    self.spawn_named("turret.spawner", position);
+                }
+                SpawnKind::Kamikaze => {
+                    // Aim the drone at wherever the player is by the time it arrives
+                    let heading = position.heading_to(&self.player.position);
+                    self.add_wave_enemy(Box::new(Kamikaze::new(position, &self.world, &self.tuning, heading)));
+                }
+                SpawnKind::Shield(charges) => {
+                    self.add_actor(Box::new(Pickup::new(position, PickupKind::Shield(charges), &self.tuning)));
+                }
+            }
+        }
+    }
+
+    /// Schedule the spawns every actor has called in this tick, such as the drones of spawner turrets, which are
+    /// marked in the arena before they arrive like the wave's own
+    fn schedule_called_spawns(&mut self) {
+        let called: Vec<(SpawnKind, Point)> = self.actors.iter_mut().flat_map(|actor| actor.take_spawns()).collect();
+        for (kind, position) in called {
+            self.spawns.schedule(REINFORCEMENT_DELAY, kind, &[position]);
+        }
+    }
+
+    /// Check if every enemy of the current wave has been destroyed or hacked
+    fn is_wave_cleared(&self) -> bool {
+        let is_hostile_wave_enemy = |id: u32, team: Team| self.wave_enemies.contains(&id) && team == Team::Hostile;
+        return !self.actors.iter().any(|actor| is_hostile_wave_enemy(actor.get_id(), actor.get_team()))
+            && !self.streamer.any_parked(is_hostile_wave_enemy)
+            && !self.spawns.has_pending_enemies();
+    }
+
+    /// Spawn a formation of turrets around an anchor at the given position that drifts with the given velocity
+    fn spawn_formation(&mut self, shape: FormationShape, count: usize, spacing: f32, position: Point,
+                       velocity: (f32, f32)) {
+        // Formations are numbered by the same counter as the actors, so no two of them share an ID
+        let id = self.get_next_actor_id();
+        let anchor = (position.x, position.y);
+        for offset in shape.get_offsets(count, spacing) {
+            let turret = Turret::in_formation(anchor, FormationSlot::new(id, offset), &self.world, &self.tuning);
+            self.add_turret(turret);
+        }
+
+        // Keep the whole formation, not just the anchor, inside the window
+        let margin = spacing * count as f32 / 2.0 + self.tuning.turret_radius;
+        self.formations.push(DriftingAnchor::new(id, anchor, velocity, margin));
+    }
+
+    /// Move every actor in a formation to its place around the formation's anchor. Members of a formation that is
+    /// gone, like the hacked turrets of a wave that is over, stay where they are
+    fn follow_formations(&mut self) {
+        for actor in &mut self.actors {
+            let anchor = actor.get_formation()
+                .and_then(|id| self.formations.iter().find(|formation| formation.get_id() == id));
+            if let Some(formation) = anchor {
+                actor.follow_formation(formation.get_position());
+            }
+        }
+    }
+
+    /// Spawn a shield pickup every time the pickup interval elapses
+    fn spawn_pickups(&mut self, dt: f32) {
+        if !self.pickup_timer.update(dt) {
+            return;
+        }
+
+        // Alternate between spawning at the top and bottom center of the window, away from the turrets
+        let (width, height) = self.world.get_size();
+        let y = if self.next_pickup_at_top { height/4.0 } else { height*0.75 };
+        self.next_pickup_at_top = !self.next_pickup_at_top;
+
+        let pickup = Pickup::new(Point::new(width/2.0, y), PickupKind::Shield(SHIELD_PICKUP_CHARGES), &self.tuning);
+        self.add_actor(Box::new(pickup));
+    }
+
+    /// Start and run the random global events
+    fn update_events(&mut self, dt: f32) {
+        let started = self.events.update(dt, self.rng.gameplay());
+        if let Some(event) = started {
+            self.note(format!("Global event started: {}", event.get_name()));
+        }
+
+        let rng = self.rng.gameplay();
+        let (width, height) = self.world.get_size();
+        match started {
+            // Overdrive every turret for as long as the event lasts
+            Some(GlobalEvent::Overdrive) => {
+                let overdrive = StatusEffect::overdrive(2.0, GlobalEvent::Overdrive.get_duration());
+                for actor in &mut self.actors {
+                    if self.wave_enemies.contains(&actor.get_id()) {
+                        actor.apply_status_effect(overdrive.clone());
+                    }
+                }
+            }
+            // Scatter pickups around the arena
+            Some(GlobalEvent::PickupRain) => {
+                let positions: Vec<Point> = (0..PICKUP_RAIN_COUNT)
+                    .map(|_| Point::new(rng.gen_range(0.1, 0.9) * width, rng.gen_range(0.1, 0.9) * height))
+                    .collect();
+                for position in positions {
+                    self.add_actor(Box::new(Pickup::new(position, PickupKind::Shield(1), &self.tuning)));
+                }
+            }
+            _ => (),
+        }
+
+        // Drop meteors from the top of the window for as long as the meteor shower lasts
+        let meteor_shower = self.events.get_active() == Some(GlobalEvent::MeteorShower);
+        let rng = self.rng.gameplay();
+        if meteor_shower && rng.gen_bool((METEORS_PER_SECOND * dt) as f64) {
+            let position = Point::new(rng.gen_range(0.0, width), self.tuning.shot_radius);
+            let velocity = Velocity::new(250.0, PI/2.0 + rng.gen_range(-0.3, 0.3));
+            let damage = Damage::explosive(30.0);
+            let mut meteor = Shot::new(position, &self.world, velocity, damage, 5.0, None, Team::Hostile);
+            meteor.radius = self.tuning.shot_radius;
+            self.add_actor(Box::new(meteor));
+        }
+    }
+
+    /// Hack the nearest damaged turret within range of the player, if the hack is ready
+    fn hack_nearest_turret(&mut self) {
+        if !self.hack_cooldown.is_ready() {
+            return;
+        }
+
+        // Try the hostile turrets in range from nearest to farthest until one is damaged enough to be hacked
+        let player_position = self.player.position;
+        let mut candidates: Vec<&mut Box<dyn Actor>> = self.actors.iter_mut()
+            .filter(|actor| actor.get_team() == Team::Hostile)
+            .filter(|actor| actor.get_position().distance_to(&player_position) < HACK_RANGE)
+            .collect();
+        candidates.sort_by(|a, b| {
+            let a_distance = a.get_position().distance_to(&player_position);
+            let b_distance = b.get_position().distance_to(&player_position);
+            return a_distance.partial_cmp(&b_distance).unwrap();
+        });
+        if candidates.into_iter().any(|actor| actor.hack(self.tuning.hack_duration)) {
+            self.hack_cooldown.trigger();
+        }
+    }
+
+    /// Point the actors on the player's team at the hostile enemies of the wave, which friendly and hacked
+    /// turrets fire at, and the other actors at the player, which dormant turrets watch for and magnetic pickups
+    /// drift towards. The enemies are shared out by the target spread of the balance, so with a spread above zero
+    /// the turrets split their fire instead of all aiming at the nearest enemy
+    fn update_targets(&mut self) {
+        self.components.gather(&self.actors);
+        let components = &self.components;
+        let enemies: Vec<Point> = (0..components.len())
+            .filter(|&i| self.wave_enemies.contains(&components.ids[i]) && components.teams[i] == Team::Hostile)
+            .map(|i| components.positions[i])
+            .collect();
+        let mut picker = TargetPicker::new(enemies, self.tuning.target_spread);
+
+        for (i, actor) in self.actors.iter_mut().enumerate() {
+            if components.teams[i] != Team::Player {
+                actor.set_target(Some(self.player.position));
+                continue;
+            }
+            actor.set_target(picker.pick(&components.positions[i]));
+        }
+    }
+
+    /// Steer every actor that flies in a flock by its neighbours, found through a broad-phase grid
+    fn update_flocks(&mut self) {
+        let members: Vec<(usize, Point, Velocity)> = self.actors.iter().enumerate()
+            .filter_map(|(i, actor)| Some((i, *actor.get_position(), actor.get_flock_velocity()?.clone())))
+            .collect();
+        let mut grid = SpatialGrid::new(FLOCK_RADIUS);
+        for (member, (_, position, _)) in members.iter().enumerate() {
+            grid.insert(member, position);
+        }
+
+        for (member, (actor_index, position, velocity)) in members.iter().enumerate() {
+            let neighbours: Vec<(Point, Velocity)> = grid.get_nearby(position, FLOCK_RADIUS).into_iter()
+                .filter(|&other| other != member && members[other].1.distance_to(position) < FLOCK_RADIUS)
+                .map(|other| (members[other].1, members[other].2.clone()))
+                .collect();
+            let heading = flocking::get_heading(position, velocity, &neighbours, Some(&self.player.position));
+            self.actors[*actor_index].set_flock_heading(heading);
+        }
+    }
+
+    /// Catch the player's flying grapple on any enemy it reaches
+    fn check_grapple_hits(&mut self) {
+        let hook = match self.player.grapple.get_flying_hook() {
+            Some(hook) => *hook,
+            None => return,
+        };
+        let anchor = self.actors.iter()
+            .filter(|actor| self.wave_enemies.contains(&actor.get_id()))
+            .find(|actor| actor.get_position().distance_to(&hook) < actor.get_radius())
+            .map(|actor| (*actor.get_position(), actor.get_radius()));
+        if let Some((position, radius)) = anchor {
+            self.player.grapple.attach(position, radius);
+        }
+    }
+
+    /// Take a read-only snapshot of the game, for agents, overlays, and tools watching it
+    pub fn observe(&self) -> Observation {
+        let (velocity_x, velocity_y) = self.player.velocity.get_components();
+        let player = PlayerObservation {
+            id: self.player.id,
+            x: self.player.position.x,
+            y: self.player.position.y,
+            velocity_x,
+            velocity_y,
+            heading: self.player.velocity.heading,
+            health: self.player.health.get_current(),
+            max_health: self.player.health.get_max(),
+            shield_charges: self.player.shield_charges,
+        };
+
+        let mut components = Components::new();
+        components.gather(&self.actors);
+        let mut actors: Vec<ActorObservation> = (0..components.len())
+            .map(|i| {
+                let (velocity_x, velocity_y) = match &components.velocities[i] {
+                    Some(velocity) => velocity.get_components(),
+                    None => (0.0, 0.0),
+                };
+                return ActorObservation {
+                    id: components.ids[i],
+                    kind: components.kinds[i],
+                    team: components.teams[i],
+                    x: components.positions[i].x,
+                    y: components.positions[i].y,
+                    velocity_x,
+                    velocity_y,
+                    radius: components.radii[i],
+                    health: components.healths[i],
+                };
+            })
+            .collect();
+        // Sort the actors from nearest to farthest from the player
+        let distance = |actor: &ActorObservation| (actor.x - player.x).hypot(actor.y - player.y);
+        actors.sort_by(|a, b| distance(a).partial_cmp(&distance(b)).unwrap_or(std::cmp::Ordering::Equal));
+
+        return Observation {
+            tick: self.tick,
+            time: self.get_time(),
+            scene: self.scene,
+            score: self.score,
+            wave: self.wave,
+            bounds: self.world.get_size(),
+            player,
+            actors,
+        };
+    }
+
+    /// Control the player with the given action instead of the keyboard, until the next action or key press
+    pub fn apply_action(&mut self, action: Action) {
+        self.inputs.record(self.tick, self.get_time(), format!("action {:?}", action));
+        self.player.apply_action(action);
+    }
+
+    /// Get the number of seconds the game has been simulated for
+    pub fn get_time(&self) -> f32 {
+        return self.tick as f32 / self.tick_rate as f32;
+    }
+
+    /// Get the number of ticks simulated per second of play
+    pub fn get_tick_rate(&self) -> u32 {
+        return self.tick_rate;
+    }
+
+    /// Simulate the given number of ticks per second of play from now on. Everything that changes over time is
+    /// measured in seconds, so a run plays out the same at any rate, only more or less finely
+    pub fn set_tick_rate(&mut self, rate: u32) {
+        self.tick_rate = rate.max(1);
+        self.scheduler.set_tick_rate(self.tick_rate);
+        if self.rewind.is_some() {
+            self.rewind = Some(RewindBuffer::new(self.tick_rate));
+        }
+    }
+
+    /// Get the length of time every tick simulates, before the time scale stretches it
+    fn get_tick_duration(&self) -> f32 {
+        return 1.0 / self.tick_rate as f32;
+    }
+
+    /// Run the scripts of the scripted actors, showing them the player and every other actor, then carry out the
+    /// commands they issued
+    #[cfg(feature = "scripts")]
+    fn run_scripts(&mut self, dt: f32) {
+        let mut views: Vec<ActorView> = self.actors.iter()
+            .map(|actor| {
+                let heading = match (actor.get_script(), actor.get_velocity()) {
+                    (Some(binding), _) => binding.heading,
+                    (None, Some(velocity)) => velocity.heading,
+                    (None, None) => 0.0,
+                };
+                return ActorView {
+                    id: actor.get_id(),
+                    x: actor.get_position().x,
+                    y: actor.get_position().y,
+                    radius: actor.get_radius(),
+                    heading,
+                    is_player: false,
+                };
+            })
+            .collect();
+        views.push(ActorView {
+            id: self.player.id,
+            x: self.player.position.x,
+            y: self.player.position.y,
+            radius: self.player.get_radius(),
+            heading: self.player.velocity.heading,
+            is_player: true,
+        });
+        let views: std::rc::Rc<[ActorView]> = views.into();
+        let mut turrets = self.actors.iter().filter(|actor| actor.get_kind() == ActorKind::Turret).count();
+
+        for i in 0..self.actors.len() {
+            let (name, time_since_last_shot) = match self.actors[i].get_script() {
+                Some(binding) => (binding.name.to_string(), binding.time_since_last_shot),
+                None => continue,
+            };
+            let budget = InstructionBudget::new(scripting::DEFAULT_INSTRUCTION_BUDGET);
+            let api = ScriptApi::new(views[i].clone(), views.clone(), self.get_time(), dt,
+                                     time_since_last_shot, budget);
+            for command in self.scripts.run(&name, api) {
+                match command {
+                    // Turrets spawned by scripts join the wave like the ones the waves spawn, until the arena is full
+                    ScriptCommand::SpawnTurret { x, y } => {
+                        if turrets < MAX_SCRIPT_TURRETS {
+                            self.spawns.schedule(0.0, SpawnKind::Turret, &[Point::new(x, y)]);
+                            turrets += 1;
+                        }
+                    }
+                    command => self.actors[i].apply_script_command(&command),
+                }
+            }
+        }
+        for issue in self.scripts.take_issues() {
+            self.warn(issue);
+        }
+    }
+
+    /// Despawn the actors over the population cap of their type. The enemies of the wave, which include the
+    /// attackers in the tower defense mode and the boss, have to be destroyed and are never despawned
+    fn enforce_population_caps(&mut self) {
+        let wave_enemies = &self.wave_enemies;
+        let excess = self.population.find_excess(&self.actors, &self.player.position,
+                                                 |actor| wave_enemies.contains(&actor.get_id()));
+        if !excess.is_empty() {
+            self.remove_actors(&excess);
+            self.note(format!("Despawned {} actors over the population caps", excess.len()));
+        }
+    }
+
+    /// Add a message to the log attached to bug reports, and log it
+    fn note(&mut self, message: String) {
+        log::info!(target: "turrets", "{}", message);
+        self.log.add(self.get_time(), message);
+    }
+
+    /// Add a message about something that went wrong to the log attached to bug reports, and report it
+    fn warn(&mut self, message: String) {
+        log::warn!(target: "turrets", "{}", message);
+        self.log.add(self.get_time(), message);
+    }
+
+    /// Describe the current state of the game in text, for bug reports
+    fn describe_state(&self) -> String {
+        let mut lines = vec![
+            format!("Tick: {}", self.tick),
+            format!("Mode: {}", self.mode.get_name()),
+            format!("Seed: {}", self.rng.get_seed()),
+            format!("Wave: {}   Score: {}   Restarts left: {}", self.wave, self.score, self.checkpoint_restarts),
+            format!("Player: position ({:.1}, {:.1})   heading {:.2}   health {:.1}/{:.1}   shield {}",
+                    self.player.position.x, self.player.position.y, self.player.velocity.heading,
+                    self.player.health.get_current(), self.player.health.get_max(), self.player.shield_charges),
+            format!("Actors ({} active, {} parked):", self.actors.len(), self.streamer.get_parked_count()),
+        ];
+        for actor in &self.actors {
+            let team = match actor.get_team() {
+                Team::Player => "player",
+                Team::Hostile => "hostile",
+                Team::Neutral => "neutral",
+            };
+            let position = actor.get_position();
+            lines.push(format!("  #{} {} at ({:.1}, {:.1}) radius {:.1}{}", actor.get_id(), team, position.x,
+                               position.y, actor.get_radius(),
+                               if self.wave_enemies.contains(&actor.get_id()) { " (wave enemy)" } else { "" }));
+        }
+        return lines.join("\n");
+    }
+
+    /// Describe the settings of the game in text, for bug reports
+    fn describe_config(&self) -> String {
+        let active_mods: Vec<&str> = self.mods.get_active_mods().map(|entry| entry.get_manifest().get_name()).collect();
+        let (width, height) = self.world.get_size();
+        return [
+            format!("Version: {}", env!("CARGO_PKG_VERSION")),
+            format!("Tick rate: {}", self.tick_rate),
+            format!("Frame cap: {}", self.frame_limiter.get_cap().map_or(String::from("off"), |cap| cap.to_string())),
+            format!("Window size: {}x{}", width, height),
+            format!("Active mods: {}", active_mods.join(", ")),
+            format!("Issues loading mods: {}", self.mods.get_issues().join("; ")),
+        ].join("\n");
+    }
+
+    /// Bundle a bug report into the reports directory
+    fn save_bug_report(&mut self, ctx: &mut Context) {
+        let report = BugReport {
+            snapshot: self.describe_state(),
+            config: self.describe_config(),
+            log: &self.log,
+            inputs: &self.inputs,
+        };
+        self.bug_report = Some(report.save(ctx, RunSummary::now()));
+    }
+
+    /// Summarize the run that ended with the given outcome for its result card
+    fn summarize(&self, outcome: RunOutcome) -> RunSummary {
+        // The active mods and challenge modifiers are both listed as mutators
+        let mut mutators: Vec<String> = self.mods.get_active_mods()
+            .map(|entry| entry.get_manifest().get_name().to_string())
+            .collect();
+        mutators.append(&mut self.modifiers.get_challenge_descriptions());
+        return RunSummary {
+            mode: self.mode,
+            outcome,
+            seed: self.rng.get_seed(),
+            score: self.score,
+            wave: self.wave,
+            accuracy: self.telemetry.get_accuracy(),
+            mutators,
+            assists: self.modifiers.get_assist_descriptions(),
+            timestamp: RunSummary::now(),
+        };
+    }
+
+    /// Throw a bomb ahead of the player, or set off the bomb the player has already thrown if it is still live
+    fn throw_or_detonate_bomb(&mut self) {
+        if let Some(bomb) = self.bomb.and_then(|id| self.find_actor_mut(id)) {
+            bomb.detonate();
+            return;
+        }
+
+        let damage = Damage::explosive(self.tuning.bomb_damage * self.player.damage_multiplier);
+        let bomb = Bomb::new(self.player.position, &self.world, &self.tuning, self.player.velocity.heading, damage);
+        self.bomb = Some(self.add_actor(Box::new(bomb)));
+    }
+
+    /// Do the damage of every explosion to everything in its blast, including the player
+    fn handle_explosions(&mut self) {
+        let explosions: Vec<(u32, Point, f32, Damage)> = self.actors.iter_mut()
+            .filter_map(|actor| {
+                let (radius, damage) = actor.take_explosion()?;
+                return Some((actor.get_id(), *actor.get_position(), radius, damage));
+            })
+            .collect();
+
+        for (id, position, radius, damage) in explosions {
+            for actor in &mut self.actors {
+                if actor.get_id() == id || actor.get_position().distance_to(&position) > radius + actor.get_radius() {
+                    continue;
+                }
+                let amount = actor.do_damage(damage);
+                self.bus.emit(GameEvent::ActorDamaged { id: actor.get_id(), source: Some(id), amount });
+            }
+            // The player is caught in its own blast too, unless its shield absorbs it
+            if self.player.position.distance_to(&position) <= radius + self.player.get_radius()
+                && damage.get_amount() > 0.0 && !self.player.absorb_hit() {
+                let heading = position.heading_to(&self.player.position);
+                let amount = self.player.take_hit(damage, heading);
+                self.bus.emit(GameEvent::ActorDamaged { id: self.player.id, source: Some(id), amount });
+            }
+            self.bus.emit(GameEvent::Impact { position });
+        }
+    }
+
+    /// Alert the actors near any actor that has raised an alarm
+    fn spread_alarms(&mut self) {
+        let alarms: Vec<Point> = self.actors.iter_mut()
+            .filter_map(|actor| if actor.take_alarm() { Some(*actor.get_position()) } else { None })
+            .collect();
+
+        for alarm in alarms {
+            for actor in &mut self.actors {
+                if actor.get_position().distance_to(&alarm) < ALERT_RADIUS {
+                    actor.alert(ALERT_DELAY);
+                }
+            }
+        }
+    }
+
+    /// Tell the player and every actor how the floor under them changes their movement speed, before they move.
+    /// Turrets and bases don't move under their own power, so the floor leaves them be
+    fn apply_floor(&mut self) {
+        let multiplier = FloorZone::get_speed_multiplier(&self.floor, &self.player.position);
+        self.player.set_floor_speed_multiplier(multiplier);
+        for actor in &mut self.actors {
+            actor.set_floor_speed_multiplier(FloorZone::get_speed_multiplier(&self.floor, actor.get_position()));
+        }
+    }
+
+    /// Push the player out of any obstacle it has moved into, and stop any shot that has hit one, if their
+    /// collision layers collide with obstacles. With collision physics on, the player and any other actor with a
+    /// mass bounce off the obstacles instead
+    fn apply_obstacles(&mut self) {
+        let player_radius = self.player.get_radius();
+        let blocks_player = self.collision_rules.collides(CollisionLayer::Player, CollisionLayer::Obstacle);
+        let physics = self.ruleset.has_collision_physics();
+        for obstacle in &self.obstacles {
+            let wall = Body::new(obstacle.get_position(), obstacle.get_radius(), f32::INFINITY, Vec2::zero());
+            if blocks_player && physics {
+                bounce_off(&mut self.player, &wall);
+            } else if blocks_player {
+                obstacle.push_out(&mut self.player.position, player_radius);
+            }
+            for actor in &mut self.actors {
+                let layer = actor.get_collision_layer();
+                if !self.collision_rules.collides(layer, CollisionLayer::Obstacle) {
+                    continue;
+                }
+                if self.collision_rules.is_stopped_by_obstacles(layer) {
+                    if obstacle.overlaps(actor.get_position(), actor.get_radius()) {
+                        if let Some(health) = actor.get_health() {
+                            actor.do_damage(Damage::energy(health));
+                        }
+                    }
+                } else if physics {
+                    bounce_off(actor.as_mut(), &wall);
+                }
+            }
+        }
+    }
+
+    /// Separate every pair of touching actors that collision physics push around, whichever teams they are on, and
+    /// bounce them off each other as their masses dictate
+    fn resolve_physics(&mut self) {
+        for actor in &mut self.actors {
+            if self.collision_rules.collides(CollisionLayer::Player, actor.get_collision_layer()) {
+                push_apart(&mut self.player, actor.as_mut());
+            }
+        }
+        // The player may have pushed some of the actors, so find the pairs where the actors are now
+        self.components.gather(&self.actors);
+        for (i, j) in systems::find_physical_contacts(&self.actors, &self.components, &self.collision_rules) {
+            let (body, other_body) = match (get_body(self.actors[i].as_ref()), get_body(self.actors[j].as_ref())) {
+                (Some(body), Some(other_body)) => (body, other_body),
+                _ => continue,
+            };
+            if let Some((response, other_response)) = physics::resolve(&body, &other_body) {
+                self.actors[i].bounce(&response.offset, &response.push);
+                self.actors[j].bounce(&other_response.offset, &other_response.push);
+            }
+        }
+    }
+
+    /// Move the player, and the shots if the teleporters allow it, from any teleporter pad they are on to the
+    /// other pad of its pair
+    fn use_teleporters(&mut self) {
+        for teleporter in &mut self.teleporters {
+            if let Some(destination) = teleporter.get_destination(self.player.id, &self.player.position) {
+                self.player.position = destination;
+                self.player.grapple.release();
+            }
+
+            if !teleporter.carries_shots() {
+                continue;
+            }
+            for actor in &mut self.actors {
+                if !actor.allows_teleporting() {
+                    continue;
+                }
+                if let Some(destination) = teleporter.get_destination(actor.get_id(), actor.get_position()) {
+                    actor.teleport(&destination);
+                }
+            }
+        }
+    }
+
+    /// Bend a shot fired by the player towards the nearest hostile enemy in front of it, as strongly as the
+    /// auto-aim assist allows
+    fn apply_auto_aim(&self, shot: &mut Shot) {
+        let strength = self.modifiers.get_auto_aim_strength();
+        if strength <= 0.0 {
+            return;
+        }
+
+        // Find the smallest turn towards an enemy within the auto-aim cone
+        let turn = self.actors.iter()
+            .filter(|actor| self.wave_enemies.contains(&actor.get_id()) && actor.get_team() == Team::Hostile)
+            .map(|actor| {
+                let difference = shot.position.heading_to(actor.get_position()) - shot.velocity.heading;
+                // Wrap the difference to the range -PI to PI
+                return (difference + PI).rem_euclid(2.0 * PI) - PI;
+            })
+            .filter(|difference| difference.abs() < AUTO_AIM_CONE)
+            .min_by(|a, b| a.abs().partial_cmp(&b.abs()).unwrap());
+
+        if let Some(turn) = turn {
+            shot.velocity.heading += turn * strength;
+        }
+    }
+
+    /// Collect any new shots created by any actor
+    fn collect_shots(&mut self) {
+        // Create a vector to hold all of the new shots, each with whether the player fired it
+        let mut new_shots: Vec<(Shot, bool)> = Vec::new();
+
+        // Collect the shots from the player and add them to the list of shots, and speed them up or slow them down
+        // by the mutators
+        let player_shot_speed = self.modifiers.get_player_shot_speed_multiplier();
+        for mut shot in self.player.collect_shots() {
+            shot.velocity.speed *= player_shot_speed;
+            self.apply_auto_aim(&mut shot);
+            new_shots.push((shot, true));
+        }
+
+        // Collect the shots from all the other actors and add them to the list of shots, slowing the enemy shots
+        // down if that assist is active and by the difficulty, and shake the view when the boss fires
+        let enemy_shot_speed = self.modifiers.get_enemy_shot_speed_multiplier()
+            * self.settings.get_difficulty().get_enemy_shot_speed_multiplier();
+        let boss = self.boss_bar.get_boss();
+        let fired = systems::collect_shots(&mut self.actors, enemy_shot_speed);
+        if fired.iter().any(|(shooter, _)| boss == Some(*shooter)) {
+            self.shake.add(BOSS_ATTACK_SHAKE);
+        }
+        new_shots.extend(fired.into_iter().map(|(_, shot)| (shot, false)));
+
+        // Add all the shots to the game, remembering the player's to measure accuracy
+        for (shot, from_player) in new_shots {
+            let team = shot.team;
+            let id = self.add_actor(Box::new(shot));
+            if from_player {
+                self.player_shots.insert(id);
+            }
+            self.bus.emit(GameEvent::ShotFired { id, team });
+        }
+    }
+
+    /// Handle collision between all of the actors, hitting the actors in contact as often as their contact
+    /// response allows
+    fn handle_collisions(&mut self, dt: f32) {
+        let _span = logging::span("turrets::collisions");
+        // Find every actor touching the player. Actors on the player's team, like the escort drone and hacked
+        // turrets, are passed through
+        let mut touching = Vec::new();
+        for actor in &self.actors {
+            let collides = self.collision_rules.collides(CollisionLayer::Player, actor.get_collision_layer());
+            if collides && actor.get_team().can_harm(Team::Player) && self.player.check_for_collision(actor) {
+                touching.push(((self.player.id, ActorKind::Player), (actor.get_id(), actor.get_kind())));
+            }
+        }
+        // Find every pair of actors touching each other, ignoring actors on the same side as the player
+        let friendly_fire = if self.ruleset.has_friendly_fire() { Some(&self.player_shots) } else { None };
+        self.components.gather(&self.actors);
+        let components = &self.components;
+        for (i, j) in systems::find_contacts(&self.actors, components, &self.collision_rules, friendly_fire) {
+            touching.push(((components.ids[i], components.kinds[i]), (components.ids[j], components.kinds[j])));
+        }
+        // Look the actors in contact up by their IDs, through a map kept between ticks so its memory is reused
+        self.index_actors();
+        let indices = std::mem::take(&mut self.actor_indices);
+
+        // Push apart the pairs that collision physics move, then work out which contacts began and ended, and which
+        // pairs hit each other this tick
+        if self.ruleset.has_collision_physics() {
+            self.resolve_physics();
+        }
+        let changes = self.contacts.update(dt, &touching);
+        for (id, other) in changes.began {
+            self.bus.emit(GameEvent::ContactBegan { id, other });
+        }
+        for (id, other) in changes.ended {
+            self.bus.emit(GameEvent::ContactEnded { id, other });
+        }
+        for (id, other) in changes.hits {
+            if id == self.player.id {
+                self.hit_player(indices[&other]);
+            } else {
+                self.hit_pair(indices[&id], indices[&other]);
+            }
+        }
+        self.actor_indices = indices;
+    }
+
+    /// Hit the player and the actor at the given index with each other
+    fn hit_player(&mut self, index: usize) {
+        let actor = &mut self.actors[index];
+        // If the actor is a pickup, give it to the player
+        if let Some(pickup) = actor.take_pickup() {
+            self.player.apply_pickup(pickup);
+            self.bus.emit(GameEvent::PickupCollected { kind: pickup });
+            return;
+        }
+
+        // Do damage to the player (unless its shield absorbs the hit) and the actor. Harmless hits don't use up a
+        // shield charge
+        self.bus.emit(GameEvent::Impact { position: *actor.get_position() });
+        if actor.get_damage().get_amount() > 0.0 && !self.player.absorb_hit() {
+            // Knock the player back the way a shot was travelling, or away from anything else that hit it
+            let heading = match actor.get_velocity() {
+                Some(velocity) if actor.get_kind() == ActorKind::Shot => velocity.heading,
+                _ => actor.get_position().heading_to(&self.player.position),
+            };
+            let amount = self.player.take_hit(actor.get_damage(), heading);
+            self.bus.emit(GameEvent::ActorDamaged { id: self.player.id, source: Some(actor.get_id()), amount });
+        }
+        let amount = actor.do_damage(self.player.get_damage());
+        self.bus.emit(GameEvent::ActorDamaged { id: actor.get_id(), source: Some(self.player.id), amount });
+        // Apply any status effects the actors carry to each other
+        if let Some(effect) = actor.get_status_effect() {
+            self.player.apply_status_effect(effect);
+        }
+        if let Some(effect) = self.player.get_status_effect() {
+            actor.apply_status_effect(effect);
+        }
+    }
+
+    /// Hit the actors at the given indices with each other, each with the weapon of the other gathered in the
+    /// components, so only one of them is changed at a time
+    fn hit_pair(&mut self, first: usize, second: usize) {
+        let components = &self.components;
+        self.bus.emit(GameEvent::Impact { position: *self.actors[first].get_position() });
+        // Do damage to both actors
+        let amount = self.actors[first].do_damage(components.weapons[second]);
+        let other_amount = self.actors[second].do_damage(components.weapons[first]);
+        self.bus.emit(GameEvent::ActorDamaged {
+            id: components.ids[first],
+            source: Some(components.ids[second]),
+            amount,
+        });
+        self.bus.emit(GameEvent::ActorDamaged {
+            id: components.ids[second],
+            source: Some(components.ids[first]),
+            amount: other_amount,
+        });
+        // Apply any status effects the actors carry to each other
+        if let Some(effect) = &components.effects[second] {
+            self.actors[first].apply_status_effect(effect.clone());
+        }
+        if let Some(effect) = &components.effects[first] {
+            self.actors[second].apply_status_effect(effect.clone());
+        }
+    }
+
+    /// Update the telemetry measurements at the end of a tick, given the player's health at the start of it
+    fn update_telemetry(&mut self, dt: f32, previous_health: f32) {
+        self.telemetry.record_damage_taken(previous_health - self.player.health.get_current());
+
+        // Count the actors that could hurt the player near it
+        let player_position = self.player.get_position();
+        let threat_density = self.actors.iter()
+            .filter(|actor| actor.get_team() == Team::Hostile && actor.get_damage().get_amount() > 0.0)
+            .filter(|actor| actor.get_position().distance_to(player_position) < telemetry::THREAT_RADIUS)
+            .count();
+
+        let health_fraction = self.player.health.get_fraction();
+        self.telemetry.update(dt, threat_density as f32, health_fraction);
+    }
+
+    /// Restart from the last checkpoint with a score penalty. Returns false if there is no checkpoint or no
+    /// restarts are left, which ends the game
+    fn restart_from_checkpoint(&mut self) -> bool {
+        let checkpoint = match self.checkpoint.take() {
+            Some(checkpoint) if self.checkpoint_restarts > 0 => checkpoint,
+            _ => return false,
+        };
+
+        checkpoint.restore(self);
+        self.checkpoint = Some(checkpoint);
+        self.checkpoint_restarts -= 1;
+        self.score = self.score.saturating_sub(CHECKPOINT_SCORE_PENALTY);
+        self.note(format!("Player died, restarted from the checkpoint at wave {}", self.wave));
+        return true;
+    }
+
+    /// Remove the dead actors from the game
+    fn remove_dead(&mut self) {
+        self.remove_actors(&HashSet::new());
+    }
+
+    /// Remove the dead actors, and the living actors with the given IDs that are being despawned, from the game
+    fn remove_actors(&mut self, despawned: &HashSet<u32>) {
+        for actor in systems::remove_dead(&mut self.actors, despawned) {
+            let (id, kind) = (actor.get_id(), actor.get_kind());
+            self.scheduler.forget(id);
+            if actor.is_dead() {
+                self.bus.emit(GameEvent::ActorDied { id, kind, position: *actor.get_position() });
+            } else {
+                self.bus.emit(GameEvent::ActorDespawned { id, kind });
+            }
+        }
+        self.index_actors();
+    }
+
+    /// Publish the events emitted during this tick, and score, measure, and play the sounds of them
+    fn handle_events(&mut self) {
+        self.bus.publish();
+        let events = self.bus.get_published().to_vec();
+        self.react_to_events(events);
+    }
+
+    /// Publish and handle the events emitted since the events of this tick were handled, when the run ends before
+    /// the next tick could handle them
+    fn handle_late_events(&mut self) {
+        let events = self.bus.publish_late();
+        self.react_to_events(events);
+    }
+
+    /// Score, measure, and play the sounds of the given events
+    fn react_to_events(&mut self, events: Vec<GameEvent>) {
+        for event in events {
+            match event {
+                // Tell the damage hooks, and measure the damage the player, its shots, and its bomb did to the
+                // enemies of the wave and how many of the player's shots hit them
+                GameEvent::ActorDamaged { id, source, amount } => {
+                    log::trace!(target: "turrets::events", "Actor {} took {:.1} damage from {:?}", id, amount, source);
+                    for hook in self.damage_hooks.iter_mut() {
+                        hook(id, source, amount);
+                    }
+                    if id == self.player.id {
+                        self.run_stats.break_streak();
+                        self.shake.add(PLAYER_HIT_SHAKE);
+                    }
+                    if self.wave_enemies.contains(&id) {
+                        let from_player = source.is_some_and(|source| {
+                            source == self.player.id || self.player_shots.contains(&source) || Some(source) == self.bomb
+                        });
+                        if from_player {
+                            self.telemetry.record_damage_dealt(amount);
+                        }
+                        if source.map(|source| self.player_shots.remove(&source)) == Some(true) {
+                            self.telemetry.record_shot_hit();
+                        }
+                    }
+                }
+                // Tell the death hooks, then count and score the enemies of the wave that were destroyed, paying for
+                // them in the tower defense mode, and scatter score gems where they were destroyed
+                GameEvent::ActorDied { id, kind, position } => {
+                    log::debug!(target: "turrets::events", "{} {} died at ({:.0}, {:.0})", kind.get_name(), id,
+                                position.x, position.y);
+                    self.run_death_hooks(id, kind, &position);
+                    self.forget_actor(id);
+                    self.explode(kind, &position);
+                    if self.wave_enemies.contains(&id) {
+                        self.telemetry.record_kill();
+                        self.run_stats.record_kill(kind.get_name());
+                        self.add_score(TURRET_KILL_SCORE);
+                        if self.mode == GameMode::Defense {
+                            self.credits += DEFENSE_KILL_CREDITS;
+                        }
+                        self.drop_gems(&position);
+                    }
+                }
+                // Forget an actor that was despawned, without scoring it
+                GameEvent::ActorDespawned { id, kind } => {
+                    log::debug!(target: "turrets::events", "{} {} was despawned", kind.get_name(), id);
+                    self.forget_actor(id);
+                }
+                GameEvent::ShotFired { id, .. } => {
+                    if self.player_shots.contains(&id) {
+                        self.telemetry.record_shot_fired();
+                        self.run_stats.record_shot(self.player.weapon.get_name());
+                    }
+                    self.audio.queue(SoundEvent::Fire);
+                }
+                // Throw sparks off where shots hit something
+                GameEvent::Impact { position } => {
+                    IMPACT_SPARKS.burst(&mut self.particles, self.rng.cosmetic(), &position, 0.0,
+                                        IMPACT_SPARK_PARTICLES);
+                    self.audio.queue(SoundEvent::Impact);
+                }
+                GameEvent::WaveCompleted { wave } => self.note(format!("Wave {} completed", wave)),
+                GameEvent::PickupCollected { kind } => {
+                    if let PickupKind::Score(points) = kind {
+                        self.add_score(points);
+                    }
+                }
+                GameEvent::ContactBegan { .. } | GameEvent::ContactEnded { .. } => {}
+            }
+        }
+    }
+
+    /// Add the given points to the score, multiplied by the mutators of the run
+    fn add_score(&mut self, points: u32) {
+        self.score += (points as f32 * self.modifiers.get_score_multiplier()).round() as u32;
+    }
+
+    /// Forget the actor with the given ID everywhere it is remembered, once it has been removed from the game
+    fn forget_actor(&mut self, id: u32) {
+        self.player_shots.remove(&id);
+        for teleporter in &mut self.teleporters {
+            teleporter.forget(id);
+        }
+    }
+
+    /// Leave an explosion and a burst of particles where an actor of the given kind was destroyed, sized to its
+    /// blast if it had one, and shake the view. Shots, pickups, and custom actors go without
+    fn explode(&mut self, kind: ActorKind, position: &Point) {
+        let (radius, shake) = match kind {
+            ActorKind::Bomb => (self.tuning.bomb_blast_radius, BOMB_SHAKE),
+            ActorKind::Turret | ActorKind::Drone | ActorKind::Kamikaze | ActorKind::Base => {
+                (EXPLOSION_RADIUS, EXPLOSION_SHAKE)
+            }
+            ActorKind::Player | ActorKind::Shot | ActorKind::Pickup | ActorKind::Custom(_) => return,
+        };
+        self.explosions.push(Explosion::new(*position, radius));
+        self.shake.add(shake);
+        DEATH_BURST.burst(&mut self.particles, self.rng.cosmetic(), position, 0.0, DEATH_BURST_PARTICLES);
+    }
+
+    /// Scatter score gems around the given position
+    fn drop_gems(&mut self, position: &Point) {
+        for _ in 0..GEMS_PER_KILL {
+            let rng = self.rng.gameplay();
+            let mut gem_position = *position;
+            gem_position.move_distance(rng.gen_range(0.0, GEM_SCATTER), rng.gen_range(0.0, 2.0 * PI));
+            gem_position.keep_in_bounds(&self.world);
+            self.add_actor(Box::new(Pickup::new(gem_position, PickupKind::Score(GEM_SCORE), &self.tuning)));
+        }
+    }
+
+    /// Get how strongly the view is shaking, from 0 for a still view to 1
+    pub fn get_screen_shake(&self) -> f32 {
+        return self.shake.get_strength();
+    }
+
+    /// Turn the shaking of the view on or off, as the setting does
+    pub fn set_screen_shake(&mut self, enabled: bool) {
+        self.shake.set_enabled(enabled);
+    }
+
+    /// Get the particles thrown off by explosions, impacts, and the player's engine
+    pub fn get_particles(&self) -> &ParticleSystem {
+        return &self.particles;
+    }
+
+    /// Get the events that happened during the last tick, in the order they happened
+    pub fn get_events(&self) -> &[GameEvent] {
+        return self.bus.get_published();
+    }
+
+    /// Simulate one tick of the game. Returns false if the player has died with no restarts left, which ends the
+    /// game
+    pub fn simulate_tick(&mut self) -> bool {
+        let _span = logging::span("turrets::update");
+        // Carry on from the live tick, not the one the history was stepped back to
+        self.leave_rewind();
+        self.tick += 1;
+        // Every tick simulates the same length of time, so a run plays out the same however fast it is drawn. The
+        // time scale is eased by the unscaled tick, then stretches or shrinks the time the tick simulates
+        self.time_scale.update(self.get_tick_duration());
+        let dt = self.get_tick_duration() * self.time_scale.get_scale();
+        // Stress the game if it is being benchmarked, and time each system while it is
+        if let Some(mut benchmark) = self.benchmark.take() {
+            benchmark.prepare_tick(self);
+            self.benchmark = Some(benchmark);
+        }
+        let mut stopwatch = Stopwatch::start(self.benchmark.is_some());
+
+        // Remember the player's health to measure the damage taken this tick
+        let previous_health = self.player.health.get_current();
+
+        // Change the speed of anything on a boost strip or slow field
+        self.apply_floor();
+        // Move the turret formations, and their members with them
+        for formation in &mut self.formations {
+            formation.update(dt, self.world.get_size());
+        }
+        self.follow_formations();
+        stopwatch.lap("floor and formations");
+        // Update the state of the player
+        self.player.update(dt);
+        stopwatch.lap("player");
+        // Park the actors far from the player and bring back the ones the player has moved close to
+        self.streamer.update(&self.player.position, &mut self.actors);
+        self.index_actors();
+        stopwatch.lap("streaming");
+        // Count down the hack ability and aim the hacked turrets
+        self.hack_cooldown.update(dt);
+        self.update_targets();
+        self.update_flocks();
+        stopwatch.lap("targeting");
+        // Let the scripted actors aim and fire
+        #[cfg(feature = "scripts")]
+        {
+            self.run_scripts(dt);
+            stopwatch.lap("scripts");
+        }
+        // Update the state of every actor, skipping ticks for distant actors that allow it
+        systems::update_actors(&mut self.actors, &mut self.scheduler, &self.player.position, dt);
+        stopwatch.lap("actors");
+
+        // Move anything standing on a teleporter pad to the other end, and keep everything out of the obstacles
+        self.use_teleporters();
+        self.apply_obstacles();
+        // Catch the grapple on any enemy it has reached
+        self.check_grapple_hits();
+        stopwatch.lap("movement");
+        // Spawn any pickups that are due, and anything in the spawn schedule whose time has come
+        self.spawn_pickups(dt);
+        self.spawn_scheduled(dt);
+        // Collect shots
+        self.collect_shots();
+        stopwatch.lap("spawning");
+        // Handle collisions
+        self.handle_collisions(dt);
+        stopwatch.lap("collisions");
+        // Blow up any bombs that have gone off
+        self.handle_explosions();
+        // Wake the turrets near any turret that was woken up, even if it was destroyed
+        self.spread_alarms();
+        // Schedule the drones the spawners have called in
+        self.schedule_called_spawns();
+        // Remove dead actors
+        self.remove_dead();
+        stopwatch.lap("explosions and removal");
+        // Update the telemetry measurements
+        self.update_telemetry(dt, previous_health);
+        // Let the director adjust the pressure and send reinforcements
+        self.director.update(dt, &self.telemetry);
+        self.spawn_reinforcements();
+        // Despawn the actors over the population caps now that this tick's spawns are in
+        self.enforce_population_caps();
+        stopwatch.lap("director and caps");
+        // Score and react to everything that happened during the tick, before a new wave replaces the enemies of
+        // the wave the kills are scored against. What the game mode emits below is handled next tick
+        self.handle_events();
+        stopwatch.lap("events");
+
+        match self.mode {
+            // Once every turret of the wave is destroyed, offer the player a choice of perks before the next wave,
+            // unless the level is won by clearing that many waves. Some levels are won by surviving long enough
+            GameMode::Waves => {
+                self.update_events(dt);
+                let win_condition = self.get_level().get_win_condition();
+                if self.perk_choices.is_none() && self.is_wave_cleared() {
+                    self.bus.emit(GameEvent::WaveCompleted { wave: self.wave });
+                    if win_condition == WinCondition::Waves(self.wave) {
+                        self.outcome = Some(RunOutcome::Won);
+                    } else {
+                        self.perk_choices = Some(perks::choose_perks(self.rng.gameplay()));
+                    }
+                }
+                if matches!(win_condition, WinCondition::Survive(seconds) if self.get_time() >= seconds) {
+                    self.outcome = Some(RunOutcome::Won);
+                }
+            }
+            // Capture the zones the player is standing in, and win once they are all captured
+            GameMode::Capture => {
+                for zone in &mut self.zones {
+                    if zone.update(dt, &self.player.position) {
+                        self.add_score(ZONE_CAPTURE_SCORE);
+                    }
+                }
+                if self.zones.iter().all(|zone| zone.is_captured()) {
+                    self.outcome = Some(RunOutcome::Won);
+                }
+            }
+            // The mission fails if the drone is destroyed, and is won once it reaches the other side
+            GameMode::Escort => {
+                let drone = self.escort_drone.and_then(|id| self.find_actor(id));
+                let (width, _) = self.world.get_size();
+                match drone {
+                    None => self.outcome = Some(RunOutcome::Failed),
+                    Some(drone) if drone.get_position().x >= width - self.tuning.drone_radius * 2.0 => {
+                        self.outcome = Some(RunOutcome::Won);
+                    }
+                    _ => (),
+                }
+            }
+            // The run is won once the player reaches the exit of the gauntlet
+            GameMode::Gauntlet => {
+                let reached_exit = self.gauntlet.as_ref()
+                    .map(|gauntlet| gauntlet.has_reached_exit(&self.player.position));
+                if reached_exit == Some(true) {
+                    self.outcome = Some(RunOutcome::Won);
+                }
+            }
+            // The run is lost once the base is destroyed, and won once every wave has been held off
+            GameMode::Defense => {
+                if self.base.and_then(|id| self.find_actor(id)).is_none() {
+                    self.outcome = Some(RunOutcome::Failed);
+                } else if self.is_wave_cleared() {
+                    self.bus.emit(GameEvent::WaveCompleted { wave: self.wave });
+                    if self.wave >= DEFENSE_WAVES {
+                        self.outcome = Some(RunOutcome::Won);
+                    } else {
+                        self.credits += DEFENSE_WAVE_CREDITS;
+                        self.spawn_attack_wave();
+                    }
+                }
+            }
+        }
+
+        stopwatch.lap("game mode");
+        // Show the health of the boss, hiding the bar once it is gone. A boss that is parked far away is still
+        // around, so the bar keeps its last health
+        if let Some(id) = self.boss_bar.get_boss() {
+            let boss_health = self.find_actor(id).and_then(|boss| boss.get_health());
+            if boss_health.is_some() || !self.streamer.any_parked(|parked, _| parked == id) {
+                self.boss_bar.update(dt, boss_health);
+            }
+        }
+        // Play out the explosions, and clear away the ones that have finished
+        for explosion in &mut self.explosions {
+            explosion.update(dt);
+        }
+        self.explosions.retain(|explosion| !explosion.is_finished());
+        // Stream exhaust out behind the player while it is moving, then play out the particles
+        if self.player.velocity.speed != 0.0 {
+            let heading = self.player.velocity.heading + PI;
+            let mut nozzle = self.player.position;
+            nozzle.move_distance(self.player.get_radius(), heading);
+            self.exhaust.stream(&mut self.particles, self.rng.cosmetic(), &nozzle, heading, dt);
+        }
+        self.particles.update(dt);
+        // Let the shake of the view die down
+        self.shake.update(dt);
+        if let Some(benchmark) = &mut self.benchmark {
+            benchmark.record_update(&stopwatch);
+        }
+        // Remember the state after this tick to step back to while paused, if the history is being kept
+        if let Some(mut rewind) = self.rewind.take() {
+            rewind.record(self.tick, self);
+            self.rewind = Some(rewind);
+        }
+
+        // Check that the state survives serialization every so often, while that is turned on
+        if let Some(interval) = self.round_trip_interval {
+            if interval > 0 && self.tick.is_multiple_of(interval as u64) {
+                self.check_round_trip();
+            }
+        }
+
+        // Once the run has an outcome, move to the game over screen
+        if self.outcome.is_some() {
+            self.finish_run();
+            self.set_scene(Scene::GameOver);
+        }
+
+        // If the player has died, restart from the last checkpoint, or end the run if there is none
+        if self.player.is_dead() && !self.restart_from_checkpoint() {
+            self.finish_run();
+            self.set_scene(Scene::GameOver);
+            return false;
+        }
+
+        return true;
+    }
+}
+
+impl EventHandler for MainState {
+    /// Update the MainState
+    fn update(&mut self, ctx: &mut Context) -> GameResult {
+        // Hold the frames to the cap in the settings, if there is one. Every update is followed by drawing a frame,
+        // and the time spent waiting is simulated like any other
+        self.frame_limiter.wait();
+
+        // Pick up any changes to the balance before simulating with it, and keep loading the content of the game
+        self.reload_tuning();
+        self.update_loading(ctx);
+
+        // Simulate as many ticks as fit in the time measured since the last frame, carrying what is left over to the
+        // next frame. After a long stall only a few ticks are caught up, instead of freezing to simulate all of them
+        let tick_duration = self.get_tick_duration();
+        self.unsimulated_time += timer::duration_to_f64(timer::delta(ctx)).min(MAX_FRAME_TIME as f64) as f32;
+        while self.unsimulated_time >= tick_duration {
+            self.unsimulated_time -= tick_duration;
+            // Only simulate while a run is being played, and freeze it while the mod list, the spawn console, or the
+            // perk choice is open. The simulation moves to the game over screen by itself once the run ends
+            let frozen = self.mod_list_selection.is_some() || self.spawn_console_selection.is_some()
+                || self.perk_choices.is_some();
+            if !self.scene.is_simulating() || frozen {
+                continue;
+            }
+            self.simulate_tick();
+        }
+        // Play the sounds of the events that happened this frame
+        self.audio.play_queued(ctx, self.rng.cosmetic())?;
+
+        // Stop timing and close the game once a benchmark has run all of its ticks, keeping the timings for whoever
+        // started the benchmark to report
+        if self.is_benchmark_finished() {
+            self.benchmark_summary = self.benchmark.take().map(|benchmark| benchmark.get_summary());
+            event::quit(ctx);
+        }
+
+        return Ok(());
+    }
+
+    /// Draw the game
+    fn draw(&mut self, ctx: &mut Context) -> GameResult {
+        return view::draw(self, ctx);
+    }
+
+    /// Handle key down event
+    fn key_down_event(&mut self, ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods, repeat: bool) {
+        // Remember the key for bug reports
+        if !repeat {
+            self.inputs.record(self.tick, self.get_time(), format!("down {:?}", keycode));
+        }
+
+        // If escape is pressed, pause or resume a run, or end the game from the menu and the game over screen
+        if keycode == KeyCode::Escape && !repeat {
+            match self.scene {
+                Scene::Playing => self.set_scene(Scene::Paused),
+                Scene::Paused => self.set_scene(Scene::Playing),
+                Scene::ModeSelect => self.open_menu(Scene::MainMenu, MenuItem::ModeSelect.get_index()),
+                // Close the keyboard for naming a preset, or go back to the menu if it isn't open
+                Scene::CustomGame if self.keyboard.is_some() => self.keyboard = None,
+                Scene::CustomGame => self.open_menu(Scene::MainMenu, MenuItem::CustomGame.get_index()),
+                Scene::Settings => self.open_menu(Scene::MainMenu, MenuItem::Settings.get_index()),
+                Scene::SeedEntry => {
+                    self.keyboard = None;
+                    self.open_menu(Scene::MainMenu, MenuItem::Seed.get_index());
+                }
+                // Leave the score out of the high-score table instead of entering initials for it
+                Scene::GameOver if self.keyboard.is_some() => self.keyboard = None,
+                Scene::ShipSelect => self.open_menu(Scene::MainMenu, MenuItem::Start.get_index()),
+                Scene::Stats => self.open_menu(Scene::MainMenu, MenuItem::Stats.get_index()),
+                Scene::Loading | Scene::MainMenu | Scene::GameOver => event::quit(ctx),
+            }
+            return;
+        }
+        // Ignore every other key until the game has loaded
+        if self.scene == Scene::Loading {
+            return;
+        }
+        // If F2 is pressed, show or hide the designer overlay
+        if keycode == KeyCode::F2 {
+            self.show_telemetry = !self.show_telemetry;
+            return;
+        }
+        // If F3 is pressed, show or hide the debug overlay
+        if keycode == KeyCode::F3 && !repeat {
+            self.show_debug_overlay = !self.show_debug_overlay;
+            return;
+        }
+        // If F4 is pressed, make the log more verbose, going back to silent after the most verbose level
+        if keycode == KeyCode::F4 && !repeat {
+            let level = logging::cycle_level();
+            self.note(format!("Log level set to {}", level));
+            return;
+        }
+        // If F8 is pressed, save a bug report
+        if keycode == KeyCode::F8 && !repeat {
+            self.save_bug_report(ctx);
+            return;
+        }
+        // If F5 is pressed during a run, quicksave it
+        if keycode == KeyCode::F5 && !repeat {
+            if matches!(self.scene, Scene::Playing | Scene::Paused) {
+                self.quicksave();
+            }
+            return;
+        }
+        // If F9 is pressed, load the quicksave
+        if keycode == KeyCode::F9 && !repeat {
+            self.quickload();
+            return;
+        }
+        // If F6 is pressed, start or stop keeping the history to step back through
+        if keycode == KeyCode::F6 && !repeat {
+            self.toggle_rewind();
+            return;
+        }
+
+        // Once the run has ended, enter the initials for a high score on the virtual keyboard, then restart the run,
+        // go back to the menu, or save the result card
+        if self.scene == Scene::GameOver {
+            if let Some(keyboard) = &mut self.keyboard {
+                if keyboard.handle_key(keycode) && !repeat {
+                    self.enter_initials();
+                }
+                return;
+            }
+            match keycode {
+                KeyCode::R if !repeat => self.restart(),
+                KeyCode::M if !repeat => self.return_to_menu(),
+                KeyCode::S if self.result_card.is_none() => {
+                    if let Some(outcome) = self.outcome {
+                        self.result_card = Some(self.summarize(outcome).save_card(ctx));
+                    }
+                }
+                _ => (),
+            }
+            return;
+        }
+        // While paused, resume, quit to the menu, or step backward and forward through the history
+        if self.scene == Scene::Paused {
+            match keycode {
+                KeyCode::P if !repeat => self.set_scene(Scene::Playing),
+                KeyCode::Comma => self.step_rewind(false),
+                KeyCode::Period => self.step_rewind(true),
+                KeyCode::Q => {
+                    self.record_run_stats();
+                    self.return_to_menu();
+                }
+                _ => (),
+            }
+            return;
+        }
+
+        // If the mod list is open, use the key to navigate it instead of controlling the player
+        if let Some(selected) = self.mod_list_selection {
+            let mod_count = self.mods.get_mods().len();
+            match keycode {
+                KeyCode::M => self.mod_list_selection = None,
+                KeyCode::Up if selected > 0 => self.mod_list_selection = Some(selected - 1),
+                KeyCode::Down if selected + 1 < mod_count => self.mod_list_selection = Some(selected + 1),
+                KeyCode::Return | KeyCode::Space if !repeat => self.mods.toggle(selected),
+                _ => (),
+            }
+            return;
+        }
+        // If the spawn console is open, use the key to pick an actor from the registry and spawn it
+        if let Some(selected) = self.spawn_console_selection {
+            let name_count = self.registry.get_names().len();
+            match keycode {
+                KeyCode::F7 => self.spawn_console_selection = None,
+                KeyCode::Up if selected > 0 => self.spawn_console_selection = Some(selected - 1),
+                KeyCode::Down if selected + 1 < name_count => self.spawn_console_selection = Some(selected + 1),
+                KeyCode::Return | KeyCode::Space if !repeat => self.spawn_from_console(selected),
+                _ => (),
+            }
+            return;
+        }
+        // On the main menu, use the key to pick where to go
+        if self.scene == Scene::MainMenu {
+            self.move_menu_selection(keycode, MenuItem::ALL.len());
+            if keycode == KeyCode::Return && !repeat {
+                match MenuItem::ALL[self.menu_selection] {
+                    MenuItem::Start => self.set_scene(Scene::ShipSelect),
+                    MenuItem::ModeSelect => {
+                        let index = GameMode::ALL.iter().position(|&mode| mode == self.mode).unwrap_or(0);
+                        self.open_menu(Scene::ModeSelect, index);
+                    }
+                    MenuItem::CustomGame => self.open_custom_game(),
+                    MenuItem::Seed => {
+                        self.keyboard = Some(VirtualKeyboard::new("Seed (leave empty for a random one)",
+                                                                  text_entry::DIGITS, SEED_DIGITS));
+                        self.open_menu(Scene::SeedEntry, 0);
+                    }
+                    MenuItem::Settings => self.open_menu(Scene::Settings, 0),
+                    MenuItem::Stats => self.open_menu(Scene::Stats, 0),
+                    MenuItem::Quit => event::quit(ctx),
+                }
+            }
+            return;
+        }
+        // On the mode select screen, use the key to pick a mode and go back to the main menu
+        if self.scene == Scene::ModeSelect {
+            self.move_menu_selection(keycode, GameMode::ALL.len());
+            if keycode == KeyCode::Return && !repeat {
+                self.mode = GameMode::ALL[self.menu_selection];
+                self.open_menu(Scene::MainMenu, MenuItem::Start.get_index());
+            }
+            return;
+        }
+        // On the settings screen, use the key to change the settings and turn assists and challenges on and off
+        if self.scene == Scene::Settings {
+            let options = Modifier::get_options();
+            self.move_menu_selection(keycode, SettingsItem::ALL.len() + options.len());
+            let pressed = (keycode == KeyCode::Return || keycode == KeyCode::Space) && !repeat;
+            if let Some(&item) = SettingsItem::ALL.get(self.menu_selection) {
+                match keycode {
+                    KeyCode::Left => self.change_setting(ctx, item, false),
+                    KeyCode::Right => self.change_setting(ctx, item, true),
+                    _ if pressed => self.change_setting(ctx, item, true),
+                    _ => (),
+                }
+            } else if pressed {
+                self.modifiers.toggle(options[self.menu_selection - SettingsItem::ALL.len()]);
+                self.update_damage_taken();
+            }
+            return;
+        }
+        // On the custom game screen, use the key to change the rules, turn mutators on and off, and save, load,
+        // and start with them. While a preset is being named, use the key on the virtual keyboard instead
+        if self.scene == Scene::CustomGame {
+            if let Some(keyboard) = &mut self.keyboard {
+                if keyboard.handle_key(keycode) && !repeat {
+                    self.save_preset();
+                }
+                return;
+            }
+            let options = Modifier::MUTATORS;
+            let actions_start = RuleItem::ALL.len() + options.len();
+            self.move_menu_selection(keycode, actions_start + CustomGameAction::ALL.len());
+            let pressed = (keycode == KeyCode::Return || keycode == KeyCode::Space) && !repeat;
+            let turn = match keycode {
+                KeyCode::Left => Some(false),
+                KeyCode::Right => Some(true),
+                _ => None,
+            };
+            if let Some(&item) = RuleItem::ALL.get(self.menu_selection) {
+                if let Some(up) = turn {
+                    self.ruleset.adjust(item, up, &self.levels);
+                } else if pressed {
+                    self.ruleset.adjust(item, true, &self.levels);
+                }
+            } else if self.menu_selection < actions_start {
+                if pressed {
+                    self.modifiers.toggle(options[self.menu_selection - RuleItem::ALL.len()]);
+                }
+            } else {
+                match CustomGameAction::ALL[self.menu_selection - actions_start] {
+                    // Cycle through the saved presets
+                    CustomGameAction::LoadPreset if turn.is_some() && !self.presets.is_empty() => {
+                        let step = if turn == Some(true) { 1 } else { self.presets.len() - 1 };
+                        self.preset_selection = (self.preset_selection + step) % self.presets.len();
+                    }
+                    _ if !pressed => (),
+                    CustomGameAction::SavePreset => {
+                        self.keyboard = Some(VirtualKeyboard::new("Preset name", text_entry::LETTERS,
+                                                                  PRESET_NAME_LENGTH));
+                    }
+                    CustomGameAction::LoadPreset => self.load_preset(),
+                    CustomGameAction::Reset => self.ruleset = Ruleset::new(),
+                    CustomGameAction::Start => self.set_scene(Scene::ShipSelect),
+                }
+            }
+            return;
+        }
+        // On the seed entry screen, use the key on the virtual keyboard, and use the seed once it is done
+        if self.scene == Scene::SeedEntry {
+            let done = match &mut self.keyboard {
+                Some(keyboard) => keyboard.handle_key(keycode),
+                None => true,
+            };
+            if done && !repeat {
+                self.enter_seed();
+            }
+            return;
+        }
+        // On the statistics screen, go back to the main menu
+        if self.scene == Scene::Stats {
+            if keycode == KeyCode::Return && !repeat {
+                self.open_menu(Scene::MainMenu, MenuItem::Stats.get_index());
+            }
+            return;
+        }
+        // If the ship class selection screen is open, use the key to pick a class
+        if self.scene == Scene::ShipSelect {
+            let selected = self.class_selection;
+            match keycode {
+                KeyCode::Left if selected > 0 => self.class_selection = selected - 1,
+                KeyCode::Right if selected + 1 < ShipClass::ALL.len() => self.class_selection = selected + 1,
+                // Cycle through the game modes
+                KeyCode::Up | KeyCode::Down => {
+                    let index = GameMode::ALL.iter().position(|&mode| mode == self.mode).unwrap_or(0);
+                    let step = if keycode == KeyCode::Up { GameMode::ALL.len() - 1 } else { 1 };
+                    self.mode = GameMode::ALL[(index + step) % GameMode::ALL.len()];
+                }
+                KeyCode::Return => self.choose_class(ShipClass::ALL[selected]),
+                _ => (),
+            }
+            return;
+        }
+        // If perks are being offered, use the key to pick one and then start the next wave
+        if self.perk_choices.is_some() {
+            match keycode {
+                KeyCode::Key1 => self.choose_perk(0),
+                KeyCode::Key2 => self.choose_perk(1),
+                KeyCode::Key3 => self.choose_perk(2),
+                _ => (),
+            }
+            return;
+        }
+        // If P is pressed, pause the run
+        if keycode == KeyCode::P && !repeat {
+            self.set_scene(Scene::Paused);
+            return;
+        }
+        // If M is pressed, open the mod list
+        if keycode == KeyCode::M {
+            self.mod_list_selection = Some(0);
+            return;
+        }
+        // If F7 is pressed, open the spawn console
+        if keycode == KeyCode::F7 && !repeat {
+            self.spawn_console_selection = Some(0);
+            return;
+        }
+        // If H is pressed, hack the nearest damaged turret
+        if keycode == KeyCode::H {
+            self.hack_nearest_turret();
+            return;
+        }
+        // If T is pressed, build a turret
+        if keycode == KeyCode::T {
+            self.build_turret();
+            return;
+        }
+        // If B is pressed, throw a bomb, or set off the one already thrown
+        if keycode == KeyCode::B {
+            if !repeat {
+                self.throw_or_detonate_bomb();
+            }
+            return;
+        }
+
+        // Forward the key event to the player object, as the arrow key it stands for in the chosen controls
+        self.player.handle_key_down_event(self.settings.get_controls().translate(keycode), repeat);
     }
 
     /// Handle key up event
     fn key_up_event(&mut self, _ctx: &mut Context, keycode: KeyCode, _keymod: KeyMods) {
-        // Forward the key event to the player object
-        self.player.handle_key_up_event(keycode);
+        // Remember the key for bug reports
+        self.inputs.record(self.tick, self.get_time(), format!("up {:?}", keycode));
+
+        // Forward the key event to the player object, as the arrow key it stands for in the chosen controls
+        self.player.handle_key_up_event(self.settings.get_controls().translate(keycode));
+    }
+
+    /// Handle text input event
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        // Type the character on the virtual keyboard, if it is open
+        if let Some(keyboard) = &mut self.keyboard {
+            keyboard.type_character(character);
+        }
+    }
+
+    /// Handle gamepad button down event
+    fn gamepad_button_down_event(&mut self, ctx: &mut Context, button: Button, _id: GamepadId) {
+        // Handle the button as the key it stands for
+        if let Some(keycode) = gamepad::get_button_key(button) {
+            self.key_down_event(ctx, keycode, KeyMods::NONE, false);
+        }
+    }
+
+    /// Handle gamepad button up event
+    fn gamepad_button_up_event(&mut self, ctx: &mut Context, button: Button, _id: GamepadId) {
+        // Handle the button as the key it stands for
+        if let Some(keycode) = gamepad::get_button_key(button) {
+            self.key_up_event(ctx, keycode, KeyMods::NONE);
+        }
+    }
+
+    /// Handle gamepad axis event
+    fn gamepad_axis_event(&mut self, ctx: &mut Context, axis: Axis, value: f32, _id: GamepadId) {
+        // Handle tilting the left stick as letting go of and pressing the arrow keys
+        let (released, pressed) = self.stick.update(axis, value);
+        if let Some(keycode) = released {
+            self.key_up_event(ctx, keycode, KeyMods::NONE);
+        }
+        if let Some(keycode) = pressed {
+            self.key_down_event(ctx, keycode, KeyMods::NONE, false);
+        }
+    }
+
+    /// Pause a run when the window loses focus, so the player isn't hit while away. Keys let go of in another
+    /// window never reach the game, so every key is let go of now. The run stays paused when the focus comes back,
+    /// until the player resumes it
+    fn focus_event(&mut self, _ctx: &mut Context, gained: bool) {
+        if gained || self.scene != Scene::Playing {
+            return;
+        }
+        self.player.apply_action(Action::default());
+        self.set_scene(Scene::Paused);
+        self.focus_paused = true;
+    }
+
+    /// Draw at the new size of the window instead of stretching the old one, and grow or shrink the arena to match
+    /// unless it has a size of its own
+    fn resize_event(&mut self, ctx: &mut Context, width: f32, height: f32) {
+        // A minimised window has no size, and is left as it was until it is restored
+        if width < 1.0 || height < 1.0 {
+            return;
+        }
+        if let Err(error) = graphics::set_screen_coordinates(ctx, graphics::Rect::new(0.0, 0.0, width, height)) {
+            self.warn(format!("Could not resize the window: {}", error));
+        }
+        self.camera.set_view_size((width, height));
+        if self.world_size.is_none() {
+            self.resize((width, height));
+        }
     }
 }
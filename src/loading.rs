@@ -0,0 +1,180 @@
+use std::collections::VecDeque;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ggez::{Context, GameResult, filesystem, graphics};
+
+use crate::assets::AssetManager;
+use crate::audio::AudioManager;
+use crate::levels::Level;
+
+/// Longest time spent loading assets in a single frame, so the loading screen keeps drawing while they load
+const FRAME_BUDGET: Duration = Duration::from_millis(8);
+/// Directory the images in the resources are loaded from
+const IMAGES_DIRECTORY: &str = "/images";
+/// Directory the fonts in the resources are loaded from
+const FONTS_DIRECTORY: &str = "/fonts";
+/// Size of the progress bar of the loading screen
+const BAR_SIZE: (f32, f32) = (300.0, 16.0);
+
+/// An asset to load, by its path in the resources
+#[derive(Clone, Debug, PartialEq)]
+pub enum LoadJob {
+    Image(String),
+    Font(String),
+    Sound(String),
+}
+
+impl LoadJob {
+    /// Get the jobs that load every image and font in the resources and the sound samples of the game
+    pub fn find_all(ctx: &mut Context) -> Vec<LoadJob> {
+        let mut jobs = Vec::new();
+        jobs.extend(find_files(ctx, IMAGES_DIRECTORY, "png").into_iter().map(LoadJob::Image));
+        jobs.extend(find_files(ctx, FONTS_DIRECTORY, "ttf").into_iter().map(LoadJob::Font));
+        jobs.extend(AudioManager::get_sample_paths(ctx).into_iter().map(LoadJob::Sound));
+        return jobs;
+    }
+
+    /// Get the path of the asset this job loads
+    pub fn get_path(&self) -> &str {
+        return match self {
+            LoadJob::Image(path) | LoadJob::Font(path) | LoadJob::Sound(path) => path,
+        };
+    }
+
+    /// Load the asset of this job into the given asset manager's cache
+    fn load(&self, ctx: &mut Context, assets: &mut AssetManager) -> GameResult {
+        match self {
+            LoadJob::Image(path) => {
+                assets.load_image(ctx, path)?;
+            }
+            LoadJob::Font(path) => {
+                assets.load_font(ctx, path)?;
+            }
+            LoadJob::Sound(path) => {
+                assets.load_sound(ctx, path)?;
+            }
+        }
+        return Ok(());
+    }
+}
+
+/// Get the paths of the files with the given extension in the given directory of the resources, in a stable order
+fn find_files(ctx: &mut Context, directory: &str, extension: &str) -> Vec<String> {
+    let mut paths: Vec<String> = match filesystem::read_dir(ctx, directory) {
+        Ok(entries) => entries
+            .filter(|path| matches!(path.extension(), Some(found) if found == extension))
+            .map(|path| path.to_string_lossy().replace('\\', "/"))
+            .collect(),
+        Err(_) => Vec::new(),
+    };
+    paths.sort();
+    return paths;
+}
+
+/// Loads the content of the game while the loading screen is shown. Levels are read on a thread of their own, and
+/// assets, which ggez can only create on the thread that owns the window, are loaded a few at a time every frame
+pub struct Loader {
+    jobs: VecDeque<LoadJob>,
+    total: usize,
+    levels: Option<Receiver<(Vec<Level>, Vec<String>)>>,
+    issues: Vec<String>,
+}
+
+impl Loader {
+    /// Start loading the given assets, and the levels at the given paths in the background
+    pub fn new(jobs: Vec<LoadJob>, level_paths: Vec<PathBuf>) -> Loader {
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            // The loader may have been dropped by the time the levels are read, which leaves no one to tell
+            let _ = sender.send(Level::load_paths(&level_paths));
+        });
+        return Loader { total: jobs.len() + 1, jobs: jobs.into(), levels: Some(receiver), issues: Vec::new() };
+    }
+
+    /// Add an asset to load before loading finishes
+    pub fn queue(&mut self, job: LoadJob) {
+        self.jobs.push_back(job);
+        self.total += 1;
+    }
+
+    /// Load assets into the given asset manager until this frame's budget runs out, and get the levels once the
+    /// thread reading them has finished
+    pub fn update(&mut self, ctx: &mut Context, assets: &mut AssetManager) -> Option<(Vec<Level>, Vec<String>)> {
+        let started = Instant::now();
+        while started.elapsed() < FRAME_BUDGET {
+            let job = match self.jobs.pop_front() {
+                Some(job) => job,
+                None => break,
+            };
+            if let Err(error) = job.load(ctx, assets) {
+                self.issues.push(format!("{}: {}", job.get_path(), error));
+            }
+        }
+        return self.take_levels(false);
+    }
+
+    /// Wait for the thread reading the levels to finish and get them, if they haven't been taken already
+    pub fn wait_for_levels(&mut self) -> Option<(Vec<Level>, Vec<String>)> {
+        return self.take_levels(true);
+    }
+
+    /// Get the levels if the thread reading them has finished, or wait for it to
+    fn take_levels(&mut self, wait: bool) -> Option<(Vec<Level>, Vec<String>)> {
+        let receiver = self.levels.as_ref()?;
+        let result = if wait { receiver.recv().map_err(|_| TryRecvError::Disconnected) } else { receiver.try_recv() };
+        return match result {
+            Ok(levels) => {
+                self.levels = None;
+                Some(levels)
+            }
+            Err(TryRecvError::Empty) => None,
+            Err(TryRecvError::Disconnected) => {
+                self.levels = None;
+                self.issues.push(String::from("The levels couldn't be loaded"));
+                None
+            }
+        };
+    }
+
+    /// Get the fraction of the content that has been loaded, from 0 to 1
+    pub fn get_progress(&self) -> f32 {
+        let remaining = self.jobs.len() + usize::from(self.levels.is_some());
+        return 1.0 - remaining as f32 / self.total as f32;
+    }
+
+    /// Check if everything has been loaded
+    pub fn is_finished(&self) -> bool {
+        return self.jobs.is_empty() && self.levels.is_none();
+    }
+
+    /// Take the descriptions of everything that couldn't be loaded since the last time they were taken
+    pub fn take_issues(&mut self) -> Vec<String> {
+        return std::mem::take(&mut self.issues);
+    }
+}
+
+/// Draw the loading screen, with a bar filled to the given fraction of the content that has been loaded
+pub fn draw(ctx: &mut Context, progress: f32) -> GameResult {
+    let (width, height) = graphics::drawable_size(ctx);
+    let (bar_width, bar_height) = BAR_SIZE;
+    let (left, top) = ((width - bar_width) / 2.0, height / 2.0);
+
+    let title = graphics::Text::new("Loading...");
+    let title_width = title.width(ctx) as f32;
+    graphics::draw(ctx, &title, ([(width - title_width) / 2.0, top - 40.0], graphics::WHITE))?;
+
+    let outline_rect = graphics::Rect::new(left, top, bar_width, bar_height);
+    let outline = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::stroke(1.0), outline_rect, graphics::WHITE)?;
+    graphics::draw(ctx, &outline, graphics::DrawParam::default())?;
+    let filled = bar_width * progress.max(0.0).min(1.0);
+    if filled > 0.0 {
+        let bar = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(),
+                                                graphics::Rect::new(left, top, filled, bar_height), graphics::WHITE)?;
+        graphics::draw(ctx, &bar, graphics::DrawParam::default())?;
+    }
+
+    return Ok(());
+}
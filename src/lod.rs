@@ -0,0 +1,98 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Distance from the player within which every actor is updated every tick
+const FULL_RATE_DISTANCE: f32 = 500.0;
+/// Number of times a second actors that are updated at the reduced rate are updated
+const REDUCED_RATE: f32 = 15.0;
+
+/// Decides which actors are updated each tick. Actors that allow it are updated less often while they are
+/// far from the player, with the time they missed added to their next update so they don't fall behind
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "Vec<PendingUpdate>", into = "Vec<PendingUpdate>")]
+pub struct UpdateScheduler {
+    pending: HashMap<u32, (f32, u32)>, // Actor ID -> (seconds not yet updated, ticks skipped)
+    reduced_count: usize,
+    interval: u32, // Ticks between updates at the reduced rate
+}
+
+impl UpdateScheduler {
+    /// Create a new scheduler with no actors waiting, for a game that runs at the default tick rate
+    pub fn new() -> UpdateScheduler {
+        let mut scheduler = UpdateScheduler { pending: HashMap::new(), reduced_count: 0, interval: 1 };
+        scheduler.set_tick_rate(crate::DEFAULT_TICK_RATE);
+        return scheduler;
+    }
+
+    /// Space the updates at the reduced rate out over the given number of ticks per second, so they happen as
+    /// often in game time at any tick rate
+    pub fn set_tick_rate(&mut self, rate: u32) {
+        self.interval = (rate as f32 / REDUCED_RATE).round().max(1.0) as u32;
+    }
+
+    /// Start a new tick
+    pub fn begin_tick(&mut self) {
+        self.reduced_count = 0;
+    }
+
+    /// Get the time to update the actor with the given ID by this tick, or None if it should skip this tick,
+    /// given whether it allows a reduced update rate and its distance from the player
+    pub fn get_update_dt(&mut self, id: u32, allows_reduced_rate: bool, distance: f32, dt: f32) -> Option<f32> {
+        let (pending_time, skipped) = self.pending.entry(id).or_insert((0.0, 0));
+        *pending_time += dt;
+
+        // Actors near the player are updated every tick, the rest only every few ticks
+        if allows_reduced_rate && distance > FULL_RATE_DISTANCE {
+            self.reduced_count += 1;
+            *skipped += 1;
+            if *skipped < self.interval {
+                return None;
+            }
+        }
+
+        return self.pending.remove(&id).map(|(pending_time, _)| pending_time);
+    }
+
+    /// Forget the actor with the given ID, once it has been removed from the game
+    pub fn forget(&mut self, id: u32) {
+        self.pending.remove(&id);
+    }
+
+    /// Get the number of actors that were updated at the reduced rate during the last tick
+    pub fn get_reduced_count(&self) -> usize {
+        return self.reduced_count;
+    }
+}
+
+/// Time an actor is waiting to be updated by, as it is saved
+#[derive(Serialize, Deserialize)]
+struct PendingUpdate {
+    id: u32,
+    time: f32,
+    skipped: u32,
+}
+
+impl From<Vec<PendingUpdate>> for UpdateScheduler {
+    fn from(saved: Vec<PendingUpdate>) -> UpdateScheduler {
+        let mut scheduler = UpdateScheduler::new();
+        scheduler.pending = saved.into_iter().map(|update| (update.id, (update.time, update.skipped))).collect();
+        return scheduler;
+    }
+}
+
+impl From<UpdateScheduler> for Vec<PendingUpdate> {
+    fn from(scheduler: UpdateScheduler) -> Vec<PendingUpdate> {
+        let mut saved: Vec<PendingUpdate> = scheduler.pending.into_iter()
+            .map(|(id, (time, skipped))| PendingUpdate { id, time, skipped })
+            .collect();
+        saved.sort_by_key(|update| update.id);
+        return saved;
+    }
+}
+
+impl Default for UpdateScheduler {
+    fn default() -> UpdateScheduler {
+        return UpdateScheduler::new();
+    }
+}
@@ -0,0 +1,91 @@
+use std::time::Instant;
+
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+/// Environment variable the verbosity of the log is read from when the game starts, like "debug" or "trace"
+pub const LOG_LEVEL_VARIABLE: &str = "TURRETS_LOG";
+/// Verbosity of the log unless it is set otherwise: only problems are reported
+pub const DEFAULT_LOG_LEVEL: LevelFilter = LevelFilter::Warn;
+/// Verbosity levels the log cycles through, from quietest to loudest
+const LEVELS: [LevelFilter; 6] = [
+    LevelFilter::Off,
+    LevelFilter::Error,
+    LevelFilter::Warn,
+    LevelFilter::Info,
+    LevelFilter::Debug,
+    LevelFilter::Trace,
+];
+
+/// Logger that writes every record at or above the current verbosity to standard error, tagged with its level and
+/// the part of the game it came from
+struct GameLogger;
+
+impl Log for GameLogger {
+    /// Check if records at the given level are being logged
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        return metadata.level() <= log::max_level();
+    }
+
+    /// Write the given record to standard error
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!("[{:<5} {}] {}", record.level(), record.target(), record.args());
+        }
+    }
+
+    /// Nothing is buffered, so there is nothing to flush
+    fn flush(&self) {}
+}
+
+/// The logger of the game
+static LOGGER: GameLogger = GameLogger;
+
+/// Install the game's logger, at the verbosity set in the environment or the default. Games embedded in programs
+/// that have installed a logger of their own log through that one instead
+pub fn init() {
+    let level = std::env::var(LOG_LEVEL_VARIABLE).ok()
+        .and_then(|level| level.parse().ok())
+        .unwrap_or(DEFAULT_LOG_LEVEL);
+    if log::set_logger(&LOGGER).is_ok() {
+        log::set_max_level(level);
+    }
+}
+
+/// Change the verbosity of the log while the game is running
+pub fn set_level(level: LevelFilter) {
+    log::set_max_level(level);
+}
+
+/// Get the current verbosity of the log
+pub fn get_level() -> LevelFilter {
+    return log::max_level();
+}
+
+/// Turn the verbosity of the log up by one level, wrapping around from the loudest to off, and return the new level
+pub fn cycle_level() -> LevelFilter {
+    let current = LEVELS.iter().position(|&level| level == get_level()).unwrap_or(0);
+    let level = LEVELS[(current + 1) % LEVELS.len()];
+    set_level(level);
+    return level;
+}
+
+/// Span of work done by one system, which logs how long it took when it ends if tracing is on
+pub struct Span {
+    target: &'static str,
+    start: Option<Instant>,
+}
+
+/// Start a span for the system with the given log target, which ends when it is dropped
+pub fn span(target: &'static str) -> Span {
+    let start = if log::log_enabled!(target: target, Level::Trace) { Some(Instant::now()) } else { None };
+    return Span { target, start };
+}
+
+impl Drop for Span {
+    /// Log how long the span took
+    fn drop(&mut self) {
+        if let Some(start) = self.start {
+            log::trace!(target: self.target, "took {:.3} ms", start.elapsed().as_secs_f64() * 1000.0);
+        }
+    }
+}
@@ -1,17 +1,104 @@
-use ggez::{event, conf, ContextBuilder, GameResult};
+use ggez::{event, conf, ContextBuilder, GameResult, graphics};
 use ggez::conf::FullscreenType;
-use turrets::MainState;
+use turrets::{GameMode, MainState, ShipClass};
+use turrets::benchmark;
+use turrets::headless::{self, HeadlessRun};
+use turrets::launch::{self, LaunchMode, LaunchOptions};
+
+/// Size of the window, and of the arena of headless runs, unless another one is chosen
+const DEFAULT_SIZE: (f32, f32) = (800.0, 600.0);
+/// Longest a headless run is played for, in ticks: ten minutes of play
+const HEADLESS_MAX_TICKS: u64 = 60 * 60 * 10;
+
+/// Play a run without a window with the baseline bot, and print how it went. Returns a description of the problem
+/// if the chosen level doesn't exist
+fn run_headless(options: &LaunchOptions) -> Result<(), String> {
+    let seed = options.seed.unwrap_or(0);
+    let mut state = MainState::headless(options.world.or(options.size).unwrap_or(DEFAULT_SIZE), seed);
+    // Play with the mods and levels in the working directory, like the window does
+    state.load_content();
+    state.set_level(options.level.as_deref())?;
+    let mut run = HeadlessRun::with_state(state, GameMode::Waves, ShipClass::ALL[1]);
+    let result = run.play(HEADLESS_MAX_TICKS, headless::spin_and_fire);
+    println!("seed {}: {:?} after {} ticks with score {} on wave {}, simulated in {:.2}s",
+             seed, result.outcome, result.ticks, result.score, result.wave, result.elapsed.as_secs_f32());
+    return Ok(());
+}
+
+/// Print the given problem with the launch options and exit
+fn exit_with_error(error: &str) -> ! {
+    eprintln!("{}", error);
+    std::process::exit(2);
+}
 
 fn main() -> GameResult {
-    // Initialize the game context and window
+    // Log to standard error at the verbosity set in the environment
+    turrets::logging::init();
+
+    let options = match LaunchOptions::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, launch::USAGE);
+            std::process::exit(2);
+        }
+    };
+    match options.mode {
+        LaunchMode::Help => {
+            println!("{}", launch::USAGE);
+            return Ok(());
+        }
+        // Run without a window if asked to, with the given seed or 0
+        LaunchMode::Headless => {
+            if let Err(error) = run_headless(&options) {
+                exit_with_error(&error);
+            }
+            return Ok(());
+        }
+        // Benchmarks without a window only time the update, in an arena of a fixed size so they can be compared
+        LaunchMode::HeadlessBenchmark => {
+            println!("{}", benchmark::run_headless(benchmark::BENCHMARK_TICKS));
+            return Ok(());
+        }
+        LaunchMode::Window | LaunchMode::Benchmark => (),
+    }
+
+    // Initialize the game context and a window of the chosen size that can be resized, which resizes the arena with
+    // it
+    let (width, height) = options.size.unwrap_or(DEFAULT_SIZE);
     let cb = ContextBuilder::new("Turrets", "jnbrauer")
-        .window_setup(conf::WindowSetup::default().title("Turrets"))
-        .window_mode(conf::WindowMode::default().fullscreen_type(FullscreenType::Windowed));
+        .window_setup(conf::WindowSetup::default().title("Turrets").vsync(options.vsync))
+        .window_mode(conf::WindowMode::default()
+            .dimensions(width, height)
+            .fullscreen_type(FullscreenType::Windowed)
+            .resizable(true));
 
     let (ctx, events_loop) = &mut cb.build()?;
 
-    // Initialize the game state
+    // Initialize the game state, which applies the saved settings, then let the command line override them for
+    // this launch
     let game = &mut MainState::new(ctx);
-    // Start the game
-    return event::run(ctx, events_loop, game);
+    if let Some(fullscreen) = options.fullscreen {
+        graphics::set_fullscreen(ctx, fullscreen)?;
+    }
+    if options.world.is_some() {
+        game.set_world_size(options.world);
+    }
+    if let Err(error) = game.set_level(options.level.as_deref()) {
+        exit_with_error(&error);
+    }
+    if options.seed.is_some() {
+        game.set_seed(options.seed);
+    }
+    if options.mode == LaunchMode::Benchmark {
+        // The window ticks at the rate chosen in the settings, so the benchmark runs for as many ticks as make up its
+        // length at that rate
+        let ticks = benchmark::BENCHMARK_SECONDS * game.get_tick_rate() as u64;
+        game.start_benchmark(ticks);
+    }
+    // Start the game, and print the timings of a benchmark once it has closed the window
+    event::run(ctx, events_loop, game)?;
+    if let Some(summary) = game.get_benchmark_summary() {
+        println!("{}", summary);
+    }
+    return Ok(());
 }
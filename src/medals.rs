@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use ggez::GameResult;
+use serde::{Deserialize, Serialize};
+
+use crate::modes::{GameMode, RunOutcome};
+use crate::stats::{LifetimeStats, RunStats};
+use crate::versions::{self, FileFormat};
+
+/// Directory the player profiles are saved in
+pub const PROFILES_DIRECTORY: &str = "profiles";
+/// Name of the profile used when the player hasn't picked one
+pub const DEFAULT_PROFILE: &str = "default";
+/// Format of the profile files
+const PROFILE_FORMAT: FileFormat = FileFormat::new("profile", &[versions::add_version]);
+/// Most scores kept in the high-score table of every mode
+pub const HIGH_SCORES_KEPT: usize = 5;
+
+/// Ratings a finished run can earn, from worst to best
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Medal {
+    Bronze,
+    Silver,
+    Gold,
+}
+
+impl Medal {
+    /// Get the name of this medal
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            Medal::Bronze => "Bronze",
+            Medal::Silver => "Silver",
+            Medal::Gold => "Gold",
+        };
+    }
+}
+
+/// What a level's par is measured in
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ParMetric {
+    /// The final score, where higher is better
+    Score,
+    /// The seconds taken to win, where lower is better
+    Time,
+}
+
+/// Thresholds a run has to reach for each medal, as they are written in a level's definition
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ParScores {
+    pub metric: ParMetric,
+    pub bronze: f32,
+    pub silver: f32,
+    pub gold: f32,
+}
+
+impl ParScores {
+    /// Get the built in par of the given mode, used on levels that don't set their own
+    pub fn for_mode(mode: GameMode) -> ParScores {
+        let (metric, bronze, silver, gold) = match mode {
+            GameMode::Waves => (ParMetric::Score, 1500.0, 4000.0, 8000.0),
+            GameMode::Capture => (ParMetric::Time, 150.0, 100.0, 60.0),
+            GameMode::Escort => (ParMetric::Score, 300.0, 700.0, 1200.0),
+            GameMode::Defense => (ParMetric::Score, 2000.0, 3500.0, 5000.0),
+            GameMode::Gauntlet => (ParMetric::Time, 90.0, 60.0, 35.0),
+        };
+        return ParScores { metric, bronze, silver, gold };
+    }
+
+    /// Get the medal earned by a run with the given outcome, final score, and length in seconds, if any. Runs
+    /// that failed their objective never earn one, and time pars are only earned by winning
+    pub fn evaluate(&self, outcome: Option<RunOutcome>, score: u32, time: f32) -> Option<Medal> {
+        let reached = |threshold: f32| match self.metric {
+            ParMetric::Score => score as f32 >= threshold,
+            ParMetric::Time => time <= threshold,
+        };
+        let counts = match (outcome, self.metric) {
+            (Some(RunOutcome::Failed), _) => false,
+            (Some(RunOutcome::Won), _) | (None, ParMetric::Score) => true,
+            (None, ParMetric::Time) => false,
+        };
+        if !counts {
+            return None;
+        }
+
+        let thresholds = [(Medal::Gold, self.gold), (Medal::Silver, self.silver), (Medal::Bronze, self.bronze)];
+        for &(medal, threshold) in thresholds.iter() {
+            if reached(threshold) {
+                return Some(medal);
+            }
+        }
+        return None;
+    }
+
+    /// Describe the thresholds to the player
+    pub fn describe(&self) -> String {
+        return match self.metric {
+            ParMetric::Score => format!("par: bronze {:.0}, silver {:.0}, gold {:.0} points",
+                                        self.bronze, self.silver, self.gold),
+            ParMetric::Time => format!("par: bronze {:.0}s, silver {:.0}s, gold {:.0}s to win",
+                                       self.bronze, self.silver, self.gold),
+        };
+    }
+}
+
+/// Score in a high-score table, with the initials of the player who set it
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct HighScore {
+    pub initials: String,
+    pub score: u32,
+}
+
+/// Best medal earned in every level by a player, the high scores of every mode, and their lifetime statistics,
+/// saved to disk between runs. The default profile is only kept in memory
+#[derive(Default, Deserialize, Serialize)]
+pub struct Profile {
+    #[serde(skip)]
+    path: Option<PathBuf>,
+    #[serde(default)]
+    medals: BTreeMap<String, Medal>,
+    #[serde(default)]
+    high_scores: BTreeMap<String, Vec<HighScore>>,
+    #[serde(default)]
+    stats: LifetimeStats,
+}
+
+impl Profile {
+    /// Load the profile with the given name from the profiles directory, starting a new one if it doesn't exist
+    /// or can't be read. A profile that can't be read is backed up first, so its medals aren't lost when the new
+    /// one is saved
+    pub fn load(directory: &Path, name: &str) -> Profile {
+        let path = directory.join(format!("{}.toml", name));
+        let mut profile: Profile = match fs::read_to_string(&path).map(|text| PROFILE_FORMAT.read(&text)) {
+            Ok(Ok(profile)) => profile,
+            Ok(Err(error)) => {
+                let kept = match versions::back_up(&path) {
+                    Some(backup) => format!(" (kept as {})", backup.display()),
+                    None => String::new(),
+                };
+                log::warn!(target: "turrets", "{}: invalid profile: {}{}", path.display(), error, kept);
+                Profile::default()
+            }
+            Err(_) => Profile::default(),
+        };
+        profile.path = Some(path);
+        return profile;
+    }
+
+    /// Get the best medal earned in the given level
+    pub fn get_best(&self, level: &str) -> Option<Medal> {
+        return self.medals.get(level).cloned();
+    }
+
+    /// Record a medal earned in the given level, saving the profile if it is the best one yet. Returns whether
+    /// it was a new best
+    pub fn record(&mut self, level: &str, medal: Medal) -> GameResult<bool> {
+        if matches!(self.get_best(level), Some(best) if best >= medal) {
+            return Ok(false);
+        }
+        self.medals.insert(level.to_string(), medal);
+        self.save()?;
+
+        return Ok(true);
+    }
+
+    /// Get the high scores of the given mode, from best to worst
+    pub fn get_high_scores(&self, mode: &str) -> &[HighScore] {
+        return self.high_scores.get(mode).map_or(&[], |scores| scores.as_slice());
+    }
+
+    /// Check if the given score would make it into the high-score table of the given mode
+    pub fn is_high_score(&self, mode: &str, score: u32) -> bool {
+        let scores = self.get_high_scores(mode);
+        return score > 0 && (scores.len() < HIGH_SCORES_KEPT || scores.iter().any(|high| score > high.score));
+    }
+
+    /// Add a score to the high-score table of the given mode under the given initials, below the scores it ties
+    /// with, and save the profile
+    pub fn record_high_score(&mut self, mode: &str, initials: &str, score: u32) -> GameResult {
+        let scores = self.high_scores.entry(mode.to_string()).or_default();
+        let rank = scores.iter().position(|high| score > high.score).unwrap_or(scores.len());
+        scores.insert(rank, HighScore { initials: initials.to_string(), score });
+        scores.truncate(HIGH_SCORES_KEPT);
+        return self.save();
+    }
+
+    /// Get the statistics of every run played with this profile
+    pub fn get_stats(&self) -> &LifetimeStats {
+        return &self.stats;
+    }
+
+    /// Add the statistics of a run that lasted the given number of seconds, and save the profile
+    pub fn record_run(&mut self, run: &RunStats, playtime: f32) -> GameResult {
+        self.stats.add(run, playtime);
+        return self.save();
+    }
+
+    /// Write this profile to its file, if it was loaded from one
+    fn save(&self) -> GameResult {
+        let path = match &self.path {
+            Some(path) => path,
+            None => return Ok(()),
+        };
+        let text = PROFILE_FORMAT.write(self).map_err(ggez::GameError::ConfigError)?;
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory)?;
+        }
+        fs::write(path, text)?;
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+
+/// The game modes that can be played
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GameMode {
+    /// Survive endless waves of turrets, picking a perk after each one
+    Waves,
+    /// Capture every zone while turrets defend them
+    Capture,
+    /// Protect a friendly drone while it crosses the arena
+    Escort,
+    /// Build friendly turrets to defend a base from waves of drones
+    Defense,
+    /// Make it to the exit at the end of a corridor lined with turrets
+    Gauntlet,
+}
+
+/// How a run that has finished ended
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunOutcome {
+    /// The player completed the mode's objective
+    Won,
+    /// The mode's objective can no longer be completed
+    Failed,
+}
+
+impl GameMode {
+    /// All the game modes, in the order they are shown on the selection screen
+    pub const ALL: [GameMode; 5] =
+        [GameMode::Waves, GameMode::Capture, GameMode::Escort, GameMode::Defense, GameMode::Gauntlet];
+
+    /// Get the name of this mode
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            GameMode::Waves => "Waves",
+            GameMode::Capture => "Capture points",
+            GameMode::Escort => "Escort",
+            GameMode::Defense => "Tower defense",
+            GameMode::Gauntlet => "Gauntlet",
+        };
+    }
+
+    /// Describe the objective of this mode to the player
+    pub fn get_description(&self) -> &'static str {
+        return match self {
+            GameMode::Waves => "Survive endless waves of turrets, picking a perk after each one",
+            GameMode::Capture => "Capture every zone while turrets defend them",
+            GameMode::Escort => "Protect a friendly drone while it crosses the arena",
+            GameMode::Defense => "Build turrets to defend your base from waves of drones",
+            GameMode::Gauntlet => "Make it to the exit at the end of a corridor lined with turrets",
+        };
+    }
+
+    /// Get the message shown when a run of this mode ends with the given outcome
+    pub fn get_outcome_message(&self, outcome: RunOutcome) -> &'static str {
+        return match (self, outcome) {
+            (GameMode::Capture, RunOutcome::Won) => "All zones captured!",
+            (GameMode::Escort, RunOutcome::Won) => "The drone made it across!",
+            (GameMode::Escort, RunOutcome::Failed) => "Mission failed, the drone was destroyed.",
+            (GameMode::Defense, RunOutcome::Won) => "The base held out!",
+            (GameMode::Defense, RunOutcome::Failed) => "The base was destroyed.",
+            (GameMode::Gauntlet, RunOutcome::Won) => "You made it through the gauntlet!",
+            (_, RunOutcome::Won) => "You win!",
+            (_, RunOutcome::Failed) => "You lose!",
+        };
+    }
+}
@@ -0,0 +1,222 @@
+use serde::{Deserialize, Serialize};
+
+/// Rules that change how a run plays, applied on top of the normal game rules
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value", rename_all = "snake_case")]
+pub enum Modifier {
+    /// Bend the player's shots towards the nearest enemy in front of them, strength from 0 (off) to 1 (locked on)
+    AutoAim(f32),
+    /// Multiply the speed of enemy shots
+    EnemyShotSpeed(f32),
+    /// Multiply the damage the player takes
+    DamageTaken(f32),
+    /// Only show the arena within the given radius of the player
+    LimitedVision(f32),
+    /// Multiply the points scored
+    ScoreMultiplier(f32),
+    /// Multiply the speed of the player's shots
+    PlayerShotSpeed(f32),
+}
+
+impl Modifier {
+    /// The accessibility assists offered before a run, with their default strength
+    pub const ASSISTS: [Modifier; 3] = [
+        Modifier::AutoAim(0.5),
+        Modifier::EnemyShotSpeed(0.75),
+        Modifier::DamageTaken(0.5),
+    ];
+
+    /// The modifiers offered before a run that make it harder
+    pub const CHALLENGES: [Modifier; 1] = [
+        Modifier::LimitedVision(220.0),
+    ];
+
+    /// The mutators offered on the custom game screen, which change the rules of a run for fun rather than making
+    /// it easier or harder to play
+    pub const MUTATORS: [Modifier; 2] = [
+        Modifier::ScoreMultiplier(2.0),
+        Modifier::PlayerShotSpeed(1.5),
+    ];
+
+    /// Get every modifier offered before a run, the assists followed by the challenges
+    pub fn get_options() -> Vec<Modifier> {
+        let mut options = Modifier::ASSISTS.to_vec();
+        options.extend_from_slice(&Modifier::CHALLENGES);
+        return options;
+    }
+
+    /// Check if this modifier is the same kind of modifier as another, whatever their strength
+    pub fn is_same_kind(&self, other: &Modifier) -> bool {
+        return std::mem::discriminant(self) == std::mem::discriminant(other);
+    }
+
+    /// Check if this modifier makes the game easier, which is flagged on the results of the run
+    pub fn is_assist(&self) -> bool {
+        return match self {
+            Modifier::AutoAim(strength) => *strength > 0.0,
+            Modifier::EnemyShotSpeed(multiplier) | Modifier::DamageTaken(multiplier) => *multiplier < 1.0,
+            Modifier::LimitedVision(_) | Modifier::ScoreMultiplier(_) | Modifier::PlayerShotSpeed(_) => false,
+        };
+    }
+
+    /// Check if this modifier is one of the mutators of custom games
+    pub fn is_mutator(&self) -> bool {
+        return Modifier::MUTATORS.iter().any(|mutator| mutator.is_same_kind(self));
+    }
+
+    /// Get this modifier with its value brought into the range the game can play with, for modifiers read from
+    /// files
+    pub fn clamped(&self) -> Modifier {
+        return match *self {
+            Modifier::AutoAim(strength) => Modifier::AutoAim(clamp_value(strength, 0.0, 0.0, 1.0)),
+            Modifier::EnemyShotSpeed(multiplier) => Modifier::EnemyShotSpeed(clamp_value(multiplier, 1.0, 0.25, 2.0)),
+            Modifier::DamageTaken(multiplier) => Modifier::DamageTaken(clamp_value(multiplier, 1.0, 0.0, 2.0)),
+            Modifier::LimitedVision(radius) => Modifier::LimitedVision(clamp_value(radius, 220.0, 100.0, 1000.0)),
+            Modifier::ScoreMultiplier(multiplier) => Modifier::ScoreMultiplier(clamp_value(multiplier, 1.0, 0.0, 4.0)),
+            Modifier::PlayerShotSpeed(multiplier) => Modifier::PlayerShotSpeed(clamp_value(multiplier, 1.0, 0.5, 2.0)),
+        };
+    }
+
+    /// Describe this modifier to the player
+    pub fn get_description(&self) -> String {
+        return match self {
+            Modifier::AutoAim(strength) => format!("Auto-aim {:.0}%", strength * 100.0),
+            Modifier::EnemyShotSpeed(multiplier) => format!("Enemy shot speed {:.0}%", multiplier * 100.0),
+            Modifier::DamageTaken(multiplier) => format!("Damage taken {:.0}%", multiplier * 100.0),
+            Modifier::LimitedVision(radius) => format!("Limited vision ({:.0}px)", radius),
+            Modifier::ScoreMultiplier(multiplier) => format!("Score {:.0}%", multiplier * 100.0),
+            Modifier::PlayerShotSpeed(multiplier) => format!("Player shot speed {:.0}%", multiplier * 100.0),
+        };
+    }
+}
+
+/// The modifiers active in a run. Modifiers of the same kind stack, so the set is read through the combined
+/// values rather than the individual modifiers
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct ModifierSet {
+    modifiers: Vec<Modifier>,
+}
+
+impl ModifierSet {
+    /// Create an empty set, which plays by the normal rules
+    pub fn new() -> ModifierSet {
+        return ModifierSet { modifiers: Vec::new() };
+    }
+
+    /// Add a modifier to the set
+    pub fn add(&mut self, modifier: Modifier) {
+        self.modifiers.push(modifier);
+    }
+
+    /// Remove every modifier of the same kind as the given one
+    pub fn remove_kind(&mut self, modifier: &Modifier) {
+        self.modifiers.retain(|active| !active.is_same_kind(modifier));
+    }
+
+    /// Add the given modifier if no modifier of its kind is active, or remove its kind otherwise
+    pub fn toggle(&mut self, modifier: Modifier) {
+        if self.has_kind(&modifier) {
+            self.remove_kind(&modifier);
+        } else {
+            self.add(modifier);
+        }
+    }
+
+    /// Get the modifiers in the set, in the order they were added
+    pub fn get_modifiers(&self) -> &[Modifier] {
+        return &self.modifiers;
+    }
+
+    /// Check if a modifier of the same kind as the given one is active
+    pub fn has_kind(&self, modifier: &Modifier) -> bool {
+        return self.modifiers.iter().any(|active| active.is_same_kind(modifier));
+    }
+
+    /// Get the combined auto-aim strength, from 0 to 1
+    pub fn get_auto_aim_strength(&self) -> f32 {
+        let strength: f32 = self.modifiers.iter()
+            .map(|modifier| if let Modifier::AutoAim(strength) = modifier { *strength } else { 0.0 })
+            .sum();
+        return strength.clamp(0.0, 1.0);
+    }
+
+    /// Get the combined multiplier for the speed of enemy shots
+    pub fn get_enemy_shot_speed_multiplier(&self) -> f32 {
+        return self.modifiers.iter()
+            .map(|modifier| if let Modifier::EnemyShotSpeed(multiplier) = modifier { *multiplier } else { 1.0 })
+            .product();
+    }
+
+    /// Get the combined multiplier for the damage the player takes
+    pub fn get_damage_taken_multiplier(&self) -> f32 {
+        return self.modifiers.iter()
+            .map(|modifier| if let Modifier::DamageTaken(multiplier) = modifier { *multiplier } else { 1.0 })
+            .product();
+    }
+
+    /// Get the combined multiplier for the points scored
+    pub fn get_score_multiplier(&self) -> f32 {
+        return self.modifiers.iter()
+            .map(|modifier| if let Modifier::ScoreMultiplier(multiplier) = modifier { *multiplier } else { 1.0 })
+            .product();
+    }
+
+    /// Get the combined multiplier for the speed of the player's shots
+    pub fn get_player_shot_speed_multiplier(&self) -> f32 {
+        return self.modifiers.iter()
+            .map(|modifier| if let Modifier::PlayerShotSpeed(multiplier) = modifier { *multiplier } else { 1.0 })
+            .product();
+    }
+
+    /// Get the radius the player can see within, if their vision is limited
+    pub fn get_vision_radius(&self) -> Option<f32> {
+        return self.modifiers.iter()
+            .filter_map(|modifier| if let Modifier::LimitedVision(radius) = modifier { Some(*radius) } else { None })
+            .min_by(|a, b| a.partial_cmp(b).unwrap());
+    }
+
+    /// Get the descriptions of the active modifiers that are not assists, to list with the mutators of the run
+    pub fn get_challenge_descriptions(&self) -> Vec<String> {
+        return self.modifiers.iter()
+            .filter(|modifier| !modifier.is_assist())
+            .map(|modifier| modifier.get_description())
+            .collect();
+    }
+
+    /// Get the active mutators, in the order they were added
+    pub fn get_mutators(&self) -> Vec<Modifier> {
+        return self.modifiers.iter().filter(|modifier| modifier.is_mutator()).copied().collect();
+    }
+
+    /// Replace the active mutators with the given ones, keeping the assists and challenges
+    pub fn set_mutators(&mut self, mutators: &[Modifier]) {
+        self.modifiers.retain(|modifier| !modifier.is_mutator());
+        self.modifiers.extend(mutators.iter().filter(|modifier| modifier.is_mutator()));
+    }
+
+    /// Check if any mutator is active
+    pub fn has_mutators(&self) -> bool {
+        return self.modifiers.iter().any(|modifier| modifier.is_mutator());
+    }
+
+    /// Check if any assist is active
+    pub fn has_assists(&self) -> bool {
+        return self.modifiers.iter().any(|modifier| modifier.is_assist());
+    }
+
+    /// Get the descriptions of the active assists, to flag on the results of the run
+    pub fn get_assist_descriptions(&self) -> Vec<String> {
+        return self.modifiers.iter()
+            .filter(|modifier| modifier.is_assist())
+            .map(|modifier| modifier.get_description())
+            .collect();
+    }
+}
+
+/// Bring the given value into the given range, or use the given default if it isn't a number
+fn clamp_value(value: f32, default: f32, min: f32, max: f32) -> f32 {
+    if value.is_nan() {
+        return default;
+    }
+    return value.clamp(min, max);
+}
@@ -0,0 +1,371 @@
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+/// Directory that mods are loaded from
+pub const MODS_DIRECTORY: &str = "mods";
+/// Name of the manifest file every mod directory must contain
+const MANIFEST_FILE: &str = "mod.toml";
+/// Name of the file storing which mods the user has disabled
+const ENABLED_FILE: &str = "enabled.toml";
+
+/// Manifest describing a mod and the content it contributes
+#[derive(Clone, Deserialize)]
+pub struct ModManifest {
+    name: String,
+    version: String,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(default)]
+    levels: Vec<String>,
+    #[serde(default)]
+    actors: Vec<String>,
+    #[serde(default)]
+    patterns: Vec<String>,
+    #[serde(default)]
+    scripts: Vec<String>,
+    #[serde(default)]
+    assets: Vec<String>,
+    /// File of balance values that override the game's tuning
+    #[serde(default)]
+    tuning: Option<String>,
+}
+
+impl ModManifest {
+    /// Get the name of this mod
+    pub fn get_name(&self) -> &str {
+        return &self.name;
+    }
+
+    /// Get the version of this mod
+    pub fn get_version(&self) -> &str {
+        return &self.version;
+    }
+
+    /// Get the paths of the levels this mod contributes, relative to its directory
+    pub fn get_levels(&self) -> &[String] {
+        return &self.levels;
+    }
+
+    /// Get the paths of the enemy definitions this mod contributes, relative to its directory
+    pub fn get_actors(&self) -> &[String] {
+        return &self.actors;
+    }
+
+    /// Get the path of the file of tuning overrides of this mod, relative to its directory
+    pub fn get_tuning(&self) -> Option<&str> {
+        return self.tuning.as_deref();
+    }
+
+    /// Get the paths of the scripts this mod contributes, relative to its directory
+    pub fn get_scripts(&self) -> &[String] {
+        return &self.scripts;
+    }
+
+    /// Get the list of (content type, content name) pairs this mod contributes
+    fn get_contents(&self) -> Vec<(&'static str, &str)> {
+        let mut contents = Vec::new();
+        for (kind, names) in &[
+            ("level", &self.levels),
+            ("actor", &self.actors),
+            ("pattern", &self.patterns),
+            ("script", &self.scripts),
+            ("asset", &self.assets),
+        ] {
+            for name in names.iter() {
+                contents.push((*kind, name.as_str()));
+            }
+        }
+        return contents;
+    }
+
+    /// Find a path in this manifest that reaches outside the mod's directory, by being absolute or by going up
+    /// with `..`, if there is one
+    fn find_escaping_path(&self) -> Option<&str> {
+        let paths = self.get_contents().into_iter().map(|(_, path)| path).chain(self.tuning.as_deref());
+        for path in paths {
+            let escapes = Path::new(path).components()
+                .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir));
+            if escapes {
+                return Some(path);
+            }
+        }
+        return None;
+    }
+}
+
+/// A mod found in the mods directory
+#[derive(Clone)]
+pub struct ModEntry {
+    manifest: ModManifest,
+    directory: PathBuf,
+    enabled: bool,
+    active: bool,
+}
+
+impl ModEntry {
+    /// Get the manifest of this mod
+    pub fn get_manifest(&self) -> &ModManifest {
+        return &self.manifest;
+    }
+
+    /// Get the directory this mod was loaded from
+    pub fn get_directory(&self) -> &Path {
+        return &self.directory;
+    }
+
+    /// Check if the user has enabled this mod
+    pub fn is_enabled(&self) -> bool {
+        return self.enabled;
+    }
+
+    /// Check if this mod is enabled and all of its dependencies could be resolved
+    pub fn is_active(&self) -> bool {
+        return self.active;
+    }
+}
+
+/// File storing the names of the mods the user has disabled (mods are enabled by default)
+#[derive(Default, Deserialize, Serialize)]
+struct EnabledFile {
+    #[serde(default)]
+    disabled: Vec<String>,
+}
+
+/// All the mods found in the mods directory, kept in load order
+#[derive(Clone)]
+pub struct ModList {
+    directory: PathBuf,
+    mods: Vec<ModEntry>,
+    issues: Vec<String>,
+}
+
+impl ModList {
+    /// Create a list with no mods, for games that don't read the mods directory
+    pub fn empty() -> ModList {
+        return ModList { directory: PathBuf::from(MODS_DIRECTORY), mods: Vec::new(), issues: Vec::new() };
+    }
+
+    /// Scan the given directory for mods and resolve their load order. Problems with individual mods are
+    /// recorded as issues rather than stopping the game from starting
+    pub fn load(directory: &Path) -> ModList {
+        let mut list = ModList {
+            directory: directory.to_path_buf(),
+            mods: Vec::new(),
+            issues: Vec::new(),
+        };
+
+        // Having no mods directory is the same as having no mods
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(_) => return list,
+        };
+
+        // Read the list of disabled mods
+        let enabled_file: EnabledFile = fs::read_to_string(directory.join(ENABLED_FILE)).ok()
+            .and_then(|text| toml::from_str(&text).ok())
+            .unwrap_or_default();
+
+        // Read the manifest of every mod directory
+        let mut directories: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.is_dir())
+            .collect();
+        directories.sort();
+
+        for mod_directory in directories {
+            let manifest_path = mod_directory.join(MANIFEST_FILE);
+            let manifest: ModManifest = match fs::read_to_string(&manifest_path) {
+                Ok(text) => match toml::from_str(&text) {
+                    Ok(manifest) => manifest,
+                    Err(error) => {
+                        list.issues.push(format!("{}: invalid manifest: {}", manifest_path.display(), error));
+                        continue;
+                    }
+                },
+                Err(_) => {
+                    list.issues.push(format!("{}: missing {}", mod_directory.display(), MANIFEST_FILE));
+                    continue;
+                }
+            };
+
+            // A mod may only read the files in its own directory
+            if let Some(path) = manifest.find_escaping_path() {
+                list.issues.push(format!("{}: '{}' is outside the mod's directory", manifest_path.display(), path));
+                continue;
+            }
+
+            // Two mods may not share a name since dependencies refer to mods by name
+            if list.mods.iter().any(|entry| entry.manifest.name == manifest.name) {
+                list.issues.push(format!("{}: another mod is already named '{}'", mod_directory.display(), manifest.name));
+                continue;
+            }
+
+            let enabled = !enabled_file.disabled.contains(&manifest.name);
+            list.mods.push(ModEntry {
+                manifest,
+                directory: mod_directory,
+                enabled,
+                active: false,
+            });
+        }
+
+        list.resolve();
+
+        return list;
+    }
+
+    /// Get the mods in load order
+    pub fn get_mods(&self) -> &[ModEntry] {
+        return &self.mods;
+    }
+
+    /// Get the active mods in load order
+    pub fn get_active_mods(&self) -> impl Iterator<Item = &ModEntry> {
+        return self.mods.iter().filter(|entry| entry.active);
+    }
+
+    /// Get the problems found while loading and ordering the mods
+    pub fn get_issues(&self) -> &[String] {
+        return &self.issues;
+    }
+
+    /// Enable or disable the mod at the given index and save the choice
+    pub fn toggle(&mut self, index: usize) {
+        if let Some(entry) = self.mods.get_mut(index) {
+            entry.enabled = !entry.enabled;
+        }
+
+        // Keep only the problems found while reading manifests, the rest are found again when resolving
+        self.issues.retain(|issue| !issue.starts_with("load order:"));
+        self.resolve();
+
+        if let Err(error) = self.save() {
+            self.issues.push(format!("could not save enabled mods: {}", error));
+        }
+    }
+
+    /// Save the list of disabled mods
+    fn save(&self) -> GameResult {
+        let enabled_file = EnabledFile {
+            disabled: self.mods.iter()
+                .filter(|entry| !entry.enabled)
+                .map(|entry| entry.manifest.name.clone())
+                .collect(),
+        };
+
+        let text = toml::to_string(&enabled_file)
+            .map_err(|error| ggez::GameError::ConfigError(error.to_string()))?;
+        fs::create_dir_all(&self.directory)?;
+        fs::write(self.directory.join(ENABLED_FILE), text)?;
+
+        return Ok(());
+    }
+
+    /// Work out which mods can be activated, sort them so every mod loads after its dependencies,
+    /// and detect mods that contribute the same content
+    fn resolve(&mut self) {
+        // A mod is active if it is enabled and all of its dependencies are active. Repeat until nothing changes
+        // so that mods depending on an inactive mod are deactivated too
+        for entry in &mut self.mods {
+            entry.active = entry.enabled;
+        }
+        loop {
+            let active: BTreeSet<String> = self.get_active_mods().map(|entry| entry.manifest.name.clone()).collect();
+            let mut changed = false;
+            for entry in &mut self.mods {
+                if !entry.active {
+                    continue;
+                }
+                if let Some(missing) = entry.manifest.dependencies.iter().find(|name| !active.contains(*name)) {
+                    self.issues.push(format!("load order: '{}' requires '{}', which is missing or disabled",
+                                             entry.manifest.name, missing));
+                    entry.active = false;
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        // Sort the mods topologically by their dependencies, breaking ties alphabetically so the order is stable
+        let mut remaining: Vec<ModEntry> = self.mods.drain(..).collect();
+        remaining.sort_by(|a, b| a.manifest.name.cmp(&b.manifest.name));
+        while !remaining.is_empty() {
+            let next = remaining.iter().position(|entry| {
+                entry.manifest.dependencies.iter().all(|dependency| {
+                    !remaining.iter().any(|other| &other.manifest.name == dependency)
+                })
+            });
+
+            match next {
+                Some(index) => self.mods.push(remaining.remove(index)),
+                None => {
+                    // Every remaining mod waits on another remaining mod, so they depend on each other in a cycle
+                    let names: Vec<&str> = remaining.iter().map(|entry| entry.manifest.name.as_str()).collect();
+                    self.issues.push(format!("load order: dependency cycle between {}", names.join(", ")));
+                    for mut entry in remaining.drain(..) {
+                        entry.active = false;
+                        self.mods.push(entry);
+                    }
+                }
+            }
+        }
+
+        // Report content contributed by more than one active mod. The mod loaded last overrides the others
+        let mut providers: HashMap<(&str, &str), &str> = HashMap::new();
+        let mut conflicts = Vec::new();
+        for entry in self.mods.iter().filter(|entry| entry.active) {
+            for content in entry.manifest.get_contents() {
+                if let Some(previous) = providers.insert(content, &entry.manifest.name) {
+                    conflicts.push(format!("load order: {} '{}' from '{}' overrides the one from '{}'",
+                                           content.0, content.1, entry.manifest.name, previous));
+                }
+            }
+        }
+        self.issues.append(&mut conflicts);
+    }
+
+    /// Draw the in-game mod list with the given mod selected
+    pub fn draw(&self, ctx: &mut Context, selected: usize) -> GameResult {
+        // Darken the game behind the list
+        let (width, height) = graphics::drawable_size(ctx);
+        let background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, width, height),
+            graphics::Color::new(0.0, 0.0, 0.0, 0.8),
+        )?;
+        graphics::draw(ctx, &background, ([0.0, 0.0],))?;
+
+        let mut lines = vec![
+            String::from("Mods (Up/Down to select, Enter to toggle, M to close)"),
+            String::from("Changes take effect the next time the game starts"),
+            String::new(),
+        ];
+        if self.mods.is_empty() {
+            lines.push(format!("No mods found in the '{}' directory", self.directory.display()));
+        }
+        for (i, entry) in self.mods.iter().enumerate() {
+            let cursor = if i == selected { ">" } else { " " };
+            let check = if entry.enabled { "x" } else { " " };
+            let status = if entry.enabled && !entry.active { " (inactive)" } else { "" };
+            lines.push(format!("{} [{}] {} {}{}", cursor, check, entry.manifest.name, entry.manifest.version, status));
+        }
+        if !self.issues.is_empty() {
+            lines.push(String::new());
+            lines.push(String::from("Issues:"));
+            lines.extend(self.issues.iter().cloned());
+        }
+
+        let text = graphics::Text::new(lines.join("\n"));
+        graphics::draw(ctx, &text, ([40.0, 40.0], graphics::WHITE))?;
+
+        return Ok(());
+    }
+}
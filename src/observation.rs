@@ -0,0 +1,110 @@
+use crate::{ActorKind, Scene, Team};
+
+/// Number of values each actor takes up in a flat observation
+pub const ACTOR_FEATURES: usize = 7;
+/// Number of values the player and the run take up at the start of a flat observation
+pub const PLAYER_FEATURES: usize = 9;
+
+/// Read-only snapshot of an actor, for agents, overlays, and tools watching the game
+#[derive(Clone, Debug)]
+pub struct ActorObservation {
+    pub id: u32,
+    pub kind: ActorKind,
+    pub team: Team,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32, // Pixels per second
+    pub velocity_y: f32, // Pixels per second
+    pub radius: f32,
+    pub health: Option<f32>, // None for actors that can't be hurt, like pickups
+}
+
+impl ActorObservation {
+    /// Write this actor as flat values: position and velocity relative to the given point, radius, health
+    /// (0 if it can't be hurt), and team (1 for the player's, -1 for hostile, 0 for neutral)
+    fn write_flat(&self, origin: (f32, f32), values: &mut Vec<f32>) {
+        let team = match self.team {
+            Team::Player => 1.0,
+            Team::Hostile => -1.0,
+            Team::Neutral => 0.0,
+        };
+        values.extend_from_slice(&[
+            self.x - origin.0,
+            self.y - origin.1,
+            self.velocity_x,
+            self.velocity_y,
+            self.radius,
+            self.health.unwrap_or(0.0),
+            team,
+        ]);
+    }
+}
+
+/// Read-only snapshot of the player, for agents, overlays, and tools watching the game
+#[derive(Clone, Debug)]
+pub struct PlayerObservation {
+    pub id: u32,
+    pub x: f32,
+    pub y: f32,
+    pub velocity_x: f32, // Pixels per second
+    pub velocity_y: f32, // Pixels per second
+    pub heading: f32, // Radians
+    pub health: f32,
+    pub max_health: f32,
+    pub shield_charges: u32,
+}
+
+/// Read-only snapshot of the whole game after a tick, for agents, overlays, and tools watching the game. Taking one
+/// never changes the game, so it can be polled every frame
+#[derive(Clone, Debug)]
+pub struct Observation {
+    pub tick: u64,
+    pub time: f32, // Seconds simulated
+    pub scene: Scene,
+    pub score: u32,
+    pub wave: u32,
+    pub bounds: (f32, f32),
+    pub player: PlayerObservation,
+    pub actors: Vec<ActorObservation>, // Sorted from nearest to farthest from the player
+}
+
+impl Observation {
+    /// Flatten this observation into a fixed length array of values, for feeding to a neural network. The
+    /// player and the run come first, followed by the given number of nearest actors relative to the player,
+    /// padded with zeros if there are fewer actors
+    pub fn to_flat(&self, max_actors: usize) -> Vec<f32> {
+        let mut values = Vec::with_capacity(PLAYER_FEATURES + max_actors * ACTOR_FEATURES);
+        let player = &self.player;
+        values.extend_from_slice(&[
+            player.x / self.bounds.0,
+            player.y / self.bounds.1,
+            player.velocity_x,
+            player.velocity_y,
+            player.heading.sin(),
+            player.heading.cos(),
+            player.health,
+            player.shield_charges as f32,
+            self.wave as f32,
+        ]);
+
+        for actor in self.actors.iter().take(max_actors) {
+            actor.write_flat((player.x, player.y), &mut values);
+        }
+        values.resize(PLAYER_FEATURES + max_actors * ACTOR_FEATURES, 0.0);
+
+        return values;
+    }
+}
+
+/// Controls an agent can press for one tick, in place of the keyboard
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Action {
+    /// Move forwards (1), backwards (-1), or stop (0)
+    pub thrust: i8,
+    /// Turn right (1), left (-1), or not at all (0)
+    pub turn: i8,
+    /// Fire a volley of shots
+    pub fire: bool,
+    /// Fire the grapple, or let go of it if it is already out
+    pub grapple: bool,
+}
@@ -0,0 +1,68 @@
+use std::f32::consts::PI;
+
+use ggez::{Context, GameResult, graphics};
+
+use crate::Point;
+
+/// Color of an obstacle
+const OBSTACLE_COLOR: graphics::Color = graphics::Color::new(0.45, 0.45, 0.5, 1.0);
+
+/// Round block in the arena that stops shots and that the player can't pass through
+#[derive(Clone)]
+pub struct Obstacle {
+    position: Point,
+    radius: f32,
+}
+
+impl Obstacle {
+    /// Create a new obstacle of the given radius at the given position
+    pub fn new(position: Point, radius: f32) -> Obstacle {
+        return Obstacle { position, radius };
+    }
+
+    /// Get the position of the center of this obstacle
+    pub fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the radius of this obstacle
+    pub fn get_radius(&self) -> f32 {
+        return self.radius;
+    }
+
+    /// Check if a circle of the given radius at the given position overlaps this obstacle
+    pub fn overlaps(&self, position: &Point, radius: f32) -> bool {
+        return self.position.distance_to(position) < self.radius + radius;
+    }
+
+    /// Move a circle of the given radius at the given position out to the edge of this obstacle if it overlaps it
+    pub fn push_out(&self, position: &mut Point, radius: f32) {
+        if !self.overlaps(position, radius) {
+            return;
+        }
+        // Push straight out from the center, or upwards from the very center where there is no direction to use
+        let heading = if self.position.distance_to(position) > 0.0 {
+            self.position.heading_to(position)
+        } else {
+            -PI / 2.0
+        };
+        let mut pushed = self.position;
+        pushed.move_distance(self.radius + radius, heading);
+        *position = pushed;
+    }
+
+    /// Draw this obstacle
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let circle = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::fill(),
+            [0.0, 0.0],
+            self.radius,
+            1.0,
+            OBSTACLE_COLOR,
+        )?;
+        graphics::draw(ctx, &circle, ([self.position.x, self.position.y],))?;
+
+        return Ok(());
+    }
+}
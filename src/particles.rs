@@ -0,0 +1,154 @@
+use ggez::{Context, GameResult, graphics};
+use rand::Rng;
+
+use crate::{Point, Velocity};
+
+/// Most particles alive at once. Emitters stop adding particles past this, so a chain of explosions can't slow the
+/// game down
+const MAX_PARTICLES: usize = 2000;
+
+/// Speck thrown off by an emitter, which drifts in a straight line and fades away over its lifetime
+struct Particle {
+    position: Point,
+    velocity: Velocity,
+    age: f32,
+    lifetime: f32,
+    size: f32,
+    color: graphics::Color,
+}
+
+impl Particle {
+    /// Get how faded this particle is, from 1 when it was thrown off down to 0 at the end of its lifetime
+    fn get_alpha(&self) -> f32 {
+        return (1.0 - self.age / self.lifetime).max(0.0);
+    }
+}
+
+/// Description of the particles thrown off by something, like the sparks of an impact or the exhaust of an engine:
+/// how long they live, how fast they fly, how far from their heading they spread, and how they look
+#[derive(Clone)]
+pub struct Emitter {
+    lifetime: (f32, f32),
+    speed: (f32, f32),
+    spread: f32,
+    size: f32,
+    color: graphics::Color,
+    rate: f32,
+    carry: f32,
+}
+
+impl Emitter {
+    /// Create an emitter whose particles live for a time in the given range, fly at a speed in the given range up to
+    /// the given angle to either side of their heading, and are circles of the given radius and color
+    pub const fn new(lifetime: (f32, f32), speed: (f32, f32), spread: f32, size: f32,
+                     color: graphics::Color) -> Emitter {
+        return Emitter { lifetime, speed, spread, size, color, rate: 0.0, carry: 0.0 };
+    }
+
+    /// Stream the given number of particles per second instead of none, for emitters left running
+    pub const fn with_rate(mut self, rate: f32) -> Emitter {
+        self.rate = rate;
+        return self;
+    }
+
+    /// Throw off the given number of particles at once from the given position, heading the given way
+    pub fn burst<R: Rng>(&self, particles: &mut ParticleSystem, rng: &mut R, position: &Point, heading: f32,
+                         count: u32) {
+        for _ in 0..count {
+            let particle = Particle {
+                position: *position,
+                velocity: Velocity::new(between(rng, self.speed),
+                                        heading + between(rng, (-self.spread, self.spread))),
+                age: 0.0,
+                lifetime: between(rng, self.lifetime).max(f32::EPSILON),
+                size: self.size,
+                color: self.color,
+            };
+            particles.add(particle);
+        }
+    }
+
+    /// Throw off as many particles as are due over the given time at this emitter's rate, keeping the fraction of a
+    /// particle left over for the next time so slow rates still emit
+    pub fn stream<R: Rng>(&mut self, particles: &mut ParticleSystem, rng: &mut R, position: &Point, heading: f32,
+                          dt: f32) {
+        self.carry += self.rate * dt;
+        let count = self.carry.floor();
+        self.carry -= count;
+        self.burst(particles, rng, position, heading, count as u32);
+    }
+}
+
+/// Get a random number in the given range, or its start if the range is empty
+fn between<R: Rng>(rng: &mut R, (min, max): (f32, f32)) -> f32 {
+    if max <= min {
+        return min;
+    }
+    return rng.gen_range(min, max);
+}
+
+/// Every particle thrown off by the emitters. Particles are only for show, so they aren't saved and don't change how
+/// a run plays out
+pub struct ParticleSystem {
+    particles: Vec<Particle>,
+}
+
+impl ParticleSystem {
+    /// Create a particle system with no particles
+    pub fn new() -> ParticleSystem {
+        return ParticleSystem { particles: Vec::new() };
+    }
+
+    /// Add a particle, unless there are already as many as there can be
+    fn add(&mut self, particle: Particle) {
+        if self.particles.len() < MAX_PARTICLES {
+            self.particles.push(particle);
+        }
+    }
+
+    /// Move and age the particles by the given time, and clear away the ones that have outlived their lifetime
+    pub fn update(&mut self, dt: f32) {
+        for particle in &mut self.particles {
+            particle.position.move_time(dt, &particle.velocity);
+            particle.age += dt;
+        }
+        self.particles.retain(|particle| particle.age < particle.lifetime);
+    }
+
+    /// Get the number of particles alive
+    pub fn len(&self) -> usize {
+        return self.particles.len();
+    }
+
+    /// Check if there are no particles alive
+    pub fn is_empty(&self) -> bool {
+        return self.particles.is_empty();
+    }
+
+    /// Get how faded each particle is, from 1 when it was thrown off down to 0 at the end of its lifetime
+    pub fn get_alphas(&self) -> Vec<f32> {
+        return self.particles.iter().map(|particle| particle.get_alpha()).collect();
+    }
+
+    /// Draw every particle as a circle, all in one mesh
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        if self.particles.is_empty() {
+            return Ok(());
+        }
+        let mut builder = graphics::MeshBuilder::new();
+        for particle in &self.particles {
+            let mut color = particle.color;
+            color.a *= particle.get_alpha();
+            builder.circle(graphics::DrawMode::fill(), [particle.position.x, particle.position.y], particle.size,
+                           0.5, color);
+        }
+        let mesh = builder.build(ctx)?;
+        return graphics::draw(ctx, &mesh, graphics::DrawParam::default());
+    }
+}
+
+impl Default for ParticleSystem {
+    fn default() -> ParticleSystem {
+        return ParticleSystem::new();
+    }
+}
@@ -0,0 +1,60 @@
+use ggez::{Context, GameResult, graphics};
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::{Deserialize, Serialize};
+
+/// Number of perks offered after each wave
+pub const PERK_CHOICES: usize = 3;
+
+/// Upgrades the Player can pick between waves, which last for the rest of the run
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Perk {
+    /// Fire 2 more shots with every volley
+    ExtraShots,
+    /// Shots bounce off the edge of the window once more
+    Ricochet,
+    /// Move 20% faster
+    Speed,
+    /// Shots do 25% more damage
+    Damage,
+    /// Gain 25 max health and heal the same amount
+    Health,
+}
+
+impl Perk {
+    /// All the perks that can be offered
+    pub const ALL: [Perk; 5] = [Perk::ExtraShots, Perk::Ricochet, Perk::Speed, Perk::Damage, Perk::Health];
+
+    /// Get the description of this perk shown to the player
+    pub fn get_description(&self) -> &'static str {
+        return match self {
+            Perk::ExtraShots => "+2 shots per volley",
+            Perk::Ricochet => "Shots ricochet off walls once more",
+            Perk::Speed => "+20% movement speed",
+            Perk::Damage => "+25% shot damage",
+            Perk::Health => "+25 max health",
+        };
+    }
+}
+
+/// Pick the perks to offer the player using the given random number generator, with no perk offered twice
+pub fn choose_perks<R: Rng>(rng: &mut R) -> Vec<Perk> {
+    return Perk::ALL.choose_multiple(rng, PERK_CHOICES).cloned().collect();
+}
+
+/// Draw the screen for picking one of the given perks after the given wave
+pub fn draw_perk_choice(ctx: &mut Context, wave: u32, choices: &[Perk]) -> GameResult {
+    let mut lines = vec![
+        format!("Wave {} cleared! Choose a perk (press its number)", wave),
+        String::new(),
+    ];
+    for (i, perk) in choices.iter().enumerate() {
+        lines.push(format!("{}. {}", i + 1, perk.get_description()));
+    }
+
+    let text = graphics::Text::new(lines.join("\n"));
+    graphics::draw(ctx, &text, ([40.0, 40.0], graphics::WHITE))?;
+
+    return Ok(());
+}
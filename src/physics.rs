@@ -0,0 +1,55 @@
+use crate::{Point, Vec2};
+
+/// Fraction of the speed two bodies close at that they part at after a collision, where 1 loses no energy
+const RESTITUTION: f32 = 1.0;
+
+/// A circle taking part in a physical collision
+pub struct Body {
+    position: Point,
+    radius: f32,
+    inverse_mass: f32, // Zero for bodies nothing can move
+    velocity: Vec2,
+}
+
+impl Body {
+    /// Create a body of the given size, mass, and velocity at the given position. An infinite mass can't be moved
+    pub fn new(position: &Point, radius: f32, mass: f32, velocity: Vec2) -> Body {
+        let inverse_mass = if mass.is_finite() && mass > 0.0 { 1.0 / mass } else { 0.0 };
+        return Body { position: *position, radius, inverse_mass, velocity };
+    }
+}
+
+/// How a body responds to a collision: the offset that moves it out of the other body, and the change in its
+/// velocity
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Response {
+    pub offset: Vec2,
+    pub push: Vec2,
+}
+
+/// Work out how two overlapping bodies separate and exchange momentum, each moving in proportion to the other's
+/// mass. Returns none if they don't overlap or neither can be moved
+pub fn resolve(first: &Body, second: &Body) -> Option<(Response, Response)> {
+    let total_inverse_mass = first.inverse_mass + second.inverse_mass;
+    let between = &second.position - &first.position;
+    let overlap = first.radius + second.radius - between.length();
+    if overlap <= 0.0 || total_inverse_mass <= 0.0 {
+        return None;
+    }
+
+    // Push the bodies apart along the line between their centers, or sideways if they are right on top of each
+    // other
+    let normal = if between.length() > 0.0 { between.normalize() } else { Vec2::new(1.0, 0.0) };
+    let separation = &normal * (overlap / total_inverse_mass);
+    let mut first_response = Response { offset: -(&separation * first.inverse_mass), push: Vec2::zero() };
+    let mut second_response = Response { offset: &separation * second.inverse_mass, push: Vec2::zero() };
+
+    // Bounce the bodies off each other if they are still closing in, exchanging momentum along the normal
+    let closing_speed = (&second.velocity - &first.velocity).dot(&normal);
+    if closing_speed < 0.0 {
+        let impulse = &normal * (-(1.0 + RESTITUTION) * closing_speed / total_inverse_mass);
+        first_response.push = -(&impulse * first.inverse_mass);
+        second_response.push = &impulse * second.inverse_mass;
+    }
+    return Some((first_response, second_response));
+}
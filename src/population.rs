@@ -0,0 +1,108 @@
+use std::fs;
+use std::path::Path;
+
+use std::collections::HashSet;
+
+use serde::Deserialize;
+
+use crate::{Actor, Point};
+
+/// File the population limits are loaded from
+pub const POPULATION_FILE: &str = "population.toml";
+
+/// Which actors are removed when there are more of a type than its cap allows
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum DespawnPolicy {
+    /// Remove the actors that were spawned first
+    OldestFirst,
+    /// Remove the actors that are furthest from the player
+    FurthestFromPlayer,
+}
+
+/// Largest number of actors of one type that can be in the game at once
+#[derive(Clone, Debug, Deserialize)]
+pub struct PopulationCap {
+    /// Type name of the actors this cap applies to, as given by ActorKind::get_name
+    kind: String,
+    limit: usize,
+    policy: DespawnPolicy,
+}
+
+impl PopulationCap {
+    /// Create a new cap on the actors of the given type
+    pub fn new(kind: &str, limit: usize, policy: DespawnPolicy) -> PopulationCap {
+        return PopulationCap { kind: kind.to_string(), limit, policy };
+    }
+}
+
+/// Caps on the number of actors of each type, which are enforced every tick once the new actors have been spawned
+#[derive(Clone, Debug, Deserialize)]
+pub struct PopulationLimits {
+    #[serde(default)]
+    caps: Vec<PopulationCap>,
+}
+
+impl PopulationLimits {
+    /// Create the default limits, which keep shots, reinforcements, and swarms from piling up
+    pub fn new() -> PopulationLimits {
+        return PopulationLimits {
+            caps: vec![
+                PopulationCap::new("shot", 400, DespawnPolicy::OldestFirst),
+                PopulationCap::new("drone", 12, DespawnPolicy::FurthestFromPlayer),
+                PopulationCap::new("kamikaze", 24, DespawnPolicy::FurthestFromPlayer),
+                PopulationCap::new("pickup", 20, DespawnPolicy::OldestFirst),
+            ],
+        };
+    }
+
+    /// Create limits with no caps, which let any number of actors of every type into the game
+    pub fn unlimited() -> PopulationLimits {
+        return PopulationLimits { caps: Vec::new() };
+    }
+
+    /// Load the limits from the given file, falling back to the defaults if there is no file. A file that can't
+    /// be read is reported as an issue rather than stopping the game from starting
+    pub fn load(path: &Path) -> (PopulationLimits, Option<String>) {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => return (PopulationLimits::new(), None),
+        };
+        return match toml::from_str(&text) {
+            Ok(limits) => (limits, None),
+            Err(error) => (PopulationLimits::new(), Some(format!("{}: invalid limits: {}", path.display(), error))),
+        };
+    }
+
+    /// Get the IDs of the actors over the cap of their type, choosing which ones by the cap's policy, so they can
+    /// be despawned. The actors the given check exempts neither count towards the caps nor are ever despawned
+    pub fn find_excess<F>(&self, actors: &[Box<dyn Actor>], player_position: &Point, is_exempt: F) -> HashSet<u32>
+        where F: Fn(&dyn Actor) -> bool {
+        let mut excess_ids = HashSet::new();
+        for cap in &self.caps {
+            let mut matching: Vec<(u32, f32)> = actors.iter()
+                .filter(|actor| actor.get_kind().get_name() == cap.kind && !is_exempt(&***actor))
+                .map(|actor| (actor.get_id(), actor.get_position().distance_to(player_position)))
+                .collect();
+            if matching.len() <= cap.limit {
+                continue;
+            }
+
+            // Put the actors that should go first at the front. Actor IDs are handed out in order, so the lowest
+            // IDs belong to the oldest actors
+            match cap.policy {
+                DespawnPolicy::OldestFirst => matching.sort_by_key(|&(id, _)| id),
+                DespawnPolicy::FurthestFromPlayer => matching.sort_by(|a, b| b.1.total_cmp(&a.1)),
+            }
+            let excess = matching.len() - cap.limit;
+            excess_ids.extend(matching.into_iter().take(excess).map(|(id, _)| id));
+        }
+        return excess_ids;
+    }
+}
+
+impl Default for PopulationLimits {
+    fn default() -> PopulationLimits {
+        return PopulationLimits::new();
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::BTreeMap;
+use std::f32::consts::PI;
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::{Point, World};
+
+/// Number of points tried when looking for a spawn point far enough from the player
+const SPAWN_ATTEMPTS: u32 = 8;
+
+/// A rectangle of the arena that spawn regions can refer to by name
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpawnZone {
+    x: f32, // Left edge
+    y: f32, // Top edge
+    width: f32,
+    height: f32,
+}
+
+impl SpawnZone {
+    /// Create a new zone with its top left corner at the given position and of the given size
+    pub fn new(x: f32, y: f32, width: f32, height: f32) -> SpawnZone {
+        return SpawnZone { x, y, width, height };
+    }
+
+    /// Stretch this zone by the given factors, to move it from one arena to another
+    pub(crate) fn scaled(&self, scale_x: f32, scale_y: f32) -> SpawnZone {
+        return SpawnZone::new(self.x * scale_x, self.y * scale_y, self.width * scale_x, self.height * scale_y);
+    }
+
+    /// Pick a random point in this zone
+    fn pick<R: Rng>(&self, rng: &mut R) -> Point {
+        return Point::new(between(rng, self.x, self.x + self.width), between(rng, self.y, self.y + self.height));
+    }
+}
+
+/// Where in the arena new enemies appear
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnRegion {
+    /// Anywhere in a rectangle given in fractions of the arena's width and height
+    Area { left: f32, top: f32, right: f32, bottom: f32 },
+    /// Anywhere along the edges of the arena, the given distance in from them. A negative distance spawns enemies
+    /// off-screen, to move in from outside
+    Edges { inset: f32 },
+    /// Anywhere in a ring around the center of the arena, between the given fractions of the distance from the
+    /// center to the nearest edge
+    Ring { inner: f32, outer: f32 },
+    /// Anywhere in the zone of the given name
+    Zone(String),
+}
+
+impl SpawnRegion {
+    /// Pick a random point in this region of an arena of the given size with the given named zones, or none if
+    /// the region is a zone that doesn't exist
+    pub fn pick<R: Rng>(&self, bounds: (f32, f32), zones: &BTreeMap<String, SpawnZone>, rng: &mut R)
+                        -> Option<Point> {
+        let (width, height) = bounds;
+        return match self {
+            SpawnRegion::Area { left, top, right, bottom } => {
+                let x = between(rng, left * width, right * width);
+                Some(Point::new(x, between(rng, top * height, bottom * height)))
+            }
+            SpawnRegion::Edges { inset } => {
+                // An inset of more than half the arena would start the rectangle outside it, so it is cut down to
+                // the middle of the arena
+                let inset = inset.min(width.min(height) / 2.0);
+                // Walk a random distance around the rectangle the inset away from the edges
+                let (inner_width, inner_height) = (width - 2.0 * inset, height - 2.0 * inset);
+                let mut distance = between(rng, 0.0, 2.0 * (inner_width + inner_height));
+                let sides = [(inner_width, 0.0), (inner_height, PI / 2.0), (inner_width, PI), (inner_height, PI * 1.5)];
+                let mut point = Point::new(inset, inset);
+                for (length, heading) in sides.iter() {
+                    let step = distance.min(*length);
+                    point.move_distance(step, *heading);
+                    distance -= step;
+                }
+                Some(point)
+            }
+            SpawnRegion::Ring { inner, outer } => {
+                let reach = width.min(height) / 2.0;
+                let mut point = Point::new(width / 2.0, height / 2.0);
+                let heading = between(rng, 0.0, 2.0 * PI);
+                point.move_distance(between(rng, inner * reach, outer * reach), heading);
+                Some(point)
+            }
+            SpawnRegion::Zone(name) => zones.get(name).map(|zone| zone.pick(rng)),
+        };
+    }
+
+    /// Pick a random point in this region of the given world at least the given distance from the given position.
+    /// Points in the parts of the window the world's shape cuts off are tried again, and if none of a few tries is
+    /// far enough the furthest one tried is used, moved inside the shape if no try could be spawned at
+    pub fn pick_away_from<R: Rng>(&self, world: &World, zones: &BTreeMap<String, SpawnZone>, avoid: &Point,
+                                  distance: f32, rng: &mut R) -> Option<Point> {
+        let mut furthest: Option<Point> = None;
+        let mut spawnable = false;
+        for _ in 0..SPAWN_ATTEMPTS {
+            let point = self.pick(world.get_size(), zones, rng)?;
+            let point_spawnable = is_spawnable(world, &point);
+            if point_spawnable && point.distance_to(avoid) >= distance {
+                return Some(point);
+            }
+            // A point that can be spawned at always beats one that can't
+            let is_further = match &furthest {
+                Some(furthest) => {
+                    (point_spawnable, point.distance_to(avoid)) > (spawnable, furthest.distance_to(avoid))
+                }
+                None => true,
+            };
+            if is_further {
+                furthest = Some(point);
+                spawnable = point_spawnable;
+            }
+        }
+        return furthest.map(|mut point| {
+            if !spawnable {
+                point.keep_in_bounds(world);
+            }
+            point
+        });
+    }
+}
+
+/// Check if the given point in the given world can be spawned at: anywhere in the world's shape, or off-screen past
+/// the window, where a negative inset puts enemies on purpose, but not in the parts of the window the shape cuts off
+fn is_spawnable(world: &World, point: &Point) -> bool {
+    let (width, height) = world.get_size();
+    let on_screen = point.x >= 0.0 && point.x <= width && point.y >= 0.0 && point.y <= height;
+    return !on_screen || world.contains(point);
+}
+
+/// Pick a random number between the given bounds, which may be equal or in either order
+fn between<R: Rng>(rng: &mut R, low: f32, high: f32) -> f32 {
+    return low + (high - low) * rng.gen::<f32>();
+}
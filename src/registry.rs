@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+use std::rc::Rc;
+
+use ggez::{Context, GameResult, graphics};
+
+use crate::{Actor, EnemyDefinition, Kamikaze, Pickup, PickupKind, Point, StatusEffect, Tuning, Turret, World};
+use crate::{GEM_SCORE, SHIELD_PICKUP_CHARGES};
+
+/// Constructor of an actor, given the position to create it at, the arena, and the balance of the game. Copies of
+/// a registry share their constructors
+pub type ActorFactory = Rc<dyn Fn(Point, &World, &Tuning) -> Box<dyn Actor>>;
+
+/// Table of the actors that can be spawned by name, like `turret.armored` or `pickup.shield`, for levels, waves,
+/// and embedders to refer to actors without knowing how to build them
+#[derive(Clone)]
+pub struct ActorRegistry {
+    factories: BTreeMap<String, ActorFactory>,
+}
+
+impl ActorRegistry {
+    /// Create a registry holding nothing
+    pub fn new() -> ActorRegistry {
+        return ActorRegistry { factories: BTreeMap::new() };
+    }
+
+    /// Create a registry holding the game's own actors
+    pub fn with_defaults() -> ActorRegistry {
+        let mut registry = ActorRegistry::new();
+        registry.register("turret.basic", |position, world, tuning| Box::new(Turret::new(position, world, tuning)));
+        registry.register("turret.burning", |position, world, tuning| {
+            Box::new(Turret::with_shot_effect(position, world, tuning, StatusEffect::burn(10.0, 3.0)))
+        });
+        registry.register("turret.slowing", |position, world, tuning| {
+            Box::new(Turret::with_shot_effect(position, world, tuning, StatusEffect::slow(0.5, 2.0)))
+        });
+        registry.register("turret.armored", |position, world, tuning| {
+            Box::new(Turret::armored(position, world, tuning))
+        });
+        registry.register("turret.boss", |position, world, tuning| Box::new(Turret::boss(position, world, tuning)));
+        registry.register("turret.friendly", |position, world, tuning| {
+            Box::new(Turret::friendly(position, world, tuning))
+        });
+        registry.register("turret.spawner", |position, world, tuning| {
+            Box::new(Turret::spawner(position, world, tuning))
+        });
+        registry.register("kamikaze", |position, world, tuning| {
+            // Head for the middle of the arena until the kamikaze spots the player
+            let (width, height) = world.get_size();
+            let heading = position.heading_to(&Point::new(width / 2.0, height / 2.0));
+            Box::new(Kamikaze::new(position, world, tuning, heading))
+        });
+        registry.register("pickup.shield", |position, _, tuning| {
+            Box::new(Pickup::new(position, PickupKind::Shield(SHIELD_PICKUP_CHARGES), tuning))
+        });
+        registry.register("pickup.gem", |position, _, tuning| {
+            Box::new(Pickup::new(position, PickupKind::Score(GEM_SCORE), tuning))
+        });
+        return registry;
+    }
+
+    /// Register the given constructor under the given name, replacing anything registered under it before
+    pub fn register<F: Fn(Point, &World, &Tuning) -> Box<dyn Actor> + 'static>(&mut self, name: &str, factory: F) {
+        self.factories.insert(name.to_string(), Rc::new(factory));
+    }
+
+    /// Register the kind of turret defined by a mod, named `turret.` followed by the name of the definition
+    pub fn register_definition(&mut self, definition: &EnemyDefinition) {
+        let definition = definition.clone();
+        self.register(&format!("turret.{}", definition.get_name()), move |position, world, tuning| {
+            Box::new(Turret::defined(position, world, tuning, &definition))
+        });
+    }
+
+    /// Create the actor registered under the given name at the given position, in the given arena with the given
+    /// balance, or none if nothing is registered under the name
+    pub fn create(&self, name: &str, position: Point, world: &World, tuning: &Tuning) -> Option<Box<dyn Actor>> {
+        return self.factories.get(name).map(|factory| factory(position, world, tuning));
+    }
+
+    /// Check if anything is registered under the given name
+    pub fn contains(&self, name: &str) -> bool {
+        return self.factories.contains_key(name);
+    }
+
+    /// Get the names of everything registered, in alphabetical order
+    pub fn get_names(&self) -> Vec<&str> {
+        return self.factories.keys().map(String::as_str).collect();
+    }
+
+    /// Draw the debug console for spawning actors by name, listing everything registered with the given one selected
+    pub fn draw_console(&self, ctx: &mut Context, selected: usize) -> GameResult {
+        // Darken the game behind the list
+        let (width, height) = graphics::drawable_size(ctx);
+        let background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, width, height),
+            graphics::Color::new(0.0, 0.0, 0.0, 0.8),
+        )?;
+        graphics::draw(ctx, &background, ([0.0, 0.0],))?;
+
+        let mut lines = vec![
+            String::from("Spawn (Up/Down to select, Enter to spawn ahead of the player, F7 to close)"),
+            String::new(),
+        ];
+        for (i, name) in self.factories.keys().enumerate() {
+            let cursor = if i == selected { ">" } else { " " };
+            lines.push(format!("{} {}", cursor, name));
+        }
+
+        let text = graphics::Text::new(lines.join("\n"));
+        graphics::draw(ctx, &text, ([40.0, 40.0], graphics::WHITE))?;
+
+        return Ok(());
+    }
+}
+
+impl Default for ActorRegistry {
+    fn default() -> ActorRegistry {
+        return ActorRegistry::with_defaults();
+    }
+}
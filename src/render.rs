@@ -0,0 +1,141 @@
+use ggez::{Context, GameResult, graphics};
+use ggez::graphics::Color;
+
+use crate::Actor;
+use crate::assets::AssetManager;
+
+/// Drawing of something in the game, kept apart from the Actor trait so actors can be simulated and tested without
+/// a window
+pub trait Render {
+    /// Draw this to the screen with shapes, along with its overlay
+    fn draw(&self, ctx: &mut Context) -> GameResult;
+
+    /// Get the sprite this is drawn with in place of its shapes, if it has one
+    fn get_sprite(&self) -> Option<Sprite> {
+        return None;
+    }
+
+    /// Draw what goes on top of this however it is drawn, like health bars and shields
+    fn draw_overlay(&self, _ctx: &mut Context) -> GameResult {
+        return Ok(());
+    }
+}
+
+/// Image in the resources holding the frames of a sprite side by side, all the same width. Still images are sheets
+/// of a single frame
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpriteSheet {
+    path: &'static str,
+    frames: u32,
+}
+
+impl SpriteSheet {
+    /// Create a sheet of the given number of frames from the image at the given path
+    pub const fn new(path: &'static str, frames: u32) -> SpriteSheet {
+        return SpriteSheet { path, frames };
+    }
+
+    /// Create a sheet of a single frame from the image at the given path
+    pub const fn still(path: &'static str) -> SpriteSheet {
+        return SpriteSheet::new(path, 1);
+    }
+
+    /// Get the path of the image of this sheet in the resources
+    pub fn get_path(&self) -> &'static str {
+        return self.path;
+    }
+}
+
+/// Picture something is drawn with in place of its shapes, from a sprite sheet in the resources. Images face right,
+/// the way a heading of 0 points, and are turned by the rotation of the sprite and tinted by its color, so white
+/// images take the colors the shapes would have had
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sprite {
+    images: &'static [SpriteSheet],
+    frame: u32,
+    position: [f32; 2],
+    size: f32,
+    rotation: f32,
+    color: Color,
+}
+
+impl Sprite {
+    /// Create a sprite drawn with the first frame of the first of the given sheets that has been loaded, centered on
+    /// the given position and fit into a square the given size across, turned by the given rotation, and tinted the
+    /// given color
+    pub fn new(images: &'static [SpriteSheet], position: [f32; 2], size: f32, rotation: f32, color: Color) -> Sprite {
+        return Sprite { images, frame: 0, position, size, rotation, color };
+    }
+
+    /// Draw the given frame of the sheet instead, from 0, like the frame an animation is on. Sheets with fewer
+    /// frames show their last one
+    pub fn with_frame(mut self, frame: u32) -> Sprite {
+        self.frame = frame;
+        return self;
+    }
+
+    /// Get the sheets this sprite can be drawn with, in the order they are preferred
+    pub fn get_images(&self) -> &[SpriteSheet] {
+        return self.images;
+    }
+
+    /// Get the frame of the sheet this sprite is drawn with
+    pub fn get_frame(&self) -> u32 {
+        return self.frame;
+    }
+
+    /// Get the rotation this sprite is drawn at
+    pub fn get_rotation(&self) -> f32 {
+        return self.rotation;
+    }
+
+    /// Draw this sprite with the first of its sheets the given assets have loaded. Returns false without drawing
+    /// anything if none of them have been
+    pub fn draw(&self, ctx: &mut Context, assets: &AssetManager) -> GameResult<bool> {
+        let (sheet, image) = match self.images.iter().find_map(|sheet| Some((sheet, assets.get_image(sheet.path)?))) {
+            Some(found) => found,
+            None => return Ok(false),
+        };
+        let frames = sheet.frames.max(1);
+        let frame = self.frame.min(frames - 1);
+        let frame_width = image.width() as f32 / frames as f32;
+        let scale = self.size / frame_width.max(image.height() as f32).max(1.0);
+        let param = graphics::DrawParam::default()
+            .src(graphics::Rect::new(frame as f32 / frames as f32, 0.0, 1.0 / frames as f32, 1.0))
+            .dest(self.position)
+            .offset([0.5, 0.5])
+            .rotation(self.rotation)
+            .scale([scale, scale])
+            .color(self.color);
+        graphics::draw(ctx, &*image, param)?;
+        return Ok(true);
+    }
+}
+
+/// Function that draws actors in place of their own drawing, for alternate renderers. It is given every actor the
+/// player can see, whether or not the actor draws itself
+pub type ActorRenderer = Box<dyn Fn(&mut Context, &dyn Actor) -> GameResult>;
+
+/// Draw the given drawing with its sprite and overlay if it has a sprite whose image has been loaded, or with its
+/// shapes otherwise
+pub fn draw(ctx: &mut Context, render: &dyn Render, assets: &AssetManager) -> GameResult {
+    if let Some(sprite) = render.get_sprite() {
+        if sprite.draw(ctx, assets)? {
+            return render.draw_overlay(ctx);
+        }
+    }
+    return render.draw(ctx);
+}
+
+/// Draw the given actor with the given renderer, or with its own drawing if there is no renderer. Actors that don't
+/// draw themselves are left out
+pub fn draw_actor(ctx: &mut Context, actor: &dyn Actor, renderer: Option<&ActorRenderer>,
+                  assets: &AssetManager) -> GameResult {
+    if let Some(renderer) = renderer {
+        return renderer(ctx, actor);
+    }
+    return match actor.as_render() {
+        Some(render) => draw(ctx, render, assets),
+        None => Ok(()),
+    };
+}
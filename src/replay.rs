@@ -0,0 +1,121 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{GameMode, MainState, ShipClass};
+use crate::observation::Action;
+use crate::snapshot::StateSnapshot;
+
+/// Action an agent or player took on a tick of a replay. Thrust and turning are held until the next input, while
+/// firing and the grapple only happen on the tick of the input
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReplayInput {
+    pub tick: u64,
+    #[serde(default)]
+    pub thrust: i8,
+    #[serde(default)]
+    pub turn: i8,
+    #[serde(default)]
+    pub fire: bool,
+    #[serde(default)]
+    pub grapple: bool,
+}
+
+/// How a replay played out, which is compared against the result recorded with it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplayResult {
+    pub tick: u64,
+    pub score: u32,
+    pub wave: u32,
+    /// Hash of the serialized state of the game at the end of the replay, in hexadecimal
+    pub state_hash: String,
+}
+
+/// Recording of a headless run: the settings it was started with and the inputs applied on each tick, along with
+/// the result it is expected to have
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub mode: GameMode,
+    pub class: ShipClass,
+    pub bounds: (f32, f32),
+    /// Number of ticks to play, unless the run ends sooner
+    pub ticks: u64,
+    /// Number of ticks simulated per second, which the run is played at. Replays recorded before the tick rate could
+    /// be changed were played at the default rate
+    #[serde(default = "crate::get_default_tick_rate")]
+    pub tick_rate: u32,
+    #[serde(default)]
+    pub inputs: Vec<ReplayInput>,
+    pub expected: Option<ReplayResult>,
+}
+
+impl Replay {
+    /// Load a replay from the given file
+    pub fn load(path: &Path) -> Result<Replay, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        return toml::from_str(&text).map_err(|error| format!("{}: invalid replay: {}", path.display(), error));
+    }
+
+    /// Save this replay to the given file
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = toml::to_string(self).map_err(|error| error.to_string())?;
+        return fs::write(path, text).map_err(|error| format!("{}: {}", path.display(), error));
+    }
+
+    /// Play this replay in a headless game and get how it played out. The first perk offered after a wave is
+    /// always taken
+    pub fn play(&self) -> ReplayResult {
+        let mut state = MainState::headless(self.bounds, self.seed);
+        state.set_tick_rate(self.tick_rate);
+        state.start(self.mode, self.class);
+
+        let mut inputs = self.inputs.iter().peekable();
+        for tick in 0..self.ticks {
+            while let Some(input) = inputs.peek() {
+                if input.tick > tick {
+                    break;
+                }
+                let input = inputs.next().unwrap();
+                state.apply_action(Action {
+                    thrust: input.thrust,
+                    turn: input.turn,
+                    fire: input.fire,
+                    grapple: input.grapple,
+                });
+            }
+            if state.is_choosing_perk() {
+                state.choose_perk(0);
+            }
+            if !state.simulate_tick() || state.get_outcome().is_some() {
+                break;
+            }
+        }
+
+        let observation = state.observe();
+        let snapshot = StateSnapshot::capture(&state).to_text().unwrap_or_default();
+        return ReplayResult {
+            tick: observation.tick,
+            score: observation.score,
+            wave: observation.wave,
+            state_hash: format!("{:016x}", hash(snapshot.as_bytes())),
+        };
+    }
+
+    /// Play this replay and record how it played out as its expected result
+    pub fn record(&mut self) {
+        self.expected = Some(self.play());
+    }
+}
+
+/// Hash the given bytes with FNV-1a, which unlike the standard library's hasher is the same on every version and
+/// platform
+fn hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    return hash;
+}
@@ -0,0 +1,139 @@
+use std::collections::VecDeque;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::PathBuf;
+
+use ggez::{Context, graphics};
+use image::ColorType;
+use image::png::PNGEncoder;
+use zip::{CompressionMethod, ZipWriter};
+use zip::write::FileOptions;
+
+/// Directory bug reports are saved in, next to the game
+pub const REPORTS_DIRECTORY: &str = "reports";
+/// Number of seconds of history kept for bug reports
+const HISTORY_TIME: f32 = 10.0;
+/// Largest number of log messages kept for bug reports
+const MAX_LOG_MESSAGES: usize = 200;
+
+/// The most recent messages about what happened in the game, kept to attach to bug reports
+pub struct RecentLog {
+    messages: VecDeque<(f32, String)>, // (time, message)
+}
+
+impl RecentLog {
+    /// Create an empty log
+    pub fn new() -> RecentLog {
+        return RecentLog { messages: VecDeque::new() };
+    }
+
+    /// Add a message that happened at the given time, forgetting the oldest message if the log is full
+    pub fn add(&mut self, time: f32, message: String) {
+        if self.messages.len() == MAX_LOG_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back((time, message));
+    }
+
+    /// Write the log as text, one message per line
+    fn to_text(&self) -> String {
+        return self.messages.iter()
+            .map(|(time, message)| format!("[{:>8.2}] {}\n", time, message))
+            .collect();
+    }
+}
+
+impl Default for RecentLog {
+    fn default() -> RecentLog {
+        return RecentLog::new();
+    }
+}
+
+/// The keys pressed and released over the last few seconds, which can be replayed from a snapshot of the game
+pub struct InputHistory {
+    inputs: VecDeque<(u64, f32, String)>, // (tick, time, input)
+}
+
+impl InputHistory {
+    /// Create an empty history
+    pub fn new() -> InputHistory {
+        return InputHistory { inputs: VecDeque::new() };
+    }
+
+    /// Record an input made during the given tick at the given time, forgetting inputs that are too old
+    pub fn record(&mut self, tick: u64, time: f32, input: String) {
+        self.inputs.push_back((tick, time, input));
+        while let Some(&(_, oldest, _)) = self.inputs.front() {
+            if oldest >= time - HISTORY_TIME {
+                break;
+            }
+            self.inputs.pop_front();
+        }
+    }
+
+    /// Write the history as text, one input per line
+    fn to_text(&self) -> String {
+        return self.inputs.iter()
+            .map(|(tick, time, input)| format!("{}\t{:.3}\t{}\n", tick, time, input))
+            .collect();
+    }
+}
+
+impl Default for InputHistory {
+    fn default() -> InputHistory {
+        return InputHistory::new();
+    }
+}
+
+/// Everything that goes into a bug report, apart from the screenshot which is taken when it is saved
+pub struct BugReport<'a> {
+    pub snapshot: String,
+    pub config: String,
+    pub log: &'a RecentLog,
+    pub inputs: &'a InputHistory,
+}
+
+impl<'a> BugReport<'a> {
+    /// Take a screenshot and bundle it with the rest of the report into a zip in the reports directory,
+    /// returning the path of the zip
+    pub fn save(&self, ctx: &mut Context, timestamp: u64) -> Result<PathBuf, String> {
+        let screenshot = take_screenshot(ctx)?;
+
+        fs::create_dir_all(REPORTS_DIRECTORY).map_err(|error| error.to_string())?;
+        let path = PathBuf::from(REPORTS_DIRECTORY).join(format!("report-{}.zip", timestamp));
+        let file = File::create(&path).map_err(|error| error.to_string())?;
+
+        let log = self.log.to_text();
+        let inputs = self.inputs.to_text();
+        let files: [(&str, &[u8]); 5] = [
+            ("snapshot.txt", self.snapshot.as_bytes()),
+            ("config.txt", self.config.as_bytes()),
+            ("log.txt", log.as_bytes()),
+            ("inputs.txt", inputs.as_bytes()),
+            ("screenshot.png", &screenshot),
+        ];
+        // The files are small or already compressed, so they are stored as they are
+        let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+        let mut zip = ZipWriter::new(file);
+        for (name, contents) in files.iter() {
+            zip.start_file(*name, options).map_err(|error| error.to_string())?;
+            zip.write_all(contents).map_err(|error| error.to_string())?;
+        }
+        zip.finish().map_err(|error| error.to_string())?;
+
+        return Ok(path);
+    }
+}
+
+/// Take a screenshot of the window and encode it as a PNG
+fn take_screenshot(ctx: &mut Context) -> Result<Vec<u8>, String> {
+    let image = graphics::screenshot(ctx).map_err(|error| error.to_string())?;
+    let pixels = image.to_rgba8(ctx).map_err(|error| error.to_string())?;
+
+    let mut png = Vec::new();
+    PNGEncoder::new(&mut png)
+        .encode(&pixels, image.width() as u32, image.height() as u32, ColorType::RGBA(8))
+        .map_err(|error| error.to_string())?;
+
+    return Ok(png);
+}
@@ -0,0 +1,90 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use ggez::{Context, GameResult, graphics};
+
+use crate::modes::{GameMode, RunOutcome};
+
+/// Size of the result card image
+const CARD_WIDTH: u16 = 480;
+const CARD_HEIGHT: u16 = 270;
+
+/// Colors of the result card
+const BACKGROUND_COLOR: graphics::Color = graphics::Color::new(0.08, 0.09, 0.12, 1.0);
+const TITLE_COLOR: graphics::Color = graphics::Color::new(1.0, 0.85, 0.3, 1.0);
+
+/// Everything shown on the result card of a finished run
+pub struct RunSummary {
+    pub mode: GameMode,
+    pub outcome: RunOutcome,
+    pub seed: u64,
+    pub score: u32,
+    pub wave: u32,
+    pub accuracy: f32, // 0 to 1
+    pub mutators: Vec<String>,
+    pub assists: Vec<String>,
+    pub timestamp: u64, // Seconds since the Unix epoch
+}
+
+impl RunSummary {
+    /// Get the current time in seconds since the Unix epoch, to stamp a summary with
+    pub fn now() -> u64 {
+        return SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+    }
+
+    /// Get the lines of text shown on the card
+    fn get_lines(&self) -> Vec<String> {
+        let mutators = if self.mutators.is_empty() { String::from("none") } else { self.mutators.join(", ") };
+        let assists = if self.assists.is_empty() { String::from("none") } else { self.assists.join(", ") };
+        return vec![
+            format!("Mode: {}", self.mode.get_name()),
+            format!("Score: {}", self.score),
+            format!("Wave: {}", self.wave),
+            format!("Accuracy: {:.0}%", self.accuracy * 100.0),
+            format!("Mutators: {}", mutators),
+            format!("Assists: {}", assists),
+            format!("Seed: {}", self.seed),
+            format_timestamp(self.timestamp),
+        ];
+    }
+
+    /// Render the result card offscreen and save it as a PNG in the user data directory, returning the path
+    /// it was saved to
+    pub fn save_card(&self, ctx: &mut Context) -> GameResult<String> {
+        let canvas = graphics::Canvas::new(ctx, CARD_WIDTH, CARD_HEIGHT, ggez::conf::NumSamples::One)?;
+        graphics::set_canvas(ctx, Some(&canvas));
+        graphics::clear(ctx, BACKGROUND_COLOR);
+
+        // Flag runs that used assists in the title, so they aren't mistaken for unassisted runs
+        let flag = if self.assists.is_empty() { "" } else { " [ASSISTED]" };
+        let title = graphics::Text::new(format!("TURRETS - {}{}", self.mode.get_outcome_message(self.outcome), flag));
+        graphics::draw(ctx, &title, ([20.0, 20.0], TITLE_COLOR))?;
+        let body = graphics::Text::new(self.get_lines().join("\n"));
+        graphics::draw(ctx, &body, ([20.0, 60.0], graphics::WHITE))?;
+
+        // Go back to drawing to the window before saving the card
+        graphics::set_canvas(ctx, None);
+        let path = format!("/result-{}.png", self.timestamp);
+        canvas.image().encode(ctx, graphics::ImageFormat::Png, &path)?;
+
+        return Ok(path);
+    }
+}
+
+/// Format a Unix timestamp as a UTC date and time
+fn format_timestamp(timestamp: u64) -> String {
+    let days = (timestamp / 86400) as i64;
+    let seconds = timestamp % 86400;
+
+    // Convert the number of days since the epoch to a civil date
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    return format!("{:04}-{:02}-{:02} {:02}:{:02} UTC", year, month, day, seconds / 3600, seconds % 3600 / 60);
+}
@@ -0,0 +1,72 @@
+use std::collections::VecDeque;
+
+use crate::MainState;
+use crate::checkpoint::Checkpoint;
+
+/// Number of seconds of history kept to step back through
+pub const REWIND_SECONDS: u32 = 5;
+
+/// Ring buffer of snapshots of the last few seconds of a run, which the game can be stepped backward and forward
+/// through while it is paused to see exactly what happened. Stepping only changes what is shown: the game goes back
+/// to the live tick before it carries on
+pub struct RewindBuffer {
+    /// Snapshot taken after each tick, with the number of the tick, from oldest to newest
+    snapshots: VecDeque<(u64, Checkpoint)>,
+    /// Index of the snapshot being shown, or none while the game is live
+    position: Option<usize>,
+    /// The live game and its tick, kept while a snapshot is shown to go back to
+    live: Option<(u64, Checkpoint)>,
+    /// Number of snapshots kept
+    capacity: usize,
+}
+
+impl RewindBuffer {
+    /// Create a new buffer with no history, for a game that runs at the given number of ticks per second
+    pub fn new(tick_rate: u32) -> RewindBuffer {
+        let capacity = (REWIND_SECONDS * tick_rate.max(1)) as usize;
+        return RewindBuffer { snapshots: VecDeque::with_capacity(capacity), position: None, live: None, capacity };
+    }
+
+    /// Take a snapshot of the game after the given tick, forgetting the oldest one once the buffer is full
+    pub fn record(&mut self, tick: u64, state: &MainState) {
+        if self.snapshots.len() == self.capacity {
+            self.snapshots.pop_front();
+        }
+        self.snapshots.push_back((tick, Checkpoint::capture(state)));
+    }
+
+    /// Step one tick backward through the history, or forward if asked to, and return the number of the tick
+    /// stepped to and its snapshot. The given game is kept as the live one when it is first stepped away from.
+    /// Returns none at either end of the history
+    pub fn step(&mut self, forward: bool, state: &MainState) -> Option<(u64, &Checkpoint)> {
+        let newest = self.snapshots.len().checked_sub(1)?;
+        let current = self.position.unwrap_or(newest);
+        let next = match forward {
+            true if current < newest => current + 1,
+            false if current > 0 => current - 1,
+            _ => return None,
+        };
+        if self.live.is_none() {
+            self.live = Some((state.tick, Checkpoint::capture(state)));
+        }
+        self.position = Some(next);
+        return self.snapshots.get(next).map(|(tick, snapshot)| (*tick, snapshot));
+    }
+
+    /// Stop showing the history, and return the live game and its tick to go back to, if a snapshot was shown
+    pub fn take_live(&mut self) -> Option<(u64, Checkpoint)> {
+        self.position = None;
+        return self.live.take();
+    }
+
+    /// Get the number of ticks the game has been stepped back from the newest snapshot, or none while it is live
+    pub fn get_ticks_back(&self) -> Option<usize> {
+        return self.position.map(|position| self.snapshots.len() - 1 - position);
+    }
+}
+
+impl Default for RewindBuffer {
+    fn default() -> RewindBuffer {
+        return RewindBuffer::new(crate::DEFAULT_TICK_RATE);
+    }
+}
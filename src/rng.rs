@@ -0,0 +1,114 @@
+use rand::{Rng, RngCore, SeedableRng};
+use rand::rngs::StdRng;
+use rand_chacha::ChaCha20Rng;
+use serde::{Deserialize, Serialize};
+
+/// The random number streams of a run. The gameplay stream is seeded so that a run can be reproduced from its
+/// seed, while the cosmetic stream is free to be used by sounds and visual effects as often as they like
+/// without changing how the run plays out. Only the gameplay stream is saved, and a loaded run starts a new
+/// cosmetic stream
+#[derive(Clone, Serialize, Deserialize)]
+pub struct RandomStreams {
+    gameplay: Stream,
+    #[serde(skip, default = "StdRng::from_entropy")]
+    cosmetic: StdRng,
+}
+
+impl RandomStreams {
+    /// Create the streams for a run with the given gameplay seed
+    pub fn new(seed: u64) -> RandomStreams {
+        return RandomStreams {
+            gameplay: Stream::new(seed),
+            cosmetic: StdRng::from_entropy(),
+        };
+    }
+
+    /// Create the streams for a run with a random gameplay seed
+    pub fn from_entropy() -> RandomStreams {
+        return RandomStreams::new(rand::thread_rng().gen());
+    }
+
+    /// Get the seed of the gameplay stream, which is all that needs recording to reproduce a run
+    pub fn get_seed(&self) -> u64 {
+        return self.gameplay.get_seed();
+    }
+
+    /// Get the stream used for everything that affects the simulation, like spawns, events, and perks
+    pub fn gameplay(&mut self) -> &mut Stream {
+        return &mut self.gameplay;
+    }
+
+    /// Get the stream used for sounds and visual effects, which must never affect the simulation
+    pub fn cosmetic(&mut self) -> &mut StdRng {
+        return &mut self.cosmetic;
+    }
+}
+
+/// Seeded stream of random numbers that can be saved, and carries on from where it was when it is loaded. It draws
+/// the same numbers as a standard generator with the same seed
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(from = "SavedStream", into = "SavedStream")]
+pub struct Stream {
+    seed: u64,
+    position: u64, // Number of 32-bit words drawn from the stream so far
+    rng: ChaCha20Rng,
+}
+
+/// Saved state of a stream. The numbers are stored as signed integers, which is all TOML can hold, and the seed is
+/// turned back into the same unsigned seed when it is loaded
+#[derive(Serialize, Deserialize)]
+struct SavedStream {
+    seed: i64,
+    position: i64,
+}
+
+impl Stream {
+    /// Create a new stream from the given seed
+    pub fn new(seed: u64) -> Stream {
+        return Stream { seed, position: 0, rng: ChaCha20Rng::seed_from_u64(seed) };
+    }
+
+    /// Get the seed this stream was started from
+    pub fn get_seed(&self) -> u64 {
+        return self.seed;
+    }
+}
+
+impl RngCore for Stream {
+    fn next_u32(&mut self) -> u32 {
+        self.position += 1;
+        return self.rng.next_u32();
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.position += 2;
+        return self.rng.next_u64();
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.position += dest.len().div_ceil(4) as u64;
+        self.rng.fill_bytes(dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.fill_bytes(dest);
+        return Ok(());
+    }
+}
+
+impl From<SavedStream> for Stream {
+    fn from(saved: SavedStream) -> Stream {
+        let mut stream = Stream::new(saved.seed as u64);
+        stream.position = saved.position.max(0) as u64;
+        if stream.position > 0 {
+            stream.rng.set_word_pos(stream.position as u128);
+        }
+        return stream;
+    }
+}
+
+impl From<Stream> for SavedStream {
+    fn from(stream: Stream) -> SavedStream {
+        return SavedStream { seed: stream.seed as i64, position: stream.position as i64 };
+    }
+}
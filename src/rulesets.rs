@@ -0,0 +1,374 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::CHECKPOINT_RESTARTS;
+use crate::levels::{Level, STANDARD_LEVEL};
+use crate::modifiers::{Modifier, ModifierSet};
+use crate::ships::ShipWeapon;
+use crate::versions::{self, FileFormat};
+
+/// Directory that custom game presets are saved in
+pub const PRESETS_DIRECTORY: &str = "presets";
+/// Format of the preset files
+const PRESET_FORMAT: FileFormat = FileFormat::new("preset", &[versions::add_version]);
+/// Amount the enemy counts change by with each step on the custom game screen
+const COUNT_STEP: f32 = 0.25;
+/// Largest multiplier of the enemy counts
+const MAX_COUNT: f32 = 3.0;
+/// Most restarts a custom game can give the player
+const MAX_LIVES: u32 = 9;
+
+/// Layouts of the arena
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MapLayout {
+    /// Teleporters in two corners, with a boost strip and slow fields on the floor
+    Standard,
+    /// An empty arena
+    Open,
+}
+
+impl MapLayout {
+    /// Get the name of this layout
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            MapLayout::Standard => "Standard",
+            MapLayout::Open => "Open",
+        };
+    }
+}
+
+/// The rules listed at the top of the custom game screen, above the mutators
+#[derive(Clone, Copy, PartialEq)]
+pub enum RuleItem {
+    Map,
+    Level,
+    Turrets,
+    Kamikazes,
+    Bosses,
+    FriendlyFire,
+    CollisionPhysics,
+    StartingWeapon,
+    Lives,
+}
+
+impl RuleItem {
+    /// All the items, in the order they are listed
+    pub const ALL: [RuleItem; 9] = [
+        RuleItem::Map,
+        RuleItem::Level,
+        RuleItem::Turrets,
+        RuleItem::Kamikazes,
+        RuleItem::Bosses,
+        RuleItem::FriendlyFire,
+        RuleItem::CollisionPhysics,
+        RuleItem::StartingWeapon,
+        RuleItem::Lives,
+    ];
+
+    /// Get the label of this item
+    pub fn get_label(&self) -> &'static str {
+        return match self {
+            RuleItem::Map => "Map",
+            RuleItem::Level => "Level",
+            RuleItem::Turrets => "Turrets in formations",
+            RuleItem::Kamikazes => "Kamikazes in swarms",
+            RuleItem::Bosses => "Bosses",
+            RuleItem::FriendlyFire => "Friendly fire",
+            RuleItem::CollisionPhysics => "Collision physics",
+            RuleItem::StartingWeapon => "Starting weapon",
+            RuleItem::Lives => "Restarts",
+        };
+    }
+}
+
+/// The actions listed at the bottom of the custom game screen, under the mutators
+#[derive(Clone, Copy, PartialEq)]
+pub enum CustomGameAction {
+    SavePreset,
+    LoadPreset,
+    Reset,
+    Start,
+}
+
+impl CustomGameAction {
+    /// All the actions, in the order they are listed
+    pub const ALL: [CustomGameAction; 4] = [
+        CustomGameAction::SavePreset,
+        CustomGameAction::LoadPreset,
+        CustomGameAction::Reset,
+        CustomGameAction::Start,
+    ];
+
+    /// Get the label of this action
+    pub fn get_label(&self) -> &'static str {
+        return match self {
+            CustomGameAction::SavePreset => "Save as a preset",
+            CustomGameAction::LoadPreset => "Load preset",
+            CustomGameAction::Reset => "Reset to the standard rules",
+            CustomGameAction::Start => "Choose a ship and start",
+        };
+    }
+}
+
+/// The rules of the runs the player starts, which the custom game screen changes from the standard ones
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Ruleset {
+    map: MapLayout,
+    /// The name of the level the waves are laid out by, or none for the standard level
+    level: Option<String>,
+    /// Multiplier of the number of turrets in the formations of each wave
+    turrets: f32,
+    /// Multiplier of the number of kamikaze drones in the swarms of each wave
+    kamikazes: f32,
+    bosses: bool,
+    /// Whether the player's shots hurt the player's allies
+    friendly_fire: bool,
+    /// Whether actors that run into each other bounce apart instead of overlapping
+    collision_physics: bool,
+    /// The weapon the player starts with, or none for the weapon of their ship
+    starting_weapon: Option<ShipWeapon>,
+    /// The number of times the player can restart from a checkpoint
+    lives: u32,
+}
+
+impl Ruleset {
+    /// Create the standard rules
+    pub fn new() -> Ruleset {
+        return Ruleset {
+            map: MapLayout::Standard,
+            level: None,
+            turrets: 1.0,
+            kamikazes: 1.0,
+            bosses: true,
+            friendly_fire: false,
+            collision_physics: false,
+            starting_weapon: None,
+            lives: CHECKPOINT_RESTARTS,
+        };
+    }
+
+    /// Get these rules with the numbers brought into the ranges the custom game screen allows, for rules read from
+    /// files
+    pub fn clamped(mut self) -> Ruleset {
+        let clamp_count = |count: f32| if count.is_nan() { 1.0 } else { count.clamp(0.0, MAX_COUNT) };
+        self.turrets = clamp_count(self.turrets);
+        self.kamikazes = clamp_count(self.kamikazes);
+        self.lives = self.lives.min(MAX_LIVES);
+        return self;
+    }
+
+    /// Check if these are the standard rules on whichever level is chosen, which medals are only awarded for
+    pub fn is_standard_on_level(&self) -> bool {
+        return *self == Ruleset { level: self.level.clone(), ..Ruleset::new() };
+    }
+
+    /// Get the layout of the arena
+    pub fn get_map(&self) -> MapLayout {
+        return self.map;
+    }
+
+    /// Get the name of the level the waves are laid out by, if it isn't the standard level
+    pub fn get_level(&self) -> Option<&str> {
+        return self.level.as_deref();
+    }
+
+    /// Set the name of the level the waves are laid out by, or none for the standard level
+    pub fn set_level(&mut self, level: Option<String>) {
+        self.level = level;
+    }
+
+    /// Scale the given number of turrets in a formation by these rules
+    pub fn scale_turrets(&self, count: usize) -> usize {
+        return (count as f32 * self.turrets).round() as usize;
+    }
+
+    /// Scale the given number of kamikaze drones in a swarm by these rules
+    pub fn scale_kamikazes(&self, count: u32) -> u32 {
+        return (count as f32 * self.kamikazes).round() as u32;
+    }
+
+    /// Check if bosses join the waves
+    pub fn has_bosses(&self) -> bool {
+        return self.bosses;
+    }
+
+    /// Check if the player's shots hurt the player's allies
+    pub fn has_friendly_fire(&self) -> bool {
+        return self.friendly_fire;
+    }
+
+    /// Check if actors that run into each other bounce apart, exchanging momentum by their masses
+    pub fn has_collision_physics(&self) -> bool {
+        return self.collision_physics;
+    }
+
+    /// Set whether actors that run into each other bounce apart
+    pub fn set_collision_physics(&mut self, on: bool) {
+        self.collision_physics = on;
+    }
+
+    /// Get the weapon the player starts with, if it replaces the weapon of their ship
+    pub fn get_starting_weapon(&self) -> Option<ShipWeapon> {
+        return self.starting_weapon;
+    }
+
+    /// Get the number of times the player can restart from a checkpoint
+    pub fn get_lives(&self) -> u32 {
+        return self.lives;
+    }
+
+    /// Describe the value of the given item to the player
+    pub fn describe(&self, item: RuleItem) -> String {
+        let switch = |on: bool| String::from(if on { "on" } else { "off" });
+        return match item {
+            RuleItem::Map => String::from(self.map.get_name()),
+            RuleItem::Level => String::from(self.level.as_deref().unwrap_or(STANDARD_LEVEL)),
+            RuleItem::Turrets => format!("{:.0}%", self.turrets * 100.0),
+            RuleItem::Kamikazes => format!("{:.0}%", self.kamikazes * 100.0),
+            RuleItem::Bosses => switch(self.bosses),
+            RuleItem::FriendlyFire => switch(self.friendly_fire),
+            RuleItem::CollisionPhysics => switch(self.collision_physics),
+            RuleItem::StartingWeapon => match self.starting_weapon {
+                Some(weapon) => String::from(weapon.get_name()),
+                None => String::from("Ship's own"),
+            },
+            RuleItem::Lives => self.lives.to_string(),
+        };
+    }
+
+    /// Step the value of the given item up or down, choosing between the standard level and the given levels.
+    /// Choices wrap around, while numbers stop at their ends
+    pub fn adjust(&mut self, item: RuleItem, up: bool, levels: &[Level]) {
+        let step = if up { COUNT_STEP } else { -COUNT_STEP };
+        match item {
+            RuleItem::Map => {
+                self.map = match self.map {
+                    MapLayout::Standard => MapLayout::Open,
+                    MapLayout::Open => MapLayout::Standard,
+                };
+            }
+            RuleItem::Level => {
+                // Cycle through the standard level followed by every loaded level
+                let mut choices = vec![None];
+                choices.extend(levels.iter().map(|level| Some(level.get_name().to_string())));
+                let index = choices.iter().position(|choice| *choice == self.level).unwrap_or(0);
+                let step = if up { 1 } else { choices.len() - 1 };
+                self.level = choices.swap_remove((index + step) % choices.len());
+            }
+            RuleItem::Turrets => self.turrets = (self.turrets + step).clamp(0.0, MAX_COUNT),
+            RuleItem::Kamikazes => self.kamikazes = (self.kamikazes + step).clamp(0.0, MAX_COUNT),
+            RuleItem::Bosses => self.bosses = !self.bosses,
+            RuleItem::FriendlyFire => self.friendly_fire = !self.friendly_fire,
+            RuleItem::CollisionPhysics => self.collision_physics = !self.collision_physics,
+            RuleItem::StartingWeapon => {
+                // Cycle through the ship's own weapon followed by every weapon
+                let choices = [None, Some(ShipWeapon::Cannon), Some(ShipWeapon::Needler), Some(ShipWeapon::Scatter)];
+                let index = choices.iter().position(|&choice| choice == self.starting_weapon).unwrap_or(0);
+                let step = if up { 1 } else { choices.len() - 1 };
+                self.starting_weapon = choices[(index + step) % choices.len()];
+            }
+            RuleItem::Lives => {
+                self.lives = if up { (self.lives + 1).min(MAX_LIVES) } else { self.lives.saturating_sub(1) };
+            }
+        }
+    }
+}
+
+impl Default for Ruleset {
+    fn default() -> Ruleset {
+        return Ruleset::new();
+    }
+}
+
+/// A named ruleset and set of mutators saved to a file, which can be shared by copying the file. The assists and
+/// challenges the player has turned on in the settings aren't part of it
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Preset {
+    name: String,
+    #[serde(default)]
+    ruleset: Ruleset,
+    #[serde(default)]
+    mutators: Vec<Modifier>,
+}
+
+impl Preset {
+    /// Create a preset with the given name from the given rules and the mutators among the given modifiers
+    pub fn new(name: &str, ruleset: Ruleset, modifiers: &ModifierSet) -> Preset {
+        return Preset { name: name.to_string(), ruleset, mutators: modifiers.get_mutators() };
+    }
+
+    /// Load every preset in the given directory, sorted by name, with their numbers brought into the ranges the
+    /// custom game screen allows. Names are compared ignoring case, as the files are named, and only the first
+    /// preset of a name is kept. Also returns a description of every file that couldn't be read
+    pub fn load_all(directory: &Path) -> (Vec<Preset>, Vec<String>) {
+        let mut presets: Vec<Preset> = Vec::new();
+        let mut issues = Vec::new();
+        let entries = match fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(_) => return (presets, issues),
+        };
+        let mut paths: Vec<PathBuf> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+        paths.sort();
+        for path in paths {
+            if !matches!(path.extension(), Some(extension) if extension == "toml") {
+                continue;
+            }
+            let loaded = fs::read_to_string(&path)
+                .map_err(|error| error.to_string())
+                .and_then(|text| PRESET_FORMAT.read::<Preset>(&text));
+            match loaded {
+                Ok(preset) if presets.iter().any(|other| other.has_name(&preset.name)) => {
+                    issues.push(format!("{}: another preset is already named '{}'", path.display(), preset.name));
+                }
+                Ok(preset) => presets.push(preset.clamped()),
+                Err(error) => issues.push(format!("{}: invalid preset: {}", path.display(), error)),
+            }
+        }
+        presets.sort_by(|a, b| a.name.cmp(&b.name));
+        return (presets, issues);
+    }
+
+    /// Save this preset to a file named after it in the given directory, replacing any preset of the same name.
+    /// Returns the path of the file
+    pub fn save(&self, directory: &Path) -> Result<PathBuf, String> {
+        fs::create_dir_all(directory).map_err(|error| format!("{}: {}", directory.display(), error))?;
+        let path = directory.join(format!("{}.toml", self.name.to_lowercase()));
+        let text = PRESET_FORMAT.write(self)?;
+        fs::write(&path, text).map_err(|error| format!("{}: {}", path.display(), error))?;
+        return Ok(path);
+    }
+
+    /// Get this preset with its numbers brought into the ranges the custom game screen allows, keeping only the
+    /// modifiers that are mutators
+    fn clamped(self) -> Preset {
+        return Preset {
+            name: self.name,
+            ruleset: self.ruleset.clamped(),
+            mutators: self.mutators.iter().filter(|modifier| modifier.is_mutator()).map(Modifier::clamped).collect(),
+        };
+    }
+
+    /// Get the name of this preset
+    pub fn get_name(&self) -> &str {
+        return &self.name;
+    }
+
+    /// Check if this preset has the given name, ignoring case as the names of the files do
+    pub fn has_name(&self, name: &str) -> bool {
+        return self.name.eq_ignore_ascii_case(name);
+    }
+
+    /// Get the rules of this preset
+    pub fn get_ruleset(&self) -> Ruleset {
+        return self.ruleset.clone();
+    }
+
+    /// Get the mutators of this preset
+    pub fn get_mutators(&self) -> &[Modifier] {
+        return &self.mutators;
+    }
+}
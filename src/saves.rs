@@ -0,0 +1,488 @@
+use std::fs;
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+use toml::value::Table;
+
+use crate::{Actor, Base, Bomb, Director, Drone, GameMode, Health, Kamikaze, MainState, Pickup, Player, ShipClass, Shot,
+            Turret, Tuning, PICKUP_LIFESPAN};
+use crate::boss::BossBar;
+use crate::capture::CaptureZone;
+use crate::contacts::SavedContact;
+use crate::events::GlobalEvents;
+use crate::formations::DriftingAnchor;
+use crate::gauntlet::Gauntlet;
+use crate::lod::UpdateScheduler;
+use crate::modifiers::ModifierSet;
+use crate::perks::Perk;
+use crate::rng::RandomStreams;
+use crate::rulesets::Ruleset;
+use crate::spawns::SpawnSchedule;
+use crate::stats::RunStats;
+use crate::telemetry::Telemetry;
+use crate::timers::{Cooldown, Timer};
+use crate::timescale::TimeScale;
+use crate::versions::FileFormat;
+use crate::weapons::{Gun, Volley, Weapon};
+use crate::world::World;
+
+/// File the quicksave hotkeys save to and load from
+pub const QUICKSAVE_FILE: &str = "saves/quicksave.toml";
+/// Format of saved games. Version 1 keeps the time since a scripted turret last fired as a stopwatch timer,
+/// version 2 keeps the arena every actor is in as a world instead of a size, version 3 keeps the values from the
+/// tuning every actor was created with, version 4 keeps the health of shots like that of other actors, version 5
+/// keeps the time a pickup has left as a timer, and version 6 keeps the values from the tuning the player and the
+/// turrets fire with
+const SAVE_FORMAT: FileFormat = FileFormat::new("save", &[time_turret_shots, give_actors_worlds, tune_actors,
+                                                          keep_shot_health, time_pickups, tune_weapons]);
+
+/// Upgrade a save to version 1, turning the time since every saved turret last fired from a number into the
+/// stopwatch timer it is now kept in
+fn time_turret_shots(table: &mut Table) -> Result<(), String> {
+    let actors = match table.get_mut("actors") {
+        Some(toml::Value::Array(actors)) => actors,
+        _ => return Ok(()),
+    };
+    for actor in actors.iter_mut().filter_map(toml::Value::as_table_mut) {
+        let elapsed = match actor.get("since_last_shot") {
+            Some(toml::Value::Float(elapsed)) => *elapsed,
+            _ => continue,
+        };
+        let mut stopwatch = Timer::stopwatch();
+        stopwatch.update(elapsed as f32);
+        let value = toml::Value::try_from(stopwatch).map_err(|error| error.to_string())?;
+        actor.insert(String::from("since_last_shot"), value);
+    }
+    return Ok(());
+}
+
+/// Upgrade a save to version 2, turning the size of the arena the player and every saved actor were in into the
+/// rectangular world of that size they would have been in
+fn give_actors_worlds(table: &mut Table) -> Result<(), String> {
+    if let Some(toml::Value::Array(actors)) = table.get_mut("actors") {
+        for actor in actors.iter_mut().filter_map(toml::Value::as_table_mut) {
+            give_world(actor)?;
+        }
+    }
+    if let Some(toml::Value::Table(player)) = table.get_mut("player") {
+        give_world(player)?;
+    }
+    return Ok(());
+}
+
+/// Replace the size of the arena the saved actor in the given table was in with a rectangular world of that size
+fn give_world(actor: &mut Table) -> Result<(), String> {
+    let size: (f32, f32) = match actor.remove("bounds") {
+        Some(bounds) => bounds.try_into().map_err(|error| format!("invalid bounds: {}", error))?,
+        None => return Ok(()),
+    };
+    let world = toml::Value::try_from(World::rect(size.0, size.1)).map_err(|error| error.to_string())?;
+    actor.insert(String::from("world"), world);
+    return Ok(());
+}
+
+/// Upgrade a save to version 3, giving the player and every saved actor the values from the tuning they now keep.
+/// The balance the save was played with isn't known, so they are given the defaults
+fn tune_actors(table: &mut Table) -> Result<(), String> {
+    let tuning = Tuning::DEFAULT;
+    if let Some(toml::Value::Array(actors)) = table.get_mut("actors") {
+        for actor in actors.iter_mut().filter_map(toml::Value::as_table_mut) {
+            let values = match actor.get("type").and_then(toml::Value::as_str) {
+                Some("shot") => vec![("radius", tuning.shot_radius)],
+                Some("turret") => vec![("sight_radius", tuning.turret_sight_radius)],
+                Some("pickup") => {
+                    let gem = matches!(actor.get("kind"), Some(toml::Value::Table(kind)) if kind.contains_key("Score"));
+                    vec![("radius", if gem { tuning.gem_radius } else { tuning.pickup_radius })]
+                }
+                Some("drone") => {
+                    let hostile = actor.get("team").and_then(toml::Value::as_str) == Some("Hostile");
+                    let damage = if hostile { tuning.attacker_damage } else { 0.0 };
+                    vec![("radius", tuning.drone_radius), ("damage", damage)]
+                }
+                Some("kamikaze") => vec![
+                    ("radius", tuning.kamikaze_radius),
+                    ("turn_speed", tuning.kamikaze_turn_speed),
+                    ("damage", tuning.kamikaze_damage),
+                ],
+                Some("bomb") => vec![
+                    ("radius", tuning.bomb_radius),
+                    ("drag", tuning.bomb_drag),
+                    ("blast_radius", tuning.bomb_blast_radius),
+                ],
+                Some("base") => vec![("radius", tuning.base_radius)],
+                _ => Vec::new(),
+            };
+            give_values(actor, &values);
+            tune_shots(actor, &tuning);
+        }
+    }
+    if let Some(toml::Value::Table(player)) = table.get_mut("player") {
+        give_values(player, &[("turn_speed", tuning.player_turn_speed)]);
+        tune_shots(player, &tuning);
+        if let Some(toml::Value::Table(weapon)) = player.get_mut("weapon") {
+            if weapon.get("type").and_then(toml::Value::as_str) == Some("gun") {
+                let cooldown = Cooldown::new(tuning.player_fire_cooldown);
+                let value = toml::Value::try_from(cooldown).map_err(|error| error.to_string())?;
+                weapon.insert(String::from("cooldown"), value);
+            }
+        }
+    }
+    return Ok(());
+}
+
+/// Upgrade a save to version 4, turning the health of every saved shot, including the shots the player and the
+/// actors have fired but not yet let go of, from a number into the health it is now kept in, full at that amount
+fn keep_shot_health(table: &mut Table) -> Result<(), String> {
+    if let Some(toml::Value::Array(actors)) = table.get_mut("actors") {
+        for actor in actors.iter_mut().filter_map(toml::Value::as_table_mut) {
+            if actor.get("type").and_then(toml::Value::as_str) == Some("shot") {
+                give_health(actor)?;
+            }
+            give_fired_health(actor)?;
+        }
+    }
+    if let Some(toml::Value::Table(player)) = table.get_mut("player") {
+        give_fired_health(player)?;
+    }
+    return Ok(());
+}
+
+/// Upgrade a save to version 5, turning the time every saved pickup had left from a number into the timer it now
+/// counts up its lifespan with
+fn time_pickups(table: &mut Table) -> Result<(), String> {
+    let actors = match table.get_mut("actors") {
+        Some(toml::Value::Array(actors)) => actors,
+        _ => return Ok(()),
+    };
+    for actor in actors.iter_mut().filter_map(toml::Value::as_table_mut) {
+        if actor.get("type").and_then(toml::Value::as_str) != Some("pickup") {
+            continue;
+        }
+        let remaining = match actor.get("lifespan") {
+            Some(toml::Value::Float(remaining)) => *remaining as f32,
+            _ => continue,
+        };
+        let mut lifespan = Timer::new(PICKUP_LIFESPAN);
+        lifespan.update((PICKUP_LIFESPAN - remaining).max(0.0));
+        let value = toml::Value::try_from(lifespan).map_err(|error| error.to_string())?;
+        actor.insert(String::from("lifespan"), value);
+    }
+    return Ok(());
+}
+
+/// Upgrade a save to version 6, giving the player and every saved turret the size of the shots they fire, and the
+/// volleys of the turrets the interval, speed, and damage they fire at, which they now keep instead of reading the
+/// balance as they fire. The balance the save was played with isn't known, so they are given the defaults
+fn tune_weapons(table: &mut Table) -> Result<(), String> {
+    let tuning = Tuning::DEFAULT;
+    if let Some(toml::Value::Array(actors)) = table.get_mut("actors") {
+        for actor in actors.iter_mut().filter_map(toml::Value::as_table_mut) {
+            if actor.get("type").and_then(toml::Value::as_str) != Some("turret") {
+                continue;
+            }
+            give_values(actor, &[("shot_radius", tuning.shot_radius)]);
+            if let Some(toml::Value::Table(weapon)) = actor.get_mut("weapon") {
+                if weapon.get("type").and_then(toml::Value::as_str) == Some("volley") {
+                    give_values(weapon, &[
+                        ("fire_interval", tuning.turret_fire_interval),
+                        ("shot_speed", tuning.turret_shot_speed),
+                        ("shot_damage", tuning.turret_shot_damage),
+                    ]);
+                }
+            }
+        }
+    }
+    if let Some(toml::Value::Table(player)) = table.get_mut("player") {
+        give_values(player, &[("shot_radius", tuning.shot_radius)]);
+    }
+    return Ok(());
+}
+
+/// Turn the health of every shot the saved actor in the given table has fired but not yet let go of into a health
+fn give_fired_health(actor: &mut Table) -> Result<(), String> {
+    if let Some(toml::Value::Array(shots)) = actor.get_mut("shots") {
+        for shot in shots.iter_mut().filter_map(toml::Value::as_table_mut) {
+            give_health(shot)?;
+        }
+    }
+    return Ok(());
+}
+
+/// Turn the health of the saved shot in the given table from a number into a full health of that amount
+fn give_health(shot: &mut Table) -> Result<(), String> {
+    let amount = match shot.get("health") {
+        Some(toml::Value::Float(amount)) => *amount as f32,
+        _ => return Ok(()),
+    };
+    let health = toml::Value::try_from(Health::new(amount)).map_err(|error| error.to_string())?;
+    shot.insert(String::from("health"), health);
+    return Ok(());
+}
+
+/// Give the saved actor in the given table each of the given values it doesn't have yet
+fn give_values(actor: &mut Table, values: &[(&str, f32)]) {
+    for &(name, value) in values {
+        if !actor.contains_key(name) {
+            actor.insert(name.to_string(), toml::Value::Float(value as f64));
+        }
+    }
+}
+
+/// Give the shots the saved actor in the given table has fired but not yet let go of the radius from the given
+/// tuning
+fn tune_shots(actor: &mut Table, tuning: &Tuning) {
+    if let Some(toml::Value::Array(shots)) = actor.get_mut("shots") {
+        for shot in shots.iter_mut().filter_map(toml::Value::as_table_mut) {
+            give_values(shot, &[("radius", tuning.shot_radius)]);
+        }
+    }
+}
+
+/// Saved state of an actor, which can be written to disk
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SavedActor(pub(crate) ActorData);
+
+/// State of each type of actor, tagged with the type
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ActorData {
+    Shot(Shot),
+    Turret(Box<Turret>),
+    Pickup(Pickup),
+    Drone(Drone),
+    Kamikaze(Kamikaze),
+    Bomb(Bomb),
+    Base(Base),
+}
+
+impl SavedActor {
+    /// Turn this saved actor back into a live one
+    pub(crate) fn into_actor(self) -> Box<dyn Actor> {
+        return match self.0 {
+            ActorData::Shot(shot) => Box::new(shot),
+            ActorData::Turret(turret) => turret,
+            ActorData::Pickup(pickup) => Box::new(pickup),
+            ActorData::Drone(drone) => Box::new(drone),
+            ActorData::Kamikaze(kamikaze) => Box::new(kamikaze),
+            ActorData::Bomb(bomb) => Box::new(bomb),
+            ActorData::Base(base) => Box::new(base),
+        };
+    }
+}
+
+/// Saved state of a weapon, which can be written to disk
+#[derive(Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SavedWeapon(pub(crate) WeaponData);
+
+/// State of each type of weapon, tagged with the type
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum WeaponData {
+    Gun(Gun),
+    Volley(Volley),
+}
+
+impl SavedWeapon {
+    /// Turn this saved weapon back into a live one
+    pub(crate) fn into_weapon(self) -> Box<dyn Weapon> {
+        return match self.0 {
+            WeaponData::Gun(gun) => Box::new(gun),
+            WeaponData::Volley(volley) => Box::new(volley),
+        };
+    }
+}
+
+/// Saved state of a run, which can be written to disk and resumed later
+#[derive(Serialize, Deserialize)]
+pub struct SaveGame {
+    mode: GameMode,
+    class: ShipClass,
+    #[serde(default)]
+    ruleset: Ruleset,
+    tick: u64,
+    /// Number of ticks simulated per second in the saved run, which saves from before it could be changed ran at
+    /// the default rate
+    #[serde(default = "crate::get_default_tick_rate")]
+    tick_rate: u32,
+    score: u32,
+    wave: u32,
+    credits: u32,
+    checkpoint_restarts: u32,
+    wave_enemies: Vec<u32>,
+    escort_drone: Option<u32>,
+    base: Option<u32>,
+    bomb: Option<u32>,
+    next_pickup_at_top: bool,
+    pickup_timer: Timer,
+    #[serde(default)]
+    spawns: SpawnSchedule,
+    director: Director,
+    zones: Vec<CaptureZone>,
+    gauntlet: Option<Gauntlet>,
+    player: Player,
+    actors: Vec<SavedActor>,
+    /// The rest of the state of the run, which saves from before it was kept leave as it was when the run started
+    modifiers: Option<ModifierSet>,
+    #[serde(default)]
+    formations: Vec<DriftingAnchor>,
+    #[serde(default)]
+    perk_choices: Option<Vec<Perk>>,
+    #[serde(default)]
+    telemetry: Telemetry,
+    #[serde(default)]
+    run_stats: RunStats,
+    #[serde(default)]
+    events: GlobalEvents,
+    #[serde(default)]
+    scheduler: UpdateScheduler,
+    #[serde(default)]
+    contacts: Vec<SavedContact>,
+    #[serde(default)]
+    player_shots: Vec<u32>,
+    hack_cooldown: Option<Cooldown>,
+    #[serde(default)]
+    time_scale: TimeScale,
+    #[serde(default)]
+    boss_bar: BossBar,
+    rng: Option<RandomStreams>,
+    #[serde(default)]
+    last_actor_id: u32,
+}
+
+impl SaveGame {
+    /// Save the state of the given game, including the actors parked far from the player
+    pub fn capture(state: &MainState) -> SaveGame {
+        let mut actors: Vec<SavedActor> = state.actors.iter().filter_map(|actor| actor.to_saved()).collect();
+        actors.extend(state.streamer.get_parked().cloned());
+        let mut player_shots: Vec<u32> = state.player_shots.iter().copied().collect();
+        player_shots.sort_unstable();
+        return SaveGame {
+            mode: state.mode,
+            class: ShipClass::ALL[state.class_selection],
+            ruleset: state.ruleset.clone(),
+            tick: state.tick,
+            tick_rate: state.tick_rate,
+            score: state.score,
+            wave: state.wave,
+            credits: state.credits,
+            checkpoint_restarts: state.checkpoint_restarts,
+            wave_enemies: state.wave_enemies.clone(),
+            escort_drone: state.escort_drone,
+            base: state.base,
+            bomb: state.bomb,
+            next_pickup_at_top: state.next_pickup_at_top,
+            pickup_timer: state.pickup_timer.clone(),
+            spawns: state.spawns.clone(),
+            director: state.director.clone(),
+            zones: state.zones.clone(),
+            gauntlet: state.gauntlet.clone(),
+            player: state.player.clone(),
+            actors,
+            modifiers: Some(state.modifiers.clone()),
+            formations: state.formations.clone(),
+            perk_choices: state.perk_choices.clone(),
+            telemetry: state.telemetry.clone(),
+            run_stats: state.run_stats.clone(),
+            events: state.events.clone(),
+            scheduler: state.scheduler.clone(),
+            contacts: state.contacts.get_saved(),
+            player_shots,
+            hack_cooldown: Some(state.hack_cooldown.clone()),
+            time_scale: state.time_scale.clone(),
+            boss_bar: state.boss_bar.clone(),
+            rng: Some(state.rng.clone()),
+            last_actor_id: state.get_last_actor_id(),
+        };
+    }
+
+    /// Load a saved game from the given file
+    pub fn load(path: &Path) -> Result<SaveGame, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        return SaveGame::from_text(&text).map_err(|error| format!("{}: {}", path.display(), error));
+    }
+
+    /// Write this saved game to the given file, headed by the version of the save format
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = self.to_text()?;
+        if let Some(directory) = path.parent() {
+            fs::create_dir_all(directory).map_err(|error| format!("{}: {}", directory.display(), error))?;
+        }
+        return fs::write(path, text).map_err(|error| format!("{}: {}", path.display(), error));
+    }
+
+    /// Serialize this saved game to text, headed by the version of the save format
+    pub fn to_text(&self) -> Result<String, String> {
+        return SAVE_FORMAT.write(self);
+    }
+
+    /// Deserialize a saved game from text written by any version of the save format
+    pub fn from_text(text: &str) -> Result<SaveGame, String> {
+        return SAVE_FORMAT.read(text).map_err(|error| format!("invalid save: {}", error));
+    }
+
+    /// Get the mode of the saved run
+    pub fn get_mode(&self) -> GameMode {
+        return self.mode;
+    }
+
+    /// Get the ship class of the saved run
+    pub fn get_class(&self) -> ShipClass {
+        return self.class;
+    }
+
+    /// Get the rules of the saved run
+    pub fn get_ruleset(&self) -> Ruleset {
+        return self.ruleset.clone().clamped();
+    }
+
+    /// Put the saved run into the given game, which should have just started a run in the same mode with the
+    /// same ship so that its arena is laid out. Returns the ID of the last actor the saved run created, or the
+    /// highest actor ID in saves from before that was kept
+    pub fn restore(self, state: &mut MainState) -> u32 {
+        // Count the ticks at the rate the game runs at now, so the run has been played for as long as it was
+        state.tick = self.tick * state.tick_rate as u64 / self.tick_rate.max(1) as u64;
+        state.score = self.score;
+        state.wave = self.wave;
+        state.credits = self.credits;
+        state.checkpoint_restarts = self.checkpoint_restarts;
+        state.wave_enemies = self.wave_enemies;
+        state.escort_drone = self.escort_drone;
+        state.base = self.base;
+        state.bomb = self.bomb;
+        state.next_pickup_at_top = self.next_pickup_at_top;
+        state.pickup_timer = self.pickup_timer;
+        state.spawns = self.spawns;
+        state.director = self.director;
+        state.zones = self.zones;
+        state.gauntlet = self.gauntlet;
+        state.player = self.player;
+        state.actors = self.actors.into_iter().map(SavedActor::into_actor).collect();
+        if let Some(modifiers) = self.modifiers {
+            state.modifiers = modifiers;
+        }
+        state.formations = self.formations;
+        state.perk_choices = self.perk_choices;
+        state.telemetry = self.telemetry;
+        state.run_stats = self.run_stats;
+        state.events = self.events;
+        state.scheduler = self.scheduler;
+        state.scheduler.set_tick_rate(state.tick_rate);
+        state.contacts.restore(self.contacts);
+        state.player_shots = self.player_shots.into_iter().collect();
+        if let Some(hack_cooldown) = self.hack_cooldown {
+            state.hack_cooldown = hack_cooldown;
+        }
+        state.time_scale = self.time_scale;
+        state.boss_bar = self.boss_bar;
+        if let Some(rng) = self.rng {
+            state.rng = rng;
+        }
+        // The checkpoint belonged to the run that was replaced
+        state.checkpoint = None;
+
+        let highest_id = state.actors.iter().map(|actor| actor.get_id()).chain(Some(state.player.id)).max();
+        return self.last_actor_id.max(highest_id.unwrap_or(0));
+    }
+}
@@ -0,0 +1,204 @@
+use ggez::{Context, GameResult, graphics};
+
+/// The screens the game moves between. The simulation only runs while a run is being played, and every other
+/// screen is drawn on its own or on top of the frozen game
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Scene {
+    /// Loading the content of the game with a progress bar, shown at launch before the main menu
+    Loading,
+    /// The menu shown after loading, which leads to the other menus and to starting a run
+    MainMenu,
+    /// Choosing the game mode
+    ModeSelect,
+    /// Mixing the rules and mutators of the runs, and saving and loading them as presets
+    CustomGame,
+    /// Turning assists and challenges on and off
+    Settings,
+    /// Entering the seed of the next run on the virtual keyboard
+    SeedEntry,
+    /// Choosing the ship to start a run with
+    ShipSelect,
+    /// The lifetime statistics of the player's profile
+    Stats,
+    /// Playing a run
+    Playing,
+    /// A run that has been paused by the player
+    Paused,
+    /// A run that has ended, either with an outcome or with the player out of restarts
+    GameOver,
+}
+
+impl Scene {
+    /// Check if the simulation runs while this scene is shown
+    pub fn is_simulating(&self) -> bool {
+        return *self == Scene::Playing;
+    }
+
+    /// Check if this scene is one of the menus before a run, which are drawn instead of the game
+    pub fn is_menu(&self) -> bool {
+        return matches!(
+            self,
+            Scene::MainMenu
+                | Scene::ModeSelect
+                | Scene::CustomGame
+                | Scene::Settings
+                | Scene::SeedEntry
+                | Scene::ShipSelect
+                | Scene::Stats
+        );
+    }
+
+    /// Check if the game can move from this scene to the given scene. A run can be started straight from the main
+    /// menu, for restarts and games that have no one to press the keys
+    pub fn can_transition_to(&self, next: Scene) -> bool {
+        return matches!(
+            (self, next),
+            (Scene::Loading, Scene::MainMenu)
+                | (Scene::MainMenu, Scene::ModeSelect)
+                | (Scene::MainMenu, Scene::CustomGame)
+                | (Scene::MainMenu, Scene::Settings)
+                | (Scene::MainMenu, Scene::SeedEntry)
+                | (Scene::MainMenu, Scene::ShipSelect)
+                | (Scene::MainMenu, Scene::Playing)
+                | (Scene::MainMenu, Scene::Stats)
+                | (Scene::ModeSelect, Scene::MainMenu)
+                | (Scene::CustomGame, Scene::MainMenu)
+                | (Scene::CustomGame, Scene::ShipSelect)
+                | (Scene::Settings, Scene::MainMenu)
+                | (Scene::SeedEntry, Scene::MainMenu)
+                | (Scene::ShipSelect, Scene::MainMenu)
+                | (Scene::ShipSelect, Scene::Playing)
+                | (Scene::Stats, Scene::MainMenu)
+                | (Scene::Playing, Scene::Paused)
+                | (Scene::Playing, Scene::GameOver)
+                | (Scene::Paused, Scene::Playing)
+                | (Scene::Paused, Scene::MainMenu)
+                | (Scene::GameOver, Scene::MainMenu)
+        );
+    }
+}
+
+/// The items of the main menu
+#[derive(Clone, Copy, PartialEq)]
+pub enum MenuItem {
+    Start,
+    ModeSelect,
+    CustomGame,
+    Seed,
+    Settings,
+    Stats,
+    Quit,
+}
+
+impl MenuItem {
+    /// All the items, in the order they are listed
+    pub const ALL: [MenuItem; 7] = [
+        MenuItem::Start,
+        MenuItem::ModeSelect,
+        MenuItem::CustomGame,
+        MenuItem::Seed,
+        MenuItem::Settings,
+        MenuItem::Stats,
+        MenuItem::Quit,
+    ];
+
+    /// Get the label of this item
+    pub fn get_label(&self) -> &'static str {
+        return match self {
+            MenuItem::Start => "Start",
+            MenuItem::ModeSelect => "Mode Select",
+            MenuItem::CustomGame => "Custom Game",
+            MenuItem::Seed => "Seed",
+            MenuItem::Settings => "Settings",
+            MenuItem::Stats => "Statistics",
+            MenuItem::Quit => "Quit",
+        };
+    }
+
+    /// Get the position of this item in the menu
+    pub fn get_index(&self) -> usize {
+        return MenuItem::ALL.iter().position(|item| item == self).unwrap_or(0);
+    }
+}
+
+/// Draw a menu with the given title and items, with the item at the given index selected and a hint about the
+/// keys underneath
+pub fn draw_menu(ctx: &mut Context, title: &str, items: &[String], selected: usize, hint: &str) -> GameResult {
+    let (width, height) = graphics::drawable_size(ctx);
+
+    let title = graphics::Text::new(title);
+    let title_width = title.width(ctx) as f32;
+    graphics::draw(ctx, &title, ([(width - title_width) / 2.0, height / 4.0], graphics::WHITE))?;
+
+    // List the items under the title, marking the selected one
+    let mut top = height / 4.0 + 50.0;
+    for (i, item) in items.iter().enumerate() {
+        let cursor = if i == selected { ">" } else { " " };
+        let text = graphics::Text::new(format!("{} {}", cursor, item));
+        let color = if i == selected { graphics::WHITE } else { graphics::Color::new(0.6, 0.6, 0.6, 1.0) };
+        graphics::draw(ctx, &text, ([width / 2.0 - 150.0, top], color))?;
+        top += 24.0;
+    }
+
+    let hint = graphics::Text::new(hint);
+    let hint_width = hint.width(ctx) as f32;
+    graphics::draw(ctx, &hint, ([(width - hint_width) / 2.0, top + 30.0], graphics::WHITE))?;
+
+    return Ok(());
+}
+
+/// Darken the frozen game underneath a screen drawn on top of it
+fn draw_shade(ctx: &mut Context, width: f32, height: f32) -> GameResult {
+    let shade = graphics::Mesh::new_rectangle(
+        ctx,
+        graphics::DrawMode::fill(),
+        graphics::Rect::new(0.0, 0.0, width, height),
+        graphics::Color::new(0.0, 0.0, 0.0, 0.6),
+    )?;
+    return graphics::draw(ctx, &shade, ([0.0, 0.0],));
+}
+
+/// Draw the pause screen on top of the frozen game, explaining the pause if the window lost focus, or how many
+/// ticks the game has been stepped back if it has, at the given number of ticks per second
+pub fn draw_paused(ctx: &mut Context, focus_lost: bool, ticks_back: Option<usize>, tick_rate: u32) -> GameResult {
+    let (width, height) = graphics::drawable_size(ctx);
+
+    // Leave the game unshaded while stepping through its history, so every actor can be seen clearly
+    if let Some(ticks) = ticks_back {
+        let seconds = ticks as f32 / tick_rate as f32;
+        let text = graphics::Text::new(format!("Rewound {} ticks ({:.2}s)   Comma/Period: step back/forward   \
+                                                Escape or P: resume from here", ticks, seconds));
+        graphics::draw(ctx, &text, ([10.0, 30.0], graphics::WHITE))?;
+        return Ok(());
+    }
+    draw_shade(ctx, width, height)?;
+
+    let reason = if focus_lost { "The game was paused when its window lost focus\n\n" } else { "" };
+    let text = graphics::Text::new(format!("Paused\n\n{}Escape or P: resume\nQ: quit to the menu", reason));
+    let text_width = text.width(ctx) as f32;
+    graphics::draw(ctx, &text, ([(width - text_width) / 2.0, height / 3.0], graphics::WHITE))?;
+
+    return Ok(());
+}
+
+/// Draw the game over screen on top of the frozen game, with the given heading and lines of detail about the run
+pub fn draw_game_over(ctx: &mut Context, heading: &str, details: &[String]) -> GameResult {
+    let (width, height) = graphics::drawable_size(ctx);
+
+    draw_shade(ctx, width, height)?;
+
+    // Center the heading, the details, and the options under each other
+    let mut lines = vec![String::from(heading), String::new()];
+    lines.extend_from_slice(details);
+    lines.push(String::new());
+    lines.push(String::from("R: restart   M: main menu   Escape: quit"));
+    let mut top = height / 3.0;
+    for line in lines {
+        let text = graphics::Text::new(line);
+        let text_width = text.width(ctx) as f32;
+        graphics::draw(ctx, &text, ([(width - text_width) / 2.0, top], graphics::WHITE))?;
+        top += 22.0;
+    }
+
+    return Ok(());
+}
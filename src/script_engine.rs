@@ -0,0 +1,193 @@
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::rc::Rc;
+
+use rhai::packages::{ArithmeticPackage, BasicMathPackage, Package};
+use rhai::{Dynamic, Engine, EvalAltResult, Scope, AST, FLOAT, INT};
+
+use crate::Damage;
+use crate::mods::ModList;
+use crate::scripting::{ActorView, FirePattern, ScriptApi, ScriptCommand, ScriptError};
+
+/// Directory that scripts are loaded from, next to the game
+pub const SCRIPTS_DIRECTORY: &str = "scripts";
+/// Name of the function every script defines, which is called once per tick for each actor it drives
+const ENTRY_POINT: &str = "tick";
+
+/// API of the script that is running, shared with the functions bound into the engine
+type CurrentApi = Rc<RefCell<Option<ScriptApi>>>;
+
+/// Call the given method of the API of the running script, turning its errors into errors that stop the script
+fn call<T>(current: &CurrentApi, method: impl FnOnce(&mut ScriptApi) -> Result<T, ScriptError>)
+           -> Result<T, Box<EvalAltResult>> {
+    let mut current = current.borrow_mut();
+    let api = current.as_mut().ok_or("no script is running")?;
+    return method(api).map_err(|error| error.to_string().into());
+}
+
+/// Rhai engine that runs the scripts driving scripted actors. The engine starts out raw, so scripts can do math
+/// and call the functions of the scripting API but can't print or reach anything outside the game
+pub struct ScriptEngine {
+    engine: Engine,
+    scripts: HashMap<String, AST>,
+    current: CurrentApi,
+    issues: Vec<String>,
+    reported: HashSet<String>,
+}
+
+impl ScriptEngine {
+    /// Create a new engine with no scripts, with the scripting API bound into it
+    pub fn new() -> ScriptEngine {
+        let current: CurrentApi = Rc::new(RefCell::new(None));
+        let mut engine = Engine::new_raw();
+        engine.register_global_module(ArithmeticPackage::new().as_shared_module());
+        engine.register_global_module(BasicMathPackage::new().as_shared_module());
+
+        // Charge every operation to the budget of the running script, stopping it once the budget is used up
+        let progress = current.clone();
+        engine.on_progress(move |_| {
+            return match progress.borrow_mut().as_mut().map(|api| api.charge(1)) {
+                Some(Err(error)) => Some(Dynamic::from(error.to_string())),
+                _ => None,
+            };
+        });
+
+        // Let scripts read the actors they are shown
+        engine.register_type_with_name::<ActorView>("ActorView")
+            .register_get("id", |view: &mut ActorView| view.id as INT)
+            .register_get("x", |view: &mut ActorView| view.x as FLOAT)
+            .register_get("y", |view: &mut ActorView| view.y as FLOAT)
+            .register_get("radius", |view: &mut ActorView| view.radius as FLOAT)
+            .register_get("heading", |view: &mut ActorView| view.heading as FLOAT)
+            .register_get("is_player", |view: &mut ActorView| view.is_player);
+
+        // Bind every function of the scripting API, and nothing else
+        let api = current.clone();
+        engine.register_fn("get_self", move || call(&api, |api| api.get_self()));
+        let api = current.clone();
+        engine.register_fn("nearest_actor", move |players_only: bool| {
+            return call(&api, |api| api.nearest_actor(players_only))
+                .map(|nearest| nearest.map(Dynamic::from).unwrap_or(Dynamic::UNIT));
+        });
+        let api = current.clone();
+        engine.register_fn("get_time", move || call(&api, |api| api.get_time()).map(|time| time as FLOAT));
+        let api = current.clone();
+        engine.register_fn("get_delta", move || call(&api, |api| api.get_delta()).map(|dt| dt as FLOAT));
+        let api = current.clone();
+        engine.register_fn("get_time_since_last_shot", move || {
+            return call(&api, |api| api.get_time_since_last_shot()).map(|time| time as FLOAT);
+        });
+        let api = current.clone();
+        engine.register_fn("spawn_turret", move |x: FLOAT, y: FLOAT| {
+            return call(&api, |api| api.spawn_turret(x as f32, y as f32));
+        });
+        let api = current.clone();
+        engine.register_fn("set_velocity", move |speed: FLOAT, heading: FLOAT| {
+            return call(&api, |api| api.set_velocity(speed as f32, heading as f32));
+        });
+        let api = current.clone();
+        engine.register_fn("aim", move |heading: FLOAT| call(&api, |api| api.aim(heading as f32)));
+        let api = current.clone();
+        engine.register_fn("fire_pattern", move |shots: INT, heading: FLOAT, spread: FLOAT, speed: FLOAT,
+                                                 damage: FLOAT| {
+            let pattern = FirePattern {
+                shots: shots.max(0) as u32,
+                heading: heading as f32,
+                spread: spread as f32,
+                speed: speed as f32,
+                // Scripts can't heal what their shots hit
+                damage: Damage::energy((damage as f32).max(0.0)),
+            };
+            return call(&api, |api| api.fire_pattern(pattern));
+        });
+
+        return ScriptEngine { engine, scripts: HashMap::new(), current, issues: Vec::new(), reported: HashSet::new() };
+    }
+
+    /// Create an engine with every script in the scripts directory, followed by the scripts of the active mods in
+    /// load order, so a mod's script replaces any script of the same name loaded before it. Scripts are named
+    /// after their file
+    pub fn load(mods: &ModList) -> ScriptEngine {
+        let mut engine = ScriptEngine::new();
+        if let Ok(entries) = fs::read_dir(SCRIPTS_DIRECTORY) {
+            let mut paths: Vec<_> = entries.filter_map(|entry| entry.ok().map(|entry| entry.path())).collect();
+            paths.sort();
+            for path in paths {
+                if matches!(path.extension(), Some(extension) if extension == "rhai") {
+                    engine.add_script(&path);
+                }
+            }
+        }
+        for entry in mods.get_active_mods() {
+            for script in entry.get_manifest().get_scripts() {
+                engine.add_script(&entry.get_directory().join(script));
+            }
+        }
+        return engine;
+    }
+
+    /// Compile the script in the given file, reporting it as an issue if it can't be read or compiled
+    fn add_script(&mut self, path: &Path) {
+        let name = match path.file_stem() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => return,
+        };
+        let compiled = fs::read_to_string(path)
+            .map_err(|error| error.to_string())
+            .and_then(|text| self.compile(&name, &text));
+        if let Err(error) = compiled {
+            self.issues.push(format!("{}: invalid script: {}", path.display(), error));
+        }
+    }
+
+    /// Compile the given source as the script with the given name, replacing any script loaded under that name
+    pub fn compile(&mut self, name: &str, source: &str) -> Result<(), String> {
+        let ast = self.engine.compile(source).map_err(|error| error.to_string())?;
+        self.scripts.insert(name.to_string(), ast);
+        return Ok(());
+    }
+
+    /// Run the script with the given name through the given API, returning the commands it issued. A script that
+    /// is stopped keeps the commands it issued before it was, and is reported the first time it goes wrong
+    pub fn run(&mut self, name: &str, api: ScriptApi) -> Vec<ScriptCommand> {
+        let result = match self.scripts.get(name) {
+            Some(ast) => {
+                *self.current.borrow_mut() = Some(api);
+                self.engine.call_fn::<Dynamic>(&mut Scope::new(), ast, ENTRY_POINT, ())
+                    .map_err(|error| error.to_string())
+            }
+            None => Err(String::from("no script with that name was loaded")),
+        };
+        let commands = self.current.borrow_mut().take().map(ScriptApi::into_commands).unwrap_or_default();
+
+        if let Err(error) = result {
+            if self.reported.insert(name.to_string()) {
+                self.issues.push(format!("Script {} stopped: {}", name, error));
+            }
+        }
+        return commands;
+    }
+
+    /// Take the problems found while loading and running scripts since they were last taken
+    pub fn take_issues(&mut self) -> Vec<String> {
+        return std::mem::take(&mut self.issues);
+    }
+}
+
+impl Clone for ScriptEngine {
+    /// Create an engine with the scripting API bound into it that runs the same compiled scripts, without
+    /// compiling them again
+    fn clone(&self) -> ScriptEngine {
+        let mut engine = ScriptEngine::new();
+        engine.scripts = self.scripts.clone();
+        return engine;
+    }
+}
+
+impl Default for ScriptEngine {
+    fn default() -> ScriptEngine {
+        return ScriptEngine::new();
+    }
+}
@@ -0,0 +1,253 @@
+use std::f32::consts::PI;
+use std::fmt;
+use std::rc::Rc;
+
+use crate::Damage;
+
+/// Number of instructions a script may use for one actor in one tick
+pub const DEFAULT_INSTRUCTION_BUDGET: u32 = 10_000;
+/// Number of commands a script may issue for one actor in one tick
+pub const MAX_COMMANDS_PER_TICK: usize = 64;
+/// Number of turrets a script may spawn for one actor in one tick
+pub const MAX_SPAWNS_PER_TICK: usize = 1;
+/// Number of shots a single fire pattern may have
+pub const MAX_PATTERN_SHOTS: u32 = 32;
+
+/// Instruction cost of an API call that searches the actors
+const QUERY_COST: u32 = 50;
+/// Instruction cost of an API call that issues a command
+const COMMAND_COST: u32 = 10;
+/// Instruction cost of an API call that reads a value
+const READ_COST: u32 = 1;
+
+/// Errors that stop a script for the rest of the tick
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScriptError {
+    /// The script used up its instruction budget
+    BudgetExceeded,
+    /// The script issued more commands than allowed in one tick
+    TooManyCommands,
+    /// The script spawned more turrets than allowed in one tick
+    TooManySpawns,
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        return match self {
+            ScriptError::BudgetExceeded => write!(f, "script exceeded its instruction budget"),
+            ScriptError::TooManyCommands => write!(f, "script issued more than {} commands", MAX_COMMANDS_PER_TICK),
+            ScriptError::TooManySpawns => write!(f, "script spawned more than {} turrets", MAX_SPAWNS_PER_TICK),
+        };
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+/// Number of instructions a script has left to use this tick. Script engines charge this from their
+/// progress callback, and every API call charges it as well, so a script that loops forever is stopped
+pub struct InstructionBudget {
+    limit: u32,
+    used: u32,
+}
+
+impl InstructionBudget {
+    /// Create a new budget with the given number of instructions
+    pub fn new(limit: u32) -> InstructionBudget {
+        return InstructionBudget { limit, used: 0 };
+    }
+
+    /// Use up the given number of instructions
+    pub fn charge(&mut self, cost: u32) -> Result<(), ScriptError> {
+        self.used = self.used.saturating_add(cost);
+        if self.used > self.limit {
+            return Err(ScriptError::BudgetExceeded);
+        }
+        return Ok(());
+    }
+
+    /// Get the number of instructions left
+    pub fn get_remaining(&self) -> u32 {
+        return self.limit.saturating_sub(self.used);
+    }
+}
+
+/// Read-only summary of an actor that scripts are allowed to see
+#[derive(Clone)]
+pub struct ActorView {
+    pub id: u32,
+    pub x: f32,
+    pub y: f32,
+    pub radius: f32,
+    pub heading: f32, // Radians, the way a turret is aimed or an actor is moving
+    pub is_player: bool,
+}
+
+impl ActorView {
+    /// Find the distance between this actor and another
+    fn distance_to(&self, other: &ActorView) -> f32 {
+        return ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt();
+    }
+}
+
+/// Ring or fan of shots fired at once
+#[derive(Clone)]
+pub struct FirePattern {
+    /// Number of shots in the pattern
+    pub shots: u32,
+    /// Heading of the first shot in radians
+    pub heading: f32,
+    /// Angle in radians the shots are spread over (2 PI fires a full ring)
+    pub spread: f32,
+    /// Speed of every shot in pixels per second
+    pub speed: f32,
+    /// Damage of every shot
+    pub damage: Damage,
+}
+
+impl FirePattern {
+    /// Get the heading of every shot in this pattern, up to the most shots a pattern may have. A full ring spaces
+    /// the shots evenly around it, while a fan puts its first and last shots at its edges
+    pub fn get_headings(&self) -> Vec<f32> {
+        let shots = self.shots.min(MAX_PATTERN_SHOTS);
+        let gaps = if self.spread >= 2.0 * PI || shots < 2 { shots.max(1) } else { shots - 1 };
+        return (0..shots).map(|i| self.heading + self.spread * i as f32 / gaps as f32).collect();
+    }
+}
+
+/// Commands scripts issue to change the game. They are applied by the game after the script has finished
+#[derive(Clone)]
+pub enum ScriptCommand {
+    /// Spawn a new turret at the given position
+    SpawnTurret { x: f32, y: f32 },
+    /// Set the velocity of the scripted actor
+    SetVelocity { speed: f32, heading: f32 },
+    /// Fire a pattern of shots from the scripted actor
+    FirePattern(FirePattern),
+    /// Turn the scripted actor to face the given heading
+    Aim { heading: f32 },
+}
+
+/// What a scripted actor tells its script about itself, beyond what every actor shows
+pub struct ScriptBinding<'a> {
+    /// Name of the script that drives the actor
+    pub name: &'a str,
+    /// Heading the actor is aimed at, in radians
+    pub heading: f32,
+    /// Number of seconds since the actor last fired
+    pub time_since_last_shot: f32,
+}
+
+/// The complete set of functions available to a script running for one actor during one tick.
+///
+/// Scripts can only look at the game through this API and can only change it by issuing commands,
+/// so they have no access to the filesystem, the network, or the rest of the game state. Script engines
+/// must be created without their standard IO modules and should bind only the methods of this type.
+pub struct ScriptApi {
+    actor: ActorView,
+    actors: Rc<[ActorView]>,
+    time: f32,
+    dt: f32,
+    time_since_last_shot: f32,
+    budget: InstructionBudget,
+    commands: Vec<ScriptCommand>,
+}
+
+impl ScriptApi {
+    /// Create the API for the given actor, able to see the given actors
+    pub fn new(actor: ActorView, actors: Rc<[ActorView]>, time: f32, dt: f32, time_since_last_shot: f32,
+               budget: InstructionBudget) -> ScriptApi {
+        return ScriptApi {
+            actor,
+            actors,
+            time,
+            dt,
+            time_since_last_shot,
+            budget,
+            commands: Vec::new(),
+        };
+    }
+
+    /// Use up instructions from the budget, called by the script engine as the script runs
+    pub fn charge(&mut self, cost: u32) -> Result<(), ScriptError> {
+        return self.budget.charge(cost);
+    }
+
+    /// Get the actor the script is running for
+    pub fn get_self(&mut self) -> Result<ActorView, ScriptError> {
+        self.budget.charge(READ_COST)?;
+        return Ok(self.actor.clone());
+    }
+
+    /// Find the nearest other actor, optionally only considering players
+    pub fn nearest_actor(&mut self, players_only: bool) -> Result<Option<ActorView>, ScriptError> {
+        self.budget.charge(QUERY_COST)?;
+
+        let actor = &self.actor;
+        let nearest = self.actors.iter()
+            .filter(|other| other.id != actor.id && (other.is_player || !players_only))
+            .min_by(|a, b| {
+                actor.distance_to(a).partial_cmp(&actor.distance_to(b)).unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+        return Ok(nearest.cloned());
+    }
+
+    /// Get the number of seconds since the game started
+    pub fn get_time(&mut self) -> Result<f32, ScriptError> {
+        self.budget.charge(READ_COST)?;
+        return Ok(self.time);
+    }
+
+    /// Get the number of seconds this tick lasts
+    pub fn get_delta(&mut self) -> Result<f32, ScriptError> {
+        self.budget.charge(READ_COST)?;
+        return Ok(self.dt);
+    }
+
+    /// Get the number of seconds since the scripted actor last fired
+    pub fn get_time_since_last_shot(&mut self) -> Result<f32, ScriptError> {
+        self.budget.charge(READ_COST)?;
+        return Ok(self.time_since_last_shot);
+    }
+
+    /// Spawn a new turret at the given position, up to the most turrets a script may spawn in one tick
+    pub fn spawn_turret(&mut self, x: f32, y: f32) -> Result<(), ScriptError> {
+        let spawns = self.commands.iter()
+            .filter(|command| matches!(command, ScriptCommand::SpawnTurret { .. }))
+            .count();
+        if spawns >= MAX_SPAWNS_PER_TICK {
+            return Err(ScriptError::TooManySpawns);
+        }
+        return self.issue(ScriptCommand::SpawnTurret { x, y });
+    }
+
+    /// Set the velocity of the scripted actor
+    pub fn set_velocity(&mut self, speed: f32, heading: f32) -> Result<(), ScriptError> {
+        return self.issue(ScriptCommand::SetVelocity { speed, heading });
+    }
+
+    /// Fire a pattern of shots from the scripted actor
+    pub fn fire_pattern(&mut self, pattern: FirePattern) -> Result<(), ScriptError> {
+        return self.issue(ScriptCommand::FirePattern(pattern));
+    }
+
+    /// Turn the scripted actor to face the given heading
+    pub fn aim(&mut self, heading: f32) -> Result<(), ScriptError> {
+        return self.issue(ScriptCommand::Aim { heading });
+    }
+
+    /// Get the commands the script issued
+    pub fn into_commands(self) -> Vec<ScriptCommand> {
+        return self.commands;
+    }
+
+    /// Queue a command, checking the budget and command limit
+    fn issue(&mut self, command: ScriptCommand) -> Result<(), ScriptError> {
+        self.budget.charge(COMMAND_COST)?;
+        if self.commands.len() >= MAX_COMMANDS_PER_TICK {
+            return Err(ScriptError::TooManyCommands);
+        }
+        self.commands.push(command);
+        return Ok(());
+    }
+}
@@ -0,0 +1,293 @@
+use std::fs;
+use std::path::Path;
+
+use ggez::input::keyboard::KeyCode;
+use serde::{Deserialize, Serialize};
+
+use crate::versions::{self, FileFormat};
+
+/// File the player's settings are saved in
+pub const SETTINGS_FILE: &str = "settings.toml";
+/// Format of the settings file
+const SETTINGS_FORMAT: FileFormat = FileFormat::new("settings", &[versions::add_version]);
+/// Amount the volume changes by with each step on the settings screen
+const VOLUME_STEP: f32 = 0.1;
+/// Tick rates the simulation can be run at, in ticks per second
+const TICK_RATES: [u32; 4] = [30, 60, 120, 144];
+/// Caps the frame rate can be held to, in frames per second
+const FRAME_CAPS: [u32; 4] = [30, 60, 120, 144];
+
+/// Keys the player steers their ship with
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ControlScheme {
+    /// The arrow keys
+    Arrows,
+    /// W, A, S, and D, as well as the arrow keys
+    Wasd,
+}
+
+impl ControlScheme {
+    /// Get the arrow key the given key stands for in this scheme, or the key itself if it doesn't stand for one
+    pub fn translate(&self, keycode: KeyCode) -> KeyCode {
+        if *self == ControlScheme::Arrows {
+            return keycode;
+        }
+        return match keycode {
+            KeyCode::W => KeyCode::Up,
+            KeyCode::A => KeyCode::Left,
+            KeyCode::S => KeyCode::Down,
+            KeyCode::D => KeyCode::Right,
+            _ => keycode,
+        };
+    }
+
+    /// Get the name of this scheme
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            ControlScheme::Arrows => "Arrow keys",
+            ControlScheme::Wasd => "WASD",
+        };
+    }
+}
+
+/// How hard the enemies are on the player
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Difficulty {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl Difficulty {
+    /// All the difficulties, from easiest to hardest
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard];
+
+    /// Get the name of this difficulty
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            Difficulty::Easy => "Easy",
+            Difficulty::Normal => "Normal",
+            Difficulty::Hard => "Hard",
+        };
+    }
+
+    /// Get the multiplier for the damage the player takes
+    pub fn get_damage_taken_multiplier(&self) -> f32 {
+        return match self {
+            Difficulty::Easy => 0.75,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.25,
+        };
+    }
+
+    /// Get the multiplier for the speed of enemy shots
+    pub fn get_enemy_shot_speed_multiplier(&self) -> f32 {
+        return match self {
+            Difficulty::Easy => 0.85,
+            Difficulty::Normal => 1.0,
+            Difficulty::Hard => 1.15,
+        };
+    }
+}
+
+/// The settings listed at the top of the settings screen, above the assists and challenges
+#[derive(Clone, Copy, PartialEq)]
+pub enum SettingsItem {
+    Volume,
+    Controls,
+    Fullscreen,
+    Difficulty,
+    TickRate,
+    FrameCap,
+    ScreenShake,
+}
+
+impl SettingsItem {
+    /// All the items, in the order they are listed
+    pub const ALL: [SettingsItem; 7] = [
+        SettingsItem::Volume,
+        SettingsItem::Controls,
+        SettingsItem::Fullscreen,
+        SettingsItem::Difficulty,
+        SettingsItem::TickRate,
+        SettingsItem::FrameCap,
+        SettingsItem::ScreenShake,
+    ];
+
+    /// Get the label of this item
+    pub fn get_label(&self) -> &'static str {
+        return match self {
+            SettingsItem::Volume => "Volume",
+            SettingsItem::Controls => "Controls",
+            SettingsItem::Fullscreen => "Fullscreen",
+            SettingsItem::Difficulty => "Difficulty",
+            SettingsItem::TickRate => "Tick rate",
+            SettingsItem::FrameCap => "Frame cap",
+            SettingsItem::ScreenShake => "Screen shake",
+        };
+    }
+}
+
+/// The player's settings, saved to disk between launches
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    volume: f32,
+    controls: ControlScheme,
+    fullscreen: bool,
+    difficulty: Difficulty,
+    /// Ticks the simulation runs per second of play
+    tick_rate: u32,
+    /// Most frames drawn per second, or none to draw as often as the display allows
+    frame_cap: Option<u32>,
+    /// Whether the view shakes when the player is hurt, things blow up, and bosses attack
+    screen_shake: bool,
+}
+
+impl Settings {
+    /// Create the default settings: full volume, arrow keys, windowed, normal difficulty, 60 ticks a second, no
+    /// frame cap, and screen shake
+    pub fn new() -> Settings {
+        return Settings {
+            volume: 1.0,
+            controls: ControlScheme::Arrows,
+            fullscreen: false,
+            difficulty: Difficulty::Normal,
+            tick_rate: crate::DEFAULT_TICK_RATE,
+            frame_cap: None,
+            screen_shake: true,
+        };
+    }
+
+    /// Load the settings from the given file, using the defaults if it doesn't exist. Also returns a description
+    /// of the problem if the file couldn't be read, in which case it is backed up before it can be replaced
+    pub fn load(path: &Path) -> (Settings, Option<String>) {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => return (Settings::new(), None),
+        };
+        return match SETTINGS_FORMAT.read::<Settings>(&text) {
+            // A volume edited out of range by hand is brought back into range
+            Ok(mut settings) => {
+                settings.volume = settings.volume.clamp(0.0, 1.0);
+                (settings, None)
+            }
+            Err(error) => {
+                let kept = match versions::back_up(path) {
+                    Some(backup) => format!(" (kept as {})", backup.display()),
+                    None => String::new(),
+                };
+                (Settings::new(), Some(format!("{}: invalid settings: {}{}", path.display(), error, kept)))
+            }
+        };
+    }
+
+    /// Save the settings to the given file, headed by the version of the settings format
+    pub fn save(&self, path: &Path) -> Result<(), String> {
+        let text = SETTINGS_FORMAT.write(self)?;
+        return fs::write(path, text).map_err(|error| format!("{}: {}", path.display(), error));
+    }
+
+    /// Get the volume of the sounds, from 0 (muted) to 1
+    pub fn get_volume(&self) -> f32 {
+        return self.volume;
+    }
+
+    /// Get the keys the player steers with
+    pub fn get_controls(&self) -> ControlScheme {
+        return self.controls;
+    }
+
+    /// Check if the game is played in fullscreen
+    pub fn is_fullscreen(&self) -> bool {
+        return self.fullscreen;
+    }
+
+    /// Get how hard the enemies are on the player
+    pub fn get_difficulty(&self) -> Difficulty {
+        return self.difficulty;
+    }
+
+    /// Get the number of ticks the simulation runs per second of play
+    pub fn get_tick_rate(&self) -> u32 {
+        return self.tick_rate.max(1);
+    }
+
+    /// Get the most frames drawn per second, or none if the frame rate isn't capped
+    pub fn get_frame_cap(&self) -> Option<u32> {
+        return self.frame_cap.filter(|&cap| cap > 0);
+    }
+
+    /// Check if the view shakes when the player is hurt, things blow up, and bosses attack
+    pub fn has_screen_shake(&self) -> bool {
+        return self.screen_shake;
+    }
+
+    /// Describe the value of the given item to the player
+    pub fn describe(&self, item: SettingsItem) -> String {
+        return match item {
+            SettingsItem::Volume => format!("{:.0}%", self.volume * 100.0),
+            SettingsItem::Controls => String::from(self.controls.get_name()),
+            SettingsItem::Fullscreen => String::from(if self.fullscreen { "on" } else { "off" }),
+            SettingsItem::Difficulty => String::from(self.difficulty.get_name()),
+            SettingsItem::TickRate => format!("{} per second", self.get_tick_rate()),
+            SettingsItem::FrameCap => match self.get_frame_cap() {
+                Some(cap) => format!("{} per second", cap),
+                None => String::from("off"),
+            },
+            SettingsItem::ScreenShake => String::from(if self.screen_shake { "on" } else { "off" }),
+        };
+    }
+
+    /// Step the value of the given item up or down. Choices wrap around, while the volume stops at its ends
+    pub fn adjust(&mut self, item: SettingsItem, up: bool) {
+        match item {
+            SettingsItem::Volume => {
+                let step = if up { VOLUME_STEP } else { -VOLUME_STEP };
+                // Round to the nearest step so repeated changes don't drift
+                self.volume = ((self.volume + step) / VOLUME_STEP).round() * VOLUME_STEP;
+                self.volume = self.volume.clamp(0.0, 1.0);
+            }
+            SettingsItem::Controls => {
+                self.controls = match self.controls {
+                    ControlScheme::Arrows => ControlScheme::Wasd,
+                    ControlScheme::Wasd => ControlScheme::Arrows,
+                };
+            }
+            SettingsItem::Fullscreen => self.fullscreen = !self.fullscreen,
+            SettingsItem::Difficulty => {
+                let count = Difficulty::ALL.len();
+                let index = Difficulty::ALL.iter().position(|&difficulty| difficulty == self.difficulty).unwrap_or(1);
+                let step = if up { 1 } else { count - 1 };
+                self.difficulty = Difficulty::ALL[(index + step) % count];
+            }
+            SettingsItem::TickRate => {
+                let index = TICK_RATES.iter().position(|&rate| rate == self.tick_rate).unwrap_or(1);
+                let step = if up { 1 } else { TICK_RATES.len() - 1 };
+                self.tick_rate = TICK_RATES[(index + step) % TICK_RATES.len()];
+            }
+            SettingsItem::FrameCap => {
+                // Off comes before the lowest cap
+                let count = FRAME_CAPS.len() + 1;
+                let index = match self.frame_cap {
+                    Some(cap) => FRAME_CAPS.iter().position(|&choice| choice == cap).map_or(0, |index| index + 1),
+                    None => 0,
+                };
+                let step = if up { 1 } else { count - 1 };
+                self.frame_cap = match (index + step) % count {
+                    0 => None,
+                    index => Some(FRAME_CAPS[index - 1]),
+                };
+            }
+            SettingsItem::ScreenShake => self.screen_shake = !self.screen_shake,
+        }
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        return Settings::new();
+    }
+}
@@ -0,0 +1,58 @@
+use rand::Rng;
+
+use crate::Vec2;
+
+/// Furthest the view is thrown from its place, in pixels, at the strongest shake
+const MAX_OFFSET: f32 = 12.0;
+/// Amount the strength of a shake dies down by every second
+const DECAY: f32 = 1.5;
+
+/// Shake of the view when something hits hard, which throws the drawing of the arena a random distance each frame.
+/// Shakes add up to a strength from 0 to 1 that dies down over time, and the view is thrown by the square of the
+/// strength so small shakes stay subtle. Shaking is only for show, so it isn't saved and doesn't change how a run
+/// plays out
+pub struct ScreenShake {
+    strength: f32,
+    enabled: bool,
+}
+
+impl ScreenShake {
+    /// Create a still view that shakes if the given setting allows it
+    pub fn new(enabled: bool) -> ScreenShake {
+        return ScreenShake { strength: 0.0, enabled };
+    }
+
+    /// Turn shaking on or off, stilling the view if it is turned off
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.strength = 0.0;
+        }
+    }
+
+    /// Shake the view by the given strength on top of any shake already going, up to the strongest shake
+    pub fn add(&mut self, strength: f32) {
+        if self.enabled {
+            self.strength = (self.strength + strength).clamp(0.0, 1.0);
+        }
+    }
+
+    /// Let the shake die down over the given time
+    pub fn update(&mut self, dt: f32) {
+        self.strength = (self.strength - DECAY * dt).max(0.0);
+    }
+
+    /// Get the strength of the shake, from 0 for a still view to 1
+    pub fn get_strength(&self) -> f32 {
+        return self.strength;
+    }
+
+    /// Get a random distance to throw the view by this frame, as far as the shake's strength allows
+    pub fn get_offset<R: Rng>(&self, rng: &mut R) -> Vec2 {
+        if self.strength <= 0.0 {
+            return Vec2::new(0.0, 0.0);
+        }
+        let distance = MAX_OFFSET * self.strength * self.strength;
+        return Vec2::new(rng.gen_range(-distance, distance), rng.gen_range(-distance, distance));
+    }
+}
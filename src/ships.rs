@@ -0,0 +1,102 @@
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+use crate::modes::GameMode;
+use crate::modifiers::ModifierSet;
+
+/// The weapons the Player can start with
+#[derive(Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum ShipWeapon {
+    /// A single medium shot
+    Cannon,
+    /// A single fast, light shot
+    Needler,
+    /// Three short-range shots in a fan
+    Scatter,
+}
+
+impl ShipWeapon {
+    /// Get the name of this weapon
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            ShipWeapon::Cannon => "Cannon",
+            ShipWeapon::Needler => "Needler",
+            ShipWeapon::Scatter => "Scatter gun",
+        };
+    }
+}
+
+/// The stats of a ship class
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub struct ShipStats {
+    pub radius: f32,
+    pub speed: f32, // Pixels per second
+    pub health: f32,
+    pub weapon: ShipWeapon,
+}
+
+/// The classes of ship the Player can choose from before a run
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ShipClass {
+    /// Small and fast, but fragile
+    Interceptor,
+    /// An all-rounder
+    Fighter,
+    /// Big and slow, but tough
+    Juggernaut,
+}
+
+impl ShipClass {
+    /// All the ship classes, in the order they are shown on the selection screen
+    pub const ALL: [ShipClass; 3] = [ShipClass::Interceptor, ShipClass::Fighter, ShipClass::Juggernaut];
+
+    /// Get the name of this class
+    pub fn get_name(&self) -> &'static str {
+        return match self {
+            ShipClass::Interceptor => "Interceptor",
+            ShipClass::Fighter => "Fighter",
+            ShipClass::Juggernaut => "Juggernaut",
+        };
+    }
+
+    /// Get the stats of this class
+    pub fn get_stats(&self) -> ShipStats {
+        return match self {
+            ShipClass::Interceptor => ShipStats { radius: 14.0, speed: 220.0, health: 60.0, weapon: ShipWeapon::Needler },
+            ShipClass::Fighter => ShipStats { radius: 20.0, speed: 150.0, health: 100.0, weapon: ShipWeapon::Cannon },
+            ShipClass::Juggernaut => ShipStats { radius: 26.0, speed: 100.0, health: 180.0, weapon: ShipWeapon::Scatter },
+        };
+    }
+}
+
+/// Draw the screen for picking a ship class with the class at the given index selected, the game mode, and the
+/// active assists and challenges
+pub fn draw_class_select(ctx: &mut Context, selected: usize, mode: GameMode, modifiers: &ModifierSet) -> GameResult {
+    let mut lines = vec![
+        format!("Mode: {} (Up/Down to change)", mode.get_name()),
+        String::new(),
+        String::from("Choose your ship (Left/Right to select, Enter to start, Escape to go back)"),
+        String::new(),
+    ];
+    for (i, class) in ShipClass::ALL.iter().enumerate() {
+        let stats = class.get_stats();
+        let cursor = if i == selected { ">" } else { " " };
+        lines.push(format!("{} {:<12} health {:>3}   speed {:>3}   {}",
+                           cursor, class.get_name(), stats.health, stats.speed, stats.weapon.get_name()));
+    }
+
+    // Remind the player of the assists and challenges they turned on in the settings
+    let mut active = modifiers.get_assist_descriptions();
+    active.extend(modifiers.get_challenge_descriptions());
+    lines.push(String::new());
+    if active.is_empty() {
+        lines.push(String::from("No assists or challenges (change them in Settings)"));
+    } else {
+        lines.push(format!("Active: {} (change them in Settings)", active.join(", ")));
+    }
+
+    let text = graphics::Text::new(lines.join("\n"));
+    graphics::draw(ctx, &text, ([40.0, 40.0], graphics::WHITE))?;
+
+    return Ok(());
+}
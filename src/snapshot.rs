@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{Actor, MainState, SaveGame, Team};
+
+/// Serializable state of an actor
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ActorSnapshot {
+    id: u32,
+    kind: String,
+    team: Team,
+    x: f32,
+    y: f32,
+    radius: f32,
+    speed: Option<f32>,
+    heading: Option<f32>,
+    health: Option<f32>,
+}
+
+impl ActorSnapshot {
+    /// Take a snapshot of the given actor
+    fn capture(actor: &Box<dyn Actor>) -> ActorSnapshot {
+        let velocity = actor.get_velocity();
+        return ActorSnapshot {
+            id: actor.get_id(),
+            kind: actor.get_kind().get_name().to_string(),
+            team: actor.get_team(),
+            x: actor.get_position().x,
+            y: actor.get_position().y,
+            radius: actor.get_radius(),
+            speed: velocity.map(|velocity| velocity.speed),
+            heading: velocity.map(|velocity| velocity.heading),
+            health: actor.get_health(),
+        };
+    }
+}
+
+/// Serializable state of the player
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSnapshot {
+    x: f32,
+    y: f32,
+    speed: f32,
+    heading: f32,
+    health: f32,
+    shield_charges: u32,
+}
+
+/// Serializable state of a run, covering everything that saves, snapshots, and netcode need to carry
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct StateSnapshot {
+    tick: u64,
+    score: u32,
+    wave: u32,
+    credits: u32,
+    checkpoint_restarts: u32,
+    wave_enemies: Vec<u32>,
+    player: PlayerSnapshot,
+    actors: Vec<ActorSnapshot>,
+}
+
+impl StateSnapshot {
+    /// Take a snapshot of the given game, including the actors parked far from the player
+    pub fn capture(state: &MainState) -> StateSnapshot {
+        let player = &state.player;
+        return StateSnapshot {
+            tick: state.tick,
+            score: state.score,
+            wave: state.wave,
+            credits: state.credits,
+            checkpoint_restarts: state.checkpoint_restarts,
+            wave_enemies: state.wave_enemies.clone(),
+            player: PlayerSnapshot {
+                x: player.position.x,
+                y: player.position.y,
+                speed: player.velocity.speed,
+                heading: player.velocity.heading,
+                health: player.health.get_current(),
+                shield_charges: player.shield_charges,
+            },
+            actors: state.actors.iter().map(ActorSnapshot::capture)
+                .chain(state.streamer.get_parked().map(|saved| ActorSnapshot::capture(&saved.clone().into_actor())))
+                .collect(),
+        };
+    }
+
+    /// Serialize this snapshot to text
+    pub fn to_text(&self) -> Result<String, String> {
+        return toml::to_string(self).map_err(|error| error.to_string());
+    }
+
+    /// Deserialize a snapshot from text
+    pub fn from_text(text: &str) -> Result<StateSnapshot, String> {
+        return toml::from_str(text).map_err(|error| error.to_string());
+    }
+}
+
+/// Save the given game to text and load it back into a copy of the game, checking that the copy starts out the same
+/// as the live game, then play out the copy alongside a copy loaded from the save without serializing it for the
+/// given number of ticks, checking that they end up the same. Returns a description of the first difference if
+/// anything was lost in the round trip
+pub fn check_round_trip(state: &MainState, ticks: u32) -> Result<(), String> {
+    let text = SaveGame::capture(state).to_text().map_err(|error| format!("could not save the state: {}", error))?;
+    let loaded = SaveGame::from_text(&text).map_err(|error| format!("could not load the state: {}", error))?;
+    let mut from_text = state.copy_from_save(loaded);
+    compare(&StateSnapshot::capture(state), &StateSnapshot::capture(&from_text))
+        .map_err(|difference| format!("on loading, {}", difference))?;
+
+    let mut from_save = state.copy_from_save(SaveGame::capture(state));
+    for _ in 0..ticks {
+        from_save.simulate_tick();
+        from_text.simulate_tick();
+    }
+    return compare(&StateSnapshot::capture(&from_save), &StateSnapshot::capture(&from_text))
+        .map_err(|difference| format!("after {} ticks, {}", ticks, difference));
+}
+
+/// Compare the snapshot of a game with the snapshot of its copy. Returns a description of the first difference
+fn compare(live: &StateSnapshot, loaded: &StateSnapshot) -> Result<(), String> {
+    if loaded.actors.len() != live.actors.len() {
+        return Err(format!("{} actors were saved but {} came back", live.actors.len(), loaded.actors.len()));
+    }
+    for (live_actor, loaded_actor) in live.actors.iter().zip(&loaded.actors) {
+        if live_actor != loaded_actor {
+            return Err(format!("actor changed in the round trip: {:?} became {:?}", live_actor, loaded_actor));
+        }
+    }
+    if loaded.player != live.player {
+        return Err(format!("player changed in the round trip: {:?} became {:?}", live.player, loaded.player));
+    }
+    if loaded != live {
+        return Err(format!("run changed in the round trip: tick {}, score {}, wave {} became tick {}, score {}, \
+                            wave {}", live.tick, live.score, live.wave, loaded.tick, loaded.score, loaded.wave));
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,45 @@
+use std::collections::HashMap;
+
+use crate::Point;
+
+/// Broad-phase structure that buckets items by the cell of a uniform grid they are in, so that the items near
+/// a position can be found without checking every item
+pub struct SpatialGrid {
+    cell_size: f32,
+    cells: HashMap<(i32, i32), Vec<usize>>,
+}
+
+impl SpatialGrid {
+    /// Create an empty grid with cells of the given size
+    pub fn new(cell_size: f32) -> SpatialGrid {
+        return SpatialGrid { cell_size, cells: HashMap::new() };
+    }
+
+    /// Get the cell containing the given position
+    fn get_cell(&self, position: &Point) -> (i32, i32) {
+        return ((position.x / self.cell_size).floor() as i32, (position.y / self.cell_size).floor() as i32);
+    }
+
+    /// Add the item with the given index at the given position
+    pub fn insert(&mut self, index: usize, position: &Point) {
+        let cell = self.get_cell(position);
+        self.cells.entry(cell).or_default().push(index);
+    }
+
+    /// Get the indices of the items in every cell within the given radius of the given position. Some of them
+    /// may be further away than the radius, so the caller checks the exact distance
+    pub fn get_nearby(&self, position: &Point, radius: f32) -> Vec<usize> {
+        let (min_x, min_y) = self.get_cell(&Point::new(position.x - radius, position.y - radius));
+        let (max_x, max_y) = self.get_cell(&Point::new(position.x + radius, position.y + radius));
+
+        let mut nearby = Vec::new();
+        for x in min_x..=max_x {
+            for y in min_y..=max_y {
+                if let Some(items) = self.cells.get(&(x, y)) {
+                    nearby.extend_from_slice(items);
+                }
+            }
+        }
+        return nearby;
+    }
+}
@@ -0,0 +1,150 @@
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+use crate::Point;
+use crate::timers::Timer;
+
+/// Radius of the marker drawn where a spawn is about to arrive
+const MARKER_RADIUS: f32 = 16.0;
+/// Number of seconds before a spawn arrives that its marker starts being drawn
+const MARKER_WARNING: f32 = 1.5;
+
+/// Color of the markers of upcoming spawns
+const MARKER_COLOR: graphics::Color = graphics::Color::new(1.0, 0.35, 0.3, 0.8);
+
+/// The things the spawn schedule can create
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpawnKind {
+    /// A turret that stays where it is spawned
+    Turret,
+    /// A turret armored against kinetic damage
+    ArmoredTurret,
+    /// A kamikaze drone that dives at the player from where it is spawned
+    Kamikaze,
+    /// A turret that calls in kamikaze drones beside itself instead of firing
+    Spawner,
+    /// A shield pickup with the given number of charges
+    Shield(u32),
+}
+
+impl SpawnKind {
+    /// Check if this is an enemy, which joins the current wave when it is spawned
+    pub fn is_hostile(&self) -> bool {
+        return !matches!(self, SpawnKind::Shield(_));
+    }
+}
+
+/// Calls in a given number of spawns of one kind, one every time its interval passes, for enemies that send
+/// others instead of fighting themselves
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Spawner {
+    kind: SpawnKind,
+    remaining: u32,
+    timer: Timer,
+}
+
+impl Spawner {
+    /// Create a new spawner that calls in the given number of the given kind, one every given number of seconds
+    pub fn new(kind: SpawnKind, count: u32, interval: f32) -> Spawner {
+        return Spawner { kind, remaining: count, timer: Timer::looping(interval) };
+    }
+
+    /// Get the number of spawns this spawner has left to call in
+    pub fn get_remaining(&self) -> u32 {
+        return self.remaining;
+    }
+
+    /// Advance this spawner by the given time, returning the kind to call in if its interval has just passed and it
+    /// has any left
+    pub fn update(&mut self, dt: f32) -> Option<SpawnKind> {
+        if self.remaining == 0 || !self.timer.update(dt) {
+            return None;
+        }
+        self.remaining -= 1;
+        return Some(self.kind);
+    }
+}
+
+/// A spawn waiting in the schedule for its time to come
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ScheduledSpawn {
+    kind: SpawnKind,
+    position: Point,
+    timer: Timer,
+}
+
+impl ScheduledSpawn {
+    /// Get what this spawn creates
+    pub fn get_kind(&self) -> SpawnKind {
+        return self.kind;
+    }
+
+    /// Get the position this spawn arrives at
+    pub fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+}
+
+/// Queue of spawns that arrive some time in the future, so waves, enemies, and levels can stagger and telegraph
+/// what they send instead of creating everything at once
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SpawnSchedule {
+    pending: Vec<ScheduledSpawn>,
+}
+
+impl SpawnSchedule {
+    /// Create a new empty schedule
+    pub fn new() -> SpawnSchedule {
+        return SpawnSchedule { pending: Vec::new() };
+    }
+
+    /// Schedule one of the given kind to spawn at each of the given positions after the given number of seconds
+    pub fn schedule(&mut self, delay: f32, kind: SpawnKind, positions: &[Point]) {
+        for position in positions {
+            self.pending.push(ScheduledSpawn { kind, position: *position, timer: Timer::new(delay) });
+        }
+    }
+
+    /// Advance the schedule by the given time, returning the spawns that are due in the order they were scheduled
+    pub fn update(&mut self, dt: f32) -> Vec<ScheduledSpawn> {
+        for spawn in &mut self.pending {
+            spawn.timer.update(dt);
+        }
+        let (due, pending) = self.pending.drain(..).partition(|spawn| spawn.timer.is_finished());
+        self.pending = pending;
+        return due;
+    }
+
+    /// Check if any enemy is still waiting to spawn, which keeps the current wave from being cleared
+    pub fn has_pending_enemies(&self) -> bool {
+        return self.pending.iter().any(|spawn| spawn.kind.is_hostile());
+    }
+
+    /// Draw a marker where each spawn that is about to arrive will appear, closing in as its time comes
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        for spawn in &self.pending {
+            let remaining = spawn.timer.get_remaining();
+            if remaining > MARKER_WARNING {
+                continue;
+            }
+            let radius = MARKER_RADIUS * (1.0 + remaining / MARKER_WARNING);
+            let marker = graphics::Mesh::new_circle(
+                ctx,
+                graphics::DrawMode::stroke(2.0),
+                [0.0, 0.0],
+                radius,
+                0.5,
+                MARKER_COLOR,
+            )?;
+            graphics::draw(ctx, &marker, ([spawn.position.x, spawn.position.y],))?;
+        }
+        return Ok(());
+    }
+}
+
+impl Default for SpawnSchedule {
+    fn default() -> SpawnSchedule {
+        return SpawnSchedule::new();
+    }
+}
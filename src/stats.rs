@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+/// Longest a bar of the charts is drawn, in pixels
+const BAR_LENGTH: f32 = 300.0;
+/// Height of a bar of the charts
+const BAR_HEIGHT: f32 = 14.0;
+/// Gap between the rows of the screen
+const ROW_HEIGHT: f32 = 22.0;
+
+/// Color of the bars of the charts
+const BAR_COLOR: graphics::Color = graphics::Color::new(0.4, 0.7, 1.0, 1.0);
+
+/// Statistics of a single run, collected while it is played
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct RunStats {
+    kills: BTreeMap<String, u32>,
+    shots: BTreeMap<String, u32>,
+    streak: u32,
+    best_streak: u32,
+}
+
+impl RunStats {
+    /// Create the statistics of a run that hasn't started yet
+    pub fn new() -> RunStats {
+        return RunStats::default();
+    }
+
+    /// Count a kill of an enemy of the given type, extending the current streak
+    pub fn record_kill(&mut self, kind: &str) {
+        *self.kills.entry(kind.to_string()).or_insert(0) += 1;
+        self.streak += 1;
+        self.best_streak = self.best_streak.max(self.streak);
+    }
+
+    /// Count a shot fired with the given weapon
+    pub fn record_shot(&mut self, weapon: &str) {
+        *self.shots.entry(weapon.to_string()).or_insert(0) += 1;
+    }
+
+    /// End the current streak, because the player has been hurt
+    pub fn break_streak(&mut self) {
+        self.streak = 0;
+    }
+}
+
+/// Statistics of every run a player has played, saved with their profile
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub struct LifetimeStats {
+    #[serde(default)]
+    runs: u32,
+    /// Seconds spent playing runs
+    #[serde(default)]
+    playtime: f32,
+    #[serde(default)]
+    kills: BTreeMap<String, u32>,
+    #[serde(default)]
+    shots: BTreeMap<String, u32>,
+    /// Most kills in a row without being hurt
+    #[serde(default)]
+    best_streak: u32,
+}
+
+impl LifetimeStats {
+    /// Add a run that lasted the given number of seconds to these statistics
+    pub fn add(&mut self, run: &RunStats, playtime: f32) {
+        self.runs += 1;
+        self.playtime += playtime;
+        for (kind, count) in &run.kills {
+            *self.kills.entry(kind.clone()).or_insert(0) += count;
+        }
+        for (weapon, count) in &run.shots {
+            *self.shots.entry(weapon.clone()).or_insert(0) += count;
+        }
+        self.best_streak = self.best_streak.max(run.best_streak);
+    }
+
+    /// Get the total number of enemies killed
+    pub fn get_total_kills(&self) -> u32 {
+        return self.kills.values().sum();
+    }
+
+    /// Get the weapon the most shots have been fired with, if any
+    pub fn get_favorite_weapon(&self) -> Option<&str> {
+        return self.shots.iter()
+            .max_by_key(|(_, &count)| count)
+            .map(|(weapon, _)| weapon.as_str());
+    }
+}
+
+/// Describe a number of seconds as hours, minutes, and seconds
+fn format_playtime(seconds: f32) -> String {
+    let seconds = seconds as u32;
+    return format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60);
+}
+
+/// Draw a bar chart of the given counts with the given heading, starting at the given height. Returns the height
+/// under the chart
+fn draw_chart(ctx: &mut Context, heading: &str, counts: &BTreeMap<String, u32>, left: f32, top: f32)
+              -> GameResult<f32> {
+    graphics::draw(ctx, &graphics::Text::new(heading), ([left, top], graphics::WHITE))?;
+    let mut top = top + ROW_HEIGHT;
+    if counts.is_empty() {
+        graphics::draw(ctx, &graphics::Text::new("  nothing yet"), ([left, top], graphics::WHITE))?;
+        return Ok(top + ROW_HEIGHT);
+    }
+
+    // Scale the bars so the largest count fills the whole length
+    let largest = counts.values().cloned().max().unwrap_or(1).max(1) as f32;
+    for (name, &count) in counts {
+        graphics::draw(ctx, &graphics::Text::new(format!("  {}", name)), ([left, top], graphics::WHITE))?;
+        let length = (count as f32 / largest * BAR_LENGTH).max(1.0);
+        let bar = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, 0.0, length, BAR_HEIGHT),
+            BAR_COLOR,
+        )?;
+        graphics::draw(ctx, &bar, ([left + 140.0, top + 2.0],))?;
+        let label = graphics::Text::new(count.to_string());
+        graphics::draw(ctx, &label, ([left + 150.0 + length, top], graphics::WHITE))?;
+        top += ROW_HEIGHT;
+    }
+
+    return Ok(top);
+}
+
+/// Draw the statistics screen, with the totals followed by charts of the kills by enemy type and the shots by
+/// weapon
+pub fn draw(ctx: &mut Context, stats: &LifetimeStats) -> GameResult {
+    let (width, height) = graphics::drawable_size(ctx);
+    let left = width / 2.0 - 250.0;
+    let mut top = height / 8.0;
+
+    let title = graphics::Text::new("Statistics");
+    let title_width = title.width(ctx) as f32;
+    graphics::draw(ctx, &title, ([(width - title_width) / 2.0, top], graphics::WHITE))?;
+    top += ROW_HEIGHT * 2.0;
+
+    let totals = [
+        format!("Runs played: {}", stats.runs),
+        format!("Total playtime: {}", format_playtime(stats.playtime)),
+        format!("Total kills: {}", stats.get_total_kills()),
+        format!("Best streak: {} kills without getting hurt", stats.best_streak),
+        format!("Favorite weapon: {}", stats.get_favorite_weapon().unwrap_or("-")),
+    ];
+    for line in totals.iter() {
+        graphics::draw(ctx, &graphics::Text::new(line.as_str()), ([left, top], graphics::WHITE))?;
+        top += ROW_HEIGHT;
+    }
+    top += ROW_HEIGHT;
+
+    top = draw_chart(ctx, "Kills by enemy type", &stats.kills, left, top)?;
+    top = draw_chart(ctx, "Shots by weapon", &stats.shots, left, top + ROW_HEIGHT)?;
+
+    let hint = graphics::Text::new("Escape or Enter to go back");
+    let hint_width = hint.width(ctx) as f32;
+    graphics::draw(ctx, &hint, ([(width - hint_width) / 2.0, top + ROW_HEIGHT], graphics::WHITE))?;
+
+    return Ok(());
+}
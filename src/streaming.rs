@@ -0,0 +1,116 @@
+use std::collections::BTreeMap;
+
+use crate::{Actor, Point, Team};
+use crate::saves::SavedActor;
+
+/// Width and height of a chunk of the arena
+const CHUNK_SIZE: f32 = 512.0;
+/// Number of chunks away from the player's chunk in which actors are kept active
+const ACTIVE_CHUNK_DISTANCE: i32 = 2;
+
+/// Actor parked far from the player, streamed out of the game as its saved state until the player comes back, with
+/// what the game needs to know about it in the meantime
+#[derive(Clone)]
+struct ParkedActor {
+    id: u32,
+    team: Team,
+    saved: SavedActor,
+}
+
+/// Splits the arena into chunks and streams the actors of chunks far from the player out of the game, so that the
+/// number of live actors depends on the area around the player rather than on the size of the arena. Parked actors
+/// are kept as their saved state, and are turned back into live actors when their chunk becomes active again
+#[derive(Clone)]
+pub struct WorldStreamer {
+    // Kept in order of chunk so the actors of several chunks come back in the same order every time a run is
+    // replayed
+    parked: BTreeMap<(i32, i32), Vec<ParkedActor>>,
+}
+
+impl WorldStreamer {
+    /// Create a new streamer with no parked actors
+    pub fn new() -> WorldStreamer {
+        return WorldStreamer { parked: BTreeMap::new() };
+    }
+
+    /// Get the chunk containing the given position
+    fn get_chunk(position: &Point) -> (i32, i32) {
+        return ((position.x / CHUNK_SIZE).floor() as i32, (position.y / CHUNK_SIZE).floor() as i32);
+    }
+
+    /// Check if the given chunk is close enough to the player's chunk to be active
+    fn is_active(chunk: (i32, i32), player_chunk: (i32, i32)) -> bool {
+        return (chunk.0 - player_chunk.0).abs() <= ACTIVE_CHUNK_DISTANCE
+            && (chunk.1 - player_chunk.1).abs() <= ACTIVE_CHUNK_DISTANCE;
+    }
+
+    /// Park the streamable actors that are in inactive chunks, and bring back the parked actors of chunks
+    /// that have become active as the player moved. Actors that can't be saved are never parked
+    pub fn update(&mut self, player_position: &Point, actors: &mut Vec<Box<dyn Actor>>) {
+        let player_chunk = WorldStreamer::get_chunk(player_position);
+
+        // Park the actors that are too far away
+        let mut i = 0;
+        while i < actors.len() {
+            let chunk = WorldStreamer::get_chunk(actors[i].get_position());
+            if !actors[i].allows_streaming() || WorldStreamer::is_active(chunk, player_chunk) {
+                i += 1;
+                continue;
+            }
+            match actors[i].to_saved() {
+                Some(saved) => {
+                    let actor = actors.swap_remove(i);
+                    let parked = ParkedActor { id: actor.get_id(), team: actor.get_team(), saved };
+                    self.parked.entry(chunk).or_default().push(parked);
+                }
+                None => i += 1,
+            }
+        }
+
+        // Bring back the actors of the chunks that are now close enough
+        let active_chunks: Vec<(i32, i32)> = self.parked.keys()
+            .filter(|&&chunk| WorldStreamer::is_active(chunk, player_chunk))
+            .cloned()
+            .collect();
+        for chunk in active_chunks {
+            if let Some(chunk_actors) = self.parked.remove(&chunk) {
+                actors.extend(chunk_actors.into_iter().map(|parked| parked.saved.into_actor()));
+            }
+        }
+    }
+
+    /// Check if the ID and team of any parked actor match the given condition
+    pub fn any_parked<F: Fn(u32, Team) -> bool>(&self, condition: F) -> bool {
+        return self.parked.values().flatten().any(|parked| condition(parked.id, parked.team));
+    }
+
+    /// Get the saved state of every parked actor
+    pub fn get_parked(&self) -> impl Iterator<Item = &SavedActor> {
+        return self.parked.values().flatten().map(|parked| &parked.saved);
+    }
+
+    /// Call the given function with every parked actor, letting it change them. Each actor is brought back to life
+    /// for the call and parked again afterwards, so this is only for changes to the whole game, like a new arena
+    pub fn for_each_parked_mut<F: FnMut(&mut Box<dyn Actor>)>(&mut self, mut function: F) {
+        for parked in self.parked.values_mut().flatten() {
+            let mut actor = parked.saved.clone().into_actor();
+            function(&mut actor);
+            if let Some(saved) = actor.to_saved() {
+                parked.team = actor.get_team();
+                parked.saved = saved;
+            }
+        }
+    }
+
+    /// Get the number of parked actors
+    pub fn get_parked_count(&self) -> usize {
+        return self.parked.values().map(|actors| actors.len()).sum();
+    }
+
+}
+
+impl Default for WorldStreamer {
+    fn default() -> WorldStreamer {
+        return WorldStreamer::new();
+    }
+}
@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+
+use crate::{Actor, ActorKind, Point, Shot, Team};
+use crate::components::Components;
+use crate::layers::CollisionRules;
+use crate::lod::UpdateScheduler;
+use crate::spatial::SpatialGrid;
+
+/// Size of the cells of the broad-phase grid used to find collisions
+const COLLISION_CELL_SIZE: f32 = 64.0;
+
+/// Movement system: update every actor, skipping ticks for actors far from the player that allow it
+pub fn update_actors(actors: &mut [Box<dyn Actor>], scheduler: &mut UpdateScheduler, player_position: &Point,
+                     dt: f32) {
+    scheduler.begin_tick();
+    for actor in actors.iter_mut() {
+        let distance = actor.get_position().distance_to(player_position);
+        let allows_reduced_rate = actor.allows_reduced_update_rate();
+        if let Some(actor_dt) = scheduler.get_update_dt(actor.get_id(), allows_reduced_rate, distance, dt) {
+            actor.update(actor_dt);
+        }
+    }
+}
+
+/// Firing system: take the shots every actor has fired over the tick, each with the ID of the actor that fired it,
+/// with the speed of the hostile shots multiplied by the given multiplier
+pub fn collect_shots(actors: &mut [Box<dyn Actor>], hostile_shot_speed: f32) -> Vec<(u32, Shot)> {
+    let mut fired = Vec::new();
+    for actor in actors.iter_mut() {
+        let id = actor.get_id();
+        for mut shot in actor.collect_shots() {
+            if shot.team == Team::Hostile {
+                shot.velocity.speed *= hostile_shot_speed;
+            }
+            fired.push((id, shot));
+        }
+    }
+    return fired;
+}
+
+/// Check if one of the actors in the given rows of the components is one of the given shots fired by the player
+/// and the other is one of the player's allies, which only hurt each other with friendly fire on
+fn is_friendly_fire(components: &Components, first: usize, second: usize, player_shots: &HashSet<u32>) -> bool {
+    let is_ally = |row: usize| {
+        return components.teams[row] == Team::Player
+            && !matches!(components.kinds[row], ActorKind::Shot | ActorKind::Bomb);
+    };
+    return (player_shots.contains(&components.ids[first]) && is_ally(second))
+        || (player_shots.contains(&components.ids[second]) && is_ally(first));
+}
+
+/// Collision system: find the indices of every pair of actors that are touching and whose teams can harm each
+/// other, with the lower index first. With friendly fire on, the given shots fired by the player can also harm the
+/// player's allies
+pub fn find_contacts(actors: &[Box<dyn Actor>], components: &Components, rules: &CollisionRules,
+                     friendly_fire: Option<&HashSet<u32>>) -> Vec<(usize, usize)> {
+    return find_pairs(actors, components, rules, |first, second| {
+        return components.teams[first].can_harm(components.teams[second])
+            || matches!(friendly_fire, Some(player_shots) if is_friendly_fire(components, first, second, player_shots));
+    });
+}
+
+/// Physics system: find the indices of every pair of actors that are touching and that collision physics push
+/// around, whichever teams they are on, with the lower index first
+pub fn find_physical_contacts(actors: &[Box<dyn Actor>], components: &Components, rules: &CollisionRules)
+                              -> Vec<(usize, usize)> {
+    return find_pairs(actors, components, rules, |first, second| {
+        return components.masses[first].is_some() && components.masses[second].is_some();
+    });
+}
+
+/// Find the indices of every pair of actors that are touching and whose rows of the components the given filter
+/// accepts, with the lower index first. Only the actors in nearby cells of a broad-phase grid whose layers collide
+/// by the given rules have their hitboxes checked against each other, where nearby reaches as far as the fastest
+/// actor moved over the tick
+fn find_pairs<F>(actors: &[Box<dyn Actor>], components: &Components, rules: &CollisionRules, accepts: F)
+                 -> Vec<(usize, usize)> where F: Fn(usize, usize) -> bool {
+    let mut grid = SpatialGrid::new(COLLISION_CELL_SIZE);
+    let mut largest_radius: f32 = 0.0;
+    for (i, position) in components.positions.iter().enumerate() {
+        grid.insert(i, position);
+        largest_radius = largest_radius.max(components.radii[i]);
+    }
+
+    let displacements = &components.displacements;
+    let mut contacts = Vec::new();
+    for i in 0..components.len() {
+        // Each pair is looked for by whichever of its actors moved further this tick (the first of them on a tie),
+        // which reaches far enough to find anything the two could have passed on their way here
+        let reach = components.radii[i] + largest_radius + displacements[i] * 2.0;
+        let owns = |j: usize| displacements[j] < displacements[i] || (displacements[j] == displacements[i] && j > i);
+        for j in grid.get_nearby(&components.positions[i], reach).into_iter().filter(|&j| j != i && owns(j)) {
+            let (first, second) = (i.min(j), i.max(j));
+            if !rules.collides(components.layers[first], components.layers[second]) {
+                continue;
+            }
+            if accepts(first, second) && actors[first].check_for_collision(&actors[second]) {
+                contacts.push((first, second));
+            }
+        }
+    }
+    // List the pairs in a fixed order so a run plays out the same every time it is replayed
+    contacts.sort_unstable();
+    return contacts;
+}
+
+/// Cleanup system: remove the dead actors and the actors with the given IDs that are being despawned, keeping the
+/// order of the rest, and return them so their deaths can be scored
+pub fn remove_dead(actors: &mut Vec<Box<dyn Actor>>, despawned: &HashSet<u32>) -> Vec<Box<dyn Actor>> {
+    let (dead, alive) = std::mem::take(actors).into_iter()
+        .partition(|actor| actor.is_dead() || despawned.contains(&actor.get_id()));
+    *actors = alive;
+    return dead;
+}
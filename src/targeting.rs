@@ -0,0 +1,33 @@
+use crate::Point;
+
+/// Shares the targets of one side out among the aiming turrets of the other side. Each turret prefers close
+/// targets, but a target that other turrets already aim at looks further away, so the fire is split rather than
+/// every turret focusing on the same one
+pub struct TargetPicker {
+    targets: Vec<Point>,
+    attention: Vec<u32>,
+    spread: f32,
+}
+
+impl TargetPicker {
+    /// Create a picker for the given targets. A spread of zero always picks the nearest target, and higher spreads
+    /// split the fire more evenly
+    pub fn new(targets: Vec<Point>, spread: f32) -> TargetPicker {
+        let attention = vec![0; targets.len()];
+        return TargetPicker { targets, attention, spread };
+    }
+
+    /// Choose the target a turret at the given position should aim at, and count it as aimed at. A target at the
+    /// turret's own position is the turret itself, so it is never chosen
+    pub fn pick(&mut self, position: &Point) -> Option<Point> {
+        let score = |index: usize| {
+            let distance = self.targets[index].distance_to(position);
+            return distance * (1.0 + self.spread * self.attention[index] as f32);
+        };
+        let chosen = (0..self.targets.len())
+            .filter(|&index| self.targets[index].distance_to(position) > 0.0)
+            .min_by(|&a, &b| score(a).partial_cmp(&score(b)).unwrap())?;
+        self.attention[chosen] += 1;
+        return Some(self.targets[chosen]);
+    }
+}
@@ -0,0 +1,220 @@
+use std::collections::VecDeque;
+
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+/// Number of seconds of damage history used to calculate damage per second
+const DPS_WINDOW: f32 = 5.0;
+/// Number of seconds of kill history used to calculate the kill rate
+const KILL_WINDOW: f32 = 30.0;
+/// Distance from the player within which dangerous actors count towards the threat density
+pub const THREAT_RADIUS: f32 = 200.0;
+
+/// Threat density at which the threat gauge is full
+const MAX_THREAT: f32 = 20.0;
+/// Damage per second at which the DPS gauges are full
+const MAX_DPS: f32 = 100.0;
+/// Kills per minute at which the kill rate gauge is full
+const MAX_KILL_RATE: f32 = 20.0;
+
+/// Live measurements of how the run is going, used to balance the difficulty
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Telemetry {
+    time: f32,
+    damage_taken: VecDeque<(f32, f32)>, // (time, amount)
+    damage_dealt: VecDeque<(f32, f32)>, // (time, amount)
+    kills: VecDeque<f32>, // time
+    threat_density: f32,
+    health_fraction: f32,
+    shots_fired: u32,
+    shots_hit: u32,
+    total_damage_taken: f32,
+    total_damage_dealt: f32,
+    total_kills: u32,
+}
+
+impl Telemetry {
+    /// Create a new set of measurements for the start of a run
+    pub fn new() -> Telemetry {
+        return Telemetry {
+            time: 0.0,
+            damage_taken: VecDeque::new(),
+            damage_dealt: VecDeque::new(),
+            kills: VecDeque::new(),
+            threat_density: 0.0,
+            health_fraction: 1.0,
+            shots_fired: 0,
+            shots_hit: 0,
+            total_damage_taken: 0.0,
+            total_damage_dealt: 0.0,
+            total_kills: 0,
+        };
+    }
+
+    /// Record damage taken by the player
+    pub fn record_damage_taken(&mut self, amount: f32) {
+        if amount > 0.0 {
+            self.damage_taken.push_back((self.time, amount));
+            self.total_damage_taken += amount;
+        }
+    }
+
+    /// Record damage the player dealt to the enemies
+    pub fn record_damage_dealt(&mut self, amount: f32) {
+        if amount > 0.0 {
+            self.damage_dealt.push_back((self.time, amount));
+            self.total_damage_dealt += amount;
+        }
+    }
+
+    /// Record a shot fired by the player
+    pub fn record_shot_fired(&mut self) {
+        self.shots_fired += 1;
+    }
+
+    /// Record a shot fired by the player hitting an enemy
+    pub fn record_shot_hit(&mut self) {
+        self.shots_hit += 1;
+    }
+
+    /// Record a turret being destroyed
+    pub fn record_kill(&mut self) {
+        self.kills.push_back(self.time);
+        self.total_kills += 1;
+    }
+
+    /// Advance the measurements by the given time, given the number of dangerous actors near the player and
+    /// the fraction of the player's health that is left
+    pub fn update(&mut self, dt: f32, threat_density: f32, health_fraction: f32) {
+        self.time += dt;
+        self.threat_density = threat_density;
+        self.health_fraction = health_fraction.clamp(0.0, 1.0);
+
+        // Forget damage that happened before the DPS window
+        let cutoff = self.time - DPS_WINDOW;
+        for history in [&mut self.damage_taken, &mut self.damage_dealt].iter_mut() {
+            while let Some(&(time, _)) = history.front() {
+                if time >= cutoff {
+                    break;
+                }
+                history.pop_front();
+            }
+        }
+        while let Some(&time) = self.kills.front() {
+            if time >= self.time - KILL_WINDOW {
+                break;
+            }
+            self.kills.pop_front();
+        }
+    }
+
+    /// Get the damage the player has taken over the whole run
+    pub fn get_total_damage_taken(&self) -> f32 {
+        return self.total_damage_taken;
+    }
+
+    /// Get the damage dealt to turrets over the whole run
+    pub fn get_total_damage_dealt(&self) -> f32 {
+        return self.total_damage_dealt;
+    }
+
+    /// Get the number of turrets destroyed over the whole run
+    pub fn get_total_kills(&self) -> u32 {
+        return self.total_kills;
+    }
+
+    /// Get the number of dangerous actors near the player
+    pub fn get_threat_density(&self) -> f32 {
+        return self.threat_density;
+    }
+
+    /// Get the damage per second the player has taken recently
+    pub fn get_dps_taken(&self) -> f32 {
+        return self.damage_taken.iter().map(|&(_, amount)| amount).sum::<f32>() / DPS_WINDOW;
+    }
+
+    /// Get the damage per second dealt to turrets recently
+    pub fn get_dps_dealt(&self) -> f32 {
+        return self.damage_dealt.iter().map(|&(_, amount)| amount).sum::<f32>() / DPS_WINDOW;
+    }
+
+    /// Get the number of turrets destroyed per minute recently
+    pub fn get_kill_rate(&self) -> f32 {
+        return self.kills.len() as f32 * 60.0 / KILL_WINDOW;
+    }
+
+    /// Get the fraction of the player's shots this run that hit an enemy
+    pub fn get_accuracy(&self) -> f32 {
+        if self.shots_fired == 0 {
+            return 0.0;
+        }
+        return self.shots_hit as f32 / self.shots_fired as f32;
+    }
+
+    /// Estimate how stressed the player is from 0 (relaxed) to 1 (overwhelmed), based on missing health,
+    /// nearby threats, and incoming damage
+    pub fn get_player_stress(&self) -> f32 {
+        let stress = 0.4 * (1.0 - self.health_fraction)
+            + 0.3 * (self.threat_density / MAX_THREAT).min(1.0)
+            + 0.3 * (self.get_dps_taken() / MAX_DPS).min(1.0);
+        return stress.min(1.0);
+    }
+
+    /// Draw the designer overlay showing the measurements as gauges, followed by the given extra gauges
+    /// as (label, value, fraction filled)
+    pub fn draw_overlay(&self, ctx: &mut Context, extra_gauges: &[(&str, f32, f32)]) -> GameResult {
+        let mut gauges = vec![
+            ("Threat density", self.threat_density, self.threat_density / MAX_THREAT),
+            ("Player stress", self.get_player_stress(), self.get_player_stress()),
+            ("DPS dealt", self.get_dps_dealt(), self.get_dps_dealt() / MAX_DPS),
+            ("DPS taken", self.get_dps_taken(), self.get_dps_taken() / MAX_DPS),
+            ("Kills per minute", self.get_kill_rate(), self.get_kill_rate() / MAX_KILL_RATE),
+        ];
+        gauges.extend_from_slice(extra_gauges);
+
+        let (width, _) = graphics::drawable_size(ctx);
+        let left = width - 260.0;
+        for (i, (label, value, fill)) in gauges.iter().enumerate() {
+            let top = 20.0 + i as f32 * 40.0;
+            draw_gauge(ctx, left, top, label, *value, *fill)?;
+        }
+
+        return Ok(());
+    }
+}
+
+impl Default for Telemetry {
+    fn default() -> Telemetry {
+        return Telemetry::new();
+    }
+}
+
+/// Draw a labeled horizontal gauge filled to the given fraction
+fn draw_gauge(ctx: &mut Context, left: f32, top: f32, label: &str, value: f32, fill: f32) -> GameResult {
+    let text = graphics::Text::new(format!("{}: {:.2}", label, value));
+    graphics::draw(ctx, &text, ([left, top], graphics::WHITE))?;
+
+    // Shade the bar from green to red as it fills
+    let fill = fill.clamp(0.0, 1.0);
+    let color = graphics::Color::new(fill, 1.0 - fill, 0.2, 1.0);
+
+    let outline = graphics::Mesh::new_rectangle(
+        ctx,
+        graphics::DrawMode::stroke(1.0),
+        graphics::Rect::new(left, top + 16.0, 240.0, 10.0),
+        graphics::WHITE,
+    )?;
+    graphics::draw(ctx, &outline, ([0.0, 0.0],))?;
+
+    if fill > 0.0 {
+        let bar = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(left, top + 16.0, 240.0 * fill, 10.0),
+            color,
+        )?;
+        graphics::draw(ctx, &bar, ([0.0, 0.0],))?;
+    }
+
+    return Ok(());
+}
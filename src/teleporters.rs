@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+
+use ggez::{Context, GameResult, graphics};
+
+use crate::Point;
+
+/// Radius of a teleporter pad
+pub const PAD_RADIUS: f32 = 30.0;
+
+/// Color of the teleporter pads
+const PAD_COLOR: graphics::Color = graphics::Color::new(0.7, 0.4, 1.0, 1.0);
+
+/// Pair of pads that instantly move whatever enters one of them to the other
+#[derive(Clone)]
+pub struct TeleporterPair {
+    pads: [Point; 2],
+    carries_shots: bool,
+    // IDs of the actors that arrived on a pad and haven't stepped off it yet, which can't teleport again until they
+    // have, so they don't flicker between the two pads
+    arrived: HashSet<u32>,
+}
+
+impl TeleporterPair {
+    /// Create a new pair of pads at the given positions, which carries shots as well as the player if asked to
+    pub fn new(first: Point, second: Point, carries_shots: bool) -> TeleporterPair {
+        return TeleporterPair { pads: [first, second], carries_shots, arrived: HashSet::new() };
+    }
+
+    /// Check if this pair carries shots as well as the player
+    pub fn carries_shots(&self) -> bool {
+        return self.carries_shots;
+    }
+
+    /// Forget the actor with the given ID, once it is gone from the game
+    pub fn forget(&mut self, id: u32) {
+        self.arrived.remove(&id);
+    }
+
+    /// Get the position the actor with the given ID and position should be moved to, if it has stepped onto one of
+    /// the pads. An actor that has just arrived on a pad has to step off it before it can teleport again
+    pub fn get_destination(&mut self, id: u32, position: &Point) -> Option<Point> {
+        let entered = match self.pads.iter().position(|pad| pad.distance_to(position) < PAD_RADIUS) {
+            Some(entered) => entered,
+            None => {
+                self.arrived.remove(&id);
+                return None;
+            }
+        };
+        if !self.arrived.insert(id) {
+            return None;
+        }
+
+        // Keep the actor's offset from the center of the pad, so it keeps its line through the pad
+        let (from, to) = (&self.pads[entered], &self.pads[1 - entered]);
+        return Some(Point::new(to.x + position.x - from.x, to.y + position.y - from.y));
+    }
+
+    /// Draw both pads and the link between them
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let [first, second] = &self.pads;
+        let link_color = graphics::Color::new(PAD_COLOR.r, PAD_COLOR.g, PAD_COLOR.b, 0.15);
+        let link = graphics::Mesh::new_line(ctx, &[[first.x, first.y], [second.x, second.y]], 1.0, link_color)?;
+        graphics::draw(ctx, &link, ([0.0, 0.0],))?;
+
+        let pad = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::stroke(3.0),
+            [0.0, 0.0],
+            PAD_RADIUS,
+            0.5,
+            PAD_COLOR,
+        )?;
+        for position in &self.pads {
+            graphics::draw(ctx, &pad, ([position.x, position.y],))?;
+        }
+
+        return Ok(());
+    }
+}
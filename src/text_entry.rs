@@ -0,0 +1,167 @@
+use ggez::{Context, GameResult, graphics};
+use ggez::input::keyboard::KeyCode;
+
+/// Most keys in a row of the virtual keyboard
+const KEYS_PER_ROW: usize = 10;
+/// Width and height of a key of the virtual keyboard
+const KEY_SIZE: f32 = 36.0;
+/// Gap between the keys of the virtual keyboard
+const KEY_GAP: f32 = 6.0;
+
+/// Color of the key that is selected
+const SELECTED_COLOR: graphics::Color = graphics::Color::new(0.4, 0.7, 1.0, 1.0);
+/// Color of the other keys
+const KEY_COLOR: graphics::Color = graphics::Color::new(0.3, 0.3, 0.35, 1.0);
+
+/// The characters of a keyboard for entering numbers, such as seeds
+pub const DIGITS: &str = "1234567890";
+/// The characters of a keyboard for entering names
+pub const LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// A key of the virtual keyboard
+#[derive(Clone, Copy, PartialEq)]
+enum VirtualKey {
+    Character(char),
+    Delete,
+    Done,
+}
+
+impl VirtualKey {
+    /// Get the label drawn on this key
+    fn get_label(&self) -> String {
+        return match self {
+            VirtualKey::Character(character) => character.to_string(),
+            VirtualKey::Delete => String::from("Del"),
+            VirtualKey::Done => String::from("Done"),
+        };
+    }
+}
+
+/// On-screen keyboard for entering text without a physical keyboard. The selection is moved with the arrow
+/// keys, which the d-pad and the stick of a gamepad stand in for, and the selected key is pressed with Enter.
+/// Characters typed on a physical keyboard are accepted too
+pub struct VirtualKeyboard {
+    title: String,
+    characters: &'static str,
+    max_length: usize,
+    text: String,
+    row: usize,
+    column: usize,
+}
+
+impl VirtualKeyboard {
+    /// Create a new keyboard with the given title, offering the given characters, for text up to the given length
+    pub fn new(title: &str, characters: &'static str, max_length: usize) -> VirtualKeyboard {
+        return VirtualKeyboard {
+            title: title.to_string(),
+            characters,
+            max_length,
+            text: String::new(),
+            row: 0,
+            column: 0,
+        };
+    }
+
+    /// Get the keys of this keyboard in rows: the characters, followed by a row with the delete and done keys
+    fn get_rows(&self) -> Vec<Vec<VirtualKey>> {
+        let characters: Vec<VirtualKey> = self.characters.chars().map(VirtualKey::Character).collect();
+        let mut rows: Vec<Vec<VirtualKey>> = characters.chunks(KEYS_PER_ROW).map(|row| row.to_vec()).collect();
+        rows.push(vec![VirtualKey::Delete, VirtualKey::Done]);
+        return rows;
+    }
+
+    /// Get the text entered so far
+    pub fn get_text(&self) -> &str {
+        return &self.text;
+    }
+
+    /// Add a character typed on a physical keyboard to the text, if this keyboard offers it and there is room
+    pub fn type_character(&mut self, character: char) {
+        let character = character.to_ascii_uppercase();
+        if self.characters.contains(character) && self.text.len() < self.max_length {
+            self.text.push(character);
+        }
+    }
+
+    /// Move the selection or press the selected key with the given key. Returns whether the done key was pressed
+    pub fn handle_key(&mut self, keycode: KeyCode) -> bool {
+        let rows = self.get_rows();
+        match keycode {
+            KeyCode::Up => self.row = (self.row + rows.len() - 1) % rows.len(),
+            KeyCode::Down => self.row = (self.row + 1) % rows.len(),
+            KeyCode::Left => {
+                let length = rows[self.row].len();
+                self.column = (self.column.min(length - 1) + length - 1) % length;
+            }
+            KeyCode::Right => {
+                let length = rows[self.row].len();
+                self.column = (self.column.min(length - 1) + 1) % length;
+            }
+            KeyCode::Back => {
+                self.text.pop();
+            }
+            KeyCode::Return => {
+                let row = &rows[self.row];
+                match row[self.column.min(row.len() - 1)] {
+                    VirtualKey::Character(character) => self.type_character(character),
+                    VirtualKey::Delete => {
+                        self.text.pop();
+                    }
+                    VirtualKey::Done => return true,
+                }
+            }
+            _ => (),
+        }
+        return false;
+    }
+
+    /// Draw this keyboard in the middle of the screen, with the text entered so far above it
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let (width, height) = graphics::drawable_size(ctx);
+        let rows = self.get_rows();
+        let keyboard_width = KEYS_PER_ROW as f32 * (KEY_SIZE + KEY_GAP) - KEY_GAP;
+        let left = (width - keyboard_width) / 2.0;
+        let mut top = height / 4.0;
+
+        // Show the title and the text, with a blank for every character that can still be entered
+        let blanks = "_".repeat(self.max_length.saturating_sub(self.text.len()));
+        for line in [self.title.clone(), format!("{}{}", self.text, blanks)].iter() {
+            let text = graphics::Text::new(line.as_str());
+            let text_width = text.width(ctx) as f32;
+            graphics::draw(ctx, &text, ([(width - text_width) / 2.0, top], graphics::WHITE))?;
+            top += 30.0;
+        }
+        top += 20.0;
+
+        // Lay the keys out in rows, stretching the keys of the last row to fill it
+        for (i, row) in rows.iter().enumerate() {
+            let key_width = if i == rows.len() - 1 {
+                (keyboard_width - KEY_GAP * (row.len() - 1) as f32) / row.len() as f32
+            } else {
+                KEY_SIZE
+            };
+            for (j, key) in row.iter().enumerate() {
+                let selected = i == self.row && j == self.column.min(row.len() - 1);
+                let x = left + j as f32 * (key_width + KEY_GAP);
+                let cap = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    graphics::Rect::new(0.0, 0.0, key_width, KEY_SIZE),
+                    if selected { SELECTED_COLOR } else { KEY_COLOR },
+                )?;
+                graphics::draw(ctx, &cap, ([x, top],))?;
+                let label = graphics::Text::new(key.get_label());
+                let label_width = label.width(ctx) as f32;
+                let label_position = [x + (key_width - label_width) / 2.0, top + KEY_SIZE / 2.0 - 8.0];
+                graphics::draw(ctx, &label, (label_position, graphics::WHITE))?;
+            }
+            top += KEY_SIZE + KEY_GAP;
+        }
+
+        let hint = graphics::Text::new("D-pad or arrows to move, A or Enter to press, B or Escape to cancel");
+        let hint_width = hint.width(ctx) as f32;
+        graphics::draw(ctx, &hint, ([(width - hint_width) / 2.0, top + 20.0], graphics::WHITE))?;
+
+        return Ok(());
+    }
+}
@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+/// Timer that counts up to a duration, either once or over and over. Timers only advance by the time they are
+/// given, so they follow the simulation's time scale and stop while it is paused
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Timer {
+    elapsed: f32,
+    duration: f32,
+    looping: bool,
+}
+
+impl Timer {
+    /// Create a new timer that runs once for the given duration
+    pub fn new(duration: f32) -> Timer {
+        return Timer { elapsed: 0.0, duration, looping: false };
+    }
+
+    /// Create a new timer that starts again every time it reaches the given duration
+    pub fn looping(duration: f32) -> Timer {
+        return Timer { elapsed: 0.0, duration, looping: true };
+    }
+
+    /// Create a timer that has already finished, for things that start out ready
+    pub fn finished() -> Timer {
+        return Timer { elapsed: 0.0, duration: 0.0, looping: false };
+    }
+
+    /// Create a timer that never finishes, to measure how long it has been since something happened
+    pub fn stopwatch() -> Timer {
+        return Timer { elapsed: 0.0, duration: f32::MAX, looping: false };
+    }
+
+    /// Advance the timer by the given time, returning whether it reached its duration during this update.
+    /// A looping timer keeps the time it overran by, so it doesn't drift
+    pub fn update(&mut self, dt: f32) -> bool {
+        if self.is_finished() {
+            return false;
+        }
+
+        self.elapsed += dt;
+        if self.elapsed < self.duration {
+            return false;
+        }
+        if self.looping {
+            self.elapsed = if self.duration > 0.0 { self.elapsed % self.duration } else { 0.0 };
+        } else {
+            self.elapsed = self.duration;
+        }
+        return true;
+    }
+
+    /// Check if this timer has run for its whole duration (looping timers never finish)
+    pub fn is_finished(&self) -> bool {
+        return !self.looping && self.elapsed >= self.duration;
+    }
+
+    /// Get the time this timer has run for
+    pub fn get_elapsed(&self) -> f32 {
+        return self.elapsed;
+    }
+
+    /// Get the time left until this timer reaches its duration
+    pub fn get_remaining(&self) -> f32 {
+        return (self.duration - self.elapsed).max(0.0);
+    }
+
+    /// Get the duration of this timer
+    pub fn get_duration(&self) -> f32 {
+        return self.duration;
+    }
+
+    /// Change the duration of this timer, keeping the time it has already run for
+    pub fn set_duration(&mut self, duration: f32) {
+        self.duration = duration;
+    }
+
+    /// Start this timer again from zero
+    pub fn restart(&mut self) {
+        self.elapsed = 0.0;
+    }
+
+    /// Start this timer again from zero with a new duration
+    pub fn restart_with(&mut self, duration: f32) {
+        self.duration = duration;
+        self.elapsed = 0.0;
+    }
+}
+
+/// Ability that can be used once, then has to wait for a duration before it can be used again
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Cooldown {
+    timer: Timer,
+    duration: f32,
+}
+
+impl Cooldown {
+    /// Create a new cooldown of the given duration that is ready straight away
+    pub fn new(duration: f32) -> Cooldown {
+        return Cooldown { timer: Timer::finished(), duration };
+    }
+
+    /// Advance the cooldown by the given time
+    pub fn update(&mut self, dt: f32) {
+        self.timer.update(dt);
+    }
+
+    /// Check if the ability can be used
+    pub fn is_ready(&self) -> bool {
+        return self.timer.is_finished();
+    }
+
+    /// Use the ability, starting the cooldown
+    pub fn trigger(&mut self) {
+        self.timer.restart_with(self.duration);
+    }
+
+    /// Get the time left until the ability can be used again
+    pub fn get_remaining(&self) -> f32 {
+        return self.timer.get_remaining();
+    }
+
+    /// Change the time the ability has to wait after it is used, from the next time it is used
+    pub fn set_duration(&mut self, duration: f32) {
+        self.duration = duration;
+    }
+
+    /// Make the ability ready straight away, cutting any cooldown short
+    pub fn reset(&mut self) {
+        self.timer = Timer::finished();
+    }
+}
@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Timer;
+
+/// Multiplier on the time every tick simulates, for slow motion, hit-stop, and fast-forwarding. The scale can be set
+/// at once or eased towards a new scale over a while. Easing runs on unscaled time, so easing out of a standstill
+/// still finishes
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TimeScale {
+    scale: f32,
+    tween: Option<Tween>,
+}
+
+/// Easing of the time scale from one scale to another, after holding the first for a while
+#[derive(Clone, Serialize, Deserialize)]
+struct Tween {
+    from: f32,
+    to: f32,
+    hold: Timer,
+    timer: Timer,
+}
+
+impl TimeScale {
+    /// Create a time scale that runs the game at normal speed
+    pub fn new() -> TimeScale {
+        return TimeScale { scale: 1.0, tween: None };
+    }
+
+    /// Get the multiplier on the time every tick simulates
+    pub fn get_scale(&self) -> f32 {
+        return self.scale;
+    }
+
+    /// Set the multiplier on the time every tick simulates straight away, stopping any easing. Negative scales are
+    /// treated as a standstill
+    pub fn set_scale(&mut self, scale: f32) {
+        self.scale = scale.max(0.0);
+        self.tween = None;
+    }
+
+    /// Ease the scale from where it is now to the given scale over the given unscaled time
+    pub fn tween_to(&mut self, scale: f32, duration: f32) {
+        let to = scale.max(0.0);
+        self.tween = Some(Tween { from: self.scale, to, hold: Timer::finished(), timer: Timer::new(duration) });
+    }
+
+    /// Stop the game for the given unscaled time, then return to the current scale at once
+    pub fn hit_stop(&mut self, duration: f32) {
+        let to = self.tween.as_ref().map_or(self.scale, |tween| tween.to);
+        self.scale = 0.0;
+        self.tween = Some(Tween { from: 0.0, to, hold: Timer::new(duration), timer: Timer::finished() });
+    }
+
+    /// Advance any easing of the scale by the given unscaled time
+    pub fn update(&mut self, dt: f32) {
+        let tween = match &mut self.tween {
+            Some(tween) => tween,
+            None => return,
+        };
+        if !tween.hold.is_finished() {
+            tween.hold.update(dt);
+            if !tween.hold.is_finished() {
+                return;
+            }
+        }
+        tween.timer.update(dt);
+        if tween.timer.is_finished() {
+            self.scale = tween.to;
+            self.tween = None;
+            return;
+        }
+        // Ease in and out, so the change in speed isn't jarring
+        let fraction = tween.timer.get_elapsed() / tween.timer.get_duration();
+        let eased = fraction * fraction * (3.0 - 2.0 * fraction);
+        self.scale = tween.from + (tween.to - tween.from) * eased;
+    }
+}
+
+impl Default for TimeScale {
+    fn default() -> TimeScale {
+        return TimeScale::new();
+    }
+}
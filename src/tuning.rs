@@ -0,0 +1,232 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+use serde::{Deserialize, Serialize};
+
+use crate::mods::ModList;
+
+/// File the balance of the game is loaded from
+pub const TUNING_FILE: &str = "tuning.toml";
+/// Real time between checks of the tuning file for changes
+const WATCH_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Values that have to be above zero, besides every radius and health
+const POSITIVE_VALUES: [&str; 2] = ["turret_fire_interval", "bomb_fuse"];
+
+/// Sizes, speeds, health, damage, and cooldowns of the actors, which can be tweaked in a file without recompiling
+/// the game. Values left out of the file keep their defaults. Every game has a balance of its own, which it hands
+/// to the actors it creates; actors keep the values they need, so it isn't read by every actor every tick
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Tuning {
+    pub player_turn_speed: f32, // Radians per second
+    pub player_fire_cooldown: f32, // Seconds between the player's shots, however fast fire is pressed
+    pub hack_cooldown: f32, // Seconds between hacks
+    pub hack_duration: f32, // Seconds a hacked turret fights for the player
+    pub target_spread: f32, // How much turrets on the player's team avoid enemies other turrets aim at
+    pub shot_radius: f32,
+    pub turret_radius: f32,
+    pub turret_health: f32,
+    pub turret_turn_speed: f32, // Radians per second
+    pub turret_fire_interval: f32, // Seconds between volleys
+    pub turret_shot_speed: f32, // Pixels per second
+    pub turret_shot_damage: f32,
+    pub turret_sight_radius: f32,
+    pub boss_radius: f32,
+    pub boss_health: f32,
+    pub pickup_radius: f32,
+    pub gem_radius: f32,
+    pub drone_radius: f32,
+    pub drone_speed: f32,
+    pub drone_health: f32,
+    pub attacker_speed: f32,
+    pub attacker_health: f32,
+    pub attacker_damage: f32,
+    pub kamikaze_radius: f32,
+    pub kamikaze_speed: f32,
+    pub kamikaze_turn_speed: f32, // Radians per second
+    pub kamikaze_health: f32,
+    pub kamikaze_damage: f32,
+    pub bomb_radius: f32,
+    pub bomb_throw_speed: f32,
+    pub bomb_drag: f32, // Pixels per second lost every second
+    pub bomb_fuse: f32, // Seconds until a thrown bomb goes off on its own
+    pub bomb_blast_radius: f32,
+    pub bomb_damage: f32,
+    pub base_radius: f32,
+    pub base_health: f32,
+}
+
+impl Tuning {
+    /// The balance the game is designed around
+    pub const DEFAULT: Tuning = Tuning {
+        player_turn_speed: 3.0,
+        player_fire_cooldown: 0.0,
+        hack_cooldown: 20.0,
+        hack_duration: 10.0,
+        target_spread: 0.0,
+        shot_radius: 5.0,
+        turret_radius: 15.0,
+        turret_health: 100.0,
+        turret_turn_speed: 1.0,
+        turret_fire_interval: 2.0,
+        turret_shot_speed: 200.0,
+        turret_shot_damage: 25.0,
+        turret_sight_radius: 250.0,
+        boss_radius: 30.0,
+        boss_health: 1200.0,
+        pickup_radius: 10.0,
+        gem_radius: 5.0,
+        drone_radius: 18.0,
+        drone_speed: 25.0,
+        drone_health: 300.0,
+        attacker_speed: 40.0,
+        attacker_health: 60.0,
+        attacker_damage: 40.0,
+        kamikaze_radius: 7.0,
+        kamikaze_speed: 160.0,
+        kamikaze_turn_speed: 3.0,
+        kamikaze_health: 20.0,
+        kamikaze_damage: 30.0,
+        bomb_radius: 8.0,
+        bomb_throw_speed: 300.0,
+        bomb_drag: 400.0,
+        bomb_fuse: 2.0,
+        bomb_blast_radius: 90.0,
+        bomb_damage: 120.0,
+        base_radius: 40.0,
+        base_health: 500.0,
+    };
+
+    /// Create the default balance
+    pub fn new() -> Tuning {
+        return Tuning::DEFAULT;
+    }
+
+    /// Load the balance from the given file, falling back to the defaults if there is no file. A file that can't
+    /// be read, names a value the game doesn't have, or sets a value out of range is reported as an issue rather
+    /// than stopping the game from starting
+    pub fn load(path: &Path) -> (Tuning, Option<String>) {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(_) => return (Tuning::new(), None),
+        };
+        let tuning = toml::from_str::<Tuning>(&text).map_err(|error| error.to_string())
+            .and_then(|tuning| tuning.validate().map(|_| tuning));
+        return match tuning {
+            Ok(tuning) => (tuning, None),
+            Err(error) => (Tuning::new(), Some(format!("{}: invalid tuning: {}", path.display(), error))),
+        };
+    }
+
+    /// Check that every value of this balance is a number the game can use: none can be negative, and the sizes,
+    /// health, fire interval, and bomb fuse have to be above zero
+    pub fn validate(&self) -> Result<(), String> {
+        let values = match toml::Value::try_from(self) {
+            Ok(toml::Value::Table(values)) => values,
+            _ => return Err(String::from("the tuning could not be written as a table")),
+        };
+        for (name, value) in &values {
+            let value = value.as_float().unwrap_or(f64::NAN);
+            let positive = name.ends_with("_radius") || name.ends_with("_health")
+                || POSITIVE_VALUES.contains(&name.as_str());
+            if !value.is_finite() || value < 0.0 || (positive && value == 0.0) {
+                let range = if positive { "above zero" } else { "zero or more" };
+                return Err(format!("{} is {}, but has to be {}", name, value, range));
+            }
+        }
+        return Ok(());
+    }
+
+    /// Override the values of this balance set in the given file, keeping the rest as they are
+    pub fn with_overrides(&self, path: &Path) -> Result<Tuning, String> {
+        let text = fs::read_to_string(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        let overrides: toml::value::Table = toml::from_str(&text)
+            .map_err(|error| format!("{}: invalid tuning: {}", path.display(), error))?;
+
+        // Replace the values of this balance one by one, so values left out of the file aren't reset to defaults
+        let mut values = match toml::Value::try_from(self) {
+            Ok(toml::Value::Table(values)) => values,
+            _ => return Err(String::from("the tuning could not be written as a table")),
+        };
+        for (name, value) in overrides {
+            values.insert(name, value);
+        }
+        let tuning: Tuning = toml::Value::Table(values).try_into()
+            .map_err(|error| format!("{}: invalid tuning: {}", path.display(), error))?;
+        tuning.validate().map_err(|error| format!("{}: invalid tuning: {}", path.display(), error))?;
+        return Ok(tuning);
+    }
+
+    /// Apply the tuning overrides of the active mods to this balance in load order, so the mod loaded last has
+    /// the final say. Also returns a description of every override that couldn't be applied
+    pub fn with_mods(&self, mods: &ModList) -> (Tuning, Vec<String>) {
+        let mut tuning = *self;
+        let mut issues = Vec::new();
+        for entry in mods.get_active_mods() {
+            if let Some(file) = entry.get_manifest().get_tuning() {
+                match tuning.with_overrides(&entry.get_directory().join(file)) {
+                    Ok(overridden) => tuning = overridden,
+                    Err(issue) => issues.push(issue),
+                }
+            }
+        }
+        return (tuning, issues);
+    }
+}
+
+impl Default for Tuning {
+    fn default() -> Tuning {
+        return Tuning::new();
+    }
+}
+
+/// Scale the given health of a live actor from the given old full health to the given new one, so it keeps the
+/// fraction it has left
+pub fn rescale_health(health: f32, old_max: f32, new_max: f32) -> f32 {
+    if old_max <= 0.0 {
+        return new_max;
+    }
+    return health * new_max / old_max;
+}
+
+/// Watches the tuning file for changes while the game is running, so the balance can be iterated on without
+/// restarting
+pub struct TuningWatcher {
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    last_check: Instant,
+}
+
+impl TuningWatcher {
+    /// Start watching the given file, taking its current version as already loaded
+    pub fn new(path: &Path) -> TuningWatcher {
+        let modified = TuningWatcher::get_modified(path);
+        return TuningWatcher { path: path.to_path_buf(), modified, last_check: Instant::now() };
+    }
+
+    /// Get the time the given file was last changed, if it exists
+    fn get_modified(path: &Path) -> Option<SystemTime> {
+        return fs::metadata(path).and_then(|metadata| metadata.modified()).ok();
+    }
+
+    /// Check the file if it hasn't been checked for a while, and load it again if it has changed. Returns the new
+    /// balance, or a description of why it couldn't be read. A file that is removed goes back to the defaults
+    pub fn poll(&mut self) -> Option<Result<Tuning, String>> {
+        if self.last_check.elapsed() < WATCH_INTERVAL {
+            return None;
+        }
+        self.last_check = Instant::now();
+
+        let modified = TuningWatcher::get_modified(&self.path);
+        if modified == self.modified {
+            return None;
+        }
+        self.modified = modified;
+        return match Tuning::load(&self.path) {
+            (tuning, None) => Some(Ok(tuning)),
+            (_, Some(issue)) => Some(Err(issue)),
+        };
+    }
+}
@@ -0,0 +1,222 @@
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Velocity, World};
+
+/// Two-dimensional vector of X and Y components, used for positions, offsets, and velocities. Converts to and from
+/// the mint types ggez draws with
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vec2 {
+    pub(crate) x: f32,
+    pub(crate) y: f32,
+}
+
+impl Vec2 {
+    /// Create a new vector with the given components
+    pub fn new(x: f32, y: f32) -> Vec2 {
+        return Vec2 { x, y };
+    }
+
+    /// Create a vector of length zero
+    pub fn zero() -> Vec2 {
+        return Vec2::new(0.0, 0.0);
+    }
+
+    /// Create a vector of length one pointing along the given heading
+    pub fn from_heading(heading: f32) -> Vec2 {
+        return Vec2::new(heading.cos(), heading.sin());
+    }
+
+    /// Get the X component of this vector
+    pub fn get_x(&self) -> f32 {
+        return self.x;
+    }
+
+    /// Get the Y component of this vector
+    pub fn get_y(&self) -> f32 {
+        return self.y;
+    }
+
+    /// Get the dot product of this vector and another
+    pub fn dot(&self, other: &Vec2) -> f32 {
+        return self.x * other.x + self.y * other.y;
+    }
+
+    /// Get the length of this vector
+    pub fn length(&self) -> f32 {
+        return self.dot(self).sqrt();
+    }
+
+    /// Get a vector of length one pointing the same way as this one, or the zero vector if this one has no length
+    pub fn normalize(&self) -> Vec2 {
+        let length = self.length();
+        return if length > 0.0 { Vec2::new(self.x / length, self.y / length) } else { Vec2::zero() };
+    }
+
+    /// Get the heading this vector points along
+    pub fn get_heading(&self) -> f32 {
+        return self.y.atan2(self.x);
+    }
+
+    /// Get this vector turned by the given angle
+    pub fn rotate(&self, angle: f32) -> Vec2 {
+        let (sin, cos) = angle.sin_cos();
+        return Vec2::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos);
+    }
+
+    /// Get the point the given fraction of the way from this point to another
+    pub fn lerp(&self, other: &Vec2, fraction: f32) -> Vec2 {
+        return Vec2::new(self.x + (other.x - self.x) * fraction, self.y + (other.y - self.y) * fraction);
+    }
+
+    /// Find the linear distance to another point
+    pub fn distance_to(&self, other: &Vec2) -> f32 {
+        // Use the Pythagorean theorem to calculate the distance between the points
+        return ((self.x - other.x).powi(2) + (self.y - other.y).powi(2)).sqrt();
+    }
+
+    /// Get the heading from this point towards another point
+    pub fn heading_to(&self, other: &Vec2) -> f32 {
+        return (other.y - self.y).atan2(other.x - self.x);
+    }
+
+    /// Update the position of this point after moving for a given time at a given velocity
+    pub fn move_time(&mut self, dt: f32, velocity: &Velocity) {
+        // Get the X and Y components of the velocity
+        let (dx, dy) = velocity.get_components();
+
+        // Multiply the components by the change in time and add to the current position
+        self.x += dx * dt;
+        self.y += dy * dt;
+    }
+
+    /// Move this point a linear distance in a given direction
+    pub(crate) fn move_distance(&mut self, distance: f32, heading: f32) {
+        // Multiply the XY components of the heading by the distance and add to the current position
+        self.x += heading.cos() * distance;
+        self.y += heading.sin() * distance;
+    }
+
+    /// Check if this point is outside of the given world
+    pub(crate) fn is_out_of_bounds(&self, world: &World) -> bool {
+        return !world.contains(self);
+    }
+
+    /// If this point is outside of the given world, wrap it to the other side of that world
+    pub(crate) fn wrap_bounds(&mut self, world: &World) {
+        world.wrap(self);
+    }
+
+    /// Prevent this point from leaving the given world, moving it to the closest place inside if it has
+    pub(crate) fn keep_in_bounds(&mut self, world: &World) {
+        world.clamp(self);
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: Vec2) -> Vec2 {
+        return Vec2::new(self.x + other.x, self.y + other.y);
+    }
+}
+
+impl Add for &Vec2 {
+    type Output = Vec2;
+
+    fn add(self, other: &Vec2) -> Vec2 {
+        return Vec2::new(self.x + other.x, self.y + other.y);
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, other: Vec2) {
+        self.x += other.x;
+        self.y += other.y;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: Vec2) -> Vec2 {
+        return Vec2::new(self.x - other.x, self.y - other.y);
+    }
+}
+
+impl Sub for &Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, other: &Vec2) -> Vec2 {
+        return Vec2::new(self.x - other.x, self.y - other.y);
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, other: Vec2) {
+        self.x -= other.x;
+        self.y -= other.y;
+    }
+}
+
+impl Mul<f32> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, scale: f32) -> Vec2 {
+        return Vec2::new(self.x * scale, self.y * scale);
+    }
+}
+
+impl Mul<f32> for &Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, scale: f32) -> Vec2 {
+        return Vec2::new(self.x * scale, self.y * scale);
+    }
+}
+
+impl MulAssign<f32> for Vec2 {
+    fn mul_assign(&mut self, scale: f32) {
+        self.x *= scale;
+        self.y *= scale;
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        return Vec2::new(-self.x, -self.y);
+    }
+}
+
+impl From<Vec2> for mint::Point2<f32> {
+    fn from(vector: Vec2) -> mint::Point2<f32> {
+        return mint::Point2 { x: vector.x, y: vector.y };
+    }
+}
+
+impl From<&Vec2> for mint::Point2<f32> {
+    fn from(vector: &Vec2) -> mint::Point2<f32> {
+        return mint::Point2 { x: vector.x, y: vector.y };
+    }
+}
+
+impl From<mint::Point2<f32>> for Vec2 {
+    fn from(point: mint::Point2<f32>) -> Vec2 {
+        return Vec2::new(point.x, point.y);
+    }
+}
+
+impl From<Vec2> for mint::Vector2<f32> {
+    fn from(vector: Vec2) -> mint::Vector2<f32> {
+        return mint::Vector2 { x: vector.x, y: vector.y };
+    }
+}
+
+impl From<mint::Vector2<f32>> for Vec2 {
+    fn from(vector: mint::Vector2<f32>) -> Vec2 {
+        return Vec2::new(vector.x, vector.y);
+    }
+}
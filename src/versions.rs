@@ -0,0 +1,84 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use toml::value::Table;
+
+/// Key of the format version written at the top of every versioned file
+pub const VERSION_KEY: &str = "format_version";
+
+/// Step that brings the contents of a file from one version of its format up to the next
+pub type Migration = fn(&mut Table) -> Result<(), String>;
+
+/// Format of a kind of file the game writes and reads back, like saves and settings. Each migration brings a file
+/// up from one version to the next, so the current version is the number of migrations. Files written before the
+/// format had a version are version 0
+pub struct FileFormat {
+    name: &'static str,
+    migrations: &'static [Migration],
+}
+
+impl FileFormat {
+    /// Create a format with the given name, used in messages, and the migrations from each version to the next
+    pub const fn new(name: &'static str, migrations: &'static [Migration]) -> FileFormat {
+        return FileFormat { name, migrations };
+    }
+
+    /// Get the version of this format that files are written in
+    pub fn get_version(&self) -> u32 {
+        return self.migrations.len() as u32;
+    }
+
+    /// Read a file of this format from its text, bringing it up to the current version first. Files from a newer
+    /// version of the game are refused rather than read wrong
+    pub fn read<T: DeserializeOwned>(&self, text: &str) -> Result<T, String> {
+        let mut table: Table = toml::from_str(text).map_err(|error| error.to_string())?;
+        let version = match table.remove(VERSION_KEY) {
+            None => 0,
+            Some(toml::Value::Integer(version)) if version >= 0 => version as u32,
+            Some(other) => return Err(format!("invalid {} {}", VERSION_KEY, other)),
+        };
+        let current = self.get_version();
+        if version > current {
+            return Err(format!("{} version {} is from a newer version of the game, which reads up to version {}",
+                               self.name, version, current));
+        }
+
+        for (from, migrate) in self.migrations.iter().enumerate().skip(version as usize) {
+            migrate(&mut table)
+                .map_err(|error| format!("couldn't upgrade {} version {}: {}", self.name, from, error))?;
+        }
+        return toml::Value::Table(table).try_into().map_err(|error| error.to_string());
+    }
+
+    /// Write a file of this format as text, headed by the current version
+    pub fn write<T: Serialize>(&self, value: &T) -> Result<String, String> {
+        // Go through a TOML value, which puts the plain values of every table ahead of its nested tables, so the
+        // version can go first
+        let text = toml::Value::try_from(value).map_err(|error| error.to_string())?.to_string();
+        return Ok(format!("{} = {}\n{}", VERSION_KEY, self.get_version(), text));
+    }
+}
+
+/// Migration for formats whose first version only added the version header to what was written before
+pub fn add_version(_table: &mut Table) -> Result<(), String> {
+    return Ok(());
+}
+
+/// Copy a file that couldn't be read next to itself, so it isn't lost when the game writes a new one in its place.
+/// The copy is named after the time it was made, so it never replaces an earlier copy. Returns the path of the
+/// copy, if it could be made
+pub fn back_up(path: &Path) -> Option<PathBuf> {
+    let name = path.file_name()?.to_string_lossy().to_string();
+    let seconds = SystemTime::now().duration_since(UNIX_EPOCH).map(|time| time.as_secs()).unwrap_or(0);
+    let mut backup = path.with_file_name(format!("{}.{}.bak", name, seconds));
+    // Number the copies made within the same second
+    let mut copy = 1;
+    while backup.exists() {
+        copy += 1;
+        backup = path.with_file_name(format!("{}.{}-{}.bak", name, seconds, copy));
+    }
+    return fs::copy(path, &backup).ok().map(|_| backup);
+}
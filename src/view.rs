@@ -0,0 +1,299 @@
+use ggez::{Context, GameResult, graphics};
+use ggez::timer;
+
+use crate::{debug_overlay, logging, perks, render, ships, stats, vision};
+use crate::{DEFENSE_TURRET_COST, GameMode, MainState, Modifier, Scene, Team, Vec2};
+use crate::benchmark::Stopwatch;
+use crate::loading::{self, Loader};
+use crate::rulesets::{CustomGameAction, RuleItem};
+use crate::scenes::{self, MenuItem};
+use crate::settings::SettingsItem;
+
+/// Draw the game as the simulation has left it. Drawing only reads the state, apart from following the player with
+/// the camera and timing itself for the benchmark, so the simulation never depends on it
+pub fn draw(state: &mut MainState, ctx: &mut Context) -> GameResult {
+    let _span = logging::span("turrets::draw");
+    // Clear the canvas
+    graphics::clear(ctx, graphics::BLACK);
+
+    // Only draw the progress of the loading until it has finished
+    if state.scene == Scene::Loading {
+        loading::draw(ctx, state.loader.as_ref().map_or(1.0, Loader::get_progress))?;
+        graphics::present(ctx)?;
+        timer::yield_now();
+        return Ok(());
+    }
+
+    // Before the run starts, only draw the menus
+    if state.scene.is_menu() {
+        draw_menus(state, ctx)?;
+        graphics::present(ctx)?;
+        timer::yield_now();
+        return Ok(());
+    }
+
+    // Time each part of the drawing while the game is being benchmarked
+    let mut stopwatch = Stopwatch::start(state.benchmark.is_some());
+    draw_arena(state, ctx, &mut stopwatch)?;
+    draw_interface(state, ctx)?;
+    stopwatch.lap("interface");
+
+    // Show the game to the user
+    graphics::present(ctx)?;
+    stopwatch.lap("present");
+    if let Some(benchmark) = &mut state.benchmark {
+        benchmark.record_draw(&stopwatch);
+    }
+
+    timer::yield_now();
+
+    return Ok(());
+}
+
+/// Draw the menu of the scene the game is on, before a run starts
+fn draw_menus(state: &MainState, ctx: &mut Context) -> GameResult {
+    match state.scene {
+        Scene::MainMenu => {
+            let items: Vec<String> = MenuItem::ALL.iter().map(|item| match item {
+                MenuItem::ModeSelect => format!("{} ({})", item.get_label(), state.mode.get_name()),
+                MenuItem::Seed => match state.seed {
+                    Some(seed) => format!("{} ({})", item.get_label(), seed),
+                    None => format!("{} (random)", item.get_label()),
+                },
+                _ => String::from(item.get_label()),
+            }).collect();
+            scenes::draw_menu(ctx, "TURRETS", &items, state.menu_selection,
+                              "Up/Down to select, Enter to choose")?;
+        }
+        Scene::ModeSelect => {
+            let items: Vec<String> = GameMode::ALL.iter()
+                .map(|mode| {
+                    let best = state.profile.get_best(mode.get_name()).map_or("-", |medal| medal.get_name());
+                    return format!("{:<16} {:<7} {}", mode.get_name(), best, mode.get_description());
+                })
+                .collect();
+            scenes::draw_menu(ctx, "Mode Select", &items, state.menu_selection,
+                              "Up/Down to select, Enter to choose, Escape to go back")?;
+        }
+        Scene::Settings => {
+            // List the settings followed by the assists and challenges
+            let mut items: Vec<String> = SettingsItem::ALL.iter()
+                .map(|item| format!("{:<10} {:<28} {}", "Setting", item.get_label(),
+                                    state.settings.describe(*item)))
+                .collect();
+            items.extend(Modifier::get_options().iter().map(|modifier| {
+                let kind = if modifier.is_assist() { "Assist" } else { "Challenge" };
+                let status = if state.modifiers.has_kind(modifier) { "on" } else { "off" };
+                return format!("{:<10} {:<28} {}", kind, modifier.get_description(), status);
+            }));
+            scenes::draw_menu(ctx, "Settings", &items, state.menu_selection,
+                              "Up/Down to select, Left/Right to change, Enter to toggle, Escape to go back \
+                               (assists are flagged on results)")?;
+        }
+        Scene::Stats => stats::draw(ctx, state.profile.get_stats())?,
+        Scene::SeedEntry => {
+            if let Some(keyboard) = &state.keyboard {
+                keyboard.draw(ctx)?;
+            }
+        }
+        Scene::CustomGame => {
+            if let Some(keyboard) = &state.keyboard {
+                keyboard.draw(ctx)?;
+            } else {
+                // List the rules, then the mutators, then what can be done with them
+                let mut items: Vec<String> = RuleItem::ALL.iter()
+                    .map(|item| format!("{:<10} {:<28} {}", "Rule", item.get_label(),
+                                        state.ruleset.describe(*item)))
+                    .collect();
+                items.extend(Modifier::MUTATORS.iter().map(|modifier| {
+                    let status = if state.modifiers.has_kind(modifier) { "on" } else { "off" };
+                    return format!("{:<10} {:<28} {}", "Mutator", modifier.get_description(), status);
+                }));
+                items.extend(CustomGameAction::ALL.iter().map(|action| match action {
+                    CustomGameAction::LoadPreset => match state.presets.get(state.preset_selection) {
+                        Some(preset) => format!("{} < {} >", action.get_label(), preset.get_name()),
+                        None => format!("{} (none saved)", action.get_label()),
+                    },
+                    _ => String::from(action.get_label()),
+                }));
+                scenes::draw_menu(ctx, "Custom Game", &items, state.menu_selection,
+                                  "Up/Down to select, Left/Right to change, Enter to choose, Escape to go \
+                                   back (custom rules don't earn medals)")?;
+            }
+        }
+        _ => ships::draw_class_select(ctx, state.class_selection, state.mode, &state.modifiers)?,
+    }
+
+    return Ok(());
+}
+
+/// Draw the arena and everything in it, timing the parts of it with the given stopwatch
+fn draw_arena(state: &mut MainState, ctx: &mut Context, stopwatch: &mut Stopwatch) -> GameResult {
+    // Draw the arena and everything in it through the camera following the player, thrown by the shake of the
+    // view while the game is being played, leaving the text where it is
+    state.camera.follow(&state.player.position, &state.world);
+    let offset = if state.scene == Scene::Playing {
+        state.shake.get_offset(state.rng.cosmetic())
+    } else {
+        Vec2::new(0.0, 0.0)
+    };
+    graphics::push_transform(ctx, Some(state.camera.get_transform(&offset).to_matrix()));
+    graphics::apply_transformations(ctx)?;
+    // Draw the edges of the arena, the floor, obstacles, capture zones and teleporters underneath everything else
+    state.world.draw(ctx)?;
+    for zone in &state.floor {
+        zone.draw(ctx)?;
+    }
+    for obstacle in &state.obstacles {
+        obstacle.draw(ctx)?;
+    }
+    for zone in &state.zones {
+        zone.draw(ctx)?;
+    }
+    if let Some(gauntlet) = &state.gauntlet {
+        gauntlet.draw(ctx)?;
+    }
+    for teleporter in &state.teleporters {
+        teleporter.draw(ctx)?;
+    }
+    // Mark where the scheduled spawns are about to arrive
+    state.spawns.draw(ctx)?;
+    stopwatch.lap("arena");
+    // Draw the player
+    render::draw(ctx, &state.player, &state.assets)?;
+    // Draw all the actors in view, hiding enemies the player can't see if their vision is limited
+    let vision_radius = state.modifiers.get_vision_radius();
+    for actor in &state.actors {
+        if !state.camera.is_visible(actor.get_position(), actor.get_radius()) {
+            continue;
+        }
+        if let Some(radius) = vision_radius {
+            let visible = vision::is_visible(&state.player.position, radius, actor.get_position(),
+                                             actor.get_radius());
+            if !visible && actor.get_team() != Team::Player {
+                continue;
+            }
+        }
+        render::draw_actor(ctx, actor.as_ref(), state.actor_renderer.as_ref(), &state.assets)?;
+    }
+    for explosion in &state.explosions {
+        explosion.draw(ctx, &state.assets)?;
+    }
+    state.particles.draw(ctx)?;
+    stopwatch.lap("actors");
+    // Cover whatever is beyond the player's vision with fog, underneath the text
+    if let Some(radius) = vision_radius {
+        vision::draw_fog(ctx, &state.player.position, radius, state.world.get_size())?;
+    }
+    // Draw the hitboxes, vectors, and IDs of the player and every actor if the debug overlay is enabled
+    if state.show_debug_overlay {
+        debug_overlay::draw_actor(ctx, &state.player)?;
+        for actor in &state.actors {
+            debug_overlay::draw_actor(ctx, actor.as_ref())?;
+        }
+    }
+    graphics::pop_transform(ctx);
+    graphics::apply_transformations(ctx)?;
+
+    return Ok(());
+}
+
+/// Draw the status of the run and the screens laid over the arena
+fn draw_interface(state: &MainState, ctx: &mut Context) -> GameResult {
+    // Draw the score, the number of checkpoint restarts left, and whether the hack is ready
+    let hack = if state.hack_cooldown.is_ready() {
+        String::from("ready")
+    } else {
+        format!("{:.0}s", state.hack_cooldown.get_remaining())
+    };
+    let mut status = format!("Score: {}   Wave: {}   Restarts: {}   Hack (H): {}",
+                             state.score, state.wave, state.checkpoint_restarts, hack);
+    if state.mode == GameMode::Defense {
+        status.push_str(&format!("   Credits: {}   Build turret (T): {}", state.credits, DEFENSE_TURRET_COST));
+    }
+    let score = graphics::Text::new(status);
+    graphics::draw(ctx, &score, ([10.0, 10.0], graphics::WHITE))?;
+    // Draw the boss's health at the top of the screen
+    state.boss_bar.draw(ctx)?;
+    // Tell the player where the last bug report was saved
+    if let Some(report) = &state.bug_report {
+        let message = match report {
+            Ok(path) => format!("Bug report saved to {}", path.display()),
+            Err(error) => format!("Could not save the bug report: {}", error),
+        };
+        let (_, height) = state.camera.get_view_size();
+        graphics::draw(ctx, &graphics::Text::new(message), ([10.0, height - 50.0], graphics::WHITE))?;
+    }
+    // Tell the player how the last quicksave or quickload went
+    if let Some(message) = &state.save_status {
+        let (_, height) = state.camera.get_view_size();
+        graphics::draw(ctx, &graphics::Text::new(message.as_str()), ([10.0, height - 70.0], graphics::WHITE))?;
+    }
+    // Announce the active global event
+    state.events.draw(ctx)?;
+
+    // Tell the player how the run ended and what they can do next
+    if state.scene == Scene::GameOver {
+        let heading = match state.outcome {
+            Some(outcome) => state.mode.get_outcome_message(outcome),
+            None => "Game over! You ran out of restarts.",
+        };
+        let assists = if state.modifiers.get_assist_descriptions().is_empty() { "" } else { ", assists on" };
+        let medal = state.medal.map_or("none", |medal| medal.get_name());
+        let mut details = vec![
+            format!("Final score: {}   Wave: {}   Time: {:.0}s", state.score, state.wave, state.get_time()),
+            format!("Medal: {} ({})", medal, state.get_level().get_par(state.mode).describe()),
+            format!("{} mode, seed {}{}", state.mode.get_name(), state.rng.get_seed(), assists),
+        ];
+        if state.outcome.is_some() {
+            details.push(match &state.result_card {
+                None => String::from("Press S to save a result card."),
+                Some(Ok(path)) => format!("Result card saved to {}.", path),
+                Some(Err(error)) => format!("Could not save the result card: {}", error),
+            });
+        }
+        let high_scores: Vec<String> = state.profile.get_high_scores(state.mode.get_name()).iter()
+            .map(|high| format!("{:<3} {}", high.initials, high.score))
+            .collect();
+        if !high_scores.is_empty() {
+            details.push(format!("High scores: {}", high_scores.join("   ")));
+        }
+        match &state.keyboard {
+            Some(keyboard) => keyboard.draw(ctx)?,
+            None => scenes::draw_game_over(ctx, heading, &details)?,
+        }
+    }
+
+    // Draw the designer overlay if it is enabled
+    if state.show_telemetry {
+        let intensity = state.director.get_intensity();
+        let reinforcements = state.director.get_reinforcements_left() as f32;
+        let reduced_rate = state.scheduler.get_reduced_count() as f32;
+        let parked = state.streamer.get_parked_count() as f32;
+        state.telemetry.draw_overlay(ctx, &[
+            ("Director intensity", intensity, intensity / 2.0),
+            ("Reinforcements left", reinforcements, reinforcements / 10.0),
+            ("Reduced-rate actors", reduced_rate, reduced_rate / state.actors.len().max(1) as f32),
+            ("Parked actors", parked, parked / (parked + state.actors.len() as f32).max(1.0)),
+        ])?;
+    }
+    // Draw the perk choice on top of the game if a wave has just been cleared
+    if let Some(choices) = &state.perk_choices {
+        perks::draw_perk_choice(ctx, state.wave, choices)?;
+    }
+    // Draw the mod list on top of the game if it is open
+    if let Some(selected) = state.mod_list_selection {
+        state.mods.draw(ctx, selected)?;
+    }
+    // Draw the spawn console on top of the game if it is open
+    if let Some(selected) = state.spawn_console_selection {
+        state.registry.draw_console(ctx, selected)?;
+    }
+    // Draw the pause screen on top of everything
+    if state.scene == Scene::Paused {
+        let ticks_back = state.rewind.as_ref().and_then(|rewind| rewind.get_ticks_back());
+        scenes::draw_paused(ctx, state.focus_paused, ticks_back, state.tick_rate)?;
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,51 @@
+use ggez::{Context, GameResult, graphics};
+
+use crate::Point;
+
+/// Width of the band over which the fog thickens at the edge of the player's vision
+const FADE_WIDTH: f32 = 80.0;
+/// Number of rings the fading band is drawn with
+const FADE_STEPS: u32 = 4;
+/// Opacity of the fog beyond the player's vision, which dims the turrets hidden in it
+const FOG_ALPHA: f32 = 0.85;
+
+/// Check if something of the given radius at the given position can be seen by a player at the given center
+/// who sees within the given radius, counting anything in the fading band as seen
+pub fn is_visible(center: &Point, radius: f32, position: &Point, object_radius: f32) -> bool {
+    return center.distance_to(position) - object_radius < radius + FADE_WIDTH;
+}
+
+/// Draw the fog over everything beyond the given radius of the given center, thickening across a fading band
+pub fn draw_fog(ctx: &mut Context, center: &Point, radius: f32, bounds: (f32, f32)) -> GameResult {
+    // Each ring is a thick circle outline covering the band from its inner to its outer radius
+    let ring = |ctx: &mut Context, inner: f32, outer: f32, alpha: f32| -> GameResult {
+        let width = outer - inner;
+        let mesh = graphics::Mesh::new_circle(
+            ctx,
+            graphics::DrawMode::stroke(width),
+            [0.0, 0.0],
+            inner + width / 2.0,
+            0.5,
+            graphics::Color::new(0.0, 0.0, 0.0, alpha),
+        )?;
+        graphics::draw(ctx, &mesh, ([center.x, center.y],))?;
+        return Ok(());
+    };
+
+    // Thicken the fog across the fading band
+    let step_width = FADE_WIDTH / FADE_STEPS as f32;
+    for step in 0..FADE_STEPS {
+        let inner = radius + step as f32 * step_width;
+        ring(ctx, inner, inner + step_width, FOG_ALPHA * (step + 1) as f32 / (FADE_STEPS + 1) as f32)?;
+    }
+
+    // Cover the rest of the arena, out past its farthest corner from the center
+    let (width, height) = bounds;
+    let farthest = (center.x.max(width - center.x)).hypot(center.y.max(height - center.y));
+    let fog_start = radius + FADE_WIDTH;
+    if farthest > fog_start {
+        ring(ctx, fog_start, farthest + 1.0, FOG_ALPHA)?;
+    }
+
+    return Ok(());
+}
@@ -0,0 +1,274 @@
+use std::f32::consts::PI;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{Cooldown, Damage, Point, ShipWeapon, Shot, StatusEffect, Team, Timer, Tuning, Velocity, World};
+use crate::jitter::Jitter;
+use crate::saves::{SavedWeapon, WeaponData};
+
+/// A gun that an Actor fires. The holder decides when to pull the trigger; the weapon decides how often it can
+/// fire and what comes out of it
+pub trait Weapon {
+    /// Get the name of this Weapon, which the run statistics count shots under
+    fn get_name(&self) -> &str;
+
+    /// Fire this Weapon from the given muzzle, starting its cooldown and returning the new shots
+    fn fire(&mut self, muzzle: &Muzzle) -> Vec<Shot>;
+
+    /// Create a boxed copy of this Weapon
+    fn clone_weapon(&self) -> Box<dyn Weapon>;
+
+    /// Advance the cooldown of this Weapon by the given time
+    fn update(&mut self, _dt: f32) {}
+
+    /// Check if this Weapon can fire
+    fn is_ready(&self) -> bool;
+
+    /// Seed any random variation of this Weapon, so that it is the same every time a run is replayed
+    fn seed(&mut self, _seed: u64) {}
+
+    /// Apply a new balance to this Weapon, if it keeps any values from the tuning
+    fn retune(&mut self, _tuning: &Tuning) {}
+
+    /// Get the saved state of this Weapon, or none if it can't be saved
+    fn to_saved(&self) -> Option<SavedWeapon> {
+        return None;
+    }
+}
+
+impl Clone for Box<dyn Weapon> {
+    fn clone(&self) -> Box<dyn Weapon> {
+        return self.clone_weapon();
+    }
+}
+
+impl Serialize for Box<dyn Weapon> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        return match self.to_saved() {
+            Some(saved) => saved.serialize(serializer),
+            None => Err(serde::ser::Error::custom(format!("the {} can't be saved", self.get_name()))),
+        };
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn Weapon> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Box<dyn Weapon>, D::Error> {
+        return Ok(SavedWeapon::deserialize(deserializer)?.into_weapon());
+    }
+}
+
+/// Where a Weapon is fired from, and the changes its holder makes to every shot
+pub struct Muzzle {
+    pub(crate) position: Point,
+    pub(crate) world: World,
+    pub(crate) radius: f32, // Shots start just outside this distance from the position
+    pub(crate) shot_radius: f32,
+    pub(crate) velocity: Velocity, // Velocity of the holder, whose heading is where it is aiming
+    pub(crate) team: Team,
+    pub(crate) effect: Option<StatusEffect>,
+    pub(crate) damage_multiplier: f32,
+    pub(crate) ricochets: u32,
+    pub(crate) extra_shots: u32,
+}
+
+impl Muzzle {
+    /// Create a muzzle at the given position for a holder of the given radius, aiming at the given heading. Its
+    /// shots are the size of a shot in the default balance until the holder sizes them by its own
+    pub fn new(position: &Point, world: &World, radius: f32, heading: f32, team: Team) -> Muzzle {
+        return Muzzle {
+            position: *position,
+            world: world.clone(),
+            radius,
+            shot_radius: Tuning::DEFAULT.shot_radius,
+            velocity: Velocity::new(0.0, heading),
+            team,
+            effect: None,
+            damage_multiplier: 1.0,
+            ricochets: 0,
+            extra_shots: 0,
+        };
+    }
+
+    /// Get the position of the holder of this muzzle
+    pub fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the heading this muzzle is aimed at
+    pub fn get_heading(&self) -> f32 {
+        return self.velocity.heading;
+    }
+
+    /// Get the team of the holder of this muzzle
+    pub fn get_team(&self) -> Team {
+        return self.team;
+    }
+
+    /// Create a shot leaving this muzzle with the given velocity, damage, and lifespan
+    pub fn shot(&self, velocity: Velocity, damage: Damage, lifespan: f32) -> Shot {
+        let mut position = self.position;
+        position.move_distance(self.radius + self.shot_radius, velocity.heading);
+        let damage = Damage::new(damage.get_amount() * self.damage_multiplier, damage.get_kind());
+        let mut shot = Shot::new(position, &self.world, velocity, damage, lifespan, self.effect.clone(), self.team);
+        shot.radius = self.shot_radius;
+        shot.ricochets = self.ricochets;
+        return shot;
+    }
+}
+
+/// One of the guns the player's ship can be armed with, which fires whenever the trigger is pulled and its
+/// cooldown from the tuning has passed
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Gun {
+    kind: ShipWeapon,
+    cooldown: Cooldown,
+}
+
+impl Gun {
+    /// Create a new gun of the given kind, with the cooldown of the given balance
+    pub fn new(kind: ShipWeapon, tuning: &Tuning) -> Gun {
+        return Gun { kind, cooldown: Cooldown::new(tuning.player_fire_cooldown) };
+    }
+}
+
+impl Weapon for Gun {
+    /// Get the name of the kind of this Gun
+    fn get_name(&self) -> &str {
+        return self.kind.get_name();
+    }
+
+    /// Fire this Gun forwards, adding the speed of the holder to its shots
+    fn fire(&mut self, muzzle: &Muzzle) -> Vec<Shot> {
+        // Get the extra speed, damage, lifespan, and angles of the shots fired by the gun
+        let (extra_speed, damage, lifespan, angles): (f32, f32, f32, &[f32]) = match self.kind {
+            ShipWeapon::Cannon => (200.0, 20.0, 5.0, &[0.0]),
+            ShipWeapon::Needler => (350.0, 12.0, 3.0, &[0.0]),
+            ShipWeapon::Scatter => (200.0, 15.0, 2.0, &[-0.2, 0.0, 0.2]),
+        };
+
+        self.cooldown.trigger();
+
+        // Add any extra shots from the holder, alternating either side of the gun's normal shots
+        let mut angles = angles.to_vec();
+        let widest = angles.iter().cloned().fold(0.0, f32::max);
+        for i in 0..muzzle.extra_shots {
+            let offset = widest + 0.15 * (i / 2 + 1) as f32;
+            angles.push(if i % 2 == 0 { offset } else { -offset });
+        }
+
+        return angles.into_iter().map(|angle| {
+            let mut velocity = muzzle.velocity.clone();
+            velocity.speed += extra_speed;
+            velocity.heading += angle;
+            muzzle.shot(velocity, Damage::kinetic(damage), lifespan)
+        }).collect();
+    }
+
+    /// Count down the cooldown of this Gun
+    fn update(&mut self, dt: f32) {
+        self.cooldown.update(dt);
+    }
+
+    /// Check if the cooldown of this Gun has passed since it last fired
+    fn is_ready(&self) -> bool {
+        return self.cooldown.is_ready();
+    }
+
+    /// Change the cooldown of this Gun to that of the new balance
+    fn retune(&mut self, tuning: &Tuning) {
+        self.cooldown.set_duration(tuning.player_fire_cooldown);
+    }
+
+    /// Create a boxed copy of this Gun
+    fn clone_weapon(&self) -> Box<dyn Weapon> {
+        return Box::new(self.clone());
+    }
+
+    /// Get the saved state of this Gun
+    fn to_saved(&self) -> Option<SavedWeapon> {
+        return Some(SavedWeapon(WeaponData::Gun(self.clone())));
+    }
+}
+
+/// A turret's cannons, which fire a ring of evenly spaced shots every time the fire interval passes. The interval,
+/// shot speed, and damage come from the tuning, and the interval and speed are varied by a jitter
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Volley {
+    shots: u32,
+    fire_interval: f32, // Seconds between volleys before the jitter
+    shot_speed: f32, // Pixels per second before the jitter
+    shot_damage: f32,
+    fire_timer: Timer,
+    loaded: bool,
+    jitter: Jitter,
+}
+
+impl Volley {
+    /// Create a new volley of the given number of shots with the given balance, varied by the given jitter
+    pub fn new(shots: u32, jitter: Jitter, tuning: &Tuning) -> Volley {
+        return Volley {
+            shots,
+            fire_interval: tuning.turret_fire_interval,
+            shot_speed: tuning.turret_shot_speed,
+            shot_damage: tuning.turret_shot_damage,
+            fire_timer: Timer::looping(tuning.turret_fire_interval),
+            loaded: false,
+            jitter,
+        };
+    }
+}
+
+impl Weapon for Volley {
+    /// Get the name of this Volley
+    fn get_name(&self) -> &str {
+        return "Volley";
+    }
+
+    /// Fire a ring of shots, all at the same (jittered) speed, and vary the time until the next volley
+    fn fire(&mut self, muzzle: &Muzzle) -> Vec<Shot> {
+        let speed = self.jitter.next_shot_speed(self.shot_speed);
+        let shots: Vec<Shot> = (0..self.shots).map(|i| {
+            let heading = muzzle.velocity.heading + i as f32 * (2.0 * PI / self.shots as f32);
+            muzzle.shot(Velocity::new(speed, heading), Damage::energy(self.shot_damage), 3.0)
+        }).collect();
+
+        self.loaded = false;
+        self.fire_timer.set_duration(self.jitter.next_fire_interval(self.fire_interval));
+        return shots;
+    }
+
+    /// Create a boxed copy of this Volley
+    fn clone_weapon(&self) -> Box<dyn Weapon> {
+        return Box::new(self.clone());
+    }
+
+    /// Count down to the next volley
+    fn update(&mut self, dt: f32) {
+        if self.fire_timer.update(dt) {
+            self.loaded = true;
+        }
+    }
+
+    /// Check if the fire interval has passed since the last volley
+    fn is_ready(&self) -> bool {
+        return self.loaded;
+    }
+
+    /// Seed the jitter of this Volley, and vary the time until its first volley
+    fn seed(&mut self, seed: u64) {
+        self.jitter.seed(seed);
+        self.fire_timer.set_duration(self.jitter.next_fire_interval(self.fire_interval));
+    }
+
+    /// Fire at the interval and with the shots of the new balance from the next volley on
+    fn retune(&mut self, tuning: &Tuning) {
+        self.fire_interval = tuning.turret_fire_interval;
+        self.shot_speed = tuning.turret_shot_speed;
+        self.shot_damage = tuning.turret_shot_damage;
+    }
+
+    /// Get the saved state of this Volley
+    fn to_saved(&self) -> Option<SavedWeapon> {
+        return Some(SavedWeapon(WeaponData::Volley(self.clone())));
+    }
+}
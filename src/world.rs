@@ -0,0 +1,397 @@
+use std::f32::consts::PI;
+
+use ggez::{Context, GameResult, graphics};
+use serde::{Deserialize, Serialize};
+
+use crate::{Point, Vec2, Velocity};
+
+/// How far inside the far edge of a round or polygonal arena a point that wraps around lands, so rounding doesn't
+/// leave it outside to wrap straight back
+const WRAP_INSET: f32 = 1.0;
+/// Number of straight lines the edge of a round arena is drawn with
+const CIRCLE_SEGMENTS: usize = 96;
+/// Width of the lines the edges of the arena are drawn with
+const EDGE_WIDTH: f32 = 2.0;
+
+/// What happens to an actor that reaches an edge of the arena
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EdgeBehavior {
+    /// The actor stops at the edge
+    Clamp,
+    /// The actor comes back in on the opposite side of the arena
+    Wrap,
+    /// The actor stops at the edge and its velocity is reflected back into the arena
+    Bounce,
+    /// The actor stops at the edge and is destroyed
+    Kill,
+}
+
+/// Shape of the play area, which fills the arena's size
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ArenaShape {
+    /// The whole rectangle of the arena. Its edges are numbered top, right, bottom, left
+    Rect,
+    /// The largest circle that fits in the middle of the arena, which has a single edge
+    Circle,
+    /// A polygon through the given corners, each given as fractions of the arena's width and height. Edge `i` runs
+    /// from corner `i` to the next one
+    Polygon { corners: Vec<(f32, f32)> },
+}
+
+impl EdgeBehavior {
+    /// Get the color edges with this behavior are drawn in
+    fn get_color(self) -> graphics::Color {
+        return match self {
+            EdgeBehavior::Clamp => graphics::Color::new(0.6, 0.6, 0.6, 1.0),
+            EdgeBehavior::Wrap => graphics::Color::new(0.4, 0.6, 1.0, 1.0),
+            EdgeBehavior::Bounce => graphics::Color::new(1.0, 0.85, 0.3, 1.0),
+            EdgeBehavior::Kill => graphics::Color::new(1.0, 0.3, 0.3, 1.0),
+        };
+    }
+}
+
+impl Default for ArenaShape {
+    fn default() -> ArenaShape {
+        return ArenaShape::Rect;
+    }
+}
+
+/// Play area the actors move in: a size, the shape that fills it, and what each of the shape's edges does to
+/// actors that reach it
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct World {
+    size: (f32, f32),
+    shape: ArenaShape,
+    /// Behavior of each edge in order. Edges past the end of the list behave like the last one, and every edge
+    /// clamps if the list is empty
+    #[serde(default)]
+    edges: Vec<EdgeBehavior>,
+}
+
+impl World {
+    /// Create a rectangular world of the given size whose edges stop the actors that reach them
+    pub const fn rect(width: f32, height: f32) -> World {
+        return World { size: (width, height), shape: ArenaShape::Rect, edges: Vec::new() };
+    }
+
+    /// Create a world of the given size and shape, with the given behavior for each of its edges
+    pub fn new(size: (f32, f32), shape: ArenaShape, edges: Vec<EdgeBehavior>) -> World {
+        return World { size, shape, edges };
+    }
+
+    /// Get the width and height of the rectangle this world fills
+    pub fn get_size(&self) -> (f32, f32) {
+        return self.size;
+    }
+
+    /// Change the size of the rectangle this world fills, stretching its shape to match
+    pub fn resize(&mut self, size: (f32, f32)) {
+        self.size = size;
+    }
+
+    /// Get the shape of this world
+    pub fn get_shape(&self) -> &ArenaShape {
+        return &self.shape;
+    }
+
+    /// Get the number of edges the shape of this world has
+    pub fn get_edge_count(&self) -> usize {
+        return match &self.shape {
+            ArenaShape::Rect => 4,
+            ArenaShape::Circle => 1,
+            ArenaShape::Polygon { corners } => corners.len(),
+        };
+    }
+
+    /// Get what the edge with the given number does to actors that reach it
+    pub fn get_edge_behavior(&self, edge: usize) -> EdgeBehavior {
+        return self.edges.get(edge).or_else(|| self.edges.last()).copied().unwrap_or(EdgeBehavior::Clamp);
+    }
+
+    /// Get the points around the edge of this world in order, for drawing it. Circles are traced with the given
+    /// number of points
+    fn get_outline(&self, segments: usize) -> Vec<Point> {
+        let (width, height) = self.size;
+        return match &self.shape {
+            ArenaShape::Rect => vec![
+                Point::new(0.0, 0.0), Point::new(width, 0.0), Point::new(width, height), Point::new(0.0, height),
+            ],
+            ArenaShape::Circle => {
+                let (center, radius) = self.get_circle();
+                (0..segments.max(3))
+                    .map(|i| &center + &(Vec2::from_heading(i as f32 * 2.0 * PI / segments.max(3) as f32) * radius))
+                    .collect()
+            }
+            ArenaShape::Polygon { .. } => self.get_corners(),
+        };
+    }
+
+    /// Check if the given point is inside this world, counting its edges as inside
+    pub fn contains(&self, point: &Point) -> bool {
+        let (width, height) = self.size;
+        return match &self.shape {
+            ArenaShape::Rect => point.x <= width && point.x >= 0.0 && point.y <= height && point.y >= 0.0,
+            ArenaShape::Circle => {
+                let (center, radius) = self.get_circle();
+                point.distance_to(&center) <= radius
+            }
+            ArenaShape::Polygon { .. } => {
+                // Count how many edges a line running right from the point crosses; it is inside if that is odd
+                let corners = self.get_corners();
+                let mut inside = false;
+                for (start, end) in get_edges(&corners) {
+                    if (start.y > point.y) != (end.y > point.y) {
+                        let crossing = start.x + (point.y - start.y) / (end.y - start.y) * (end.x - start.x);
+                        if point.x < crossing {
+                            inside = !inside;
+                        }
+                    }
+                }
+                inside
+            }
+        };
+    }
+
+    /// Get the number of the edge the given point has crossed to leave this world, or none if it is inside. A point
+    /// past a corner of a rectangle is counted as crossing whichever of the two edges it is further past
+    pub fn find_edge(&self, point: &Point) -> Option<usize> {
+        if self.contains(point) {
+            return None;
+        }
+        return Some(match &self.shape {
+            ArenaShape::Rect => {
+                let (width, height) = self.size;
+                let past_x = (-point.x).max(point.x - width);
+                let past_y = (-point.y).max(point.y - height);
+                if past_x > past_y {
+                    if point.x < 0.0 { 3 } else { 1 }
+                } else if point.y < 0.0 {
+                    0
+                } else {
+                    2
+                }
+            }
+            ArenaShape::Circle => 0,
+            ArenaShape::Polygon { .. } => self.find_closest_edge(point).0,
+        });
+    }
+
+    /// Move the given point to the closest place inside this world if it has left
+    pub fn clamp(&self, point: &mut Point) {
+        if let Some(clamped) = self.find_closest_inside(point) {
+            *point = clamped.0;
+        }
+    }
+
+    /// Move the given point to the opposite side of this world if it has left
+    pub fn wrap(&self, point: &mut Point) {
+        let (width, height) = self.size;
+        match &self.shape {
+            ArenaShape::Rect => {
+                if point.x > width {point.x = 0.0}
+                else if point.x < 0.0 {point.x = width}
+
+                if point.y > height {point.y = 0.0}
+                else if point.y < 0.0 {point.y = height}
+            }
+            ArenaShape::Circle => {
+                let (center, radius) = self.get_circle();
+                if point.distance_to(&center) > radius {
+                    *point = &center - &((&*point - &center).normalize() * (radius - WRAP_INSET));
+                }
+            }
+            ArenaShape::Polygon { .. } => {
+                let (exit, _) = match self.find_closest_inside(point) {
+                    Some(clamped) => clamped,
+                    None => return,
+                };
+                // Come back in where a line from the exit through the middle of the arena leaves it again
+                let corners = self.get_corners();
+                let middle = corners.iter().fold(Vec2::zero(), |sum, corner| sum + *corner)
+                    * (1.0 / corners.len() as f32);
+                let across = &middle - &exit;
+                let far = get_edges(&corners)
+                    .filter_map(|(start, end)| intersect(&exit, &across, start, end))
+                    .fold(0.0_f32, f32::max);
+                if far > 0.0 {
+                    let entry = &exit + &(&across * far);
+                    *point = &entry - &(across.normalize() * WRAP_INSET);
+                }
+            }
+        }
+    }
+
+    /// Move the given point back inside this world if it has left, and reflect the given velocity off whatever
+    /// edges it crossed, whatever those edges do
+    pub fn bounce(&self, point: &mut Point, velocity: &mut Velocity) {
+        match &self.shape {
+            ArenaShape::Rect => {
+                let (width, height) = self.size;
+                if point.x < 0.0 || point.x > width {
+                    velocity.heading = PI - velocity.heading;
+                }
+                if point.y < 0.0 || point.y > height {
+                    velocity.heading = -velocity.heading;
+                }
+                self.clamp(point);
+            }
+            _ => {
+                if let Some((clamped, normal)) = self.find_closest_inside(point) {
+                    reflect(velocity, &normal);
+                    *point = clamped;
+                }
+            }
+        }
+    }
+
+    /// Bring the given point of an actor back inside this world if it has left, doing what the edge it crossed
+    /// does, and reflecting the given velocity of the actor off that edge if it bounces. Returns whether the edge
+    /// destroys the actor. Each side of a rectangle acts on its own, so an actor can leave across a corner
+    pub fn confine(&self, point: &mut Point, velocity: &mut Velocity) -> bool {
+        if let ArenaShape::Rect = self.shape {
+            let (width, height) = self.size;
+            let mut killed = false;
+            // Apply what the crossed side along each axis does, mirroring the heading along that axis for bounces
+            let mut cross = |edge: usize, position: &mut f32, max: f32, heading: &mut f32, mirror: fn(f32) -> f32| {
+                match self.get_edge_behavior(edge) {
+                    EdgeBehavior::Wrap => *position = if *position > max { 0.0 } else { max },
+                    behavior => {
+                        *position = position.max(0.0).min(max);
+                        if behavior == EdgeBehavior::Bounce {
+                            *heading = mirror(*heading);
+                        }
+                        killed |= behavior == EdgeBehavior::Kill;
+                    }
+                }
+            };
+            if point.x > width || point.x < 0.0 {
+                let edge = if point.x < 0.0 { 3 } else { 1 };
+                cross(edge, &mut point.x, width, &mut velocity.heading, |heading| PI - heading);
+            }
+            if point.y > height || point.y < 0.0 {
+                let edge = if point.y < 0.0 { 0 } else { 2 };
+                cross(edge, &mut point.y, height, &mut velocity.heading, |heading| -heading);
+            }
+            return killed;
+        }
+
+        let edge = match self.find_edge(point) {
+            Some(edge) => edge,
+            None => return false,
+        };
+        let behavior = self.get_edge_behavior(edge);
+        match behavior {
+            EdgeBehavior::Wrap => self.wrap(point),
+            EdgeBehavior::Bounce => self.bounce(point, velocity),
+            EdgeBehavior::Clamp | EdgeBehavior::Kill => self.clamp(point),
+        }
+        return behavior == EdgeBehavior::Kill;
+    }
+
+    /// Draw the edges of this world in the colors of their behaviors, unless it is a rectangle that only stops
+    /// actors at the edges of the window
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        if self.shape == ArenaShape::Rect && (0..4).all(|edge| self.get_edge_behavior(edge) == EdgeBehavior::Clamp) {
+            return Ok(());
+        }
+        let outline = self.get_outline(CIRCLE_SEGMENTS);
+        if self.shape == ArenaShape::Circle {
+            let color = self.get_edge_behavior(0).get_color();
+            let circle = graphics::Mesh::new_polygon(ctx, graphics::DrawMode::stroke(EDGE_WIDTH), &outline, color)?;
+            graphics::draw(ctx, &circle, graphics::DrawParam::default())?;
+            return Ok(());
+        }
+        for (edge, (start, end)) in get_edges(&outline).enumerate() {
+            let color = self.get_edge_behavior(edge).get_color();
+            let line = graphics::Mesh::new_line(ctx, &[*start, *end], EDGE_WIDTH, color)?;
+            graphics::draw(ctx, &line, graphics::DrawParam::default())?;
+        }
+        return Ok(());
+    }
+
+    /// Get the center and radius of this world's circle, which fits in the middle of its size
+    fn get_circle(&self) -> (Point, f32) {
+        let (width, height) = self.size;
+        return (Point::new(width / 2.0, height / 2.0), width.min(height) / 2.0);
+    }
+
+    /// Get the corners of this world's polygon, stretched to its size
+    fn get_corners(&self) -> Vec<Point> {
+        let (width, height) = self.size;
+        return match &self.shape {
+            ArenaShape::Polygon { corners } => corners.iter().map(|(x, y)| Point::new(x * width, y * height)).collect(),
+            _ => Vec::new(),
+        };
+    }
+
+    /// Get the number of the edge of this world's polygon closest to the given point, and the closest point on it
+    fn find_closest_edge(&self, point: &Point) -> (usize, Point) {
+        let corners = self.get_corners();
+        return get_edges(&corners)
+            .map(|(start, end)| {
+                let along = end - start;
+                let length_squared = along.dot(&along);
+                let fraction = if length_squared > 0.0 {
+                    ((point - start).dot(&along) / length_squared).max(0.0).min(1.0)
+                } else {
+                    0.0
+                };
+                start.lerp(end, fraction)
+            })
+            .enumerate()
+            .min_by(|(_, first), (_, second)| {
+                point.distance_to(first).partial_cmp(&point.distance_to(second)).unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .unwrap_or((0, *point));
+    }
+
+    /// Get the closest point inside this world to the given point, and the direction from there out to the given
+    /// point, or none if the point is already inside
+    fn find_closest_inside(&self, point: &Point) -> Option<(Point, Vec2)> {
+        if self.contains(point) {
+            return None;
+        }
+        let clamped = match &self.shape {
+            ArenaShape::Rect => {
+                let (width, height) = self.size;
+                Point::new(point.x.max(0.0).min(width), point.y.max(0.0).min(height))
+            }
+            ArenaShape::Circle => {
+                let (center, radius) = self.get_circle();
+                &center + &((point - &center).normalize() * radius)
+            }
+            ArenaShape::Polygon { .. } => self.find_closest_edge(point).1,
+        };
+        let normal = (point - &clamped).normalize();
+        return Some((clamped, normal));
+    }
+}
+
+/// Get the edges of a closed polygon through the given corners, as the start and end of each
+fn get_edges(corners: &[Point]) -> impl Iterator<Item = (&Point, &Point)> {
+    return corners.iter().zip(corners.iter().cycle().skip(1));
+}
+
+/// Get how many times the given direction the line from the given origin goes before it crosses the edge from
+/// the given start to the given end, if it does
+fn intersect(origin: &Point, direction: &Vec2, start: &Point, end: &Point) -> Option<f32> {
+    let edge = end - start;
+    let cross = |a: &Vec2, b: &Vec2| a.x * b.y - a.y * b.x;
+    let denominator = cross(direction, &edge);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let offset = start - origin;
+    let along_line = cross(&offset, &edge) / denominator;
+    let along_edge = cross(&offset, direction) / denominator;
+    return if (0.0..=1.0).contains(&along_edge) { Some(along_line) } else { None };
+}
+
+/// Reflect the given velocity off a wall whose outward direction is the given normal
+fn reflect(velocity: &mut Velocity, normal: &Vec2) {
+    let vector = velocity.to_vector();
+    let reflected = &vector - &(normal * (2.0 * vector.dot(normal)));
+    velocity.heading = reflected.get_heading();
+}
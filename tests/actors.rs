@@ -0,0 +1,634 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use turrets::{
+    Actor, ActorKind, ArenaShape, CollisionLayer, CollisionRules, ContactResponse, Damage, EdgeBehavior, GameEvent,
+    GameMode, Health, Hitbox, MainState, Muzzle, PickupKind, Point, Render, Resistances, Shot, ShipClass,
+    StatusEffect, Team, Tuning, Turret, Vec2, Velocity, Weapon, World,
+};
+use turrets::scripting::{FirePattern, ScriptCommand};
+
+/// Size of the arena the tests play in
+const BOUNDS: (f32, f32) = (800.0, 600.0);
+/// Arena of that size, for actors created outside of a game
+const WORLD: World = World::rect(BOUNDS.0, BOUNDS.1);
+
+/// Mine drifting slowly to the right, defined outside the game the way an embedder would
+#[derive(Clone)]
+struct Mine {
+    id: u32,
+    position: Point,
+    velocity: Velocity,
+    health: f32,
+}
+
+impl Mine {
+    /// Create a new Mine at the given position, which is given its ID when it is added to a game
+    fn new(position: Point) -> Mine {
+        return Mine { id: 0, position, velocity: Velocity::new(30.0, 0.0), health: 10.0 };
+    }
+}
+
+impl Actor for Mine {
+    /// Get the ID of this Mine
+    fn get_id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Give this Mine the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
+    /// Get the radius of this Mine
+    fn get_radius(&self) -> f32 {
+        return 8.0;
+    }
+
+    /// Get the position of this Mine
+    fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the kind of this Mine
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Custom("mine");
+    }
+
+    /// Drift along
+    fn update(&mut self, dt: f32) {
+        self.position.move_time(dt, &self.velocity);
+    }
+
+    /// Get the damage this Mine does when it is touched
+    fn get_damage(&self) -> Damage {
+        return Damage::explosive(20.0);
+    }
+
+    /// Do damage to this Mine
+    fn do_damage(&mut self, damage: Damage) -> f32 {
+        self.health -= damage.get_amount();
+        return damage.get_amount();
+    }
+
+    /// Mines carry no status effects
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
+    }
+
+    /// Mines shrug off status effects
+    fn apply_status_effect(&mut self, _effect: StatusEffect) {}
+
+    /// Mines give no pickups
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
+    }
+
+    /// Mines don't fire
+    fn collect_shots(&mut self) -> Vec<Shot> {
+        return Vec::new();
+    }
+
+    /// Create a boxed copy of this Mine
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// Get the health of this Mine
+    fn get_health(&self) -> Option<f32> {
+        return Some(self.health);
+    }
+
+    /// Check if this Mine has been destroyed
+    fn is_dead(&self) -> bool {
+        return self.health <= 0.0;
+    }
+}
+
+/// Gun that fires a single heavy shot straight ahead every time it is asked to
+#[derive(Clone)]
+struct Lance;
+
+impl Weapon for Lance {
+    /// Get the name of this Lance
+    fn get_name(&self) -> &str {
+        return "Lance";
+    }
+
+    /// Fire one shot along the aim of the muzzle
+    fn fire(&mut self, muzzle: &Muzzle) -> Vec<Shot> {
+        let velocity = Velocity::new(400.0, muzzle.get_heading());
+        return vec![muzzle.shot(velocity, Damage::kinetic(50.0), 2.0)];
+    }
+
+    /// Create a boxed copy of this Lance
+    fn clone_weapon(&self) -> Box<dyn Weapon> {
+        return Box::new(self.clone());
+    }
+
+    /// A Lance has no cooldown, so it can always fire
+    fn is_ready(&self) -> bool {
+        return true;
+    }
+}
+
+/// Sentry on the player's team that only reports the target the game gives it
+#[derive(Clone)]
+struct Spotter {
+    id: u32,
+    position: Point,
+    target: Rc<RefCell<Option<Point>>>,
+}
+
+impl Actor for Spotter {
+    /// Get the ID of this Spotter
+    fn get_id(&self) -> u32 {
+        return self.id;
+    }
+
+    /// Give this Spotter the ID the game chose for it
+    fn set_id(&mut self, id: u32) {
+        self.id = id;
+    }
+
+    /// Get the radius of this Spotter
+    fn get_radius(&self) -> f32 {
+        return 4.0;
+    }
+
+    /// Get the position of this Spotter
+    fn get_position(&self) -> &Point {
+        return &self.position;
+    }
+
+    /// Get the kind of this Spotter
+    fn get_kind(&self) -> ActorKind {
+        return ActorKind::Custom("spotter");
+    }
+
+    /// Spotters stay where they are
+    fn update(&mut self, _dt: f32) {}
+
+    /// Spotters do no damage
+    fn get_damage(&self) -> Damage {
+        return Damage::kinetic(0.0);
+    }
+
+    /// Spotters can't be hurt
+    fn do_damage(&mut self, _damage: Damage) -> f32 {
+        return 0.0;
+    }
+
+    /// Spotters carry no status effects
+    fn get_status_effect(&self) -> Option<StatusEffect> {
+        return None;
+    }
+
+    /// Spotters shrug off status effects
+    fn apply_status_effect(&mut self, _effect: StatusEffect) {}
+
+    /// Spotters give no pickups
+    fn take_pickup(&mut self) -> Option<PickupKind> {
+        return None;
+    }
+
+    /// Spotters don't fire
+    fn collect_shots(&mut self) -> Vec<Shot> {
+        return Vec::new();
+    }
+
+    /// Create a boxed copy of this Spotter
+    fn clone_actor(&self) -> Box<dyn Actor> {
+        return Box::new(self.clone());
+    }
+
+    /// Spotters are on the player's team
+    fn get_team(&self) -> Team {
+        return Team::Player;
+    }
+
+    /// Report the target the game chose for this Spotter
+    fn set_target(&mut self, target: Option<Point>) {
+        *self.target.borrow_mut() = target;
+    }
+
+    /// Spotters are never destroyed
+    fn is_dead(&self) -> bool {
+        return false;
+    }
+}
+
+/// Find the position of the actor with the given ID in the game, if it is still there
+fn find_actor(state: &MainState, id: u32) -> Option<(f32, f32)> {
+    return state.find_actor(id).map(|actor| (actor.get_position().get_x(), actor.get_position().get_y()));
+}
+
+/// An actor added from outside the game is simulated along with the game's own actors
+#[test]
+fn added_actors_are_simulated() {
+    let mut state = MainState::headless(BOUNDS, 3);
+    state.start(GameMode::Waves, ShipClass::ALL[1]);
+    let mine = Mine::new(Point::new(100.0, 500.0));
+    let id = state.add_actor(Box::new(mine));
+
+    for _ in 0..60 {
+        state.simulate_tick();
+    }
+
+    let (x, _) = find_actor(&state, id).expect("the mine was removed from the game");
+    assert!(x > 120.0, "the mine only drifted to {}", x);
+}
+
+/// Games on different threads number their actors independently, so the same game gives its actors the same IDs
+/// wherever it runs
+#[test]
+fn actor_ids_are_numbered_per_game() {
+    let spawn_mine = || {
+        let mut state = MainState::headless(BOUNDS, 3);
+        state.start(GameMode::Waves, ShipClass::ALL[1]);
+        let mine = Mine::new(Point::new(100.0, 500.0));
+        let id = state.add_actor(Box::new(mine));
+        assert!(state.find_actor(id).is_some());
+        assert!(state.find_actor(id + 1).is_none(), "an ID that was never given out found an actor");
+        return id;
+    };
+    let id = spawn_mine();
+    let other_id = std::thread::spawn(spawn_mine).join().unwrap();
+    assert_eq!(id, other_id);
+}
+
+/// Wave hooks are called as each wave starts, and the hostile actors they spawn join the wave
+#[test]
+fn wave_hooks_spawn_wave_enemies() {
+    let mut state = MainState::headless(BOUNDS, 3);
+    state.add_wave_hook(|wave, world| {
+        let (width, height) = world.get_size();
+        let mut actors: Vec<Box<dyn Actor>> = Vec::new();
+        for i in 0..wave {
+            actors.push(Box::new(Mine::new(Point::new(width / 4.0 + i as f32 * 20.0, height - 30.0))));
+        }
+        return actors;
+    });
+    state.start(GameMode::Waves, ShipClass::ALL[1]);
+
+    let mines: Vec<u32> = state.observe().actors.iter()
+        .filter(|actor| actor.team == Team::Hostile && actor.kind == ActorKind::Custom("mine"))
+        .map(|actor| actor.id)
+        .collect();
+    assert_eq!(mines.len(), 1, "the first wave should bring one mine");
+}
+
+/// A scripted turret leaves aiming and firing to its script, and fires the patterns its script commands
+#[test]
+fn scripted_turrets_fire_commanded_patterns() {
+    let mut turret = Turret::scripted(Point::new(400.0, 100.0), &WORLD, &Tuning::DEFAULT, "spiral");
+    for _ in 0..600 {
+        turret.update(1.0 / 60.0);
+    }
+    assert!(turret.collect_shots().is_empty(), "the turret fired without its script");
+
+    let spread = 2.0 * std::f32::consts::PI;
+    let pattern = FirePattern { shots: 8, heading: 0.0, spread, speed: 150.0, damage: Damage::energy(10.0) };
+    turret.apply_script_command(&ScriptCommand::FirePattern(pattern));
+    let shots = turret.collect_shots();
+    assert_eq!(shots.len(), 8);
+    assert!(shots.iter().all(|shot| shot.get_team() == Team::Hostile));
+}
+
+/// A turret armed with a weapon from outside the game fires that weapon instead of its own volley
+#[test]
+fn turrets_fire_swapped_weapons() {
+    let mut turret = Turret::new(Point::new(400.0, 100.0), &WORLD, &Tuning::DEFAULT);
+    turret.set_weapon(Box::new(Lance));
+    turret.update(1.0 / 60.0);
+
+    let shots = turret.collect_shots();
+    assert_eq!(shots.len(), 1, "the lance should fire one shot whenever it is ready");
+    assert_eq!(shots[0].get_damage().get_amount(), 50.0);
+    assert_eq!(shots[0].get_team(), Team::Hostile);
+}
+
+/// Velocities convert to and from the vectors they move by, and vectors add, scale, and normalize
+#[test]
+fn velocities_are_vectors() {
+    let velocity = Velocity::from_vector(&Vec2::new(3.0, 4.0));
+    let vector = velocity.to_vector();
+    assert!((vector.get_x() - 3.0).abs() < 1e-4 && (vector.get_y() - 4.0).abs() < 1e-4);
+    assert_eq!(vector.length().round(), 5.0);
+
+    let sum = Vec2::new(1.0, 2.0) + Vec2::new(2.0, 2.0) * 2.0;
+    assert_eq!(sum, Vec2::new(5.0, 6.0));
+    assert_eq!(Vec2::new(0.0, -2.0).normalize(), Vec2::new(0.0, -1.0));
+    assert_eq!(Vec2::zero().normalize(), Vec2::zero());
+    assert_eq!(Vec2::new(1.0, 0.0).dot(&Vec2::new(0.0, 1.0)), 0.0);
+}
+
+/// Rectangular hitboxes only touch what reaches their edges, not everything inside their bounding circle
+#[test]
+fn rectangular_hitboxes_collide_by_shape() {
+    let wall = Hitbox::Rect { half_width: 100.0, half_height: 10.0 };
+    let center = Point::new(400.0, 300.0);
+    assert!(wall.overlaps(&center, &Hitbox::Circle(5.0), &Point::new(480.0, 312.0)));
+    assert!(!wall.overlaps(&center, &Hitbox::Circle(5.0), &Point::new(400.0, 330.0)));
+
+    // Turned a quarter turn, the wall now stands upright
+    let turned = Hitbox::OrientedRect { half_width: 100.0, half_height: 10.0, rotation: std::f32::consts::FRAC_PI_2 };
+    assert!(turned.overlaps(&center, &Hitbox::Circle(5.0), &Point::new(400.0, 380.0)));
+    assert!(!turned.overlaps(&center, &Hitbox::Circle(5.0), &Point::new(480.0, 300.0)));
+
+    let beam = Hitbox::OrientedRect { half_width: 50.0, half_height: 2.0, rotation: std::f32::consts::FRAC_PI_4 };
+    assert!(beam.overlaps(&Point::new(400.0, 200.0), &wall, &Point::new(400.0, 240.0)));
+    assert!(!beam.overlaps(&Point::new(400.0, 200.0), &wall, &center));
+}
+
+/// A shot fast enough to cross a mine in a single tick still hits it, but not one that passes beside it
+#[test]
+fn fast_shots_hit_what_they_pass_through() {
+    let mine = Mine { velocity: Velocity::new(0.0, 0.0), ..Mine::new(Point::new(400.0, 300.0)) };
+    let mine: Box<dyn Actor> = Box::new(mine);
+    let fire = |y: f32| {
+        let (velocity, damage) = (Velocity::new(3000.0, 0.0), Damage::kinetic(10.0));
+        let mut shot = Shot::new(Point::new(370.0, y), &WORLD, velocity, damage, 1.0, None, Team::Player);
+        shot.update(1.0 / 60.0);
+        return shot;
+    };
+
+    let shot = fire(300.0);
+    assert!(shot.get_position().distance_to(mine.get_position()) > 20.0, "the shot didn't get past the mine");
+    assert!(shot.check_for_collision(&mine), "the shot tunnelled through the mine");
+    assert!(!fire(330.0).check_for_collision(&mine), "the shot hit a mine it passed beside");
+}
+
+/// Shots live as long and turrets turn as far in a second of game time at any tick rate
+#[test]
+fn lifespans_and_turning_keep_to_game_time() {
+    let run_for_a_second = |rate: u32| {
+        let dt = 1.0 / rate as f32;
+        let velocity = Velocity::new(0.0, 0.0);
+        let mut shot = Shot::new(Point::new(400.0, 300.0), &WORLD, velocity, Damage::kinetic(1.0), 1.0, None,
+                                 Team::Player);
+        let mut turret = Turret::new(Point::new(400.0, 100.0), &WORLD, &Tuning::DEFAULT);
+        let mut ticks = 0;
+        while !shot.is_dead() {
+            shot.update(dt);
+            if ticks < rate {
+                turret.update(dt);
+            }
+            ticks += 1;
+        }
+        return (ticks as f32 * dt, turret.get_aim().unwrap());
+    };
+
+    let (lifespan, aim) = run_for_a_second(60);
+    for &rate in &[30, 144] {
+        let (lived, aimed) = run_for_a_second(rate);
+        assert!((lived - lifespan).abs() <= 1.0 / rate as f32 + 1e-4, "a shot lived {}s at {} ticks per second, \
+                                                                       not {}s", lived, rate, lifespan);
+        assert!((aimed - aim).abs() < 1e-3, "a turret turned to {} at {} ticks per second, not {}", aimed, rate, aim);
+    }
+}
+
+/// Turrets and shots are drawn with sprites turned the way they face, and with images that tell their kinds apart
+#[test]
+fn sprites_face_the_way_actors_do() {
+    let get_sprite = |actor: &dyn Actor| actor.as_render().and_then(|render| render.get_sprite()).unwrap();
+
+    let mut turret = Turret::new(Point::new(400.0, 100.0), &WORLD, &Tuning::DEFAULT);
+    turret.update(0.5);
+    let sprite = get_sprite(&turret);
+    assert_eq!(Some(sprite.get_rotation()), turret.get_aim(), "the turret's sprite isn't turned to its aim");
+    let boss = Turret::boss(Point::new(400.0, 100.0), &WORLD, &Tuning::DEFAULT);
+    assert_ne!(get_sprite(&boss).get_images(), sprite.get_images(), "boss turrets look like the others");
+
+    let fire = |team: Team| {
+        let velocity = Velocity::new(300.0, 1.2);
+        return Shot::new(Point::new(400.0, 300.0), &WORLD, velocity, Damage::kinetic(1.0), 1.0, None, team);
+    };
+    let (ours, theirs) = (get_sprite(&fire(Team::Player)), get_sprite(&fire(Team::Hostile)));
+    assert_eq!(ours.get_rotation(), 1.2, "the shot's sprite doesn't point the way it is flying");
+    assert_ne!(ours.get_images(), theirs.get_images(), "the player's shots look like the enemies'");
+}
+
+/// Arenas can be round or polygonal, and their edges wrap, bounce, or destroy the shots that reach them
+#[test]
+fn world_edges_act_on_shots() {
+    let fire = |world: &World, x: f32, y: f32, heading: f32| {
+        let velocity = Velocity::new(600.0, heading);
+        let mut shot = Shot::new(Point::new(x, y), world, velocity, Damage::kinetic(1.0), 1.0, None, Team::Player);
+        shot.update(0.1);
+        return shot;
+    };
+
+    // A shot leaving the right of a round arena that wraps comes back in on the left
+    let circle = World::new(BOUNDS, ArenaShape::Circle, vec![EdgeBehavior::Wrap]);
+    assert!(!circle.contains(&Point::new(10.0, 10.0)), "the corners of the window are outside a round arena");
+    let shot = fire(&circle, 695.0, 300.0, 0.0);
+    assert!(!shot.is_dead() && shot.get_position().get_x() < 400.0, "the shot didn't wrap around the arena");
+    assert_eq!(shot.get_displacement().map(|moved| moved.length()), Some(0.0), "the shot swept across the arena");
+
+    // A shot reaching the edge of an arena that bounces turns back
+    let bouncy = World::new(BOUNDS, ArenaShape::Rect, vec![EdgeBehavior::Bounce]);
+    let mut shot = fire(&bouncy, 790.0, 300.0, 0.0);
+    assert_eq!(shot.get_position().get_x(), 800.0);
+    shot.update(0.1);
+    assert!(!shot.is_dead() && shot.get_position().get_x() < 800.0, "the shot didn't bounce off the edge");
+
+    // A shot crossing an edge of a diamond that destroys what reaches it is gone
+    let corners = vec![(0.5, 0.0), (1.0, 0.5), (0.5, 1.0), (0.0, 0.5)];
+    let diamond = World::new(BOUNDS, ArenaShape::Polygon { corners }, vec![EdgeBehavior::Kill]);
+    assert!(diamond.contains(&Point::new(600.0, 200.0)));
+    assert!(fire(&diamond, 600.0, 200.0, -std::f32::consts::FRAC_PI_4).is_dead());
+}
+
+/// Actors registered under a name can be spawned by it, and hostile ones join the wave
+#[test]
+fn registered_actors_are_spawned_by_name() {
+    let mut state = MainState::headless(BOUNDS, 3);
+    state.register_actor("mine", |position, _, _| Box::new(Mine::new(position)));
+    state.start(GameMode::Waves, ShipClass::ALL[1]);
+    assert!(state.get_registry().contains("turret.armored"));
+    assert!(state.spawn_named("turret.homing", Point::new(300.0, 300.0)).is_none());
+
+    let id = state.spawn_named("mine", Point::new(100.0, 500.0)).expect("the mine wasn't registered");
+    assert_eq!(find_actor(&state, id), Some((100.0, 500.0)));
+}
+
+/// Health stays between zero and its maximum, and takes damage through its armor
+#[test]
+fn health_is_clamped_at_both_ends() {
+    let mut health = Health::armored(100.0, Resistances::new(0.5, 0.0, 0.0));
+    health.damage(Damage::kinetic(100.0));
+    assert_eq!(health.get_current(), 50.0);
+    assert_eq!(health.heal(80.0), 50.0, "healing should stop at the maximum");
+    health.lose(-20.0);
+    assert_eq!(health.get_current(), 100.0, "losing a negative amount should never raise health");
+    health.damage(Damage::energy(130.0));
+    assert_eq!(health.get_current(), 0.0);
+    assert!(health.is_depleted());
+}
+
+/// An actor resting on the player hits it again at the capped rate, or only once if its response says so
+#[test]
+fn touching_actors_hit_at_their_contact_response() {
+    let count_hits = |response: Option<ContactResponse>| {
+        let mut state = MainState::headless(BOUNDS, 3);
+        state.start(GameMode::Waves, ShipClass::ALL[1]);
+        if let Some(response) = response {
+            state.set_contact_response(ActorKind::Player, ActorKind::Custom("mine"), response);
+        }
+        let player = state.observe().player;
+        let position = Point::new(player.x, player.y);
+        let mine = Mine { velocity: Velocity::new(0.0, 0.0), health: 1000.0, ..Mine::new(position) };
+        let id = state.add_actor(Box::new(mine));
+
+        let mut hits = 0;
+        for _ in 0..60 {
+            state.simulate_tick();
+            hits += state.get_events().iter()
+                .filter(|event| matches!(event, GameEvent::ActorDamaged { id: damaged, .. } if *damaged == id))
+                .count();
+        }
+        return hits;
+    };
+    assert_eq!(count_hits(None), 2, "a second of contact should hit twice");
+    assert_eq!(count_hits(Some(ContactResponse::Once)), 1);
+    assert_eq!(count_hits(Some(ContactResponse::Ignore)), 0);
+}
+
+/// Actors on layers that don't collide are never tested for contact, so they pass through each other unharmed
+#[test]
+fn layers_that_dont_collide_pass_through_each_other() {
+    let mut state = MainState::headless(BOUNDS, 3);
+    state.start(GameMode::Waves, ShipClass::ALL[1]);
+    state.set_layers_collide(CollisionLayer::Player, CollisionLayer::Enemy, false);
+    let player = state.observe().player;
+    let mine = Mine { velocity: Velocity::new(0.0, 0.0), ..Mine::new(Point::new(player.x, player.y)) };
+    let id = state.add_actor(Box::new(mine));
+
+    for _ in 0..30 {
+        state.simulate_tick();
+        let touched = state.get_events().iter().any(|event| {
+            matches!(event, GameEvent::ContactBegan { id: first, other } if *first == id || *other == id)
+        });
+        assert!(!touched, "the mine touched something it can't collide with");
+    }
+    assert!(state.find_actor(id).is_some(), "the mine was destroyed by the player it can't touch");
+}
+
+/// By default shots are never tested against other shots, and are the only actors obstacles destroy
+#[test]
+fn default_rules_skip_shots_against_shots() {
+    let rules = CollisionRules::new();
+    assert!(!rules.collides(CollisionLayer::PlayerShot, CollisionLayer::EnemyShot));
+    assert!(!rules.collides(CollisionLayer::EnemyShot, CollisionLayer::EnemyShot));
+    assert!(rules.collides(CollisionLayer::PlayerShot, CollisionLayer::Enemy));
+    assert!(rules.is_stopped_by_obstacles(CollisionLayer::PlayerShot));
+    assert!(!rules.is_stopped_by_obstacles(CollisionLayer::Player));
+}
+
+/// With collision physics on, the player is pushed out of a turret it runs into instead of sitting inside it
+#[test]
+fn collision_physics_separate_overlapping_actors() {
+    let mut state = MainState::headless(BOUNDS, 3);
+    state.start(GameMode::Waves, ShipClass::ALL[1]);
+    state.set_collision_physics(true);
+    let player = state.observe().player;
+    let turret_x = player.x + 10.0;
+    let turret = Turret::new(Point::new(turret_x, player.y), state.get_world(), state.get_tuning());
+    let radius = turret.get_radius();
+    let id = state.add_actor(Box::new(turret));
+
+    state.simulate_tick();
+    let player = state.observe().player;
+    let (x, y) = find_actor(&state, id).expect("the turret was removed from the game");
+    assert_eq!(x, turret_x, "the turret was moved, though nothing can move it");
+    let distance = (player.x - x).hypot(player.y - y);
+    let touching = ShipClass::ALL[1].get_stats().radius + radius;
+    assert!(distance >= touching - 0.001, "the player is still inside the turret, {} from its center", distance);
+}
+
+/// Damage and death hooks hear about every actor hurt and destroyed, and death hooks can leave actors behind
+#[test]
+fn damage_and_death_hooks_are_called() {
+    let mut state = MainState::headless(BOUNDS, 3);
+    state.start(GameMode::Waves, ShipClass::ALL[1]);
+    let damaged = Rc::new(RefCell::new(Vec::new()));
+    let died = Rc::new(RefCell::new(Vec::new()));
+    let record_damage = damaged.clone();
+    state.add_damage_hook(move |id, _source, amount| record_damage.borrow_mut().push((id, amount)));
+    let record_death = died.clone();
+    state.add_death_hook(move |id, kind, position| {
+        record_death.borrow_mut().push(id);
+        if kind != ActorKind::Custom("mine") {
+            return Vec::new();
+        }
+        // Leave a fresh mine behind, well away from the player
+        let mine = Mine::new(Point::new(position.get_x(), BOUNDS.1 - 20.0));
+        return vec![Box::new(mine) as Box<dyn Actor>];
+    });
+
+    let player = state.observe().player;
+    let mine = Mine { velocity: Velocity::new(0.0, 0.0), ..Mine::new(Point::new(player.x, player.y)) };
+    let id = state.add_actor(Box::new(mine));
+    state.simulate_tick();
+    state.simulate_tick();
+
+    assert!(damaged.borrow().iter().any(|&(damaged, amount)| damaged == id && amount > 0.0));
+    assert!(died.borrow().contains(&id), "the mine wasn't destroyed");
+    assert!(state.find_actor(id).is_none());
+    let mines = state.observe().actors.iter().filter(|actor| actor.kind == ActorKind::Custom("mine")).count();
+    assert_eq!(mines, 1, "the death hook should have left a mine behind");
+}
+
+/// Turrets on the player's team all aim at the nearest enemy of the wave, unless the balance spreads their fire,
+/// in which case an enemy another turret already aims at is passed over for one a little further away
+#[test]
+fn target_spread_splits_fire_between_enemies() {
+    let spotted_targets = |spread: f32| {
+        let mut state = MainState::headless(BOUNDS, 3);
+        let mut tuning = Tuning::new();
+        tuning.target_spread = spread;
+        state.set_tuning(tuning);
+        state.add_wave_hook(|_, _| {
+            let near = Mine { velocity: Velocity::new(0.0, 0.0), ..Mine::new(Point::new(200.0, 570.0)) };
+            let far = Mine { velocity: Velocity::new(0.0, 0.0), ..Mine::new(Point::new(260.0, 570.0)) };
+            return vec![Box::new(near) as Box<dyn Actor>, Box::new(far)];
+        });
+        state.start(GameMode::Waves, ShipClass::ALL[1]);
+        let targets: Vec<Rc<RefCell<Option<Point>>>> = (0..2).map(|_| Rc::new(RefCell::new(None))).collect();
+        for (i, target) in targets.iter().enumerate() {
+            let position = Point::new(200.0 + i as f32 * 5.0, 560.0);
+            state.add_actor(Box::new(Spotter { id: 0, position, target: target.clone() }));
+        }
+        state.simulate_tick();
+        return targets.iter().map(|target| target.borrow().expect("a spotter was given no target")).collect::<Vec<_>>();
+    };
+
+    let focused = spotted_targets(0.0);
+    assert_eq!(focused[0], focused[1], "without a spread both spotters should aim at the nearest mine");
+    let spread = spotted_targets(5.0);
+    assert_ne!(spread[0], spread[1], "with a spread the spotters should aim at different mines");
+}
+
+/// Damage hooks hear how much health an actor actually lost, after its armor, rather than what hit it
+#[test]
+fn damage_hooks_report_the_damage_taken() {
+    let mut state = MainState::headless(BOUNDS, 3);
+    state.start(GameMode::Waves, ShipClass::ALL[1]);
+    let damaged = Rc::new(RefCell::new(Vec::new()));
+    let record_damage = damaged.clone();
+    state.add_damage_hook(move |id, _source, amount| record_damage.borrow_mut().push((id, amount)));
+
+    let position = Point::new(100.0, 100.0);
+    let id = state.spawn_named("turret.armored", position).expect("armored turrets are registered");
+    let health = |state: &MainState| state.find_actor(id).and_then(|turret| turret.get_health()).unwrap_or(0.0);
+    let full_health = health(&state);
+    let shot = Shot::new(position, &WORLD, Velocity::new(0.0, 0.0), Damage::kinetic(40.0), 1.0, None, Team::Player);
+    state.add_actor(Box::new(shot));
+    state.simulate_tick();
+
+    let reported: f32 = damaged.borrow().iter().filter(|&&(damaged, _)| damaged == id).map(|&(_, amount)| amount).sum();
+    assert!(reported > 0.0 && reported < 40.0, "the armor should have blocked part of the shot, but {} was reported",
+            reported);
+    assert!((reported - (full_health - health(&state))).abs() < 1e-3);
+}
@@ -0,0 +1,188 @@
+use std::fs;
+use std::path::PathBuf;
+
+use turrets::{EnemyDefinition, Level, MainState, ModList, Point, SpawnRegion, TurretKind, Tuning, WinCondition};
+
+/// Write a mods directory with the given mods, each a list of (file name, contents) pairs, and return its path
+fn write_mods(test: &str, mods: &[(&str, &[(&str, &str)])]) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("turrets-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    for (name, files) in mods {
+        fs::create_dir_all(directory.join(name)).unwrap();
+        for (file, contents) in files.iter() {
+            fs::write(directory.join(name).join(file), contents).unwrap();
+        }
+    }
+    return directory;
+}
+
+/// Tuning overrides only change the values they set, and the mod loaded last has the final say
+#[test]
+fn mod_tuning_overrides_stack_in_load_order() {
+    let directory = write_mods("tuning", &[
+        ("base", &[
+            ("mod.toml", "name = \"base\"\nversion = \"1.0.0\"\ntuning = \"tuning.toml\""),
+            ("tuning.toml", "turret_health = 150.0\nturret_radius = 20.0"),
+        ]),
+        ("harder", &[
+            ("mod.toml", "name = \"harder\"\nversion = \"1.0.0\"\ndependencies = [\"base\"]\ntuning = \"tuning.toml\""),
+            ("tuning.toml", "turret_health = 200.0"),
+        ]),
+    ]);
+    let mods = ModList::load(&directory);
+    let (tuning, issues) = Tuning::new().with_mods(&mods);
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert!(issues.is_empty(), "{:?}", issues);
+    assert_eq!(tuning.turret_health, 200.0);
+    assert_eq!(tuning.turret_radius, 20.0);
+    assert_eq!(tuning.drone_health, Tuning::DEFAULT.drone_health);
+}
+
+/// Changing the balance of one game leaves the actors every other game spawns as they were
+#[test]
+fn games_keep_their_own_balance() {
+    let mut tuned = MainState::headless((800.0, 600.0), 1);
+    let mut tuning = Tuning::new();
+    tuning.turret_radius = 40.0;
+    tuned.set_tuning(tuning);
+    let mut other = MainState::headless((800.0, 600.0), 1);
+
+    let spawn_radius = |state: &mut MainState| {
+        let id = state.spawn_named("turret.basic", Point::new(400.0, 300.0)).expect("turrets are registered");
+        state.observe().actors.iter().find(|actor| actor.id == id).map(|actor| actor.radius)
+    };
+    assert_eq!(spawn_radius(&mut tuned), Some(40.0));
+    assert_eq!(spawn_radius(&mut other), Some(Tuning::DEFAULT.turret_radius));
+}
+
+/// Tuning files that name a value the game doesn't have or set one out of range are refused, keeping the defaults
+#[test]
+fn invalid_tuning_is_refused() {
+    let directory = write_mods("invalid-tuning", &[("files", &[
+        ("unknown.toml", "turret_helth = 150.0"),
+        ("negative.toml", "kamikaze_speed = -10.0"),
+        ("zero.toml", "shot_radius = 0.0"),
+        ("valid.toml", "turret_health = 150.0\nplayer_fire_cooldown = 0.25"),
+    ])]);
+    let load = |file: &str| Tuning::load(&directory.join("files").join(file));
+    let results = [load("unknown.toml"), load("negative.toml"), load("zero.toml"), load("valid.toml")];
+    fs::remove_dir_all(&directory).unwrap();
+
+    for (tuning, issue) in &results[..3] {
+        assert!(issue.is_some(), "an invalid tuning file was accepted");
+        assert_eq!(*tuning, Tuning::DEFAULT);
+    }
+    let (tuning, issue) = &results[3];
+    assert!(issue.is_none(), "{:?}", issue);
+    assert_eq!(tuning.turret_health, 150.0);
+    assert_eq!(tuning.player_fire_cooldown, 0.25);
+}
+
+/// Mods whose manifests point outside their own directory are refused
+#[test]
+fn mod_paths_outside_the_mod_are_refused() {
+    let directory = write_mods("escaping", &[
+        ("climber", &[("mod.toml", "name = \"climber\"\nversion = \"1.0.0\"\nlevels = [\"../../secret.json\"]")]),
+        ("rooted", &[("mod.toml", "name = \"rooted\"\nversion = \"1.0.0\"\ntuning = \"/etc/tuning.toml\"")]),
+        ("tidy", &[("mod.toml", "name = \"tidy\"\nversion = \"1.0.0\"\nlevels = [\"./levels/north.json\"]")]),
+    ]);
+    let mods = ModList::load(&directory);
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(mods.get_issues().len(), 2, "{:?}", mods.get_issues());
+    let names: Vec<&str> = mods.get_mods().iter().map(|entry| entry.get_manifest().get_name()).collect();
+    assert_eq!(names, vec!["tidy"]);
+}
+
+/// Enemy definitions are named after their file, and fill in what they leave out with a normal turret
+#[test]
+fn mod_enemies_are_loaded() {
+    let directory = write_mods("enemies", &[
+        ("snipers", &[
+            ("mod.toml", "name = \"snipers\"\nversion = \"1.0.0\"\nactors = [\"sniper.toml\", \"broken.toml\"]"),
+            ("sniper.toml", "from_wave = 3\ncount = 2\nsize = 0.5\narmored = true"),
+            ("broken.toml", "count = \"many\""),
+        ]),
+    ]);
+    let mods = ModList::load(&directory);
+    let (enemies, issues) = EnemyDefinition::load_all(&mods);
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert_eq!(issues.len(), 1, "{:?}", issues);
+    assert_eq!(enemies.len(), 1);
+    let sniper = &enemies[0];
+    assert_eq!(sniper.get_name(), "sniper");
+    assert_eq!(sniper.get_count(2), 0);
+    assert_eq!(sniper.get_count(3), 2);
+    assert_eq!(sniper.get_scale(), (0.5, 1.0));
+    assert!(sniper.is_armored());
+    assert_eq!(sniper.get_script(), None);
+}
+
+/// Levels are stretched from their own arena to the game's, and leave out what they don't need
+#[test]
+fn mod_levels_are_loaded() {
+    let directory = write_mods("levels", &[
+        ("arenas", &[
+            ("mod.toml", "name = \"arenas\"\nversion = \"1.0.0\"\nlevels = [\"pillars.json\"]"),
+            ("pillars.json", r#"{
+                "arena": [400, 300],
+                "turrets": [{"x": 100, "y": 75, "kind": "armored"}, {"x": 300, "y": 75}],
+                "obstacles": [{"x": 200, "y": 150, "radius": 20}],
+                "win": {"waves": 5}
+            }"#),
+        ]),
+    ]);
+    let mods = ModList::load(&directory);
+    let (levels, issues) = Level::load_all(&mods);
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert!(issues.is_empty(), "{:?}", issues);
+    let pillars = levels.iter().find(|level| level.get_name() == "pillars").expect("the level wasn't loaded");
+    let turrets = pillars.get_turrets((800.0, 600.0));
+    assert_eq!(turrets.len(), 2);
+    assert_eq!((turrets[0].0.get_x(), turrets[0].0.get_y(), turrets[0].1), (200.0, 150.0, TurretKind::Armored));
+    assert_eq!(turrets[1].1, TurretKind::Normal);
+    assert_eq!(pillars.create_obstacles((800.0, 600.0)).len(), 1);
+    assert_eq!(pillars.get_win_condition(), WinCondition::Waves(5));
+}
+
+/// Levels name zones of their arena, which are stretched with it, for enemies to spawn in
+#[test]
+fn level_spawn_regions_are_loaded() {
+    let directory = write_mods("regions", &[
+        ("arenas", &[
+            ("mod.toml", "name = \"arenas\"\nversion = \"1.0.0\"\nlevels = [\"north.json\"]"),
+            ("north.json", r#"{
+                "arena": [400, 300],
+                "turrets": [],
+                "zones": {"north": {"x": 100, "y": 0, "width": 200, "height": 30}},
+                "spawn_region": {"zone": "north"}
+            }"#),
+        ]),
+    ]);
+    let mods = ModList::load(&directory);
+    let (levels, issues) = Level::load_all(&mods);
+    fs::remove_dir_all(&directory).unwrap();
+
+    assert!(issues.is_empty(), "{:?}", issues);
+    let north = levels.iter().find(|level| level.get_name() == "north").expect("the level wasn't loaded");
+    let region = north.get_spawn_region().expect("the level has a spawn region");
+    assert_eq!(region, &SpawnRegion::Zone(String::from("north")));
+
+    let zones = north.get_zones((800.0, 600.0));
+    let mut rng = rand::thread_rng();
+    for _ in 0..20 {
+        let point = region.pick((800.0, 600.0), &zones, &mut rng).expect("the zone wasn't found");
+        assert!(point.get_x() >= 200.0 && point.get_x() <= 600.0 && point.get_y() <= 60.0,
+                "({}, {}) is outside the zone", point.get_x(), point.get_y());
+    }
+    assert!(SpawnRegion::Zone(String::from("south")).pick((800.0, 600.0), &zones, &mut rng).is_none());
+
+    // An inset past the middle of the arena still spawns inside it
+    let deep = SpawnRegion::Edges { inset: 1000.0 };
+    let point = deep.pick((800.0, 600.0), &zones, &mut rng).expect("edges always have a point");
+    assert!(point.get_x() >= 0.0 && point.get_x() <= 800.0 && point.get_y() >= 0.0 && point.get_y() <= 600.0,
+            "({}, {}) is outside the arena", point.get_x(), point.get_y());
+}
@@ -0,0 +1,54 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use turrets::replay::Replay;
+
+/// Get the paths of the replays bundled with the tests, in a stable order
+fn get_replay_paths() -> Vec<PathBuf> {
+    let directory = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/replays");
+    let mut paths: Vec<PathBuf> = fs::read_dir(&directory)
+        .unwrap_or_else(|error| panic!("{}: {}", directory.display(), error))
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| matches!(path.extension(), Some(extension) if extension == "toml"))
+        .collect();
+    paths.sort();
+    return paths;
+}
+
+/// Play every bundled replay and check that it ends the way it did when it was recorded
+#[test]
+fn replays_match_recorded_results() {
+    let paths = get_replay_paths();
+    assert!(!paths.is_empty(), "no replays found in tests/replays");
+
+    for path in paths {
+        let replay = Replay::load(&path).unwrap_or_else(|error| panic!("{}", error));
+        let expected = replay.expected.clone()
+            .unwrap_or_else(|| panic!("{}: no recorded result, record it with the ignored test", path.display()));
+        let actual = replay.play();
+        assert_eq!(actual, expected, "{} played out differently than it was recorded. If the change in gameplay is \
+                                      intended, record the replays again with `cargo test --test replays -- \
+                                      --ignored`", path.display());
+    }
+}
+
+/// Play every bundled replay twice in the same process and check that both plays end the same way, so that
+/// nothing outside the seed and the inputs changes how a run plays out
+#[test]
+fn replays_are_deterministic() {
+    for path in get_replay_paths() {
+        let replay = Replay::load(&path).unwrap_or_else(|error| panic!("{}", error));
+        assert_eq!(replay.play(), replay.play(), "{} played out differently the second time", path.display());
+    }
+}
+
+/// Play every bundled replay and save how it played out as its expected result
+#[test]
+#[ignore]
+fn record_replays() {
+    for path in get_replay_paths() {
+        let mut replay = Replay::load(&path).unwrap_or_else(|error| panic!("{}", error));
+        replay.record();
+        replay.save(&path).unwrap_or_else(|error| panic!("{}", error));
+    }
+}
@@ -0,0 +1,480 @@
+use turrets::{ActorKind, Animation, Camera, Cooldown, Damage, Emitter, GameMode, Level, MainState, ParticleSystem,
+              Point, Scene, ShipClass, Shot, SpawnKind, Team, Timer, Velocity, World};
+#[cfg(feature = "scripts")]
+use turrets::Turret;
+use turrets::benchmark;
+use turrets::headless::{self, HeadlessRun};
+use turrets::launch::{LaunchMode, LaunchOptions};
+use turrets::observation::Action;
+
+/// Size of the arena the tests play in
+const BOUNDS: (f32, f32) = (800.0, 600.0);
+/// Longest a test run is played for, in ticks
+const MAX_TICKS: u64 = 60 * 60 * 5;
+
+/// Start a waves run with the balanced ship and the given seed
+fn start_run(seed: u64) -> HeadlessRun {
+    return HeadlessRun::new(BOUNDS, seed, GameMode::Waves, ShipClass::ALL[1]);
+}
+
+/// A player that never fires can't score, and is worn down by the turrets until the run is over
+#[test]
+fn idle_player_dies_without_scoring() {
+    let mut run = start_run(7);
+    let result = run.play(MAX_TICKS, |_| Action { thrust: 0, turn: 1, fire: false, grapple: false });
+
+    assert!(run.is_over(), "the run lasted {} ticks without ending", result.ticks);
+    assert_eq!(result.score, 0);
+    assert_eq!(result.wave, 1);
+    assert!(!run.step(Action::default()), "a run that is over kept simulating");
+}
+
+/// Shots fired by the player hit and damage the turrets they reach
+#[test]
+fn shots_damage_turrets() {
+    let mut run = start_run(7);
+    let total_health = |run: &HeadlessRun| -> f32 {
+        return run.get_state().observe().actors.iter()
+            .filter(|actor| actor.team == Team::Hostile)
+            .filter_map(|actor| actor.health)
+            .sum();
+    };
+    let starting_health = total_health(&run);
+
+    for _ in 0..120 {
+        run.step(headless::spin_and_fire(&run.get_state().observe()));
+    }
+
+    assert!(total_health(&run) < starting_health, "two seconds of firing didn't damage any turret");
+}
+
+/// Clearing waves of turrets scores points and brings on the next waves
+#[test]
+fn clearing_waves_scores() {
+    let mut run = start_run(7);
+    let result = run.play(MAX_TICKS, headless::spin_and_fire);
+
+    assert!(result.score > 0, "no points scored in {} ticks", result.ticks);
+    assert!(result.wave > 1, "stuck on the first wave for {} ticks", result.ticks);
+}
+
+/// Scheduled spawns only arrive once their delay has passed, and the enemies among them join the wave
+#[test]
+fn scheduled_spawns_arrive_on_time() {
+    let mut run = start_run(7);
+    let turret_count = |run: &HeadlessRun| -> usize {
+        return run.get_state().observe().actors.iter().filter(|actor| actor.kind == ActorKind::Turret).count();
+    };
+    let starting_count = turret_count(&run);
+    let positions = [Point::new(100.0, 300.0), Point::new(700.0, 300.0), Point::new(400.0, 550.0)];
+    run.get_state_mut().schedule_spawns(1.0, SpawnKind::Turret, &positions);
+
+    for _ in 0..30 {
+        run.step(Action::default());
+    }
+    assert_eq!(turret_count(&run), starting_count, "the turrets arrived before their delay");
+
+    for _ in 0..31 {
+        run.step(Action::default());
+    }
+    assert_eq!(turret_count(&run), starting_count + 3, "the turrets didn't arrive after their delay");
+}
+
+/// A spawner turret calls kamikaze drones in beside itself as the wave goes on
+#[test]
+fn spawners_call_in_kamikazes() {
+    let mut run = start_run(7);
+    let spawner = Point::new(400.0, 500.0);
+    run.get_state_mut().schedule_spawns(0.0, SpawnKind::Spawner, &[spawner]);
+    let kamikazes = |run: &HeadlessRun| -> Vec<Point> {
+        return run.get_state().observe().actors.iter()
+            .filter(|actor| actor.kind == ActorKind::Kamikaze)
+            .map(|actor| Point::new(actor.x, actor.y))
+            .collect();
+    };
+    assert!(kamikazes(&run).is_empty(), "the first wave started with kamikazes");
+
+    // The first drone is called in after a few seconds, and is marked in the arena before it arrives
+    let mut called = Vec::new();
+    for _ in 0..60 * 6 {
+        run.step(Action::default());
+        called = kamikazes(&run);
+        if !called.is_empty() {
+            break;
+        }
+    }
+    assert_eq!(called.len(), 1, "the spawner didn't call in a kamikaze");
+    assert!(called[0].distance_to(&spawner) < 80.0, "the kamikaze arrived away from the spawner");
+}
+
+/// A shot fired into one of the level's obstacles stops there instead of flying through it
+#[test]
+fn obstacles_stop_shots() {
+    let name = format!("turrets-pillar-{}", std::process::id());
+    let path = std::env::temp_dir().join(format!("{}.json", name));
+    std::fs::write(&path, r#"{"arena": [400, 300], "turrets": [], "obstacles": [{"x": 300, "y": 200, "radius": 20}]}"#)
+        .unwrap();
+    let level = Level::load(&path);
+    std::fs::remove_file(&path).unwrap();
+    let mut state = MainState::headless(BOUNDS, 7);
+    state.add_level(level.unwrap());
+    assert!(state.set_level(Some("no such level")).is_err(), "a level the game doesn't have was chosen");
+    state.set_level(Some(name.as_str())).unwrap();
+    let mut run = HeadlessRun::with_state(state, GameMode::Waves, ShipClass::ALL[1]);
+
+    // Without the obstacle in the way, the shot would still be flying after a second
+    let world = run.get_state().get_world().clone();
+    let shot = Shot::new(Point::new(480.0, 400.0), &world, Velocity::new(300.0, 0.0), Damage::kinetic(10.0), 3.0, None,
+                         Team::Player);
+    run.get_state_mut().add_actor(Box::new(shot));
+    for _ in 0..60 {
+        run.step(Action::default());
+    }
+    let player_shots = run.get_state().observe().actors.iter()
+        .filter(|actor| actor.kind == ActorKind::Shot && actor.team == Team::Player)
+        .count();
+    assert_eq!(player_shots, 0, "the shot flew through the obstacle");
+}
+
+/// A turret driven by a Rhai script fires the pattern its script commands, and a script can only spawn one turret a
+/// tick however many it asks for
+#[cfg(feature = "scripts")]
+#[test]
+fn rhai_scripts_drive_turrets() {
+    let mut run = start_run(7);
+    let source = "fn tick() { fire_pattern(8, 0.0, 6.3, 150.0, 10.0); spawn_turret(100.0, 300.0); \
+                  spawn_turret(700.0, 300.0); }";
+    run.get_state_mut().add_script("ring", source).unwrap();
+    let position = Point::new(400.0, 450.0);
+    let turret = Turret::scripted(position, run.get_state().get_world(), run.get_state().get_tuning(), "ring");
+    run.get_state_mut().add_actor(Box::new(turret));
+    let count = |run: &HeadlessRun, kind: ActorKind, near: f32| -> usize {
+        return run.get_state().observe().actors.iter()
+            .filter(|actor| actor.kind == kind && Point::new(actor.x, actor.y).distance_to(&position) < near)
+            .count();
+    };
+    let turrets = count(&run, ActorKind::Turret, f32::INFINITY);
+
+    // The turrets a script spawns arrive on the tick after
+    run.step(Action::default());
+    assert_eq!(count(&run, ActorKind::Shot, 40.0), 8, "the script's ring of shots wasn't fired");
+    run.step(Action::default());
+    assert_eq!(count(&run, ActorKind::Turret, f32::INFINITY), turrets + 1, "the script spawned more than one turret");
+}
+
+/// Shrinking the arena moves the player and the turrets back inside it, and the player stays inside as it moves
+#[test]
+fn resizing_keeps_everything_in_the_arena() {
+    let mut run = start_run(7);
+    let small = (400.0, 300.0);
+    run.get_state_mut().resize(small);
+    let inside = |x: f32, y: f32| x >= 0.0 && x <= small.0 && y >= 0.0 && y <= small.1;
+
+    let observation = run.get_state().observe();
+    assert!(inside(observation.player.x, observation.player.y), "the player was left outside the arena");
+    for actor in observation.actors.iter().filter(|actor| actor.team == Team::Hostile) {
+        assert!(inside(actor.x, actor.y), "an enemy was left outside the arena at ({}, {})", actor.x, actor.y);
+    }
+
+    for _ in 0..120 {
+        run.step(Action { thrust: 1, turn: 0, fire: false, grapple: false });
+    }
+    let observation = run.get_state().observe();
+    assert!(inside(observation.player.x, observation.player.y), "the player flew out of the resized arena");
+}
+
+/// Observations follow the game as it is simulated, and taking them leaves the game as it was
+#[test]
+fn observations_follow_the_game() {
+    let mut run = start_run(7);
+    let first = run.get_state().observe();
+    assert_eq!(first.scene, Scene::Playing);
+    assert_eq!(first.player.health, first.player.max_health);
+    assert_eq!(run.get_state().observe().actors.len(), first.actors.len());
+
+    for _ in 0..60 {
+        run.step(Action { thrust: 1, turn: 0, fire: false, grapple: false });
+    }
+
+    let later = run.get_state().observe();
+    assert_eq!(later.tick, first.tick + 60);
+    assert!(later.time > first.time);
+    assert_eq!(later.player.id, first.player.id);
+    assert!((later.player.x, later.player.y) != (first.player.x, first.player.y), "the player didn't move");
+}
+
+/// The benchmark keeps the arena full of turrets and shots with the player alive, and times every system
+#[test]
+fn benchmark_reports_system_timings() {
+    let summary = benchmark::run_headless(30);
+
+    assert!(summary.contains("30 ticks"), "{}", summary);
+    assert!(summary.contains("collisions"), "{}", summary);
+    assert!(!summary.contains("Draw"), "a benchmark without a window timed drawing: {}", summary);
+}
+
+/// Timers count up to their duration once or over and over, and cooldowns are ready again once theirs has passed
+#[test]
+fn timers_and_cooldowns_count_down() {
+    let mut repeating = Timer::looping(1.0);
+    let fired = (0..10).filter(|_| repeating.update(0.25)).count();
+    assert_eq!(fired, 2, "a one second timer should repeat twice in two and a half seconds");
+    assert!(!repeating.is_finished());
+
+    let mut stopwatch = Timer::stopwatch();
+    stopwatch.update(100.0);
+    assert!(!stopwatch.is_finished());
+    assert_eq!(stopwatch.get_elapsed(), 100.0);
+
+    let mut cooldown = Cooldown::new(2.0);
+    assert!(cooldown.is_ready(), "a cooldown should start out ready");
+    cooldown.trigger();
+    cooldown.update(1.5);
+    assert!(!cooldown.is_ready());
+    assert_eq!(cooldown.get_remaining(), 0.5);
+    cooldown.reset();
+    assert!(cooldown.is_ready(), "a reset cooldown should be ready straight away");
+}
+
+/// Animations step through their frames on time: looping ones start over, and one-shot ones hold their last frame
+/// until they are restarted
+#[test]
+fn animations_play_their_frames() {
+    let mut once = Animation::once(4, 0.1);
+    assert_eq!(once.get_frame(), 0);
+    once.update(0.25);
+    assert_eq!(once.get_frame(), 2);
+    once.update(0.5);
+    assert_eq!(once.get_frame(), 3);
+    assert!(!once.is_playing(), "a one-shot animation kept playing past its last frame");
+    once.restart();
+    assert!(once.is_playing() && once.get_frame() == 0, "a restarted animation didn't play from the start");
+
+    let mut looping = Animation::looping(4, 0.1);
+    looping.update(0.45);
+    assert_eq!(looping.get_frame(), 0, "a looping animation didn't start over");
+    assert!(looping.is_playing());
+    assert!(!Animation::played(4, 0.1).is_playing(), "an animation that has played started again by itself");
+}
+
+/// Particles fade over their lifetime and are gone once it is over, and the player leaves exhaust behind while it
+/// moves
+#[test]
+fn particles_fade_and_vanish() {
+    let mut particles = ParticleSystem::new();
+    let emitter = Emitter::new((1.0, 1.0), (10.0, 20.0), 0.5, 1.0, ggez::graphics::WHITE);
+    emitter.burst(&mut particles, &mut rand::thread_rng(), &Point::new(0.0, 0.0), 0.0, 10);
+    assert_eq!(particles.len(), 10);
+    particles.update(0.5);
+    assert!(particles.get_alphas().iter().all(|&alpha| (alpha - 0.5).abs() < 1e-4),
+            "particles didn't fade halfway through their lifetime");
+    particles.update(0.6);
+    assert!(particles.is_empty(), "{} particles outlived their lifetime", particles.len());
+
+    let mut run = start_run(7);
+    for _ in 0..30 {
+        run.step(Action { thrust: 1, turn: 0, fire: false, grapple: false });
+    }
+    assert!(!run.get_state().get_particles().is_empty(), "the moving player left no exhaust");
+}
+
+/// The view shakes when the player is hurt and settles down afterwards, unless shaking is turned off
+#[test]
+fn screen_shakes_when_the_player_is_hurt() {
+    let shake_until_hurt = |enabled: bool| {
+        let mut run = start_run(7);
+        run.get_state_mut().set_screen_shake(enabled);
+        let starting_health = run.get_state().observe().player.health;
+        while run.get_state().observe().player.health == starting_health {
+            assert!(run.step(Action { thrust: 0, turn: 1, fire: false, grapple: false }), "the player was never hurt");
+        }
+        return run.get_state().get_screen_shake();
+    };
+
+    assert!(shake_until_hurt(true) > 0.0, "the view didn't shake when the player was hurt");
+    assert_eq!(shake_until_hurt(false), 0.0, "the view shook with shaking turned off");
+}
+
+/// A time scale of zero freezes the game while ticks go on, and easing the scale reaches its target on time
+#[test]
+fn time_scale_stretches_the_simulation() {
+    let mut run = start_run(7);
+    let thrust = Action { thrust: 1, turn: 0, fire: false, grapple: false };
+    let player_position = |run: &HeadlessRun| {
+        let player = run.get_state().observe().player;
+        return (player.x, player.y);
+    };
+
+    run.get_state_mut().set_time_scale(0.0);
+    let frozen_at = player_position(&run);
+    for _ in 0..30 {
+        run.step(thrust);
+    }
+    assert_eq!(player_position(&run), frozen_at, "the player moved while time was stopped");
+
+    run.get_state_mut().tween_time_scale(0.5, 0.5);
+    for _ in 0..40 {
+        run.step(thrust);
+    }
+    assert_eq!(run.get_state().get_time_scale(), 0.5);
+    assert_ne!(player_position(&run), frozen_at, "the player didn't move once time was eased back in");
+}
+
+/// Turning and the clock keep to game time whatever the tick rate, so a second of play is a second at any rate
+#[test]
+fn tick_rate_keeps_game_time() {
+    let turn_for_a_second = |rate: u32| {
+        let mut run = start_run(7);
+        run.get_state_mut().set_tick_rate(rate);
+        for _ in 0..rate {
+            run.step(Action { thrust: 0, turn: 1, fire: false, grapple: false });
+        }
+        assert!((run.get_state().get_time() - 1.0).abs() < 1e-4, "{} ticks at {} per second weren't a second",
+                rate, rate);
+        return run.get_state().observe().player.heading;
+    };
+
+    let heading = turn_for_a_second(60);
+    for &rate in &[30, 144] {
+        let turned = turn_for_a_second(rate);
+        assert!((turned - heading).abs() < 1e-3, "the player turned to {} at {} ticks per second, not {}",
+                turned, rate, heading);
+    }
+}
+
+/// The camera keeps the player in the middle of the view in an arena bigger than the window, stopping at the edges
+/// of the arena, and the arena keeps its own size however big the window is
+#[test]
+fn camera_follows_the_player_across_a_bigger_arena() {
+    let world = World::rect(2000.0, 1500.0);
+    let mut camera = Camera::new(BOUNDS);
+    let player = Point::new(1000.0, 700.0);
+    camera.follow(&player, &world);
+    let on_screen = camera.to_screen(&player);
+    assert_eq!((on_screen.x, on_screen.y), (400.0, 300.0), "the player wasn't in the middle of the view");
+    assert_eq!(camera.to_world(&on_screen), player);
+    assert!(!camera.is_visible(&Point::new(100.0, 100.0), 10.0), "a far corner of the arena was in view");
+
+    camera.follow(&Point::new(20.0, 1490.0), &world);
+    assert_eq!(camera.get_top_left(), Point::new(0.0, 900.0), "the view went past the edges of the arena");
+    camera.follow(&player, &World::rect(600.0, 400.0));
+    assert_eq!(camera.get_center(), &Point::new(300.0, 200.0), "a small arena wasn't centered in the view");
+
+    let mut run = start_run(7);
+    run.get_state_mut().set_world_size(Some((2000.0, 1500.0)));
+    assert_eq!(run.get_state().get_world().get_size(), (2000.0, 1500.0));
+    assert_eq!(run.get_state().get_camera().get_view_size(), BOUNDS);
+    run.get_state_mut().set_world_size(None);
+    assert_eq!(run.get_state().get_world().get_size(), BOUNDS, "the arena didn't go back to filling the window");
+
+    let options = parse_launch(&["--world", "3000x2000"]).unwrap();
+    assert_eq!(options.world, Some((3000.0, 2000.0)));
+}
+
+/// Saves are headed by their format version, and saves from a newer version of the game are refused
+#[test]
+fn saves_are_versioned() {
+    let mut run = start_run(7);
+    for _ in 0..30 {
+        run.step(headless::spin_and_fire(&run.get_state().observe()));
+    }
+    let path = std::env::temp_dir().join(format!("turrets_versioned_save_{}.toml", std::process::id()));
+    run.get_state().save_game(&path).expect("the run couldn't be saved");
+    let text = std::fs::read_to_string(&path).expect("the save couldn't be read back");
+    assert!(text.starts_with("format_version = 6\n"), "the save doesn't start with its version");
+    run.get_state_mut().load_game(&path).expect("the save couldn't be loaded");
+
+    std::fs::write(&path, text.replacen("format_version = 6", "format_version = 99", 1)).unwrap();
+    let error = run.get_state_mut().load_game(&path).expect_err("a save from a newer version was loaded");
+    assert!(error.contains("newer version"), "{}", error);
+    let _ = std::fs::remove_file(&path);
+}
+
+/// Saves written before there was a version are upgraded as they are read, like the time since a turret last
+/// fired, which was saved as a plain number of seconds
+#[test]
+fn unversioned_saves_are_upgraded() {
+    let mut run = start_run(7);
+    for _ in 0..30 {
+        run.step(headless::spin_and_fire(&run.get_state().observe()));
+    }
+    let path = std::env::temp_dir().join(format!("turrets_unversioned_save_{}.toml", std::process::id()));
+    run.get_state().save_game(&path).expect("the run couldn't be saved");
+
+    // Turn the save into one the first version of the game could have written, with no version and the time since
+    // a turret last fired in seconds
+    let mut save: toml::Value = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let table = save.as_table_mut().unwrap();
+    table.remove("format_version");
+    let turret = table.get_mut("actors").and_then(toml::Value::as_array_mut).unwrap().iter_mut()
+        .filter_map(toml::Value::as_table_mut)
+        .find(|actor| actor.get("type").and_then(toml::Value::as_str) == Some("turret"))
+        .expect("the run has no turrets");
+    turret.insert(String::from("since_last_shot"), toml::Value::Float(1.5));
+    let id = turret.get("id").and_then(toml::Value::as_integer);
+    std::fs::write(&path, toml::to_string(&save).unwrap()).unwrap();
+
+    run.get_state_mut().load_game(&path).expect("the unversioned save couldn't be loaded");
+    run.get_state().save_game(&path).expect("the upgraded run couldn't be saved");
+    let upgraded: toml::Value = toml::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+    let _ = std::fs::remove_file(&path);
+    let elapsed = upgraded.get("actors").and_then(toml::Value::as_array).unwrap().iter()
+        .find(|actor| actor.get("id").and_then(toml::Value::as_integer) == id)
+        .and_then(|actor| actor.get("since_last_shot")?.get("elapsed")?.as_float());
+    assert_eq!(elapsed, Some(1.5), "the time since the turret last fired wasn't kept");
+}
+
+/// A loaded run carries on exactly as the run that was saved, down to the random numbers it draws
+#[test]
+fn loaded_runs_play_out_like_the_saved_ones() {
+    let mut run = start_run(7);
+    for _ in 0..120 {
+        run.step(headless::spin_and_fire(&run.get_state().observe()));
+    }
+    let path = std::env::temp_dir().join(format!("turrets_resumed_save_{}.toml", std::process::id()));
+    run.get_state().save_game(&path).expect("the run couldn't be saved");
+    for _ in 0..300 {
+        run.step(headless::spin_and_fire(&run.get_state().observe()));
+    }
+    let played = format!("{:?}", run.get_state().observe());
+
+    // The run the save is loaded into starts from another seed, so only the save can make the two match
+    let mut loaded = start_run(99);
+    loaded.get_state_mut().load_game(&path).expect("the save couldn't be loaded");
+    for _ in 0..300 {
+        loaded.step(headless::spin_and_fire(&loaded.get_state().observe()));
+    }
+    let _ = std::fs::remove_file(&path);
+
+    assert_eq!(format!("{:?}", loaded.get_state().observe()), played, "the loaded run played out differently");
+}
+
+/// Read launch options from the given arguments
+fn parse_launch(args: &[&str]) -> Result<LaunchOptions, String> {
+    return LaunchOptions::parse(args.iter().map(|arg| arg.to_string()));
+}
+
+/// Launch options are read from the command line, still accepting the older forms, and mistakes are reported
+#[test]
+fn launch_options_are_parsed() {
+    assert_eq!(parse_launch(&[]).unwrap(), LaunchOptions::new());
+
+    let options = parse_launch(&["--size", "1280x720", "--no-vsync", "--level", "Maze", "--seed", "9"]).unwrap();
+    assert_eq!(options.mode, LaunchMode::Window);
+    assert_eq!(options.size, Some((1280.0, 720.0)));
+    assert!(!options.vsync);
+    assert_eq!(options.level.as_deref(), Some("Maze"));
+    assert_eq!(options.seed, Some(9));
+
+    let options = parse_launch(&["--headless", "42"]).unwrap();
+    assert_eq!((options.mode, options.seed), (LaunchMode::Headless, Some(42)));
+    assert_eq!(parse_launch(&["--benchmark", "--headless"]).unwrap().mode, LaunchMode::HeadlessBenchmark);
+    assert!(parse_launch(&["--benchmark", "--headless", "--size", "1280x720"]).is_err());
+    assert_eq!(parse_launch(&["--benchmark"]).unwrap().mode, LaunchMode::Benchmark);
+    assert_eq!(parse_launch(&["--headless", "--help"]).unwrap().mode, LaunchMode::Help);
+
+    assert!(parse_launch(&["--bogus"]).is_err());
+    assert!(parse_launch(&["--size", "wide"]).is_err());
+    assert!(parse_launch(&["--size", "0x600"]).is_err());
+    assert!(parse_launch(&["--seed"]).is_err());
+}